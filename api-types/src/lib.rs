@@ -180,12 +180,40 @@ pub mod indexer {
         pub onchain_reserved_index: Option<u64>,
         #[serde(default)]
         pub onchain_proved_index: Option<u64>,
+        /// Last contiguous event index indexed up to the chain tip ("seen").
         #[serde(default)]
         pub events_synced_index: Option<u64>,
+        /// Last contiguous event index in a block that is final under the token's finality rule.
+        /// The tree and the root prover never go past it.
+        #[serde(default)]
+        pub events_final_index: Option<u64>,
         #[serde(default)]
         pub tree_synced_index: Option<u64>,
         #[serde(default)]
         pub ivc_generated_index: Option<u64>,
+        /// `false` while the hash-chain auditor finds the indexed hash chain diverging from the
+        /// chain; the root prover stays halted until it matches again.
+        #[serde(default = "default_healthy")]
+        pub healthy: bool,
+        #[serde(default)]
+        pub hash_chain_divergence: Option<HashChainDivergence>,
+    }
+
+    fn default_healthy() -> bool {
+        true
+    }
+
+    /// Checkpoint at which a stored snapshot's hash chain differs from the on-chain one.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct HashChainDivergence {
+        /// `"hashChain"` for the token's own hash chain, `"reservedHashChains"` for a checkpoint
+        /// reserved on the verifier.
+        pub checkpoint: String,
+        pub index: u64,
+        #[serde(with = "crate::serde_utils::u256_hex")]
+        pub onchain_hash_chain: U256,
+        #[serde(with = "crate::serde_utils::u256_hex")]
+        pub indexed_hash_chain: U256,
     }
 
     #[serde_as]
@@ -240,6 +268,39 @@ pub mod indexer {
         pub limit: Option<usize>,
     }
 
+    /// Body of `POST /events/batch`: the events of several recipients of one token in a single
+    /// round trip.
+    #[serde_as]
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct EventsBatchRequest {
+        pub chain_id: u64,
+        #[serde_as(as = "DisplayFromStr")]
+        pub token_address: Address,
+        pub recipients: Vec<EventsBatchRecipient>,
+        /// Maximum number of events returned per recipient.
+        #[serde(default)]
+        pub limit: Option<usize>,
+    }
+
+    #[serde_as]
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct EventsBatchRecipient {
+        #[serde_as(as = "DisplayFromStr")]
+        pub to: Address,
+        /// Skips events below this index; pass the last returned index + 1 to fetch the next page.
+        #[serde(default)]
+        pub from_event_index: Option<u64>,
+    }
+
+    /// Events of one recipient, in the order the recipients were requested.
+    #[serde_as]
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct RecipientEvents {
+        #[serde_as(as = "DisplayFromStr")]
+        pub to: Address,
+        pub events: Vec<IndexedEvent>,
+    }
+
     #[serde_as]
     #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
     pub struct TreeIndexQuery {
@@ -16,15 +16,16 @@ use client_common::{
     },
     tokens::{HubEntry, TokenEntry},
 };
-use zkp::utils::general_recipient::GeneralRecipient;
+use zkp::utils::general_recipient::{GeneralRecipient, RecipientAccount};
 
 use crate::{
     CommonArgs, InvoiceIssueArgs, InvoiceListArgs, InvoiceReceiveArgs, build_indexer_client,
     commands::{
         shared::{
-            build_erc20, build_hub, build_stealth_client, build_verifier, find_token_by_chain,
+            build_erc20, build_hub, build_stealth_client, build_verifier,
+            check_artifacts_pow_difficulty, find_token_by_chain, payout_split,
         },
-        teleport::{RedeemResult, print_events, redeem_transfers},
+        teleport::{RedeemOptions, RedeemResult, print_events, redeem_transfers},
     },
 };
 use hex;
@@ -60,23 +61,19 @@ async fn build_invoice_receive_context(
     let seed = compute_seed_from_signature(private_key)
         .await
         .context("failed to derive seed from signature")?;
-    let recipient_address = get_address_from_private_key(private_key);
-    let recipient_chain_id = args.chain_id;
+    let recipient =
+        RecipientAccount::new_evm(args.chain_id, get_address_from_private_key(private_key));
+    let pow_difficulty = token_entry.pow_difficulty;
 
     let is_single = is_single(args.invoice_id);
     let mut burn_address_to_secret_and_tweak: HashMap<Address, SecretAndTweak> = HashMap::new();
 
     if is_single {
-        let secret_and_tweak = SecretAndTweak::single_invoice(
-            args.invoice_id,
-            seed,
-            recipient_chain_id,
-            recipient_address,
-        );
-        let burn_payload =
-            FullBurnAddress::new(recipient_chain_id, recipient_address, &secret_and_tweak)?;
+        let secret_and_tweak =
+            SecretAndTweak::single_invoice(args.invoice_id, seed, recipient, pow_difficulty);
+        let burn_payload = FullBurnAddress::new(recipient, &secret_and_tweak, pow_difficulty)?;
         let burn_address = burn_payload
-            .burn_address()
+            .burn_address(pow_difficulty)
             .context("failed to derive burn address for single invoice")?;
         burn_address_to_secret_and_tweak.insert(burn_address, secret_and_tweak.clone());
     } else {
@@ -85,12 +82,11 @@ async fn build_invoice_receive_context(
                 args.invoice_id,
                 sub_id as u32,
                 seed,
-                recipient_chain_id,
-                recipient_address,
+                recipient,
+                pow_difficulty,
             );
-            let burn_payload =
-                FullBurnAddress::new(recipient_chain_id, recipient_address, &secret_and_tweak)?;
-            let burn_address = burn_payload.burn_address().with_context(|| {
+            let burn_payload = FullBurnAddress::new(recipient, &secret_and_tweak, pow_difficulty)?;
+            let burn_address = burn_payload.burn_address(pow_difficulty).with_context(|| {
                 format!(
                     "failed to derive burn address for batch invoice sub {}",
                     sub_id
@@ -109,7 +105,7 @@ async fn build_invoice_receive_context(
         .next()
         .expect("at least one burn address")
         .tweak;
-    let gr = GeneralRecipient::new_evm(recipient_chain_id, recipient_address, tweak);
+    let gr = recipient.with_tweak(tweak);
     let burn_addresses: Vec<_> = burn_address_to_secret_and_tweak.keys().cloned().collect();
     let token_clients: Vec<_> = token_entries
         .iter()
@@ -151,6 +147,7 @@ pub async fn list(
     private_key: B256,
 ) -> Result<()> {
     let entry = find_token_by_chain(tokens, args.chain_id)?;
+    check_artifacts_pow_difficulty(common_args, [entry])?;
     let client = build_stealth_client(common_args)
         .await
         .context("failed to construct stealth canister client")?;
@@ -195,6 +192,7 @@ pub async fn issue(
     private_key: B256,
 ) -> Result<()> {
     let entry = find_token_by_chain(tokens, args.chain_id)?;
+    check_artifacts_pow_difficulty(common_args, [entry])?;
     let client = build_stealth_client(common_args)
         .await
         .context("failed to construct stealth canister client")?;
@@ -205,6 +203,8 @@ pub async fn issue(
         .recipient
         .unwrap_or_else(|| get_address_from_private_key(private_key));
     let recipient_chain_id = entry.chain_id;
+    let recipient_account = RecipientAccount::new_evm(recipient_chain_id, recipient);
+    let pow_difficulty = entry.pow_difficulty;
 
     let recipient_bytes = address_to_array(recipient);
     let existing_invoice_ids = decode_invoice_ids(
@@ -253,12 +253,12 @@ pub async fn issue(
                 invoice_id,
                 sub_id as u32,
                 seed,
-                recipient_chain_id,
-                recipient,
+                recipient_account,
+                pow_difficulty,
             );
             let burn_payload =
-                FullBurnAddress::new(recipient_chain_id, recipient, &secret_and_tweak)?;
-            let burn_address = burn_payload.burn_address().with_context(|| {
+                FullBurnAddress::new(recipient_account, &secret_and_tweak, pow_difficulty)?;
+            let burn_address = burn_payload.burn_address(pow_difficulty).with_context(|| {
                 format!(
                     "failed to derive burn address for issued batch invoice sub {}",
                     sub_id
@@ -268,10 +268,11 @@ pub async fn issue(
         }
     } else {
         let secret_and_tweak =
-            SecretAndTweak::single_invoice(invoice_id, seed, recipient_chain_id, recipient);
-        let burn_payload = FullBurnAddress::new(recipient_chain_id, recipient, &secret_and_tweak)?;
+            SecretAndTweak::single_invoice(invoice_id, seed, recipient_account, pow_difficulty);
+        let burn_payload =
+            FullBurnAddress::new(recipient_account, &secret_and_tweak, pow_difficulty)?;
         let burn_address = burn_payload
-            .burn_address()
+            .burn_address(pow_difficulty)
             .context("failed to derive burn address for issued single invoice")?;
         println!("Burn address        : {}", burn_address);
     }
@@ -335,6 +336,12 @@ pub async fn receive(
         token_entries,
         private_key,
         artifacts_dir,
+        RedeemOptions {
+            amount: args.amount,
+            relayer: args.relayer,
+            fee: args.fee.unwrap_or_default(),
+            payout_split: payout_split(&args.payouts)?,
+        },
     )
     .await?
     {
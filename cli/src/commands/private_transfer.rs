@@ -10,10 +10,14 @@ use client_common::{
 };
 use rand::rngs::OsRng;
 use stealth_client::encryption::encrypt_payload;
+use zkp::utils::general_recipient::RecipientAccount;
 
 use crate::{
     CommonArgs, PrivateTransferArgs,
-    commands::shared::{build_erc20, build_stealth_client, find_token_by_chain, format_tx_hash},
+    commands::shared::{
+        build_erc20, build_stealth_client, check_artifacts_pow_difficulty, find_token_by_chain,
+        format_tx_hash,
+    },
 };
 use hex;
 
@@ -30,11 +34,16 @@ pub async fn run(
     let seed = compute_seed_from_signature(private_key)
         .await
         .context("failed to derive seed from signature")?;
+    let recipient = RecipientAccount::new_evm(args.to_chain_id, args.to);
+    // the burn is redeemed by the verifier on the recipient chain, so its PoW difficulty applies
+    let to_entry = find_token_by_chain(tokens, args.to_chain_id)?;
+    check_artifacts_pow_difficulty(common, [to_entry])?;
+    let pow_difficulty = to_entry.pow_difficulty;
     let secret_and_tweak =
-        SecretAndTweak::payment_advice(payment_advice_id, seed, args.to_chain_id, args.to);
-    let burn_payload = FullBurnAddress::new(args.to_chain_id, args.to, &secret_and_tweak)?;
+        SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, pow_difficulty);
+    let burn_payload = FullBurnAddress::new(recipient, &secret_and_tweak, pow_difficulty)?;
     let burn_address = burn_payload
-        .burn_address()
+        .burn_address(pow_difficulty)
         .context("failed to derive burn address for private transfer")?;
     let burn_payload_bytes = burn_payload.to_bytes();
 
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fs, path::Path};
 
-use alloy::primitives::{Address, B256};
+use alloy::primitives::B256;
 use anyhow::{Context, Result, anyhow};
 use client_common::{
     payment::burn_address::FullBurnAddress,
@@ -16,8 +16,8 @@ use crate::{
     CommonArgs, ReceiveTransferArgs, build_indexer_client,
     commands::{
         scan_receive_transfers::ScannedTransfer,
-        shared::{build_erc20, build_hub, build_verifier, find_token_by_chain},
-        teleport::{RedeemResult, print_events, redeem_transfers},
+        shared::{build_erc20, build_hub, build_verifier, find_token_by_chain, payout_split},
+        teleport::{RedeemOptions, RedeemResult, print_events, redeem_transfers},
     },
 };
 
@@ -34,11 +34,17 @@ pub async fn run(
 
     let burn_payload =
         FullBurnAddress::from_bytes(&burn_bytes).context("failed to decode FullBurnAddress")?;
+    let recipient_address = burn_payload.gr.account().evm_address().with_context(|| {
+        format!(
+            "recipient {} cannot be redeemed through an EVM verifier",
+            burn_payload.gr.account()
+        )
+    })?;
+    let chain_id = burn_payload.gr.chain_id;
+    let token_entry = find_token_by_chain(tokens, chain_id)?;
     let burn_address = burn_payload
-        .burn_address()
+        .burn_address(token_entry.pow_difficulty)
         .context("failed to derive burn address from payload")?;
-    let recipient_address = Address::from_word(burn_payload.gr.address);
-    let chain_id = burn_payload.gr.chain_id;
 
     println!("Recipient address  : {}", recipient_address);
     println!("Recipient chain ID : {}", chain_id);
@@ -46,7 +52,6 @@ pub async fn run(
 
     let hub_entry = hub.ok_or_else(|| anyhow!("hub entry is required to redeem transfers"))?;
     let hub_contract = build_hub(hub_entry)?;
-    let token_entry = find_token_by_chain(tokens, chain_id)?;
     let verifier = build_verifier(token_entry)?;
     let token_clients = tokens.iter().map(build_erc20).collect::<Result<Vec<_>>>()?;
     let indexer = build_indexer_client(common_args, "receive transfer command")?;
@@ -108,6 +113,12 @@ pub async fn run(
         tokens,
         private_key,
         artifacts_dir,
+        RedeemOptions {
+            amount: args.amount,
+            relayer: args.relayer,
+            fee: args.fee.unwrap_or_default(),
+            payout_split: payout_split(&args.payouts)?,
+        },
     )
     .await?
     {
@@ -15,7 +15,10 @@ use stealth_client::{
     types::EncryptedViewKeyRequest,
 };
 
-use crate::{CommonArgs, ScanReceiveTransfersArgs, commands::shared::build_stealth_client};
+use crate::{
+    CommonArgs, ScanReceiveTransfersArgs,
+    commands::shared::{build_stealth_client, check_artifacts_pow_difficulty, find_token_by_chain},
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ScannedTransfer {
@@ -30,9 +33,10 @@ pub struct ScannedTransfer {
 pub async fn run(
     common: &CommonArgs,
     args: &ScanReceiveTransfersArgs,
-    _tokens: &[TokenEntry],
+    tokens: &[TokenEntry],
     private_key: B256,
 ) -> Result<()> {
+    check_artifacts_pow_difficulty(common, tokens)?;
     let client = build_stealth_client(common)
         .await
         .context("failed to construct stealth canister client")?;
@@ -114,7 +118,17 @@ pub async fn run(
                 continue;
             }
 
-            let burn_address = match burn_payload.burn_address() {
+            let token_entry = match find_token_by_chain(tokens, burn_payload.gr.chain_id) {
+                Ok(token_entry) => token_entry,
+                Err(err) => {
+                    eprintln!(
+                        "Warning: announcement {} targets an unconfigured chain: {err}",
+                        entry.id
+                    );
+                    continue;
+                }
+            };
+            let burn_address = match burn_payload.burn_address(token_entry.pow_difficulty) {
                 Ok(address) => address,
                 Err(err) => {
                     eprintln!(
@@ -169,15 +183,11 @@ fn address_from_signing_key(signing_key: &SigningKey) -> Address {
 }
 
 fn is_recipient(burn: &FullBurnAddress, recipient: Address) -> bool {
-    recipient_address_from_payload(burn) == recipient
-}
-
-fn recipient_address_from_payload(burn: &FullBurnAddress) -> Address {
-    Address::from_word(burn.gr.address)
+    burn.gr.account().evm_address() == Some(recipient)
 }
 
 fn recipient_address_string(burn: &FullBurnAddress) -> String {
-    recipient_address_from_payload(burn).to_string()
+    Address::from_word(burn.gr.address).to_string()
 }
 
 fn write_results(path: &PathBuf, transfers: &[ScannedTransfer]) -> Result<()> {
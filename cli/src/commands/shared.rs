@@ -1,16 +1,18 @@
-use std::str::FromStr;
+use std::{path::Path, str::FromStr};
 
 use alloy::primitives::{Address, B256, U256};
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, bail, ensure};
 use candid::Principal;
 use client_common::{
     contracts::{hub::HubContract, verifier::VerifierContract, z_erc20::ZErc20Contract},
+    payment::payout::{Payout, PayoutSplit},
     tokens::{HubEntry, TokenEntry},
 };
 use hex;
 use ic_agent::{Agent, identity::AnonymousIdentity};
 use num_bigint::BigUint;
 use stealth_client::client::StealthCanisterClient;
+use zkp::artifacts::ArtifactDir;
 
 use crate::CommonArgs;
 
@@ -26,10 +28,68 @@ pub fn find_token_by_chain<'a>(tokens: &'a [TokenEntry], chain_id: u64) -> Resul
     }
 }
 
+/// Opens the prover artifacts and checks that each of `tokens` derives burn addresses with the PoW
+/// difficulty the circuits were generated with. A token that does not would hand out burn addresses
+/// that can never be proven.
+pub fn open_artifacts<'a>(
+    artifacts_dir: &Path,
+    tokens: impl IntoIterator<Item = &'a TokenEntry>,
+) -> Result<ArtifactDir> {
+    let artifacts = ArtifactDir::open(artifacts_dir).with_context(|| {
+        format!(
+            "failed to open prover artifacts in {}",
+            artifacts_dir.display()
+        )
+    })?;
+    let manifest_difficulty = artifacts.manifest().pow_difficulty;
+    for token in tokens {
+        ensure!(
+            token.pow_difficulty == manifest_difficulty,
+            "token '{}' has pow_difficulty {} but the artifacts in {} were generated with pow_difficulty {}",
+            token.label,
+            token.pow_difficulty,
+            artifacts_dir.display(),
+            manifest_difficulty
+        );
+    }
+    Ok(artifacts)
+}
+
+/// Runs [`open_artifacts`] when an artifacts directory is configured; commands that only derive
+/// burn addresses do not require one.
+pub fn check_artifacts_pow_difficulty<'a>(
+    common: &CommonArgs,
+    tokens: impl IntoIterator<Item = &'a TokenEntry>,
+) -> Result<()> {
+    if let Some(artifacts_dir) = common.nova_artifacts_dir.as_deref() {
+        open_artifacts(artifacts_dir, tokens)?;
+    }
+    Ok(())
+}
+
 pub fn parse_address(value: &str) -> Result<Address, String> {
     Address::from_str(value).map_err(|err| err.to_string())
 }
 
+/// Parses one `ADDRESS:AMOUNT` entry of a payout split.
+pub fn parse_payout(value: &str) -> Result<Payout, String> {
+    let (recipient, amount) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected ADDRESS:AMOUNT, got '{}'", value))?;
+    Ok(Payout {
+        recipient: parse_address(recipient.trim())?,
+        amount: parse_u256(amount)?,
+    })
+}
+
+/// Payout split given on the command line, `None` when no `--payout` was passed.
+pub fn payout_split(payouts: &[Payout]) -> Result<Option<PayoutSplit>> {
+    if payouts.is_empty() {
+        return Ok(None);
+    }
+    PayoutSplit::new(payouts.to_vec()).map(Some)
+}
+
 pub fn parse_b256(value: &str) -> Result<B256, String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
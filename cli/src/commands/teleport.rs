@@ -1,30 +1,58 @@
 use std::{collections::HashMap, path::Path};
 
 use alloy::primitives::{Address, B256, U256};
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use ark_bn254::Fr;
 use client_common::{
     contracts::verifier::VerifierContract,
     indexer::{HttpIndexerClient, IndexedEvent},
+    payment::payout::PayoutSplit,
     teleport::{
         aggregation_tree::AggregationTreeState,
         events::EventsWithEligibility,
         merkle_proofs::{
-            fetch_local_teleport_merkle_proofs, generate_global_teleport_merkle_proofs,
+            GlobalTeleportMerkleProof, fetch_local_teleport_merkle_proofs,
+            generate_global_teleport_merkle_proofs,
         },
     },
     tokens::TokenEntry,
 };
 use zkp::{
+    groth16::{
+        multi_withdraw::MULTI_WITHDRAW_SIZES,
+        payout::{PAYOUT_LEAVES, PAYOUT_SIZES},
+    },
     nova::constants::GLOBAL_TRANSFER_TREE_HEIGHT,
-    utils::{convertion::b256_to_fr, general_recipient::GeneralRecipient},
+    utils::{
+        convertion::b256_to_fr, general_recipient::GeneralRecipient, tree::merkle_tree::MerkleProof,
+    },
 };
 
 use crate::{
     CommonArgs, build_decider_client,
-    commands::shared::{find_token_by_chain, format_tx_hash},
-    proof::{batch::batch_teleport_proof, single::single_teleport_proof},
+    commands::shared::{find_token_by_chain, format_tx_hash, open_artifacts},
+    proof::{
+        TeleportOptions,
+        batch::batch_teleport_proof,
+        multi::{multi_teleport_proof, multi_withdraw_artifacts_exist},
+        payout::{payout_artifacts_exist, payout_teleport_proof},
+        single::single_teleport_proof,
+    },
 };
 
+/// What a redemption claims and who is paid for submitting it.
+#[derive(Debug, Clone, Default)]
+pub struct RedeemOptions {
+    /// Amount minted on top of the already teleported total; all claimable value when unset.
+    pub amount: Option<U256>,
+    /// Relayer paid `fee` out of the minted amount.
+    pub relayer: Option<Address>,
+    pub fee: U256,
+    /// Splits the minted amount between these payouts instead of minting it to the recipient.
+    /// The amount minted is then the payout total plus `fee`, and `amount` is ignored.
+    pub payout_split: Option<PayoutSplit>,
+}
+
 pub enum RedeemResult {
     AlreadyClaimed,
     NoProofs,
@@ -32,7 +60,9 @@ pub enum RedeemResult {
 }
 
 /// Redeem eligible teleport transfers by generating the necessary proofs and submitting
-/// the corresponding transactions.
+/// the corresponding transactions. When `redeem.amount` is set, only that amount is minted on top
+/// of the already teleported total and the rest stays claimable for a later redemption. With
+/// `redeem.payout_split`, the minted amount is split between the payouts by a payout withdraw proof.
 pub async fn redeem_transfers(
     common_args: &CommonArgs,
     verifier: &VerifierContract,
@@ -44,7 +74,14 @@ pub async fn redeem_transfers(
     token_entries: &[TokenEntry],
     private_key: B256,
     artifacts_dir: &Path,
+    redeem: RedeemOptions,
 ) -> Result<RedeemResult> {
+    // burn addresses are derived with the recipient chain's difficulty, which the proofs must match
+    open_artifacts(
+        artifacts_dir,
+        [find_token_by_chain(token_entries, gr.chain_id)?],
+    )?;
+
     let total_eligible_value = separated_events
         .values()
         .map(|events| events.eligible_total_value())
@@ -57,6 +94,17 @@ pub async fn redeem_transfers(
     if total_eligible_value <= total_teleported {
         return Ok(RedeemResult::AlreadyClaimed);
     }
+    let amount = match &redeem.payout_split {
+        Some(split) => Some(split.total() + redeem.fee),
+        None => redeem.amount,
+    };
+    let delta = withdraw_delta(total_eligible_value, total_teleported, amount)?;
+    let options = TeleportOptions {
+        delta,
+        relayer: redeem.relayer.unwrap_or(Address::ZERO),
+        fee: redeem.fee,
+    };
+    check_relayer_fee(&options, total_eligible_value - total_teleported - delta)?;
 
     let mut local_teleport_mps = HashMap::new();
     for (chain_id, events_with_eligibility) in separated_events {
@@ -82,6 +130,51 @@ pub async fn redeem_transfers(
         return Ok(RedeemResult::NoProofs);
     }
 
+    if let Some(split) = &redeem.payout_split {
+        if global_merkle_proofs.len() > PAYOUT_LEAVES {
+            bail!(
+                "{} eligible transfers exceed the {} leaves a payout withdraw can prove",
+                global_merkle_proofs.len(),
+                PAYOUT_LEAVES
+            );
+        }
+        let num_payouts = payout_size(verifier, artifacts_dir, split.len()).await?;
+        let prove = match num_payouts {
+            4 => payout_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT, 4>,
+            16 => payout_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT, 16>,
+            _ => bail!("unsupported payout withdraw size {}", num_payouts),
+        };
+        let inputs = WithdrawInputs::collect(&global_merkle_proofs, burn_address_to_secret)?;
+        let payout_proof = prove(
+            artifacts_dir,
+            gr.to_fr(),
+            aggregation_tree_state.aggregation_root,
+            &inputs.events,
+            &inputs.merkle_proofs,
+            &inputs.leaf_indices,
+            &inputs.secrets,
+            options,
+            total_teleported,
+            split,
+        )
+        .context("failed to generate payout teleport proof")?;
+        let pending = verifier
+            .payout_teleport(
+                private_key,
+                true,
+                num_payouts as u8,
+                aggregation_tree_state.latest_agg_seq,
+                gr,
+                split,
+                &payout_proof,
+            )
+            .await
+            .context("failed to submit payout global teleport transaction")?;
+        let tx_hash = format_tx_hash(pending.tx_hash().as_slice());
+        println!("Submitted teleport  : {}", tx_hash);
+        return Ok(RedeemResult::Submitted);
+    }
+
     if global_merkle_proofs.len() == 1 {
         let global_proof = &global_merkle_proofs[0];
         let secret = burn_address_to_secret
@@ -95,6 +188,7 @@ pub async fn redeem_transfers(
             global_proof.global_merkle_proof.clone(),
             global_proof.global_leaf_index,
             b256_to_fr(*secret),
+            options,
         )
         .context("failed to generate single teleport proof")?;
         let pending = verifier
@@ -110,22 +204,53 @@ pub async fn redeem_transfers(
         let tx_hash = format_tx_hash(pending.tx_hash().as_slice());
         println!("Submitted teleport  : {}", tx_hash);
     } else {
-        let mut events = Vec::new();
-        let mut merkle_proofs = Vec::new();
-        let mut leaf_indices = Vec::new();
-        let mut secrets = Vec::new();
-        for global_proof in &global_merkle_proofs {
-            events.push(global_proof.event.clone());
-            merkle_proofs.push(global_proof.global_merkle_proof.clone());
-            leaf_indices.push(global_proof.global_leaf_index);
-            let secret = burn_address_to_secret
-                .get(&global_proof.event.to)
-                .context("missing secret for burn address")?;
-            secrets.push(b256_to_fr(*secret));
+        let WithdrawInputs {
+            events,
+            merkle_proofs,
+            leaf_indices,
+            secrets,
+        } = WithdrawInputs::collect(&global_merkle_proofs, burn_address_to_secret)?;
+
+        if let Some(num_leaves) =
+            multi_withdraw_size(verifier, artifacts_dir, global_merkle_proofs.len()).await?
+        {
+            let prove = match num_leaves {
+                2 => multi_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT, 2>,
+                4 => multi_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT, 4>,
+                8 => multi_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT, 8>,
+                _ => bail!("unsupported multi withdraw size {}", num_leaves),
+            };
+            let multi_proof = prove(
+                artifacts_dir,
+                gr.to_fr(),
+                aggregation_tree_state.aggregation_root,
+                &events,
+                &merkle_proofs,
+                &leaf_indices,
+                &secrets,
+                options,
+            )
+            .context("failed to generate multi teleport proof")?;
+            let pending = verifier
+                .multi_teleport(
+                    private_key,
+                    true,
+                    num_leaves as u8,
+                    aggregation_tree_state.latest_agg_seq,
+                    gr,
+                    &multi_proof,
+                )
+                .await
+                .context("failed to submit multi global teleport transaction")?;
+            let tx_hash = format_tx_hash(pending.tx_hash().as_slice());
+            println!("Submitted teleport  : {}", tx_hash);
+            return Ok(RedeemResult::Submitted);
         }
+
         let decider = build_decider_client(common_args, "teleport redemption")?;
         let batch_proof = batch_teleport_proof::<GLOBAL_TRANSFER_TREE_HEIGHT>(
             artifacts_dir,
+            common_args.ivc_checkpoint_dir.as_deref(),
             &decider,
             gr.to_fr(),
             aggregation_tree_state.aggregation_root,
@@ -133,6 +258,7 @@ pub async fn redeem_transfers(
             &merkle_proofs,
             &leaf_indices,
             &secrets,
+            options,
         )
         .await
         .context("failed to generate batch teleport proof")?;
@@ -154,6 +280,137 @@ pub async fn redeem_transfers(
     Ok(RedeemResult::Submitted)
 }
 
+/// Per-leaf inputs of the multi-leaf proofs, in the order of the global Merkle proofs.
+struct WithdrawInputs {
+    events: Vec<IndexedEvent>,
+    merkle_proofs: Vec<MerkleProof>,
+    leaf_indices: Vec<u64>,
+    secrets: Vec<Fr>,
+}
+
+impl WithdrawInputs {
+    fn collect(
+        global_merkle_proofs: &[GlobalTeleportMerkleProof],
+        burn_address_to_secret: &HashMap<Address, B256>,
+    ) -> Result<Self> {
+        let mut inputs = Self {
+            events: Vec::new(),
+            merkle_proofs: Vec::new(),
+            leaf_indices: Vec::new(),
+            secrets: Vec::new(),
+        };
+        for global_proof in global_merkle_proofs {
+            inputs.events.push(global_proof.event.clone());
+            inputs
+                .merkle_proofs
+                .push(global_proof.global_merkle_proof.clone());
+            inputs.leaf_indices.push(global_proof.global_leaf_index);
+            let secret = burn_address_to_secret
+                .get(&global_proof.event.to)
+                .context("missing secret for burn address")?;
+            inputs.secrets.push(b256_to_fr(*secret));
+        }
+        Ok(inputs)
+    }
+}
+
+/// Picks the smallest payout withdraw circuit that fits `num_payouts`. Unlike plain redemptions
+/// there is no fallback, so missing artifacts or an unregistered verifier are errors.
+async fn payout_size(
+    verifier: &VerifierContract,
+    artifacts_dir: &Path,
+    num_payouts: usize,
+) -> Result<usize> {
+    let Some(size) = PAYOUT_SIZES.into_iter().find(|&size| size >= num_payouts) else {
+        bail!(
+            "{} payouts exceed the largest payout withdraw size {}",
+            num_payouts,
+            PAYOUT_SIZES[PAYOUT_SIZES.len() - 1]
+        );
+    };
+    if !payout_artifacts_exist(artifacts_dir, GLOBAL_TRANSFER_TREE_HEIGHT, size) {
+        bail!(
+            "payout withdraw artifacts for {} payouts are missing from {}",
+            size,
+            artifacts_dir.display()
+        );
+    }
+    let registered = verifier
+        .payout_verifier(true, size as u8)
+        .await
+        .context("failed to fetch payout verifier")?;
+    if registered == Address::ZERO {
+        bail!("no payout verifier registered for {} payouts", size);
+    }
+    Ok(size)
+}
+
+/// Picks the smallest multi-leaf Groth16 circuit that fits `num_events`, provided its artifacts
+/// are available locally and a verifier is registered on-chain. Otherwise the Nova batch flow is
+/// used.
+async fn multi_withdraw_size(
+    verifier: &VerifierContract,
+    artifacts_dir: &Path,
+    num_events: usize,
+) -> Result<Option<usize>> {
+    let Some(num_leaves) = MULTI_WITHDRAW_SIZES
+        .into_iter()
+        .find(|&size| size >= num_events)
+    else {
+        return Ok(None);
+    };
+    if !multi_withdraw_artifacts_exist(artifacts_dir, GLOBAL_TRANSFER_TREE_HEIGHT, num_leaves) {
+        return Ok(None);
+    }
+    let registered = verifier
+        .multi_withdraw_verifier(true, num_leaves as u8)
+        .await
+        .context("failed to fetch multi withdraw verifier")?;
+    Ok((registered != Address::ZERO).then_some(num_leaves))
+}
+
+/// Returns the part of the eligible total that stays unclaimed so that the proven total equals
+/// `total_teleported + amount`.
+fn withdraw_delta(
+    total_eligible_value: U256,
+    total_teleported: U256,
+    amount: Option<U256>,
+) -> Result<U256> {
+    let Some(amount) = amount else {
+        return Ok(U256::ZERO);
+    };
+    if amount.is_zero() {
+        bail!("redeem amount must be greater than zero");
+    }
+    let claimable = total_eligible_value - total_teleported;
+    if amount > claimable {
+        bail!(
+            "redeem amount {} exceeds claimable value {}",
+            amount,
+            claimable
+        );
+    }
+    Ok(claimable - amount)
+}
+
+/// The verifier pays the fee out of the newly minted amount and needs a relayer to pay it to.
+fn check_relayer_fee(options: &TeleportOptions, minted: U256) -> Result<()> {
+    if options.fee.is_zero() {
+        return Ok(());
+    }
+    if options.relayer == Address::ZERO {
+        bail!("a relayer fee requires a non-zero relayer address");
+    }
+    if options.fee > minted {
+        bail!(
+            "relayer fee {} exceeds redeemed amount {}",
+            options.fee,
+            minted
+        );
+    }
+    Ok(())
+}
+
 pub fn print_events(chain_id: u64, events: &EventsWithEligibility) {
     println!("Chain ID {}:", chain_id);
     println!(
@@ -12,12 +12,13 @@ use anyhow::{Context, Result, bail};
 use clap::{Args, Parser, Subcommand};
 use client_common::{
     indexer::HttpIndexerClient,
+    payment::payout::Payout,
     prover::HttpDeciderClient,
     tokens::{HubEntry, TokenEntry, TokensFile},
 };
 use commands::{
     invoice, private_transfer, receive_transfer, scan_receive_transfers,
-    shared::{parse_address, parse_b256, parse_u256},
+    shared::{parse_address, parse_b256, parse_payout, parse_u256},
     transfer,
 };
 use hex;
@@ -84,6 +85,11 @@ pub struct CommonArgs {
     /// Directory containing Nova prover artifacts (defaults to workspace nova_artifacts/).
     #[arg(long, env = "NOVA_ARTIFACTS_DIR", value_name = "PATH")]
     pub nova_artifacts_dir: Option<PathBuf>,
+
+    /// Directory for batch withdraw IVC checkpoints; an interrupted batch redemption resumes
+    /// from its last proven step. Checkpoints contain burn secrets.
+    #[arg(long, env = "IVC_CHECKPOINT_DIR", value_name = "PATH")]
+    pub ivc_checkpoint_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -195,6 +201,29 @@ pub struct ReceiveTransferArgs {
         default_value = "output.json"
     )]
     pub scan_results_path: Option<PathBuf>,
+
+    /// Amount to redeem (accepts decimal or 0x-prefixed hex units). Defaults to all eligible value.
+    #[arg(long, value_parser = parse_u256)]
+    pub amount: Option<U256>,
+
+    /// Relayer address bound into the proof and paid `--fee` out of the redeemed amount.
+    #[arg(long, value_parser = parse_address, value_name = "ADDRESS")]
+    pub relayer: Option<Address>,
+
+    /// Relayer fee (accepts decimal or 0x-prefixed hex units). Requires `--relayer`.
+    #[arg(long, value_parser = parse_u256, requires = "relayer")]
+    pub fee: Option<U256>,
+
+    /// Splits the redeemed amount between addresses instead of minting it to the recipient,
+    /// repeatable as `--payout ADDRESS:AMOUNT` (amounts in decimal or 0x-prefixed hex units).
+    /// The redeemed amount becomes the payout total plus `--fee`.
+    #[arg(
+        long = "payout",
+        value_parser = parse_payout,
+        value_name = "ADDRESS:AMOUNT",
+        conflicts_with = "amount"
+    )]
+    pub payouts: Vec<Payout>,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -232,6 +261,29 @@ pub struct InvoiceReceiveArgs {
     /// Invoice identifier to redeem.
     #[arg(long, value_name = "INVOICE_ID", value_parser = parse_b256)]
     pub invoice_id: B256,
+
+    /// Amount to redeem (accepts decimal or 0x-prefixed hex units). Defaults to all eligible value.
+    #[arg(long, value_parser = parse_u256)]
+    pub amount: Option<U256>,
+
+    /// Relayer address bound into the proof and paid `--fee` out of the redeemed amount.
+    #[arg(long, value_parser = parse_address, value_name = "ADDRESS")]
+    pub relayer: Option<Address>,
+
+    /// Relayer fee (accepts decimal or 0x-prefixed hex units). Requires `--relayer`.
+    #[arg(long, value_parser = parse_u256, requires = "relayer")]
+    pub fee: Option<U256>,
+
+    /// Splits the redeemed amount between addresses instead of minting it to the recipient,
+    /// repeatable as `--payout ADDRESS:AMOUNT` (amounts in decimal or 0x-prefixed hex units).
+    /// The redeemed amount becomes the payout total plus `--fee`.
+    #[arg(
+        long = "payout",
+        value_parser = parse_payout,
+        value_name = "ADDRESS:AMOUNT",
+        conflicts_with = "amount"
+    )]
+    pub payouts: Vec<Payout>,
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -5,27 +5,43 @@ use ark_bn254::Fr;
 use ark_ff::AdditiveGroup;
 use ark_ff::Zero;
 use ark_serialize::CanonicalSerialize;
-use client_common::{indexer::IndexedEvent, prover::DeciderClient};
-use folding_schemes::FoldingScheme;
+use client_common::{
+    indexer::IndexedEvent,
+    prover::{DeciderClient, verify_decider_proof},
+};
 use rand::Rng;
-use std::{fs, path::Path};
+use std::{
+    fs::OpenOptions,
+    io::{ErrorKind, Write as _},
+    path::Path,
+};
 use zkp::nova::constants::GLOBAL_TRANSFER_TREE_HEIGHT;
 use zkp::nova::constants::TRANSFER_TREE_HEIGHT;
 use zkp::{
+    artifacts::ArtifactDir,
     nova::{
         params::NovaParams,
-        withdraw_nova::{WithdrawCircuit, WithdrawExternalInputs, dummy_withdraw_ext_input},
+        withdraw_nova::{
+            WithdrawCircuit, WithdrawCircuitParams, WithdrawExternalInputs,
+            dummy_withdraw_ext_input,
+        },
+        withdraw_session::WithdrawSession,
     },
     utils::{
-        convertion::u256_to_fr, poseidon::utils::circom_poseidon_config,
+        convertion::{address_to_fr, fr_to_b256, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
         tree::merkle_tree::MerkleProof,
     },
 };
 
-use crate::commands::invoice::NUM_BATCH_INVOICES;
+use crate::{commands::invoice::NUM_BATCH_INVOICES, proof::TeleportOptions};
 
+/// Proves a batch withdraw of `events`. When `checkpoint_dir` is set, the IVC session is
+/// checkpointed there after every step and an interrupted redemption of the same leaves resumes
+/// from its last proven step.
 pub async fn batch_teleport_proof<const DEPTH: usize>(
     artifacts_dir: &Path,
+    checkpoint_dir: Option<&Path>,
     decider: &dyn DeciderClient,
     recipient: Fr,
     merkle_root: U256,
@@ -33,7 +49,13 @@ pub async fn batch_teleport_proof<const DEPTH: usize>(
     merkle_proofs: &[MerkleProof],
     leaf_indices: &[u64],
     secrets: &[Fr],
+    options: TeleportOptions,
 ) -> anyhow::Result<Vec<u8>> {
+    let TeleportOptions {
+        delta,
+        relayer,
+        fee,
+    } = options;
     if events.len() != merkle_proofs.len()
         || events.len() != leaf_indices.len()
         || events.len() != secrets.len()
@@ -47,10 +69,26 @@ pub async fn batch_teleport_proof<const DEPTH: usize>(
         );
     }
 
+    let total_value = events.iter().map(|event| event.value).sum::<U256>();
+    if delta > total_value {
+        anyhow::bail!(
+            "withdraw delta {} exceeds total leaf value {}",
+            delta,
+            total_value
+        );
+    }
+    if fee > total_value - delta {
+        anyhow::bail!(
+            "relayer fee {} exceeds withdraw value {}",
+            fee,
+            total_value - delta
+        );
+    }
+
     let nova_params = load_withdraw_params::<DEPTH>(artifacts_dir)
         .context("failed to load batch withdraw Nova params")?;
 
-    let mut external_inputs = Vec::new();
+    let mut leaves = Vec::new();
 
     for i in 0..events.len() {
         let event = &events[i];
@@ -61,40 +99,79 @@ pub async fn batch_teleport_proof<const DEPTH: usize>(
             merkle_proof.siblings.clone().try_into().map_err(|_| {
                 anyhow::anyhow!("invalid number of siblings in global Merkle proof")
             })?;
-        external_inputs.push(WithdrawExternalInputs::<Fr, DEPTH> {
+        leaves.push(WithdrawExternalInputs::<Fr, DEPTH> {
             is_dummy: Fr::ZERO,
             value: u256_to_fr(event.value),
             secret,
             leaf_index: Fr::from(leaf_index),
             siblings,
+            association_siblings: [Fr::ZERO; DEPTH],
         });
     }
 
-    // add dummy steps
-    let mut rng = rand::thread_rng();
-    let num_dummy_steps = rng.gen_range(1..NUM_BATCH_INVOICES);
-    let offset = (1u64 << DEPTH) - 1 - num_dummy_steps as u64;
-    for i in 0..num_dummy_steps {
-        let index = offset + i as u64;
-        let dummy_input = dummy_withdraw_ext_input::<DEPTH>(index, U256::ZERO);
-        external_inputs.push(dummy_input);
-    }
-
-    log::info!(
-        "Start IVC proof generation for batch withdraw with {} events and {} dummy steps (total {})",
-        events.len(),
-        num_dummy_steps,
-        external_inputs.len()
+    let z0 = initial_state(
+        u256_to_fr(merkle_root),
+        recipient,
+        address_to_fr(relayer),
+        u256_to_fr(fee),
     );
-    let mut nova = nova_params
-        .initial_nova(initial_state(u256_to_fr(merkle_root), recipient))
-        .context("failed to initialize batch withdraw Nova")?;
+    let checkpoint_path = match checkpoint_dir {
+        Some(dir) => Some(dir.join(format!(
+            "{}_{}.ckpt",
+            withdraw_prefix::<DEPTH>()?,
+            fr_to_b256(recipient)
+        ))),
+        None => None,
+    };
 
-    for external_input in external_inputs {
-        nova.prove_step(&mut rng, external_input, None)
+    let mut rng = rand::thread_rng();
+    let resumed = checkpoint_path
+        .as_deref()
+        .and_then(|path| resume_session(&nova_params, path, &z0, &leaves, u256_to_fr(delta)));
+    let mut session = match resumed {
+        Some(session) => {
+            log::info!(
+                "Resuming batch withdraw IVC proof at step {} of {}",
+                session.steps_done(),
+                session.total_steps()
+            );
+            session
+        }
+        None => {
+            // add dummy steps; the first one subtracts `delta` from the running total so that
+            // only the requested amount is withdrawn
+            let mut external_inputs = leaves;
+            let num_dummy_steps = rng.gen_range(1..NUM_BATCH_INVOICES);
+            let offset = (1u64 << DEPTH) - 1 - num_dummy_steps as u64;
+            for i in 0..num_dummy_steps {
+                let index = offset + i as u64;
+                let dummy_value = if i == 0 { delta } else { U256::ZERO };
+                let dummy_input = dummy_withdraw_ext_input::<DEPTH>(index, dummy_value);
+                external_inputs.push(dummy_input);
+            }
+            log::info!(
+                "Start IVC proof generation for batch withdraw with {} events and {} dummy steps (total {})",
+                events.len(),
+                num_dummy_steps,
+                external_inputs.len()
+            );
+            WithdrawSession::new(&nova_params, z0.clone(), external_inputs)
+                .context("invalid batch withdraw witness")?
+        }
+    };
+
+    while !session.is_complete() {
+        session
+            .prove_next_step(&mut rng)
             .context("failed to prove step in batch withdraw Nova")?;
+        if let Some(Err(err)) = checkpoint_path
+            .as_deref()
+            .map(|path| write_checkpoint(path, &session))
+        {
+            log::warn!("failed to write batch withdraw checkpoint: {err:#}");
+        }
     }
-    let ivc_proof = nova.ivc_proof();
+    let ivc_proof = session.ivc_proof();
     nova_params
         .verify(ivc_proof.clone())
         .context("failed to verify batch withdraw Nova proof")?;
@@ -121,28 +198,141 @@ pub async fn batch_teleport_proof<const DEPTH: usize>(
         .await
         .context("failed to produce decider proof for batch withdraw")?;
     log::info!("Decider proof for batch withdraw produced");
+
+    let artifacts = ArtifactDir::open(artifacts_dir)?;
+    // an unverified proof would only be found invalid on-chain, after paying for the transaction
+    let decider_vp_name = format!("{}_decider_vp.bin", withdraw_prefix::<DEPTH>()?);
+    let decider_vp = artifacts
+        .read(&decider_vp_name)
+        .with_context(|| format!("failed to load {decider_vp_name}"))?;
+    verify_decider_proof(
+        circuit_kind,
+        DEPTH,
+        &decider_vp,
+        artifacts.manifest().compression,
+        &z0,
+        &session.state(),
+        &decider_proof,
+    )
+    .context("decider prover returned an invalid batch withdraw proof")?;
+    log::info!("Decider proof for batch withdraw verified");
+    if let Some(Err(err)) = checkpoint_path.as_deref().map(std::fs::remove_file) {
+        log::warn!("failed to remove batch withdraw checkpoint: {err}");
+    }
     Ok(decider_proof)
 }
 
+/// Resumes the session checkpointed at `path` if it proves the same leaves, `z0` and `delta` as
+/// the current redemption. Missing, unreadable or stale checkpoints yield `None` so that the
+/// caller starts over.
+fn resume_session<const DEPTH: usize>(
+    nova_params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+    path: &Path,
+    z0: &[Fr],
+    leaves: &[WithdrawExternalInputs<Fr, DEPTH>],
+    delta: Fr,
+) -> Option<WithdrawSession<DEPTH>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return None,
+        Err(err) => {
+            log::warn!(
+                "failed to read batch withdraw checkpoint {}: {err}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    let session = match WithdrawSession::resume(nova_params, &bytes) {
+        Ok(session) => session,
+        Err(err) => {
+            log::warn!(
+                "ignoring unusable batch withdraw checkpoint {}: {err}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    let same_redemption = session.z_0() == z0
+        && session.steps().starts_with(leaves)
+        && session
+            .steps()
+            .get(leaves.len())
+            .is_some_and(|first_dummy| first_dummy.value == delta);
+    if !same_redemption {
+        log::info!(
+            "batch withdraw checkpoint {} belongs to a different redemption; starting over",
+            path.display()
+        );
+        return None;
+    }
+    Some(session)
+}
+
+/// Replaces the checkpoint at `path` atomically, so that an interruption while writing leaves
+/// the previous checkpoint intact. The file holds burn secrets and is created owner-only.
+fn write_checkpoint<const DEPTH: usize>(
+    path: &Path,
+    session: &WithdrawSession<DEPTH>,
+) -> anyhow::Result<()> {
+    let bytes = session.checkpoint()?;
+    let tmp_path = path.with_extension("ckpt.tmp");
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    };
+    write().with_context(|| format!("failed to write {}", path.display()))
+}
+
 pub fn load_withdraw_params<const DEPTH: usize>(
     artifacts_dir: &Path,
 ) -> anyhow::Result<NovaParams<WithdrawCircuit<Fr, DEPTH>>> {
-    let prefix = match DEPTH {
-        TRANSFER_TREE_HEIGHT => "withdraw_local",
-        GLOBAL_TRANSFER_TREE_HEIGHT => "withdraw_global",
+    let prefix = withdraw_prefix::<DEPTH>()?;
+    let artifacts = ArtifactDir::open(artifacts_dir)?;
+    let f_params = WithdrawCircuitParams::new(
+        circom_poseidon_config::<Fr>(),
+        artifacts.manifest().pow_difficulty,
+    );
+    let pp = artifacts.map(&format!("{}_nova_pp.bin", prefix))?;
+    let vp = artifacts.map(&format!("{}_nova_vp.bin", prefix))?;
+    NovaParams::from_readers(
+        f_params,
+        &pp[..],
+        &vp[..],
+        artifacts.compress(),
+        artifacts.validate(),
+    )
+    .map_err(|err| anyhow!("failed to deserialize withdraw nova params: {}", err))
+}
+
+fn withdraw_prefix<const DEPTH: usize>() -> anyhow::Result<&'static str> {
+    match DEPTH {
+        TRANSFER_TREE_HEIGHT => Ok("withdraw_local"),
+        GLOBAL_TRANSFER_TREE_HEIGHT => Ok("withdraw_global"),
         _ => {
             anyhow::bail!("Unsupported transfer tree depth: {}", DEPTH)
         }
-    };
-    let poseidon_params = circom_poseidon_config::<Fr>();
-    let pp = fs::read(artifacts_dir.join(format!("{}_nova_pp.bin", prefix)))
-        .with_context(|| format!("failed to read {}_nova_pp.bin", prefix))?;
-    let vp = fs::read(artifacts_dir.join(format!("{}_nova_vp.bin", prefix)))
-        .with_context(|| format!("failed to read {}_nova_vp.bin", prefix))?;
-    NovaParams::from_bytes(poseidon_params, pp, vp)
-        .map_err(|err| anyhow!("failed to deserialize withdraw nova params: {}", err))
+    }
 }
 
-fn initial_state(root: Fr, recipient: Fr) -> Vec<Fr> {
-    vec![root, recipient, Fr::zero(), Fr::zero()]
+/// The CLI does not prove association-set membership, so the association root stays zero.
+fn initial_state(root: Fr, recipient: Fr, relayer: Fr, fee: Fr) -> Vec<Fr> {
+    vec![
+        root,
+        recipient,
+        Fr::zero(),
+        Fr::zero(),
+        relayer,
+        fee,
+        Fr::zero(),
+    ]
 }
@@ -1,2 +1,16 @@
+use alloy::primitives::{Address, U256};
+
 pub mod batch;
+pub mod multi;
+pub mod payout;
 pub mod single;
+
+/// Withdraw parameters bound into a teleport proof besides the root and recipient.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TeleportOptions {
+    /// Part of the proven leaf value that is left unclaimed.
+    pub delta: U256,
+    /// Address paid `fee` out of the minted amount; zero when the recipient submits.
+    pub relayer: Address,
+    pub fee: U256,
+}
@@ -0,0 +1,178 @@
+use alloy::primitives::U256;
+use anyhow::Context as _;
+use ark_bn254::Fr;
+use ark_ff::Zero;
+use client_common::indexer::IndexedEvent;
+use rand::rngs::OsRng;
+use std::path::Path;
+use zkp::{
+    artifacts::ArtifactDir,
+    groth16::{
+        multi_withdraw::{MultiWithdrawCircuit, MultiWithdrawLeaf},
+        params::Groth16Params,
+    },
+    nova::constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+    utils::{
+        convertion::{address_to_fr, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::merkle_tree::MerkleProof,
+    },
+};
+
+use crate::proof::TeleportOptions;
+
+/// Proves up to `N` leaves with a single Groth16 proof. Unused slots are filled with dummy
+/// leaves at the top of the index range, so `leaf_indices` must lie below them.
+pub fn multi_teleport_proof<const DEPTH: usize, const N: usize>(
+    artifacts_dir: &Path,
+    recipient: Fr,
+    merkle_root: U256,
+    events: &[IndexedEvent],
+    merkle_proofs: &[MerkleProof],
+    leaf_indices: &[u64],
+    secrets: &[Fr],
+    options: TeleportOptions,
+) -> anyhow::Result<Vec<u8>> {
+    let TeleportOptions {
+        delta,
+        relayer,
+        fee,
+    } = options;
+    let artifacts = ArtifactDir::open(artifacts_dir)?;
+    let mut circuit = MultiWithdrawCircuit::<Fr, DEPTH, N>::new(
+        circom_poseidon_config(),
+        artifacts.manifest().pow_difficulty,
+    );
+    fill_withdraw_leaves(
+        &mut circuit.leaves,
+        events,
+        merkle_proofs,
+        leaf_indices,
+        secrets,
+    )?;
+
+    let total_value = events.iter().map(|event| event.value).sum::<U256>();
+    if delta > total_value {
+        anyhow::bail!(
+            "withdraw delta {} exceeds total leaf value {}",
+            delta,
+            total_value
+        );
+    }
+    if fee > total_value - delta {
+        anyhow::bail!(
+            "relayer fee {} exceeds withdraw value {}",
+            fee,
+            total_value - delta
+        );
+    }
+
+    circuit.merkle_root = Some(u256_to_fr(merkle_root));
+    circuit.recipient = Some(recipient);
+    circuit.withdraw_value = Some(u256_to_fr(total_value - delta));
+    circuit.relayer = Some(address_to_fr(relayer));
+    circuit.fee = Some(u256_to_fr(fee));
+    circuit.association_root = Some(Fr::zero());
+    circuit.delta = Some(u256_to_fr(delta));
+
+    let withdraw_params = load_multi_withdraw_params(&artifacts, DEPTH, N)
+        .context("failed to load multi withdraw Groth16 params")?;
+    let public_inputs = circuit.public_inputs()?;
+
+    log::info!(
+        "Start Groth16 proof generation for {}-leaf withdraw with {} events",
+        N,
+        events.len()
+    );
+    let proof = withdraw_params
+        .generate_proof(&mut OsRng, circuit, &public_inputs)
+        .context("failed to create multi teleport Groth16 proof")?;
+    log::info!("Multi withdraw Groth16 proof generated");
+    Ok(proof)
+}
+
+/// Fills `leaves` with the given events ordered by leaf index, as the multi-leaf circuits
+/// require, and pads the remaining slots with dummy leaves at the top of the index range.
+pub(crate) fn fill_withdraw_leaves<const DEPTH: usize>(
+    leaves: &mut [MultiWithdrawLeaf<Fr, DEPTH>],
+    events: &[IndexedEvent],
+    merkle_proofs: &[MerkleProof],
+    leaf_indices: &[u64],
+    secrets: &[Fr],
+) -> anyhow::Result<()> {
+    if events.len() != merkle_proofs.len()
+        || events.len() != leaf_indices.len()
+        || events.len() != secrets.len()
+    {
+        anyhow::bail!(
+            "Mismatched lengths: events {}, merkle_proofs {}, leaf_indices {}, secrets {}",
+            events.len(),
+            merkle_proofs.len(),
+            leaf_indices.len(),
+            secrets.len()
+        );
+    }
+    if events.len() > leaves.len() {
+        anyhow::bail!(
+            "{} events do not fit a {}-leaf withdraw",
+            events.len(),
+            leaves.len()
+        );
+    }
+
+    // the circuit requires strictly increasing leaf indices
+    let mut order = (0..events.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| leaf_indices[i]);
+    for (slot, &i) in order.iter().enumerate() {
+        let siblings: [Fr; DEPTH] =
+            merkle_proofs[i].siblings.clone().try_into().map_err(|_| {
+                anyhow::anyhow!("invalid number of siblings in global Merkle proof")
+            })?;
+        leaves[slot] = MultiWithdrawLeaf::new(
+            u256_to_fr(events[i].value),
+            secrets[i],
+            leaf_indices[i],
+            siblings,
+        );
+    }
+    let num_dummies = leaves.len() - events.len();
+    let offset = (1u64 << DEPTH) - num_dummies as u64;
+    for i in 0..num_dummies {
+        leaves[events.len() + i] = MultiWithdrawLeaf::dummy(offset + i as u64);
+    }
+    Ok(())
+}
+
+pub fn multi_withdraw_artifacts_exist(artifacts_dir: &Path, depth: usize, n: usize) -> bool {
+    let Ok(artifacts) = ArtifactDir::open(artifacts_dir) else {
+        return false;
+    };
+    multi_withdraw_prefix(depth, n)
+        .map(|prefix| artifacts.contains(&format!("{}_groth16_pk.bin", prefix)))
+        .unwrap_or(false)
+}
+
+pub fn load_multi_withdraw_params(
+    artifacts: &ArtifactDir,
+    depth: usize,
+    n: usize,
+) -> anyhow::Result<Groth16Params> {
+    let prefix = multi_withdraw_prefix(depth, n)?;
+    let pk = artifacts.map(&format!("{}_groth16_pk.bin", prefix))?;
+    let vk = artifacts.map(&format!("{}_groth16_vk.bin", prefix))?;
+    let params =
+        Groth16Params::from_readers(&pk[..], &vk[..], artifacts.compress(), artifacts.validate())
+            .with_context(|| format!("failed to parse {} Groth16 params", prefix))?;
+    Ok(params)
+}
+
+fn multi_withdraw_prefix(depth: usize, n: usize) -> anyhow::Result<String> {
+    let tree = match depth {
+        TRANSFER_TREE_HEIGHT => "local",
+        GLOBAL_TRANSFER_TREE_HEIGHT => "global",
+        _ => {
+            anyhow::bail!("Unsupported transfer tree depth: {}", depth)
+        }
+    };
+    Ok(format!("withdraw_{}_multi{}", tree, n))
+}
@@ -0,0 +1,133 @@
+use alloy::primitives::U256;
+use anyhow::Context as _;
+use ark_bn254::Fr;
+use ark_ff::Zero;
+use client_common::{indexer::IndexedEvent, payment::payout::PayoutSplit};
+use rand::rngs::OsRng;
+use std::path::Path;
+use zkp::{
+    artifacts::ArtifactDir,
+    groth16::{
+        params::Groth16Params,
+        payout::{PAYOUT_LEAVES, PayoutSlot, PayoutWithdrawCircuit, payout_artifact_prefix},
+    },
+    utils::{
+        convertion::{address_to_fr, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::merkle_tree::MerkleProof,
+    },
+};
+
+use crate::proof::{TeleportOptions, multi::fill_withdraw_leaves};
+
+/// Proves up to `PAYOUT_LEAVES` leaves and commits to `split`, which must fit the `M` payout
+/// slots. `teleported_before` is the recipient's `totalTeleported` the proof is bound to; the
+/// split plus `options.fee` must add up to the value minted on top of it.
+pub fn payout_teleport_proof<const DEPTH: usize, const M: usize>(
+    artifacts_dir: &Path,
+    recipient: Fr,
+    merkle_root: U256,
+    events: &[IndexedEvent],
+    merkle_proofs: &[MerkleProof],
+    leaf_indices: &[u64],
+    secrets: &[Fr],
+    options: TeleportOptions,
+    teleported_before: U256,
+    split: &PayoutSplit,
+) -> anyhow::Result<Vec<u8>> {
+    let TeleportOptions {
+        delta,
+        relayer,
+        fee,
+    } = options;
+    if split.len() > M {
+        anyhow::bail!("{} payouts do not fit a {}-payout withdraw", split.len(), M);
+    }
+
+    let artifacts = ArtifactDir::open(artifacts_dir)?;
+    let mut circuit = PayoutWithdrawCircuit::<Fr, DEPTH, PAYOUT_LEAVES, M>::new(
+        circom_poseidon_config(),
+        artifacts.manifest().pow_difficulty,
+    );
+    fill_withdraw_leaves(
+        &mut circuit.leaves,
+        events,
+        merkle_proofs,
+        leaf_indices,
+        secrets,
+    )?;
+
+    let total_value = events.iter().map(|event| event.value).sum::<U256>();
+    if delta > total_value {
+        anyhow::bail!(
+            "withdraw delta {} exceeds total leaf value {}",
+            delta,
+            total_value
+        );
+    }
+    let withdraw_value = total_value - delta;
+    if teleported_before + split.total() + fee != withdraw_value {
+        anyhow::bail!(
+            "payouts {} plus fee {} do not add up to the redeemed amount {}",
+            split.total(),
+            fee,
+            withdraw_value.saturating_sub(teleported_before)
+        );
+    }
+
+    let slots = split.to_fields();
+    for (slot, payout) in circuit.payouts.iter_mut().enumerate() {
+        *payout = match slots.get(slot) {
+            Some(&(recipient, amount)) => PayoutSlot::new(recipient, amount),
+            None => PayoutSlot::empty(),
+        };
+    }
+    circuit.merkle_root = Some(u256_to_fr(merkle_root));
+    circuit.recipient = Some(recipient);
+    circuit.withdraw_value = Some(u256_to_fr(withdraw_value));
+    circuit.relayer = Some(address_to_fr(relayer));
+    circuit.fee = Some(u256_to_fr(fee));
+    circuit.association_root = Some(Fr::zero());
+    circuit.teleported_before = Some(u256_to_fr(teleported_before));
+    circuit.payout_commitment = Some(u256_to_fr(split.commitment()));
+    circuit.delta = Some(u256_to_fr(delta));
+
+    let payout_params = load_payout_params(&artifacts, DEPTH, M)
+        .context("failed to load payout withdraw Groth16 params")?;
+    let public_inputs = circuit.public_inputs()?;
+
+    log::info!(
+        "Start Groth16 proof generation for {}-payout withdraw with {} events",
+        M,
+        events.len()
+    );
+    let proof = payout_params
+        .generate_proof(&mut OsRng, circuit, &public_inputs)
+        .context("failed to create payout teleport Groth16 proof")?;
+    log::info!("Payout withdraw Groth16 proof generated");
+    Ok(proof)
+}
+
+pub fn payout_artifacts_exist(artifacts_dir: &Path, depth: usize, num_payouts: usize) -> bool {
+    let Ok(artifacts) = ArtifactDir::open(artifacts_dir) else {
+        return false;
+    };
+    payout_artifact_prefix(depth, num_payouts)
+        .map(|prefix| artifacts.contains(&format!("{}_groth16_pk.bin", prefix)))
+        .unwrap_or(false)
+}
+
+pub fn load_payout_params(
+    artifacts: &ArtifactDir,
+    depth: usize,
+    num_payouts: usize,
+) -> anyhow::Result<Groth16Params> {
+    let prefix = payout_artifact_prefix(depth, num_payouts)
+        .with_context(|| format!("Unsupported transfer tree depth: {}", depth))?;
+    let pk = artifacts.map(&format!("{}_groth16_pk.bin", prefix))?;
+    let vk = artifacts.map(&format!("{}_groth16_vk.bin", prefix))?;
+    let params =
+        Groth16Params::from_readers(&pk[..], &vk[..], artifacts.compress(), artifacts.validate())
+            .with_context(|| format!("failed to parse {} Groth16 params", prefix))?;
+    Ok(params)
+}
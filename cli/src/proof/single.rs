@@ -1,19 +1,24 @@
 use alloy::primitives::U256;
 use anyhow::Context as _;
 use ark_bn254::Fr;
-use ark_ff::fields::AdditiveGroup;
+use ark_ff::Zero;
 use client_common::indexer::IndexedEvent;
 use rand::rngs::OsRng;
-use std::{fs, path::Path};
+use std::path::Path;
 use zkp::{
+    artifacts::ArtifactDir,
     groth16::{params::Groth16Params, withdraw::SingleWithdrawCircuit},
     nova::constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
     utils::{
-        convertion::u256_to_fr, poseidon::utils::circom_poseidon_config,
+        convertion::{address_to_fr, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
         tree::merkle_tree::MerkleProof,
     },
+    witness::validate_single_withdraw,
 };
 
+use crate::proof::TeleportOptions;
+
 pub fn single_teleport_proof<const DEPTH: usize>(
     artifacts_dir: &Path,
     recipient: Fr,
@@ -22,12 +27,34 @@ pub fn single_teleport_proof<const DEPTH: usize>(
     merkle_proof: MerkleProof,
     leaf_index: u64,
     secret: Fr,
+    options: TeleportOptions,
 ) -> anyhow::Result<Vec<u8>> {
-    let withdraw_params = load_single_withdraw_params(artifacts_dir, DEPTH)
+    let TeleportOptions {
+        delta,
+        relayer,
+        fee,
+    } = options;
+    if delta > event.value {
+        anyhow::bail!(
+            "withdraw delta {} exceeds leaf value {}",
+            delta,
+            event.value
+        );
+    }
+    if fee > event.value - delta {
+        anyhow::bail!(
+            "relayer fee {} exceeds withdraw value {}",
+            fee,
+            event.value - delta
+        );
+    }
+    let artifacts = ArtifactDir::open(artifacts_dir)?;
+    let withdraw_params = load_single_withdraw_params(&artifacts, DEPTH)
         .context("failed to load single withdraw Groth16 params")?;
     let poseidon_params = circom_poseidon_config();
     let merkle_root = u256_to_fr(merkle_root);
     let value = u256_to_fr(event.value);
+    let withdraw_value = u256_to_fr(event.value - delta);
     let siblings: [Option<Fr>; DEPTH] = merkle_proof
         .siblings
         .into_iter()
@@ -37,15 +64,21 @@ pub fn single_teleport_proof<const DEPTH: usize>(
         .map_err(|_| anyhow::anyhow!("invalid number of siblings in global Merkle proof"))?;
     let circuit = SingleWithdrawCircuit::<Fr, DEPTH> {
         poseidon_params,
+        pow_difficulty: artifacts.manifest().pow_difficulty,
         merkle_root: Some(merkle_root),
         recipient: Some(recipient),
-        withdraw_value: Some(value),
+        withdraw_value: Some(withdraw_value),
+        relayer: Some(address_to_fr(relayer)),
+        fee: Some(u256_to_fr(fee)),
+        association_root: Some(Fr::zero()),
         value: Some(value),
-        delta: Some(Fr::ZERO),
+        delta: Some(u256_to_fr(delta)),
         secret: Some(secret),
         leaf_index: Some(leaf_index),
         siblings,
+        association_siblings: [Some(Fr::zero()); DEPTH],
     };
+    validate_single_withdraw(&circuit).context("invalid single withdraw witness")?;
     let public_inputs = circuit.public_inputs()?;
 
     log::info!("Start Groth16 proof generation for single withdraw",);
@@ -57,7 +90,7 @@ pub fn single_teleport_proof<const DEPTH: usize>(
 }
 
 pub fn load_single_withdraw_params(
-    artifacts_dir: &Path,
+    artifacts: &ArtifactDir,
     depth: usize,
 ) -> anyhow::Result<Groth16Params> {
     let prefix = match depth {
@@ -67,11 +100,10 @@ pub fn load_single_withdraw_params(
             anyhow::bail!("Unsupported transfer tree depth: {}", depth)
         }
     };
-    let pk = fs::read(artifacts_dir.join(format!("{}_groth16_pk.bin", prefix)))
-        .with_context(|| format!("failed to read {}_groth16_pp.bin", prefix))?;
-    let vk = fs::read(artifacts_dir.join(format!("{}_groth16_vk.bin", prefix)))
-        .with_context(|| format!("failed to read {}_groth16_vp.bin", prefix))?;
-    let params = Groth16Params::from_bytes(pk, vk)
-        .with_context(|| format!("failed to parse {} Groth16 params", prefix))?;
+    let pk = artifacts.map(&format!("{}_groth16_pk.bin", prefix))?;
+    let vk = artifacts.map(&format!("{}_groth16_vk.bin", prefix))?;
+    let params =
+        Groth16Params::from_readers(&pk[..], &vk[..], artifacts.compress(), artifacts.validate())
+            .with_context(|| format!("failed to parse {} Groth16 params", prefix))?;
     Ok(params)
 }
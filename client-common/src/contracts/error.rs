@@ -1,6 +1,7 @@
 use alloy::contract;
 use std::error::Error as StdError;
 use thiserror::Error;
+use zkp::utils::general_recipient::RecipientFamily;
 
 #[derive(Debug, Error)]
 pub enum ContractError {
@@ -14,6 +15,8 @@ pub enum ContractError {
     },
     #[error("event `{0}` not found in transaction logs")]
     MissingEvent(&'static str),
+    #[error("{0} recipients cannot be paid out by the EVM verifier")]
+    UnsupportedRecipient(RecipientFamily),
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -5,11 +5,14 @@ use alloy::{
     rpc::types::TransactionReceipt,
     sol,
 };
-use zkp::utils::general_recipient::GeneralRecipient;
-
-use crate::contracts::{
-    ContractError, ContractResult,
-    utils::{NormalProvider, get_provider_with_signer, send_call_with_legacy},
+use zkp::utils::general_recipient::{GeneralRecipient, RecipientFamily};
+
+use crate::{
+    contracts::{
+        ContractError, ContractResult,
+        utils::{NormalProvider, get_provider_with_signer, send_call_with_legacy},
+    },
+    payment::payout::PayoutSplit,
 };
 
 sol!(
@@ -150,6 +153,20 @@ impl VerifierContract {
         Ok(root)
     }
 
+    /// Root the verifier accepted at `root_hint`, read from `globalTransferRoots` or
+    /// `provedTransferRoots`; zero if the slot is unset.
+    pub async fn accepted_transfer_root(
+        &self,
+        is_global: bool,
+        root_hint: u64,
+    ) -> ContractResult<U256> {
+        if is_global {
+            self.global_transfer_root(root_hint).await
+        } else {
+            self.proved_transfer_root(root_hint).await
+        }
+    }
+
     pub async fn total_teleported(&self, recipient: U256) -> ContractResult<U256> {
         let total = self
             .contract_with_provider()
@@ -187,6 +204,40 @@ impl VerifierContract {
         Ok(addr)
     }
 
+    pub async fn multi_withdraw_verifier(
+        &self,
+        is_global: bool,
+        num_leaves: u8,
+    ) -> ContractResult<Address> {
+        let contract = self.contract_with_provider();
+        let addr = if is_global {
+            contract
+                .multiWithdrawGlobalVerifiers(num_leaves)
+                .call()
+                .await?
+        } else {
+            contract
+                .multiWithdrawLocalVerifiers(num_leaves)
+                .call()
+                .await?
+        };
+        Ok(addr)
+    }
+
+    pub async fn payout_verifier(
+        &self,
+        is_global: bool,
+        num_payouts: u8,
+    ) -> ContractResult<Address> {
+        let contract = self.contract_with_provider();
+        let addr = if is_global {
+            contract.payoutGlobalVerifiers(num_payouts).call().await?
+        } else {
+            contract.payoutLocalVerifiers(num_payouts).call().await?
+        };
+        Ok(addr)
+    }
+
     pub async fn reserve_hash_chain(
         &self,
         private_key: B256,
@@ -291,7 +342,7 @@ impl VerifierContract {
             .teleport(
                 is_global,
                 root_hint,
-                gr_to_contract(gr),
+                gr_to_contract(gr)?,
                 Bytes::copy_from_slice(proof),
             )
             .with_cloned_provider();
@@ -312,7 +363,65 @@ impl VerifierContract {
             .singleTeleport(
                 is_global,
                 root_hint,
-                gr_to_contract(gr),
+                gr_to_contract(gr)?,
+                Bytes::copy_from_slice(proof),
+            )
+            .with_cloned_provider();
+        send_call_with_legacy(call, &signer, self.legacy_tx).await
+    }
+
+    pub async fn multi_teleport(
+        &self,
+        private_key: B256,
+        is_global: bool,
+        num_leaves: u8,
+        root_hint: u64,
+        gr: GeneralRecipient,
+        proof: &[u8],
+    ) -> ContractResult<PendingTransactionBuilder<Ethereum>> {
+        let signer = get_provider_with_signer(&self.provider, private_key);
+        let contract = Verifier::new(self.address, signer.clone());
+        let call = contract
+            .multiTeleport(
+                is_global,
+                num_leaves,
+                root_hint,
+                gr_to_contract(gr)?,
+                Bytes::copy_from_slice(proof),
+            )
+            .with_cloned_provider();
+        send_call_with_legacy(call, &signer, self.legacy_tx).await
+    }
+
+    /// Submits a payout withdraw proof; `split` must be the split the proof commits to.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn payout_teleport(
+        &self,
+        private_key: B256,
+        is_global: bool,
+        num_payouts: u8,
+        root_hint: u64,
+        gr: GeneralRecipient,
+        split: &PayoutSplit,
+        proof: &[u8],
+    ) -> ContractResult<PendingTransactionBuilder<Ethereum>> {
+        let signer = get_provider_with_signer(&self.provider, private_key);
+        let contract = Verifier::new(self.address, signer.clone());
+        let payouts = split
+            .payouts()
+            .iter()
+            .map(|payout| Verifier::Payout {
+                recipient: payout.recipient,
+                amount: payout.amount,
+            })
+            .collect();
+        let call = contract
+            .payoutTeleport(
+                is_global,
+                num_payouts,
+                root_hint,
+                gr_to_contract(gr)?,
+                payouts,
                 Bytes::copy_from_slice(proof),
             )
             .with_cloned_provider();
@@ -450,10 +559,14 @@ impl VerifierContract {
     }
 }
 
-fn gr_to_contract(gr: GeneralRecipient) -> GeneralRecipientLib::GeneralRecipient {
-    GeneralRecipientLib::GeneralRecipient {
+// `GeneralRecipientLib` hashes with the EVM version byte, so other families would only revert.
+fn gr_to_contract(gr: GeneralRecipient) -> ContractResult<GeneralRecipientLib::GeneralRecipient> {
+    if gr.family != RecipientFamily::Evm {
+        return Err(ContractError::UnsupportedRecipient(gr.family));
+    }
+    Ok(GeneralRecipientLib::GeneralRecipient {
         chainId: gr.chain_id,
         recipient: gr.address,
         tweak: gr.tweak,
-    }
+    })
 }
@@ -2,6 +2,7 @@ use crate::contracts::{
     ContractError, ContractResult,
     utils::{NormalProvider, get_provider_with_signer, send_call_with_legacy, uint256_as_u64},
 };
+use alloy::eips::{BlockId, BlockNumberOrTag};
 use alloy::network::Ethereum;
 use alloy::providers::{PendingTransactionBuilder, Provider};
 use alloy::sol_types::SolCall;
@@ -31,6 +32,14 @@ pub struct TeleportEvent {
     pub eth_block_number: u64,
 }
 
+/// An `IndexedTransfer` event together with the hash of the block that emitted it, so indexers
+/// can later tell whether the log was orphaned by a reorg.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedTransferLog {
+    pub event: IndexedEvent,
+    pub block_hash: B256,
+}
+
 #[derive(Clone)]
 pub struct ZErc20Contract {
     provider: NormalProvider,
@@ -99,6 +108,28 @@ impl ZErc20Contract {
         Ok(uint256_as_u64(index))
     }
 
+    /// `hashChain()` as of block `number`, so answers from different providers can be compared.
+    pub async fn hash_chain_at(&self, number: u64) -> ContractResult<U256> {
+        let contract = zERC20::new(self.address, self.provider.clone());
+        let hash_chain = contract
+            .hashChain()
+            .block(BlockId::number(number))
+            .call()
+            .await?;
+        Ok(hash_chain)
+    }
+
+    /// `index()` as of block `number`.
+    pub async fn index_at(&self, number: u64) -> ContractResult<u64> {
+        let contract = zERC20::new(self.address, self.provider.clone());
+        let index = contract
+            .index()
+            .block(BlockId::number(number))
+            .call()
+            .await?;
+        Ok(uint256_as_u64(index))
+    }
+
     pub async fn verifier(&self) -> ContractResult<Address> {
         let contract = zERC20::new(self.address, self.provider.clone());
         let addr = contract.verifier().call().await?;
@@ -191,6 +222,15 @@ impl ZErc20Contract {
         from_block: u64,
         to_block: u64,
     ) -> ContractResult<Vec<IndexedEvent>> {
+        let logs = self.get_indexed_transfer_logs(from_block, to_block).await?;
+        Ok(logs.into_iter().map(|log| log.event).collect())
+    }
+
+    pub async fn get_indexed_transfer_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> ContractResult<Vec<IndexedTransferLog>> {
         let contract = zERC20::new(self.address, self.provider.clone());
         let event_with_logs = contract
             .event_filter::<zERC20::IndexedTransfer>()
@@ -199,17 +239,20 @@ impl ZErc20Contract {
             .to_block(to_block)
             .query()
             .await?;
-        let events = event_with_logs
+        let logs = event_with_logs
             .into_iter()
-            .map(|(e, l)| IndexedEvent {
-                event_index: uint256_as_u64(e.index),
-                from: e.from,
-                to: e.to,
-                value: e.value,
-                eth_block_number: l.block_number.unwrap_or_default(),
+            .map(|(e, l)| IndexedTransferLog {
+                event: IndexedEvent {
+                    event_index: uint256_as_u64(e.index),
+                    from: e.from,
+                    to: e.to,
+                    value: e.value,
+                    eth_block_number: l.block_number.unwrap_or_default(),
+                },
+                block_hash: l.block_hash.unwrap_or_default(),
             })
             .collect();
-        Ok(events)
+        Ok(logs)
     }
 
     pub async fn get_teleport_events(
@@ -244,4 +287,25 @@ impl ZErc20Contract {
             .map_err(|err| ContractError::transport("get_block_number", err))?;
         Ok(n)
     }
+
+    /// Number of the block behind `tag`, or `None` when the node does not know it yet (e.g. no
+    /// `finalized` block right after genesis).
+    pub async fn block_number_at(&self, tag: BlockNumberOrTag) -> ContractResult<Option<u64>> {
+        let block = self
+            .provider
+            .get_block_by_number(tag)
+            .await
+            .map_err(|err| ContractError::transport("get_block_by_number", err))?;
+        Ok(block.map(|block| block.header.number))
+    }
+
+    /// Hash of the canonical block at `number`, or `None` once the chain no longer reaches it.
+    pub async fn block_hash(&self, number: u64) -> ContractResult<Option<B256>> {
+        let block = self
+            .provider
+            .get_block_by_number(number.into())
+            .await
+            .map_err(|err| ContractError::transport("get_block_by_number", err))?;
+        Ok(block.map(|block| block.header.hash))
+    }
 }
@@ -0,0 +1,434 @@
+//! Selective-disclosure packages for compliance audits. A package proves that the burns at the
+//! listed leaf indices of a transfer tree were made to burn addresses of one `GeneralRecipient`
+//! and sum to `total_value`, without revealing their secrets or any other transfer.
+//!
+//! `verify_disclosure` also ties the package to the audited mint: the caller passes the root the
+//! verifier contract accepted (`VerifierContract::accepted_transfer_root`) and the recipient and
+//! amount of the `Teleport` being audited, and the package must match all three.
+
+use alloy::primitives::{B256, Bytes, U256};
+use ark_bn254::Fr;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zkp::{
+    artifacts::ArtifactCompression,
+    groth16::{
+        disclosure::{
+            DISCLOSURE_SIZES, DisclosureCircuit, disclosure_artifact_prefix,
+            disclosure_public_inputs, prove_disclosure, verify_disclosure as verify_groth16,
+        },
+        multi_withdraw::MultiWithdrawLeaf,
+        params::{Groth16Error, Groth16Params, verifying_key_from_bytes},
+    },
+    nova::{
+        constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+        withdraw_nova::{WITHDRAW_STATE_LEN, WithdrawExternalInputs, dummy_withdraw_ext_input},
+    },
+    utils::{
+        convertion::u256_to_fr,
+        general_recipient::{GeneralRecipient, RecipientFamily},
+        poseidon::utils::circom_poseidon_config,
+        tree::merkle_tree::MerkleProof,
+    },
+    witness::{WitnessError, validate_withdraw_steps},
+};
+
+#[derive(Debug, Error)]
+pub enum DisclosureError {
+    #[error("a disclosure needs at least one burn")]
+    NoBurns,
+    #[error("{count} burns do not fit a {num_slots}-slot disclosure circuit")]
+    TooManyBurns { count: usize, num_slots: usize },
+    #[error("no disclosure circuit for tree depth {tree_depth} with {num_slots} slots")]
+    UnsupportedCircuit { tree_depth: usize, num_slots: usize },
+    #[error("burn at leaf {leaf_index}: expected {expected} siblings, got {actual}")]
+    InvalidSiblings {
+        leaf_index: u64,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("disclosed leaf indices must be strictly increasing")]
+    UnorderedLeafIndices,
+    #[error("invalid disclosure witness: {0}")]
+    Witness(#[from] WitnessError),
+    #[error("disclosed root {disclosed} is not the accepted root {accepted}")]
+    RootNotAccepted { disclosed: U256, accepted: U256 },
+    #[error("disclosed recipient does not match the minted recipient")]
+    RecipientMismatch,
+    #[error("disclosed value {disclosed} does not match the minted amount {minted}")]
+    AmountMismatch { disclosed: U256, minted: U256 },
+    #[error("disclosure proof error: {0}")]
+    Proof(#[from] Groth16Error),
+}
+
+/// A burn to disclose: its leaf in the transfer tree, the burned value and the secret its burn
+/// address was derived from.
+#[derive(Clone, Debug)]
+pub struct DisclosedBurn {
+    pub leaf_index: u64,
+    pub value: U256,
+    pub secret: Fr,
+    pub merkle_proof: MerkleProof,
+}
+
+/// The mint a package is audited against: the recipient the redeemed burns were made to and the
+/// amount they are expected to add up to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuditedMint {
+    pub recipient: GeneralRecipient,
+    pub amount: U256,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisclosurePackage {
+    pub tree_depth: usize,
+    pub num_slots: usize,
+    pub merkle_root: U256,
+    pub recipient_chain_id: u64,
+    pub recipient_address: B256,
+    pub recipient_tweak: B256,
+    /// Packages without a family predate non-EVM recipients.
+    #[serde(default)]
+    pub recipient_family: RecipientFamily,
+    pub total_value: U256,
+    /// Disclosed leaves in increasing order.
+    pub leaf_indices: Vec<u64>,
+    /// Compressed arkworks encoding of the Groth16 proof.
+    pub proof: Bytes,
+}
+
+impl DisclosurePackage {
+    pub fn recipient(&self) -> GeneralRecipient {
+        GeneralRecipient {
+            chain_id: self.recipient_chain_id,
+            address: self.recipient_address,
+            tweak: self.recipient_tweak,
+            family: self.recipient_family,
+        }
+    }
+
+    /// Whether `merkle_root` is a global root, to be looked up in `globalTransferRoots` rather
+    /// than `provedTransferRoots`.
+    pub fn is_global(&self) -> bool {
+        self.tree_depth == GLOBAL_TRANSFER_TREE_HEIGHT
+    }
+
+    /// Prefix of the `*_groth16_{pk,vk}.bin` artifacts the package is proven and verified with.
+    pub fn artifact_prefix(&self) -> Option<String> {
+        disclosure_artifact_prefix(self.tree_depth, self.num_slots)
+    }
+}
+
+/// Smallest disclosure circuit that fits `num_burns` burns. Larger sets can be split into several
+/// packages against the same root; their leaf indices must then be disjoint.
+pub fn disclosure_slots(num_burns: usize) -> Option<usize> {
+    DISCLOSURE_SIZES
+        .into_iter()
+        .find(|&num_slots| num_slots >= num_burns)
+}
+
+/// Proves that `burns` are leaves of the `tree_depth`-level tree with root `merkle_root` burned
+/// to addresses of `recipient`. `params` must be the `disclosure_{local,global}_{num_slots}` keys,
+/// generated for `pow_difficulty`.
+pub fn produce_disclosure(
+    params: &Groth16Params,
+    pow_difficulty: usize,
+    tree_depth: usize,
+    num_slots: usize,
+    merkle_root: U256,
+    recipient: GeneralRecipient,
+    burns: &[DisclosedBurn],
+) -> Result<DisclosurePackage, DisclosureError> {
+    if burns.is_empty() {
+        return Err(DisclosureError::NoBurns);
+    }
+    if burns.len() > num_slots {
+        return Err(DisclosureError::TooManyBurns {
+            count: burns.len(),
+            num_slots,
+        });
+    }
+    let mut burns = burns.to_vec();
+    burns.sort_by_key(|burn| burn.leaf_index);
+
+    let prove = match (tree_depth, num_slots) {
+        (TRANSFER_TREE_HEIGHT, 1) => prove_with::<TRANSFER_TREE_HEIGHT, 1>,
+        (TRANSFER_TREE_HEIGHT, 4) => prove_with::<TRANSFER_TREE_HEIGHT, 4>,
+        (TRANSFER_TREE_HEIGHT, 8) => prove_with::<TRANSFER_TREE_HEIGHT, 8>,
+        (GLOBAL_TRANSFER_TREE_HEIGHT, 1) => prove_with::<GLOBAL_TRANSFER_TREE_HEIGHT, 1>,
+        (GLOBAL_TRANSFER_TREE_HEIGHT, 4) => prove_with::<GLOBAL_TRANSFER_TREE_HEIGHT, 4>,
+        (GLOBAL_TRANSFER_TREE_HEIGHT, 8) => prove_with::<GLOBAL_TRANSFER_TREE_HEIGHT, 8>,
+        _ => {
+            return Err(DisclosureError::UnsupportedCircuit {
+                tree_depth,
+                num_slots,
+            });
+        }
+    };
+    let (total_value, proof) = prove(
+        params,
+        pow_difficulty,
+        u256_to_fr(merkle_root),
+        recipient.to_fr(),
+        &burns,
+    )?;
+
+    Ok(DisclosurePackage {
+        tree_depth,
+        num_slots,
+        merkle_root,
+        recipient_chain_id: recipient.chain_id,
+        recipient_address: recipient.address,
+        recipient_tweak: recipient.tweak,
+        recipient_family: recipient.family,
+        total_value,
+        leaf_indices: burns.iter().map(|burn| burn.leaf_index).collect(),
+        proof: proof.into(),
+    })
+}
+
+/// Checks `package` against the verifying key of its `artifact_prefix()`, the root the verifier
+/// contract accepted at the audited `rootHint` and the audited `mint`.
+pub fn verify_disclosure(
+    vk_bytes: &[u8],
+    compression: ArtifactCompression,
+    package: &DisclosurePackage,
+    accepted_root: U256,
+    mint: &AuditedMint,
+) -> Result<(), DisclosureError> {
+    if package.artifact_prefix().is_none() || !DISCLOSURE_SIZES.contains(&package.num_slots) {
+        return Err(DisclosureError::UnsupportedCircuit {
+            tree_depth: package.tree_depth,
+            num_slots: package.num_slots,
+        });
+    }
+    if package.leaf_indices.is_empty() {
+        return Err(DisclosureError::NoBurns);
+    }
+    if package.leaf_indices.len() > package.num_slots {
+        return Err(DisclosureError::TooManyBurns {
+            count: package.leaf_indices.len(),
+            num_slots: package.num_slots,
+        });
+    }
+    if package
+        .leaf_indices
+        .windows(2)
+        .any(|pair| pair[0] >= pair[1])
+    {
+        return Err(DisclosureError::UnorderedLeafIndices);
+    }
+    // an unset root slot reads as zero
+    if accepted_root.is_zero() || package.merkle_root != accepted_root {
+        return Err(DisclosureError::RootNotAccepted {
+            disclosed: package.merkle_root,
+            accepted: accepted_root,
+        });
+    }
+    if package.recipient() != mint.recipient {
+        return Err(DisclosureError::RecipientMismatch);
+    }
+    if package.total_value != mint.amount {
+        return Err(DisclosureError::AmountMismatch {
+            disclosed: package.total_value,
+            minted: mint.amount,
+        });
+    }
+
+    let vk = verifying_key_from_bytes(vk_bytes, compression.into())?;
+    let public_inputs = disclosure_public_inputs(
+        u256_to_fr(package.merkle_root),
+        package.recipient().to_fr(),
+        u256_to_fr(package.total_value),
+        &package.leaf_indices,
+        package.num_slots,
+    );
+    verify_groth16(&vk, &public_inputs, &package.proof)?;
+    Ok(())
+}
+
+fn prove_with<const DEPTH: usize, const N: usize>(
+    params: &Groth16Params,
+    pow_difficulty: usize,
+    merkle_root: Fr,
+    recipient: Fr,
+    burns: &[DisclosedBurn],
+) -> Result<(U256, Vec<u8>), DisclosureError> {
+    let mut steps = Vec::with_capacity(N);
+    let mut leaves = Vec::with_capacity(N);
+    for burn in burns {
+        let siblings: [Fr; DEPTH] =
+            burn.merkle_proof
+                .siblings
+                .clone()
+                .try_into()
+                .map_err(|siblings: Vec<Fr>| DisclosureError::InvalidSiblings {
+                    leaf_index: burn.leaf_index,
+                    expected: DEPTH,
+                    actual: siblings.len(),
+                })?;
+        let value = u256_to_fr(burn.value);
+        steps.push(WithdrawExternalInputs::<Fr, DEPTH> {
+            is_dummy: Fr::from(0u64),
+            value,
+            secret: burn.secret,
+            leaf_index: Fr::from(burn.leaf_index),
+            siblings,
+            association_siblings: [Fr::from(0u64); DEPTH],
+        });
+        leaves.push(MultiWithdrawLeaf::new(
+            value,
+            burn.secret,
+            burn.leaf_index,
+            siblings,
+        ));
+    }
+    // pad with zero-valued dummies at the top of the tree, after every real leaf
+    let num_dummies = N - burns.len();
+    for i in 0..num_dummies {
+        let index = (1u64 << DEPTH) - (num_dummies - i) as u64;
+        steps.push(dummy_withdraw_ext_input::<DEPTH>(index, U256::ZERO));
+        leaves.push(MultiWithdrawLeaf::dummy(index));
+    }
+
+    // the slots follow the withdraw ordering and membership rules, so reuse its witness checks
+    let mut z0 = vec![Fr::from(0u64); WITHDRAW_STATE_LEN];
+    z0[0] = merkle_root;
+    z0[1] = recipient;
+    let final_state = validate_withdraw_steps(&z0, &steps, pow_difficulty)?;
+
+    let mut circuit =
+        DisclosureCircuit::<Fr, DEPTH, N>::new(circom_poseidon_config(), pow_difficulty);
+    circuit.merkle_root = Some(merkle_root);
+    circuit.recipient = Some(recipient);
+    circuit.total_value = Some(final_state[3]);
+    for (slot, leaf) in leaves.into_iter().enumerate() {
+        circuit.leaves[slot] = leaf;
+    }
+    let public_inputs = circuit.public_inputs().map_err(|e| {
+        Groth16Error::ProofGenerationError(format!("disclosure public inputs: {e}"))
+    })?;
+    let proof = prove_disclosure(params, &mut OsRng, circuit, &public_inputs)?;
+
+    Ok((burns.iter().map(|burn| burn.value).sum(), proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AuditedMint, DisclosureError, DisclosurePackage, disclosure_slots, verify_disclosure,
+    };
+    use alloy::primitives::{B256, Bytes, U256};
+    use zkp::{artifacts::ArtifactCompression, nova::constants::GLOBAL_TRANSFER_TREE_HEIGHT};
+
+    fn sample_package(leaf_indices: Vec<u64>) -> DisclosurePackage {
+        DisclosurePackage {
+            tree_depth: GLOBAL_TRANSFER_TREE_HEIGHT,
+            num_slots: 4,
+            merkle_root: U256::from(7u64),
+            recipient_chain_id: 1,
+            recipient_address: B256::left_padding_from(&[9]),
+            recipient_tweak: B256::ZERO,
+            recipient_family: Default::default(),
+            total_value: U256::from(300u64),
+            leaf_indices,
+            proof: Bytes::from(vec![0u8; 128]),
+        }
+    }
+
+    fn sample_mint(package: &DisclosurePackage) -> AuditedMint {
+        AuditedMint {
+            recipient: package.recipient(),
+            amount: package.total_value,
+        }
+    }
+
+    #[test]
+    fn disclosure_slots_picks_smallest_circuit() {
+        assert_eq!(disclosure_slots(1), Some(1));
+        assert_eq!(disclosure_slots(3), Some(4));
+        assert_eq!(disclosure_slots(8), Some(8));
+        assert_eq!(disclosure_slots(9), None);
+    }
+
+    #[test]
+    fn disclosure_package_round_trips_and_rejects_bad_layouts() {
+        let package = sample_package(vec![3, 17]);
+        let json = serde_json::to_string(&package).unwrap();
+        assert!(json.contains("\"leafIndices\":[3,17]"));
+        assert_eq!(
+            serde_json::from_str::<DisclosurePackage>(&json).unwrap(),
+            package
+        );
+
+        let unordered = sample_package(vec![17, 3]);
+        assert!(matches!(
+            verify_disclosure(
+                &[],
+                ArtifactCompression::Uncompressed,
+                &unordered,
+                unordered.merkle_root,
+                &sample_mint(&unordered),
+            ),
+            Err(DisclosureError::UnorderedLeafIndices)
+        ));
+        let mut unsupported = sample_package(vec![3]);
+        unsupported.num_slots = 3;
+        assert!(matches!(
+            verify_disclosure(
+                &[],
+                ArtifactCompression::Uncompressed,
+                &unsupported,
+                unsupported.merkle_root,
+                &sample_mint(&unsupported),
+            ),
+            Err(DisclosureError::UnsupportedCircuit { num_slots: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn verify_disclosure_checks_accepted_root_and_mint() {
+        let package = sample_package(vec![3, 17]);
+        let mint = sample_mint(&package);
+        let verify = |accepted_root: U256, mint: &AuditedMint| {
+            verify_disclosure(
+                &[],
+                ArtifactCompression::Uncompressed,
+                &package,
+                accepted_root,
+                mint,
+            )
+        };
+
+        assert!(matches!(
+            verify(U256::from(8u64), &mint),
+            Err(DisclosureError::RootNotAccepted { .. })
+        ));
+        assert!(matches!(
+            verify(U256::ZERO, &mint),
+            Err(DisclosureError::RootNotAccepted { .. })
+        ));
+
+        let mut other_recipient = mint;
+        other_recipient.recipient.tweak = B256::left_padding_from(&[1]);
+        assert!(matches!(
+            verify(package.merkle_root, &other_recipient),
+            Err(DisclosureError::RecipientMismatch)
+        ));
+
+        let mut other_amount = mint;
+        other_amount.amount = U256::from(299u64);
+        assert!(matches!(
+            verify(package.merkle_root, &other_amount),
+            Err(DisclosureError::AmountMismatch { .. })
+        ));
+
+        // a matching root and mint gets as far as the verifying key
+        assert!(matches!(
+            verify(package.merkle_root, &mint),
+            Err(DisclosureError::Proof(_))
+        ));
+    }
+}
@@ -7,7 +7,10 @@ use reqwest::{Client, Url};
 use thiserror::Error;
 use tokio::sync::Mutex;
 
-pub use api_types::indexer::{EventsQuery, HistoricalProof, IndexedEvent, TreeIndexQuery};
+pub use api_types::indexer::{
+    EventsBatchRecipient, EventsBatchRequest, EventsQuery, HistoricalProof, IndexedEvent,
+    RecipientEvents, TreeIndexQuery,
+};
 
 #[derive(Debug, Error)]
 pub enum IndexerError {
@@ -53,6 +56,16 @@ pub trait IndexerClient: Send + Sync {
         limit: Option<usize>,
     ) -> IndexerResult<Vec<IndexedEvent>>;
 
+    /// Events of every recipient in one request, grouped per recipient in the order given.
+    /// `limit` applies to each recipient separately.
+    async fn events_by_recipients(
+        &self,
+        chain_id: u64,
+        token_address: Address,
+        recipients: &[EventsBatchRecipient],
+        limit: Option<usize>,
+    ) -> IndexerResult<Vec<RecipientEvents>>;
+
     async fn prove_many(
         &self,
         chain_id: u64,
@@ -137,6 +150,37 @@ impl IndexerClient for HttpIndexerClient {
         Ok(events)
     }
 
+    async fn events_by_recipients(
+        &self,
+        chain_id: u64,
+        token_address: Address,
+        recipients: &[EventsBatchRecipient],
+        limit: Option<usize>,
+    ) -> IndexerResult<Vec<RecipientEvents>> {
+        let url = self.endpoint("events/batch")?;
+        let payload = EventsBatchRequest {
+            chain_id,
+            token_address,
+            recipients: recipients.to_vec(),
+            limit,
+        };
+
+        let response = self
+            .client
+            .post(url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(IndexerError::EventsRequest)?
+            .error_for_status()
+            .map_err(IndexerError::EventsStatus)?;
+
+        let events: Vec<RecipientEvents> =
+            response.json().await.map_err(IndexerError::EventsDecode)?;
+
+        Ok(events)
+    }
+
     async fn prove_many(
         &self,
         chain_id: u64,
@@ -202,6 +246,7 @@ impl IndexerClient for HttpIndexerClient {
 #[derive(Clone, Debug, Default)]
 pub struct TestIndexerClient {
     events: Arc<Mutex<VecDeque<IndexerResult<Vec<IndexedEvent>>>>>,
+    events_batch: Arc<Mutex<VecDeque<IndexerResult<Vec<RecipientEvents>>>>>,
     prove_many: Arc<Mutex<VecDeque<IndexerResult<Vec<HistoricalProof>>>>>,
     tree_index: Arc<Mutex<VecDeque<IndexerResult<u64>>>>,
 }
@@ -215,6 +260,13 @@ impl TestIndexerClient {
         self.events.lock().await.push_back(response);
     }
 
+    pub async fn enqueue_events_batch_response(
+        &self,
+        response: IndexerResult<Vec<RecipientEvents>>,
+    ) {
+        self.events_batch.lock().await.push_back(response);
+    }
+
     pub async fn enqueue_prove_many_response(&self, response: IndexerResult<Vec<HistoricalProof>>) {
         self.prove_many.lock().await.push_back(response);
     }
@@ -248,6 +300,17 @@ impl IndexerClient for TestIndexerClient {
         Self::take_next(&self.events, "events_by_recipient").await
     }
 
+    async fn events_by_recipients(
+        &self,
+        chain_id: u64,
+        token_address: Address,
+        recipients: &[EventsBatchRecipient],
+        limit: Option<usize>,
+    ) -> IndexerResult<Vec<RecipientEvents>> {
+        let _ = (chain_id, token_address, recipients, limit);
+        Self::take_next(&self.events_batch, "events_by_recipients").await
+    }
+
     async fn prove_many(
         &self,
         chain_id: u64,
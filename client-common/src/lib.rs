@@ -1,5 +1,6 @@
 #[cfg(not(target_arch = "wasm32"))]
 pub mod contracts;
+pub mod disclosure;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod indexer;
 pub mod payment;
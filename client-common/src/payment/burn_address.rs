@@ -4,7 +4,7 @@ use zkp::{
     circuits::burn_address::compute_burn_address_from_secret,
     utils::{
         convertion::{b256_to_fr, fr_to_address},
-        general_recipient::GeneralRecipient,
+        general_recipient::{GeneralRecipient, RecipientAccount, RecipientFamily},
     },
 };
 
@@ -16,23 +16,24 @@ pub struct FullBurnAddress {
     pub secret: B256,
 }
 
+// Version 1 payloads carry EVM recipients only and are still emitted for them, so existing
+// wallets keep decoding them. Version 2 adds the recipient version byte after the payload
+// version to support non-EVM recipients.
 const FULL_BURN_ADDRESS_VERSION: u8 = 1;
+const FULL_BURN_ADDRESS_FAMILY_VERSION: u8 = 2;
 const FULL_BURN_ADDRESS_SERIALIZED_LEN: usize = 1 + 8 + 32 + 32 + 32;
+const FULL_BURN_ADDRESS_FAMILY_SERIALIZED_LEN: usize = FULL_BURN_ADDRESS_SERIALIZED_LEN + 1;
 
 impl FullBurnAddress {
     pub fn new(
-        recipient_chain_id: u64,
-        recipient_address: Address,
+        recipient: RecipientAccount,
         secret_and_tweak: &SecretAndTweak,
+        pow_difficulty: usize,
     ) -> Result<Self> {
-        let gr = GeneralRecipient::new_evm(
-            recipient_chain_id,
-            recipient_address,
-            secret_and_tweak.tweak,
-        );
+        let gr = recipient.with_tweak(secret_and_tweak.tweak);
         let recipient_fr = gr.to_fr();
         let secret_fr = b256_to_fr(secret_and_tweak.secret);
-        compute_burn_address_from_secret(recipient_fr, secret_fr)
+        compute_burn_address_from_secret(recipient_fr, secret_fr, pow_difficulty)
             .context("burn secret must satisfy PoW")?;
         Ok(Self {
             gr,
@@ -40,17 +41,24 @@ impl FullBurnAddress {
         })
     }
 
-    pub fn burn_address(&self) -> Result<Address> {
+    /// The payload does not carry the PoW difficulty; pass the one of the recipient chain's
+    /// deployment.
+    pub fn burn_address(&self, pow_difficulty: usize) -> Result<Address> {
         let recipient_fr = self.gr.to_fr();
         let burn_address_fr =
-            compute_burn_address_from_secret(recipient_fr, b256_to_fr(self.secret))
+            compute_burn_address_from_secret(recipient_fr, b256_to_fr(self.secret), pow_difficulty)
                 .context("burn secret must satisfy PoW")?;
         Ok(fr_to_address(burn_address_fr))
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(FULL_BURN_ADDRESS_SERIALIZED_LEN);
-        bytes.push(FULL_BURN_ADDRESS_VERSION);
+        let mut bytes = Vec::with_capacity(FULL_BURN_ADDRESS_FAMILY_SERIALIZED_LEN);
+        if self.gr.family == RecipientFamily::Evm {
+            bytes.push(FULL_BURN_ADDRESS_VERSION);
+        } else {
+            bytes.push(FULL_BURN_ADDRESS_FAMILY_VERSION);
+            bytes.push(self.gr.version());
+        }
         bytes.extend_from_slice(&self.gr.chain_id.to_be_bytes());
         bytes.extend_from_slice(self.gr.address.as_slice());
         bytes.extend_from_slice(self.gr.tweak.as_slice());
@@ -59,6 +67,22 @@ impl FullBurnAddress {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(!bytes.is_empty(), "FullBurnAddress payload is empty");
+        let (family, bytes) = match bytes[0] {
+            FULL_BURN_ADDRESS_VERSION => (RecipientFamily::Evm, bytes),
+            FULL_BURN_ADDRESS_FAMILY_VERSION => {
+                ensure!(bytes.len() > 1, "FullBurnAddress payload is truncated");
+                let family = RecipientFamily::from_version(bytes[1])?;
+                ensure!(
+                    family != RecipientFamily::Evm,
+                    "EVM recipients must use FullBurnAddress version {}",
+                    FULL_BURN_ADDRESS_VERSION
+                );
+                // drop the payload version so the family byte takes its place in the v1 layout
+                (family, &bytes[1..])
+            }
+            version => anyhow::bail!("unsupported FullBurnAddress version {}", version),
+        };
         ensure!(
             bytes.len() == FULL_BURN_ADDRESS_SERIALIZED_LEN,
             "FullBurnAddress expects {} bytes, got {}",
@@ -66,12 +90,6 @@ impl FullBurnAddress {
             bytes.len()
         );
 
-        ensure!(
-            bytes[0] == FULL_BURN_ADDRESS_VERSION,
-            "unsupported FullBurnAddress version {}",
-            bytes[0]
-        );
-
         let mut chain_id_bytes = [0u8; 8];
         chain_id_bytes.copy_from_slice(&bytes[1..9]);
         let chain_id = u64::from_be_bytes(chain_id_bytes);
@@ -84,7 +102,15 @@ impl FullBurnAddress {
             chain_id,
             address,
             tweak,
+            family,
         };
+        if family == RecipientFamily::Evm {
+            ensure!(
+                gr.account().evm_address().is_some(),
+                "EVM recipient address {} is not a 20-byte address",
+                address
+            );
+        }
 
         Ok(Self { gr, secret })
     }
@@ -95,6 +121,7 @@ mod tests {
     use super::*;
     use crate::payment::invoice::SecretAndTweak;
     use anyhow::Result;
+    use zkp::circuits::constants::POW_DIFFICULTY;
 
     #[test]
     fn round_trip_serialize() -> Result<()> {
@@ -102,10 +129,11 @@ mod tests {
         let chain_id = 10u64;
         let payment_advice_id = B256::from_slice(&[0xAA; 32]);
         let seed = B256::from_slice(&[0xBB; 32]);
+        let recipient = RecipientAccount::new_evm(chain_id, recipient_address);
         let secret_and_tweak =
-            SecretAndTweak::payment_advice(payment_advice_id, seed, chain_id, recipient_address);
+            SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, POW_DIFFICULTY);
 
-        let burn = FullBurnAddress::new(chain_id, recipient_address, &secret_and_tweak)?;
+        let burn = FullBurnAddress::new(recipient, &secret_and_tweak, POW_DIFFICULTY)?;
         let encoded = burn.to_bytes();
         let decoded = FullBurnAddress::from_bytes(&encoded)?;
 
@@ -113,6 +141,55 @@ mod tests {
         assert_eq!(decoded.gr.address, burn.gr.address);
         assert_eq!(decoded.gr.tweak, burn.gr.tweak);
         assert_eq!(decoded.secret, burn.secret);
+        assert_eq!(encoded[0], FULL_BURN_ADDRESS_VERSION);
+        assert_eq!(encoded.len(), FULL_BURN_ADDRESS_SERIALIZED_LEN);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_serialize_non_evm() -> Result<()> {
+        let recipient = RecipientAccount::new_solana(900, [0x33; 32]);
+        let payment_advice_id = B256::from_slice(&[0xAA; 32]);
+        let seed = B256::from_slice(&[0xBB; 32]);
+        let secret_and_tweak =
+            SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, POW_DIFFICULTY);
+
+        let burn = FullBurnAddress::new(recipient, &secret_and_tweak, POW_DIFFICULTY)?;
+        let encoded = burn.to_bytes();
+        assert_eq!(encoded[0], FULL_BURN_ADDRESS_FAMILY_VERSION);
+        assert_eq!(encoded.len(), FULL_BURN_ADDRESS_FAMILY_SERIALIZED_LEN);
+        let decoded = FullBurnAddress::from_bytes(&encoded)?;
+
+        assert_eq!(decoded.gr, burn.gr);
+        assert_eq!(decoded.gr.account(), recipient);
+        assert_eq!(
+            decoded.burn_address(POW_DIFFICULTY)?,
+            burn.burn_address(POW_DIFFICULTY)?
+        );
+
+        let mut unknown_family = encoded.clone();
+        unknown_family[1] = 0xff;
+        assert!(FullBurnAddress::from_bytes(&unknown_family).is_err());
+        let mut evm_family = encoded;
+        evm_family[1] = RecipientFamily::Evm.version();
+        assert!(FullBurnAddress::from_bytes(&evm_family).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn derivation_grinds_for_requested_pow_difficulty() -> Result<()> {
+        let recipient = RecipientAccount::new_evm(10, Address::from_slice(&[0x44; 20]));
+        let payment_advice_id = B256::from_slice(&[0xAA; 32]);
+        let seed = B256::from_slice(&[0xBB; 32]);
+        let low = SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, 4);
+        let default =
+            SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, POW_DIFFICULTY);
+        assert_eq!(low.tweak, default.tweak);
+
+        let burn = FullBurnAddress::new(recipient, &low, 4)?;
+        // the difficulty only gates the secret, the address itself does not depend on it
+        assert_eq!(burn.burn_address(4)?, burn.burn_address(0)?);
+        FullBurnAddress::new(recipient, &default, POW_DIFFICULTY)?;
         Ok(())
     }
 
@@ -122,10 +199,11 @@ mod tests {
         let chain_id = 77u64;
         let payment_advice_id = B256::from_slice(&[0xCC; 32]);
         let seed = B256::from_slice(&[0xDD; 32]);
+        let recipient = RecipientAccount::new_evm(chain_id, recipient_address);
         let secret_and_tweak =
-            SecretAndTweak::payment_advice(payment_advice_id, seed, chain_id, recipient_address);
+            SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, POW_DIFFICULTY);
 
-        let burn = FullBurnAddress::new(chain_id, recipient_address, &secret_and_tweak)?;
+        let burn = FullBurnAddress::new(recipient, &secret_and_tweak, POW_DIFFICULTY)?;
         let mut encoded = burn.to_bytes();
         encoded[0] = 0;
 
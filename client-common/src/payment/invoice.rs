@@ -1,10 +1,10 @@
-use alloy::primitives::{Address, B256, keccak256};
+use alloy::primitives::{B256, keccak256};
 use rand::{RngCore, rngs::OsRng};
 use zkp::{
     circuits::burn_address::{find_pow_nonce, secret_from_nonce},
     utils::{
         convertion::{b256_to_fr, fr_to_b256},
-        general_recipient::GeneralRecipient,
+        general_recipient::RecipientAccount,
     },
 };
 
@@ -58,8 +58,8 @@ impl SecretAndTweak {
     pub fn single_invoice(
         invoice_id: B256,
         seed: B256,
-        recipient_chain_id: u64,
-        recipient_address: Address,
+        recipient: RecipientAccount,
+        pow_difficulty: usize,
     ) -> Self {
         let base_secret = keccak256(
             [
@@ -77,8 +77,7 @@ impl SecretAndTweak {
             ]
             .concat(),
         );
-        let secret =
-            Self::pow_adjusted_secret(base_secret, tweak, recipient_chain_id, recipient_address);
+        let secret = Self::pow_adjusted_secret(base_secret, tweak, recipient, pow_difficulty);
         Self { secret, tweak }
     }
 
@@ -87,8 +86,8 @@ impl SecretAndTweak {
         invoice_id: B256,
         sub_id: u32,
         seed: B256,
-        recipient_chain_id: u64,
-        recipient_address: Address,
+        recipient: RecipientAccount,
+        pow_difficulty: usize,
     ) -> Self {
         let sub_id_bytes = sub_id.to_be_bytes();
         let base_secret = keccak256(
@@ -109,8 +108,7 @@ impl SecretAndTweak {
             ]
             .concat(),
         );
-        let secret =
-            Self::pow_adjusted_secret(base_secret, tweak, recipient_chain_id, recipient_address);
+        let secret = Self::pow_adjusted_secret(base_secret, tweak, recipient, pow_difficulty);
         Self { secret, tweak }
     }
 
@@ -118,8 +116,8 @@ impl SecretAndTweak {
     pub fn payment_advice(
         payment_advice_id: B256,
         seed: B256,
-        recipient_chain_id: u64,
-        recipient_address: Address,
+        recipient: RecipientAccount,
+        pow_difficulty: usize,
     ) -> Self {
         let base_secret = keccak256(
             [
@@ -137,21 +135,20 @@ impl SecretAndTweak {
             ]
             .concat(),
         );
-        let secret =
-            Self::pow_adjusted_secret(base_secret, tweak, recipient_chain_id, recipient_address);
+        let secret = Self::pow_adjusted_secret(base_secret, tweak, recipient, pow_difficulty);
         Self { secret, tweak }
     }
 
+    // `pow_difficulty` must match the withdraw circuits of the recipient chain's deployment
     fn pow_adjusted_secret(
         base_secret: B256,
         tweak: B256,
-        recipient_chain_id: u64,
-        recipient_address: Address,
+        recipient: RecipientAccount,
+        pow_difficulty: usize,
     ) -> B256 {
-        let gr = GeneralRecipient::new_evm(recipient_chain_id, recipient_address, tweak);
-        let recipient_fr = gr.to_fr();
+        let recipient_fr = recipient.with_tweak(tweak).to_fr();
         let secret_seed_fr = b256_to_fr(base_secret);
-        let nonce = find_pow_nonce(recipient_fr, secret_seed_fr);
+        let nonce = find_pow_nonce(recipient_fr, secret_seed_fr, pow_difficulty);
         let secret_fr = secret_from_nonce(secret_seed_fr, nonce);
         fr_to_b256(secret_fr)
     }
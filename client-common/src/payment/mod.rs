@@ -1,3 +1,4 @@
 pub mod burn_address;
 pub mod invoice;
+pub mod payout;
 pub mod seed;
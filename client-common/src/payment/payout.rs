@@ -0,0 +1,230 @@
+//! Payout splits for payroll-style withdrawals. A split lists the addresses that share a redeemed
+//! total; the payout withdraw circuit binds it through `payout_commitment`, so the split cannot be
+//! changed after the proof is generated.
+
+use alloy::primitives::{Address, U256};
+use anyhow::{Result, ensure};
+use ark_bn254::Fr;
+use serde::{Deserialize, Serialize};
+use zkp::{
+    circuits::constants::BYTES31_BIT_LENGTH,
+    groth16::payout::{PAYOUT_SIZES, payout_commitment},
+    utils::convertion::{address_to_fr, fr_to_u256, u256_to_fr},
+};
+
+const PAYOUT_SPLIT_VERSION: u8 = 1;
+const PAYOUT_SERIALIZED_LEN: usize = 20 + 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payout {
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+/// Ordered, non-empty list of payouts. Order matters: the same payouts in another order have a
+/// different commitment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Payout>", into = "Vec<Payout>")]
+pub struct PayoutSplit {
+    payouts: Vec<Payout>,
+}
+
+impl PayoutSplit {
+    pub fn new(payouts: Vec<Payout>) -> Result<Self> {
+        ensure!(
+            !payouts.is_empty(),
+            "a payout split needs at least one payout"
+        );
+        // the largest payout withdraw circuit bounds how many payouts one proof can commit to
+        let max_payouts = *PAYOUT_SIZES.last().expect("PAYOUT_SIZES is not empty");
+        ensure!(
+            payouts.len() <= max_payouts,
+            "a payout split holds at most {} payouts, got {}",
+            max_payouts,
+            payouts.len()
+        );
+        let max_amount = U256::from(1u8) << BYTES31_BIT_LENGTH;
+        let mut total = U256::ZERO;
+        for payout in &payouts {
+            // a zero recipient marks an empty slot in the circuit
+            ensure!(
+                payout.recipient != Address::ZERO,
+                "payout recipient must not be the zero address"
+            );
+            ensure!(
+                payout.amount < max_amount,
+                "payout amount {} exceeds 31 bytes",
+                payout.amount
+            );
+            total += payout.amount;
+        }
+        ensure!(
+            total < max_amount,
+            "payout total {} exceeds 31 bytes",
+            total
+        );
+        Ok(Self { payouts })
+    }
+
+    pub fn payouts(&self) -> &[Payout] {
+        &self.payouts
+    }
+
+    pub fn len(&self) -> usize {
+        self.payouts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payouts.is_empty()
+    }
+
+    /// Sum of the payouts. A payout withdraw proves `total() + fee` equals the minted delta,
+    /// i.e. `withdraw_value - teleported_before`.
+    pub fn total(&self) -> U256 {
+        self.payouts.iter().map(|payout| payout.amount).sum()
+    }
+
+    /// `payout_commitment` public input of the payout withdraw circuit for this split.
+    pub fn commitment(&self) -> U256 {
+        fr_to_u256(payout_commitment(&self.to_fields()))
+    }
+
+    /// `(recipient, amount)` field elements in split order, for filling the circuit's payout
+    /// slots. Fill any remaining slots with `PayoutSlot::empty()`.
+    pub fn to_fields(&self) -> Vec<(Fr, Fr)> {
+        self.payouts
+            .iter()
+            .map(|payout| (address_to_fr(payout.recipient), u256_to_fr(payout.amount)))
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.payouts.len() * PAYOUT_SERIALIZED_LEN);
+        bytes.push(PAYOUT_SPLIT_VERSION);
+        bytes.push(self.payouts.len() as u8);
+        for payout in &self.payouts {
+            bytes.extend_from_slice(payout.recipient.as_slice());
+            bytes.extend_from_slice(&payout.amount.to_be_bytes::<32>());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= 2, "PayoutSplit payload is truncated");
+        ensure!(
+            bytes[0] == PAYOUT_SPLIT_VERSION,
+            "unsupported PayoutSplit version {}",
+            bytes[0]
+        );
+        let count = bytes[1] as usize;
+        let body = &bytes[2..];
+        ensure!(
+            body.len() == count * PAYOUT_SERIALIZED_LEN,
+            "PayoutSplit with {} payouts expects {} bytes, got {}",
+            count,
+            2 + count * PAYOUT_SERIALIZED_LEN,
+            bytes.len()
+        );
+        let payouts = body
+            .chunks_exact(PAYOUT_SERIALIZED_LEN)
+            .map(|chunk| Payout {
+                recipient: Address::from_slice(&chunk[..20]),
+                amount: U256::from_be_slice(&chunk[20..]),
+            })
+            .collect();
+        Self::new(payouts)
+    }
+}
+
+impl TryFrom<Vec<Payout>> for PayoutSplit {
+    type Error = anyhow::Error;
+
+    fn try_from(payouts: Vec<Payout>) -> Result<Self> {
+        Self::new(payouts)
+    }
+}
+
+impl From<PayoutSplit> for Vec<Payout> {
+    fn from(split: PayoutSplit) -> Self {
+        split.payouts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkp::utils::poseidon::utils::poseidon2;
+
+    fn split() -> PayoutSplit {
+        PayoutSplit::new(vec![
+            Payout {
+                recipient: Address::repeat_byte(0x11),
+                amount: U256::from(150u64),
+            },
+            Payout {
+                recipient: Address::repeat_byte(0x22),
+                amount: U256::from(145u64),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_serialize() -> Result<()> {
+        let split = split();
+        let decoded = PayoutSplit::from_bytes(&split.to_bytes())?;
+        assert_eq!(decoded, split);
+        assert_eq!(decoded.total(), U256::from(295u64));
+
+        let mut truncated = split.to_bytes();
+        truncated.pop();
+        assert!(PayoutSplit::from_bytes(&truncated).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn commitment_matches_circuit_encoding() {
+        let split = split();
+        let expected = split
+            .payouts()
+            .iter()
+            .fold(Fr::from(0u64), |commitment, payout| {
+                poseidon2(
+                    commitment,
+                    poseidon2(address_to_fr(payout.recipient), u256_to_fr(payout.amount)),
+                )
+            });
+        assert_eq!(split.commitment(), fr_to_u256(expected));
+
+        let mut reordered = split.payouts().to_vec();
+        reordered.reverse();
+        assert_ne!(
+            PayoutSplit::new(reordered).unwrap().commitment(),
+            split.commitment()
+        );
+    }
+
+    #[test]
+    fn rejects_more_payouts_than_the_largest_circuit() {
+        let max_payouts = *PAYOUT_SIZES.last().unwrap();
+        let payouts = |count: usize| {
+            (0..count)
+                .map(|_| Payout {
+                    recipient: Address::repeat_byte(0x11),
+                    amount: U256::from(1u64),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(PayoutSplit::new(payouts(max_payouts)).is_ok());
+        assert!(PayoutSplit::new(payouts(max_payouts + 1)).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_recipient() {
+        let payouts = vec![Payout {
+            recipient: Address::ZERO,
+            amount: U256::from(1u64),
+        }];
+        assert!(PayoutSplit::new(payouts).is_err());
+    }
+}
@@ -1,12 +1,26 @@
 use std::time::{Duration, Instant};
 
 use api_types::prover::{CircuitKind, JobRequest, JobStatus, JobStatusResponse};
+use ark_bn254::Fr;
 use async_trait::async_trait;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use reqwest::{Client, Url};
 use thiserror::Error;
 use tokio::time::sleep;
+use zkp::{
+    artifacts::ArtifactCompression,
+    nova::{
+        constants::{
+            DEV_TRANSFER_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP,
+            STAGING_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT,
+        },
+        decider_verifier,
+        params::NovaError,
+        root_nova::RootCircuit,
+        withdraw_nova::WithdrawCircuit,
+    },
+};
 
 const DEFAULT_POLL_INTERVAL_MS: u64 = 1_000;
 
@@ -111,6 +125,14 @@ pub enum DeciderError {
     },
     #[error("timed out waiting for prover job {job_id} after {timeout:?}")]
     Timeout { job_id: String, timeout: Duration },
+    #[error("no {circuit} decider circuit for transfer tree height {tree_height}")]
+    UnsupportedTreeHeight { circuit: String, tree_height: usize },
+    #[error("{circuit} decider proof rejected")]
+    InvalidProof {
+        circuit: String,
+        #[source]
+        source: NovaError,
+    },
 }
 
 pub type DeciderResult<T> = Result<T, DeciderError>;
@@ -221,6 +243,62 @@ impl DeciderClient for HttpDeciderClient {
     }
 }
 
+/// Verifies a decider proof returned by [`DeciderClient::produce_decider_proof`] against the
+/// circuit's `*_decider_vp.bin` and the expected initial/final IVC states, so that a bad proof is
+/// rejected before it is submitted on-chain. `tree_height` is the transfer tree height the
+/// `decider_vp` artifact was generated for: any of the root heights (see `root_artifact_prefix`)
+/// for `CircuitKind::Root`, and `TRANSFER_TREE_HEIGHT`/`GLOBAL_TRANSFER_TREE_HEIGHT` for the local
+/// and global withdraw circuits. `compression` is the artifact manifest's serialization mode for
+/// `decider_vp`.
+pub fn verify_decider_proof(
+    circuit: CircuitKind,
+    tree_height: usize,
+    decider_vp: &[u8],
+    compression: ArtifactCompression,
+    z_0: &[Fr],
+    z_i: &[Fr],
+    proof: &[u8],
+) -> DeciderResult<()> {
+    let compress = compression.into();
+    let result = match (&circuit, tree_height) {
+        (CircuitKind::Root, DEV_TRANSFER_TREE_HEIGHT) => {
+            decider_verifier::verify_decider_proof::<
+                RootCircuit<Fr, DEV_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>,
+            >(decider_vp, compress, z_0, z_i, proof)
+        }
+        (CircuitKind::Root, STAGING_TRANSFER_TREE_HEIGHT) => {
+            decider_verifier::verify_decider_proof::<
+                RootCircuit<Fr, STAGING_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>,
+            >(decider_vp, compress, z_0, z_i, proof)
+        }
+        (CircuitKind::Root, TRANSFER_TREE_HEIGHT) => {
+            decider_verifier::verify_decider_proof::<
+                RootCircuit<Fr, TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>,
+            >(decider_vp, compress, z_0, z_i, proof)
+        }
+        (CircuitKind::WithdrawLocal, TRANSFER_TREE_HEIGHT) => {
+            decider_verifier::verify_decider_proof::<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>(
+                decider_vp, compress, z_0, z_i, proof,
+            )
+        }
+        (CircuitKind::WithdrawGlobal, GLOBAL_TRANSFER_TREE_HEIGHT) => {
+            decider_verifier::verify_decider_proof::<WithdrawCircuit<Fr, GLOBAL_TRANSFER_TREE_HEIGHT>>(
+                decider_vp, compress, z_0, z_i, proof,
+            )
+        }
+        _ => {
+            return Err(DeciderError::UnsupportedTreeHeight {
+                circuit: circuit.to_string(),
+                tree_height,
+            });
+        }
+    };
+    result.map_err(|source| DeciderError::InvalidProof {
+        circuit: circuit.to_string(),
+        source,
+    })
+}
+
 pub fn decode_base64_payload(value: &str) -> DeciderResult<Vec<u8>> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -233,8 +311,17 @@ pub fn decode_base64_payload(value: &str) -> DeciderResult<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use super::decode_base64_payload;
+    use super::{DeciderError, decode_base64_payload, verify_decider_proof};
+    use api_types::prover::CircuitKind;
+    use ark_bn254::Fr;
     use base64::Engine;
+    use zkp::{
+        artifacts::ArtifactCompression,
+        nova::constants::{
+            DEV_TRANSFER_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, STAGING_TRANSFER_TREE_HEIGHT,
+            TRANSFER_TREE_HEIGHT,
+        },
+    };
 
     #[test]
     fn decode_base64_payload_decodes() {
@@ -243,4 +330,60 @@ mod tests {
         let bytes = decode_base64_payload(&encoded).expect("decode base64");
         assert_eq!(bytes, data);
     }
+
+    #[test]
+    fn verify_decider_proof_rejects_truncated_proof() {
+        let state = vec![Fr::from(1u64); 6];
+        let err = verify_decider_proof(
+            CircuitKind::WithdrawGlobal,
+            GLOBAL_TRANSFER_TREE_HEIGHT,
+            &[],
+            ArtifactCompression::Uncompressed,
+            &state,
+            &state,
+            &[0u8; 64],
+        )
+        .expect_err("truncated proof must be rejected");
+        assert!(matches!(err, DeciderError::InvalidProof { .. }));
+    }
+
+    #[test]
+    fn verify_decider_proof_dispatches_on_tree_height() {
+        let state = vec![Fr::from(1u64); 3];
+        for tree_height in [
+            DEV_TRANSFER_TREE_HEIGHT,
+            STAGING_TRANSFER_TREE_HEIGHT,
+            TRANSFER_TREE_HEIGHT,
+        ] {
+            let err = verify_decider_proof(
+                CircuitKind::Root,
+                tree_height,
+                &[],
+                ArtifactCompression::Uncompressed,
+                &state,
+                &state,
+                &[0u8; 64],
+            )
+            .expect_err("truncated proof must be rejected");
+            assert!(matches!(err, DeciderError::InvalidProof { .. }));
+        }
+
+        let err = verify_decider_proof(
+            CircuitKind::WithdrawLocal,
+            GLOBAL_TRANSFER_TREE_HEIGHT,
+            &[],
+            ArtifactCompression::Uncompressed,
+            &state,
+            &state,
+            &[0u8; 64],
+        )
+        .expect_err("withdraw circuits only exist at their own depth");
+        assert!(matches!(
+            err,
+            DeciderError::UnsupportedTreeHeight {
+                tree_height: GLOBAL_TRANSFER_TREE_HEIGHT,
+                ..
+            }
+        ));
+    }
 }
@@ -51,11 +51,14 @@ pub async fn fetch_aggregation_tree_state(
         );
     }
     let mut aggregation_tree = MerkleTree::new(AGGREGATION_TREE_HEIGHT);
-    for (idx, &root) in aggregation_event.snapshot.iter().enumerate() {
-        if root != U256::ZERO {
-            aggregation_tree.update_leaf(idx as u64, u256_to_fr(root));
-        }
-    }
+    aggregation_tree.update_leaves(
+        aggregation_event
+            .snapshot
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| !root.is_zero())
+            .map(|(idx, &root)| (idx as u64, u256_to_fr(root))),
+    );
     if aggregation_tree.get_root() != u256_to_fr(aggregation_event.root) {
         anyhow::bail!(
             "Aggregation tree root mismatch: computed root = {}, event root = {}",
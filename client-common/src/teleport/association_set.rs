@@ -0,0 +1,213 @@
+//! Association sets for privacy-pools-style withdrawals. An association tree mirrors a transfer
+//! tree: every approved burn keeps its leaf hash and position, every other slot stays empty. A
+//! withdrawal proving membership of each burned leaf in a published association root shows that
+//! its burns belong to the approved set without revealing which ones they are.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context as _;
+use api_types::indexer::IndexedEvent;
+use ark_bn254::Fr;
+use zkp::{
+    nova::constants::{AGGREGATION_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+    utils::tree::merkle_tree::{MerkleProof, MerkleTree},
+};
+
+use crate::teleport::{aggregation_tree::AggregationTreeState, merkle_proofs::leaf_hash};
+
+/// Association tree over a single token's transfer tree.
+#[derive(Clone, Debug)]
+pub struct AssociationSet {
+    tree: MerkleTree,
+    members: BTreeMap<u64, Fr>,
+}
+
+impl AssociationSet {
+    /// Builds the set from indexed transfer events of one token, keeping the events accepted by
+    /// `predicate` (e.g. burns whose `from` is not sanctioned).
+    pub fn from_events(
+        events: &[IndexedEvent],
+        predicate: impl Fn(&IndexedEvent) -> bool,
+    ) -> anyhow::Result<Self> {
+        Self::from_events_with_height(TRANSFER_TREE_HEIGHT, events, predicate)
+    }
+
+    pub fn from_events_with_height(
+        height: usize,
+        events: &[IndexedEvent],
+        predicate: impl Fn(&IndexedEvent) -> bool,
+    ) -> anyhow::Result<Self> {
+        let mut members = BTreeMap::new();
+        for event in events.iter().filter(|event| predicate(event)) {
+            if height < 64 && event.event_index >> height != 0 {
+                anyhow::bail!(
+                    "event index {} does not fit in a transfer tree of height {}",
+                    event.event_index,
+                    height
+                );
+            }
+            let hash = leaf_hash(event);
+            match members.insert(event.event_index, hash) {
+                Some(existing) if existing != hash => anyhow::bail!(
+                    "conflicting events for index {}: leaf hashes {} and {}",
+                    event.event_index,
+                    existing,
+                    hash
+                ),
+                _ => {}
+            }
+        }
+        let mut tree = MerkleTree::new(height);
+        tree.update_leaves(members.iter().map(|(&index, &hash)| (index, hash)));
+        Ok(Self { tree, members })
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.height()
+    }
+
+    pub fn root(&self) -> Fr {
+        self.tree.get_root()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn contains(&self, event: &IndexedEvent) -> bool {
+        self.members.get(&event.event_index) == Some(&leaf_hash(event))
+    }
+
+    /// Association path of `event`, or `None` if the event is not part of the set. The path is
+    /// indexed by `event.event_index`, the same position as the transfer-tree proof.
+    pub fn prove(&self, event: &IndexedEvent) -> Option<MerkleProof> {
+        self.contains(event)
+            .then(|| self.tree.prove(event.event_index))
+    }
+}
+
+/// Association tree over the global transfer tree, aggregating the per-chain sets the same way
+/// the Hub aggregates transfer roots. Chains without a set contribute an empty slot.
+#[derive(Clone, Debug)]
+pub struct GlobalAssociationSet {
+    aggregation_tree: MerkleTree,
+    chain_ids: Vec<u64>,
+    sets: HashMap<u64, AssociationSet>,
+}
+
+impl GlobalAssociationSet {
+    pub fn new(
+        aggregation_state: &AggregationTreeState,
+        sets: HashMap<u64, AssociationSet>,
+    ) -> anyhow::Result<Self> {
+        let mut leaves = Vec::with_capacity(sets.len());
+        for (chain_id, set) in &sets {
+            if set.height() != TRANSFER_TREE_HEIGHT {
+                anyhow::bail!(
+                    "association set of chain {} has height {}, expected {}",
+                    chain_id,
+                    set.height(),
+                    TRANSFER_TREE_HEIGHT
+                );
+            }
+            let aggregation_index = aggregation_index(&aggregation_state.chain_ids, *chain_id)?;
+            leaves.push((aggregation_index, set.root()));
+        }
+        let mut aggregation_tree = MerkleTree::new(AGGREGATION_TREE_HEIGHT);
+        aggregation_tree.update_leaves(leaves);
+        Ok(Self {
+            aggregation_tree,
+            chain_ids: aggregation_state.chain_ids.clone(),
+            sets,
+        })
+    }
+
+    pub fn root(&self) -> Fr {
+        self.aggregation_tree.get_root()
+    }
+
+    /// Global leaf index and association path of `event` on `chain_id`, or `None` if the event is
+    /// not part of that chain's set.
+    pub fn prove(&self, chain_id: u64, event: &IndexedEvent) -> Option<(u64, MerkleProof)> {
+        let local_proof = self.sets.get(&chain_id)?.prove(event)?;
+        let aggregation_index = aggregation_index(&self.chain_ids, chain_id).ok()?;
+        let global_proof = local_proof.extend(&self.aggregation_tree.prove(aggregation_index));
+        let global_leaf_index = (aggregation_index << TRANSFER_TREE_HEIGHT) + event.event_index;
+        Some((global_leaf_index, global_proof))
+    }
+}
+
+fn aggregation_index(chain_ids: &[u64], chain_id: u64) -> anyhow::Result<u64> {
+    chain_ids
+        .iter()
+        .position(|&id| id == chain_id)
+        .map(|index| index as u64)
+        .context(format!(
+            "Chain ID {} not found in aggregation state",
+            chain_id
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+
+    fn event(event_index: u64, from: u8) -> IndexedEvent {
+        IndexedEvent {
+            event_index,
+            from: Address::repeat_byte(from),
+            to: Address::repeat_byte(0x40 + event_index as u8),
+            value: U256::from(100 * (event_index + 1)),
+            eth_block_number: 1,
+        }
+    }
+
+    #[test]
+    fn association_set_mirrors_transfer_positions() {
+        let events = (0..4).map(|i| event(i, i as u8 + 1)).collect::<Vec<_>>();
+        let sanctioned = Address::repeat_byte(2);
+        let set = AssociationSet::from_events(&events, |event| event.from != sanctioned).unwrap();
+
+        assert_eq!(set.len(), 3);
+        assert!(set.prove(&events[1]).is_none());
+        let mut tampered = events[0].clone();
+        tampered.value += U256::from(1u64);
+        assert!(set.prove(&tampered).is_none());
+        for event in [&events[0], &events[2], &events[3]] {
+            let proof = set.prove(event).unwrap();
+            assert_eq!(
+                proof.get_root(leaf_hash(event), event.event_index),
+                set.root()
+            );
+        }
+    }
+
+    #[test]
+    fn global_association_set_extends_local_paths() {
+        let events = (0..3).map(|i| event(i, 1)).collect::<Vec<_>>();
+        let set = AssociationSet::from_events(&events, |event| event.event_index != 1).unwrap();
+        let aggregation_state = AggregationTreeState {
+            latest_agg_seq: 1,
+            aggregation_root: U256::ZERO,
+            aggregation_tree: MerkleTree::new(AGGREGATION_TREE_HEIGHT),
+            tree_root_indices: vec![3, 3],
+            chain_ids: vec![10, 20],
+        };
+        let global =
+            GlobalAssociationSet::new(&aggregation_state, HashMap::from([(20, set)])).unwrap();
+
+        assert!(global.prove(10, &events[0]).is_none());
+        assert!(global.prove(20, &events[1]).is_none());
+        let (global_leaf_index, proof) = global.prove(20, &events[2]).unwrap();
+        assert_eq!(global_leaf_index, (1 << TRANSFER_TREE_HEIGHT) + 2);
+        assert_eq!(
+            proof.get_root(leaf_hash(&events[2]), global_leaf_index),
+            global.root()
+        );
+    }
+}
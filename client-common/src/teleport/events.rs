@@ -5,8 +5,10 @@ use anyhow::Context;
 use api_types::indexer::IndexedEvent;
 
 use crate::{
-    contracts::z_erc20::ZErc20Contract, indexer::IndexerClient,
-    teleport::aggregation_tree::AggregationTreeState, tokens::TokenEntry,
+    contracts::z_erc20::ZErc20Contract,
+    indexer::{EventsBatchRecipient, IndexerClient},
+    teleport::aggregation_tree::AggregationTreeState,
+    tokens::TokenEntry,
 };
 
 pub async fn fetch_transfer_events(
@@ -48,18 +50,24 @@ pub async fn fetch_transfer_events(
             .iter()
             .find(|entry| entry.chain_id == chain_id)
             .context(format!("token entry not found for chain_id: {}", chain_id))?;
-        let mut events = Vec::new();
-        for address in &addresses {
-            let events_of_address = indexer
-                .events_by_recipient(
-                    token_entry.chain_id,
-                    token_entry.token_address,
-                    *address,
-                    indexer_fetch_limit.clone(),
-                )
-                .await?;
-            events.extend(events_of_address);
-        }
+        let recipients: Vec<EventsBatchRecipient> = addresses
+            .iter()
+            .map(|address| EventsBatchRecipient {
+                to: *address,
+                from_event_index: None,
+            })
+            .collect();
+        let events = indexer
+            .events_by_recipients(
+                token_entry.chain_id,
+                token_entry.token_address,
+                &recipients,
+                indexer_fetch_limit,
+            )
+            .await?
+            .into_iter()
+            .flat_map(|group| group.events)
+            .collect();
         all_events.insert(chain_id, events);
     }
 
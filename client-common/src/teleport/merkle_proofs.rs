@@ -134,6 +134,6 @@ pub fn generate_global_teleport_merkle_proofs(
     Ok(global_proofs)
 }
 
-fn leaf_hash(event: &IndexedEvent) -> Fr {
+pub(crate) fn leaf_hash(event: &IndexedEvent) -> Fr {
     compute_leaf_hash(address_to_fr(event.to), u256_to_fr(event.value))
 }
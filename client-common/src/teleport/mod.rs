@@ -1,3 +1,4 @@
 pub mod aggregation_tree;
+pub mod association_set;
 pub mod events;
 pub mod merkle_proofs;
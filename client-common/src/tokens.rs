@@ -7,6 +7,7 @@ use crate::contracts::utils::{NormalProvider, get_provider, get_provider_with_fa
 use alloy::primitives::Address;
 use anyhow::{Context, Result, anyhow};
 use serde::Deserialize;
+use zkp::circuits::{burn_address::check_pow_difficulty, constants::POW_DIFFICULTY};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct TokenEntry {
@@ -21,6 +22,38 @@ pub struct TokenEntry {
     pub rpc_urls: Vec<String>,
     #[serde(default)]
     pub legacy_tx: bool,
+    /// Burn-address PoW difficulty of this deployment's withdraw circuits. Must match the
+    /// `pow_difficulty` of the artifacts manifest the verifier keys were generated from.
+    #[serde(default = "default_pow_difficulty")]
+    pub pow_difficulty: usize,
+    /// Which blocks the indexer treats as final. Only events in final blocks reach the Merkle tree
+    /// and the root prover; newer ones are reported as seen.
+    #[serde(default)]
+    pub finality: Finality,
+    /// Query every entry of `rpc_urls` separately and require them to agree, instead of treating
+    /// them as interchangeable fallbacks. Only the event indexer honours this.
+    #[serde(default)]
+    pub rpc_quorum: bool,
+}
+
+fn default_pow_difficulty() -> usize {
+    POW_DIFFICULTY
+}
+
+/// Finality rule of a token's chain, written in `tokens.json` as `"latest"`, `"safe"`,
+/// `"finalized"` or `{ "confirmations": N }`.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Finality {
+    /// Every mined block is final.
+    #[default]
+    Latest,
+    /// Blocks at least `N` below the latest block are final.
+    Confirmations(u64),
+    /// Blocks up to the RPC `safe` tag are final.
+    Safe,
+    /// Blocks up to the RPC `finalized` tag are final.
+    Finalized,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -56,6 +89,15 @@ impl TokenEntry {
                 self.label
             ));
         }
+        if self.rpc_quorum && self.rpc_urls.len() < 2 {
+            return Err(anyhow!(
+                "token '{}' enables rpc_quorum but configures {} rpc url(s); at least two are required",
+                self.label,
+                self.rpc_urls.len()
+            ));
+        }
+        check_pow_difficulty(self.pow_difficulty)
+            .with_context(|| format!("token '{}' has an invalid pow_difficulty", self.label))?;
         Ok(())
     }
 
@@ -70,6 +70,7 @@ contract DeployVerifierAndToken is DeterministicDeployer {
         address withdrawLocal;
         address withdrawGlobalGroth16;
         address withdrawLocalGroth16;
+        uint256 initialTransferRoot;
     }
 
     struct VerifierDeps {
@@ -200,7 +201,8 @@ contract DeployVerifierAndToken is DeterministicDeployer {
             withdrawGlobal: deps.withdrawGlobal,
             withdrawLocal: deps.withdrawLocal,
             withdrawGlobalGroth16: deps.withdrawGlobalGroth16,
-            withdrawLocalGroth16: deps.withdrawLocalGroth16
+            withdrawLocalGroth16: deps.withdrawLocalGroth16,
+            initialTransferRoot: _loadInitialTransferRoot()
         });
         bytes memory verifierInit = _encodeVerifierInit(args);
         ERC1967Proxy verifierProxy =
@@ -224,7 +226,8 @@ contract DeployVerifierAndToken is DeterministicDeployer {
                 args.withdrawGlobal,
                 args.withdrawLocal,
                 args.withdrawGlobalGroth16,
-                args.withdrawLocalGroth16
+                args.withdrawLocalGroth16,
+                args.initialTransferRoot
             )
         );
     }
@@ -42,6 +42,7 @@ contract DeployLocal is DeterministicDeployer {
         address withdrawLocal;
         address withdrawGlobalGroth16;
         address withdrawLocalGroth16;
+        uint256 initialTransferRoot;
     }
 
     struct VerifierDeps {
@@ -221,7 +222,8 @@ contract DeployLocal is DeterministicDeployer {
             withdrawGlobal: deps.withdrawGlobal,
             withdrawLocal: deps.withdrawLocal,
             withdrawGlobalGroth16: deps.withdrawGlobalGroth16,
-            withdrawLocalGroth16: deps.withdrawLocalGroth16
+            withdrawLocalGroth16: deps.withdrawLocalGroth16,
+            initialTransferRoot: _loadInitialTransferRoot()
         });
         bytes memory initData = _encodeVerifierInit(args);
         ERC1967Proxy proxy =
@@ -244,7 +246,8 @@ contract DeployLocal is DeterministicDeployer {
                 args.withdrawGlobal,
                 args.withdrawLocal,
                 args.withdrawGlobalGroth16,
-                args.withdrawLocalGroth16
+                args.withdrawLocalGroth16,
+                args.initialTransferRoot
             )
         );
     }
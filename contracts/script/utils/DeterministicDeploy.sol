@@ -6,6 +6,9 @@ import {Script} from "forge-std/Script.sol";
 /// @notice Shared helpers for deterministic CREATE2 deployments across scripts.
 abstract contract DeterministicDeployer is Script {
     bytes32 internal constant DEFAULT_DEPLOY_SALT = keccak256("zerc20.deploy.default");
+    /// @dev Empty transfer tree root at the production tree height (40).
+    uint256 internal constant DEFAULT_INITIAL_TRANSFER_ROOT =
+        8687547638004116013653730449839507042090717944911454416140763808366589487233;
 
     /// @dev Reads `DEPLOY_SALT` from the environment. Falls back to a fixed value when unset.
     function _loadBaseSalt() internal view returns (bytes32) {
@@ -16,6 +19,12 @@ abstract contract DeterministicDeployer is Script {
         return keccak256(bytes(saltEnv));
     }
 
+    /// @dev Reads `INITIAL_TRANSFER_ROOT` from the environment. Dev and staging deployments built for a
+    ///      shallower transfer tree must set it to the root printed by `generate_circuit_artifacts`.
+    function _loadInitialTransferRoot() internal view returns (uint256) {
+        return vm.envOr("INITIAL_TRANSFER_ROOT", DEFAULT_INITIAL_TRANSFER_ROOT);
+    }
+
     /// @dev Derives a contract-specific salt from the shared base salt.
     function _deriveSalt(bytes32 baseSalt, string memory label) internal pure returns (bytes32) {
         return keccak256(abi.encodePacked(baseSalt, label));
@@ -6,8 +6,9 @@ import {MessagingFee, MessagingReceipt} from "@layerzerolabs/lz-evm-oapp-v2/cont
 import {Origin} from "@layerzerolabs/lz-evm-oapp-v2/contracts/oapp/OApp.sol";
 import {IzERC20} from "./interfaces/IzERC20.sol";
 import {IRootDecider, IWithdrawDecider} from "./interfaces/IDecider.sol";
-import {IWithdrawVerifier} from "./interfaces/IVerifier.sol";
+import {IPayoutVerifier, IReburnVerifier, IWithdrawVerifier} from "./interfaces/IVerifier.sol";
 import {GeneralRecipientLib} from "./utils/GeneralRecipientLib.sol";
+import {PoseidonT3} from "poseidon-solidity/contracts/PoseidonT3.sol";
 import {OAppUpgradeable} from "./utils/layerzero/OAppUpgradeable.sol";
 import {UUPSUpgradeable} from "@openzeppelin/contracts-upgradeable/proxy/utils/UUPSUpgradeable.sol";
 
@@ -27,6 +28,7 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
     event EmergencyTriggered(uint64 indexed index, uint256 root1, uint256 root2);
     event DeactivateEmergency();
     event Teleport(address indexed to, uint256 value);
+    event RelayerFeePaid(address indexed relayer, uint256 fee);
     event VerifiersSet(
         address rootDecider,
         address withdrawGlobalDecider,
@@ -34,6 +36,10 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         address singleWithdrawGlobalVerifier,
         address singleWithdrawLocalVerifier
     );
+    event MultiWithdrawVerifierSet(bool indexed isGlobal, uint8 indexed numLeaves, address verifier);
+    event ReburnVerifierSet(bool indexed isGlobal, uint8 indexed numLeaves, address verifier);
+    event PayoutVerifierSet(bool indexed isGlobal, uint8 indexed numPayouts, address verifier);
+    event AssociationRootSet(uint256 indexed root, bool allowed);
 
     error InvalidProof();
     error NoProvedRoot();
@@ -48,15 +54,32 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
     error InvalidInitialTotalValue(uint256 value);
     error FinalTransferRootMismatch(uint256 expected, uint256 actual);
     error FinalRecipientMismatch(uint256 expected, uint256 actual);
+    error FinalRelayerMismatch(uint256 expected, uint256 actual);
+    error FinalFeeMismatch(uint256 expected, uint256 actual);
+    error FinalAssociationRootMismatch(uint256 expected, uint256 actual);
+    error UnknownAssociationRoot(uint256 root);
+    error InvalidRelayer(uint256 relayer);
+    error FeeExceedsWithdrawal(uint256 fee, uint256 amount);
     error ExpectedRootZero(uint64 rootHint);
     error TransferRootMismatch(uint256 expected, uint256 actual);
     error RecipientMismatch(uint256 expected, uint256 actual);
     error InvalidRecipientChainId(uint64 provided, uint64 expected);
     error NothingToWithdraw(uint256 currentTotal, uint256 totalValue);
     error InsufficientMsgValue(uint256 required, uint256 provided);
-
-    uint256 constant INITIAL_TRANSFER_ROOT =
-        8687547638004116013653730449839507042090717944911454416140763808366589487233;
+    error MultiWithdrawVerifierNotSet(bool isGlobal, uint8 numLeaves);
+    error ReburnVerifierNotSet(bool isGlobal, uint8 numLeaves);
+    error InvalidBurnAddress(uint256 burnAddress);
+    error ZeroTransferRoot();
+    error PayoutVerifierNotSet(bool isGlobal, uint8 numPayouts);
+    error TeleportedBeforeMismatch(uint256 expected, uint256 actual);
+    error PayoutCommitmentMismatch(uint256 expected, uint256 actual);
+    error InvalidPayoutAmount(uint256 amount);
+
+    /// @notice One entry of a payout split: `amount` is minted to `recipient`.
+    struct Payout {
+        address recipient;
+        uint256 amount;
+    }
 
     address private _token;
     uint32 private _hubEid;
@@ -76,6 +99,13 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
     mapping(uint64 => uint256) public provedTransferRoots;
     mapping(uint64 => uint256) public globalTransferRoots;
     mapping(uint256 => uint256) public totalTeleported;
+    mapping(uint8 => address) public multiWithdrawGlobalVerifiers;
+    mapping(uint8 => address) public multiWithdrawLocalVerifiers;
+    mapping(uint256 => bool) public associationRoots;
+    mapping(uint8 => address) public reburnGlobalVerifiers;
+    mapping(uint8 => address) public reburnLocalVerifiers;
+    mapping(uint8 => address) public payoutGlobalVerifiers;
+    mapping(uint8 => address) public payoutLocalVerifiers;
 
     function token() public view returns (address) {
         return _token;
@@ -99,6 +129,7 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
     /// @param withdrawLocalDecider_ Nova verifier for local teleport proofs.
     /// @param singleWithdrawGlobalVerifier_ Groth16 verifier for global single teleports.
     /// @param singleWithdrawLocalVerifier_ Groth16 verifier for local single teleports.
+    /// @param initialTransferRoot_ Empty transfer tree root at the tree height the root circuit was built for.
     function initialize(
         address token_,
         uint32 hubEid_,
@@ -108,7 +139,8 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         address withdrawGlobalDecider_,
         address withdrawLocalDecider_,
         address singleWithdrawGlobalVerifier_,
-        address singleWithdrawLocalVerifier_
+        address singleWithdrawLocalVerifier_,
+        uint256 initialTransferRoot_
     ) external initializer {
         if (token_ == address(0)) revert ZeroToken();
         if (
@@ -117,6 +149,7 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         ) {
             revert ZeroAddress();
         }
+        if (initialTransferRoot_ == 0) revert ZeroTransferRoot();
 
         __OApp_init(endpoint, delegate);
         __UUPSUpgradeable_init();
@@ -127,11 +160,12 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
             withdrawGlobalDecider_,
             withdrawLocalDecider_,
             singleWithdrawGlobalVerifier_,
-            singleWithdrawLocalVerifier_
+            singleWithdrawLocalVerifier_,
+            initialTransferRoot_
         );
     }
 
-    /// @dev Internal initializer that wires storage pointers and seeds the transfer root history with the constant from the spec.
+    /// @dev Internal initializer that wires storage pointers and seeds the transfer root history with the empty tree root.
     /// forge-lint: disable-next-line(mixed-case-function)
     function __Verifier_init(
         address token_,
@@ -140,7 +174,8 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         address withdrawGlobalDecider_,
         address withdrawLocalDecider_,
         address singleWithdrawGlobalVerifier_,
-        address singleWithdrawLocalVerifier_
+        address singleWithdrawLocalVerifier_,
+        uint256 initialTransferRoot_
     ) internal onlyInitializing {
         __Pausable_init();
         _token = token_;
@@ -159,7 +194,7 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
             singleWithdrawLocalVerifier_
         );
 
-        provedTransferRoots[0] = INITIAL_TRANSFER_ROOT;
+        provedTransferRoots[0] = initialTransferRoot_;
         latestRelayedIndex = 0;
     }
 
@@ -222,6 +257,8 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
 
     /// @notice Executes the multi-note Nova teleport flow, minting the delta on zERC20.
     /// @dev Proof enforces that `totalValue` strictly increases per recipient hash; `isGlobal` selects local/global root arrays.
+    ///      The relayer address and fee are part of the proof state, so a submitter cannot redirect or inflate the fee.
+    ///      A non-zero association root in the proof state must have been published via `setAssociationRoot`.
     /// @param isGlobal Whether the proof references Hub-derived global roots.
     /// @param rootHint Index into either `provedTransferRoots` or `globalTransferRoots`.
     /// @param gr GeneralRecipient struct encoding chain id, recipient, tweak, and version byte.
@@ -233,19 +270,27 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         bytes calldata proof
     ) external whenNotPaused {
         // decode and verify proof
-        uint256[34] memory proof_ = abi.decode(proof, (uint256[34]));
+        uint256[40] memory proof_ = abi.decode(proof, (uint256[40]));
         uint256 transferRoot = proof_[1];
         uint256 recipient = proof_[2];
         require(proof_[3] == 0, InvalidInitialLastLeafIndex(proof_[3]));
         require(proof_[4] == 0, InvalidInitialTotalValue(proof_[4]));
-        require(proof_[5] == transferRoot, FinalTransferRootMismatch(proof_[5], transferRoot));
-        require(proof_[6] == recipient, FinalRecipientMismatch(proof_[6], recipient));
-        proof_[7]; // lastLeafIndex is unused
-        uint256 totalValue = proof_[8];
+        uint256 relayer = proof_[5];
+        uint256 fee = proof_[6];
+        uint256 associationRoot = proof_[7];
+        require(proof_[8] == transferRoot, FinalTransferRootMismatch(proof_[8], transferRoot));
+        require(proof_[9] == recipient, FinalRecipientMismatch(proof_[9], recipient));
+        proof_[10]; // lastLeafIndex is unused
+        uint256 totalValue = proof_[11];
+        require(proof_[12] == relayer, FinalRelayerMismatch(proof_[12], relayer));
+        require(proof_[13] == fee, FinalFeeMismatch(proof_[13], fee));
+        require(proof_[14] == associationRoot, FinalAssociationRootMismatch(proof_[14], associationRoot));
+        _checkAssociationRoot(associationRoot);
         address withdrawDecider = isGlobal ? withdrawGlobalDecider : withdrawLocalDecider;
         require(IWithdrawDecider(withdrawDecider).verifyOpaqueNovaProof(proof_), InvalidProof());
 
-        _teleport(isGlobal, rootHint, transferRoot, recipient, gr, totalValue);
+        address to = address(uint160(uint256(gr.recipient)));
+        _teleport(isGlobal, rootHint, transferRoot, recipient, gr, totalValue, relayer, fee, to);
     }
 
     /// @notice Executes the Groth16 teleport flow for lightweight single withdrawals.
@@ -260,30 +305,197 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         GeneralRecipientLib.GeneralRecipient calldata gr,
         bytes calldata proof
     ) external whenNotPaused {
+        address singleWithdrawVerifier = isGlobal ? singleWithdrawGlobalVerifier : singleWithdrawLocalVerifier;
+        _groth16Teleport(singleWithdrawVerifier, isGlobal, rootHint, gr, proof);
+    }
+
+    /// @notice Executes the Groth16 teleport flow for small batches proven by a single `numLeaves`-leaf circuit.
+    /// @dev Public inputs share the single-withdraw layout, so only the verifier lookup differs from `singleTeleport`.
+    /// @param isGlobal Whether the proof references Hub-derived global roots.
+    /// @param numLeaves Leaf capacity of the circuit that produced `proof` (e.g. 2, 4 or 8).
+    /// @param rootHint Index into either `provedTransferRoots` or `globalTransferRoots`.
+    /// @param gr GeneralRecipient struct encoding chain id, recipient, tweak, and version byte.
+    /// @param proof ABI-encoded Groth16 proof blob consumed by `IWithdrawVerifier`.
+    function multiTeleport(
+        bool isGlobal,
+        uint8 numLeaves,
+        uint64 rootHint,
+        GeneralRecipientLib.GeneralRecipient calldata gr,
+        bytes calldata proof
+    ) external whenNotPaused {
+        address multiWithdrawVerifier =
+            isGlobal ? multiWithdrawGlobalVerifiers[numLeaves] : multiWithdrawLocalVerifiers[numLeaves];
+        require(multiWithdrawVerifier != address(0), MultiWithdrawVerifierNotSet(isGlobal, numLeaves));
+        _groth16Teleport(multiWithdrawVerifier, isGlobal, rootHint, gr, proof);
+    }
+
+    /// @notice Re-burns up to `numLeaves` notes of `gr` into a fresh burn address without minting to `gr.recipient`.
+    /// @dev The proof shows `newBurnAddress` is a valid burn address of a private recipient and secret, so the minted
+    ///      delta becomes a new transfer-tree leaf instead of a public balance. Totals are still tracked under `gr`,
+    ///      so re-burned notes cannot be teleported again.
+    /// @param isGlobal Whether the proof references Hub-derived global roots.
+    /// @param numLeaves Leaf capacity of the circuit that produced `proof` (e.g. 1 or 4).
+    /// @param rootHint Index into either `provedTransferRoots` or `globalTransferRoots`.
+    /// @param gr GeneralRecipient the consumed notes were burned to.
+    /// @param proof ABI-encoded Groth16 proof blob consumed by `IReburnVerifier`.
+    function reburn(
+        bool isGlobal,
+        uint8 numLeaves,
+        uint64 rootHint,
+        GeneralRecipientLib.GeneralRecipient calldata gr,
+        bytes calldata proof
+    ) external whenNotPaused {
+        address reburnVerifier = isGlobal ? reburnGlobalVerifiers[numLeaves] : reburnLocalVerifiers[numLeaves];
+        require(reburnVerifier != address(0), ReburnVerifierNotSet(isGlobal, numLeaves));
+
+        (uint256[2] memory pA, uint256[2][2] memory pB, uint256[2] memory pC, uint256[7] memory pubSignals) =
+            abi.decode(proof, (uint256[2], uint256[2][2], uint256[2], uint256[7]));
+        uint256 newBurnAddress = pubSignals[6];
+        require(newBurnAddress != 0 && newBurnAddress <= type(uint160).max, InvalidBurnAddress(newBurnAddress));
+        _checkAssociationRoot(pubSignals[5]);
+        require(IReburnVerifier(reburnVerifier).verifyProof(pA, pB, pC, pubSignals), InvalidProof());
+
+        _teleport(
+            isGlobal,
+            rootHint,
+            pubSignals[0],
+            pubSignals[1],
+            gr,
+            pubSignals[2],
+            pubSignals[3],
+            pubSignals[4],
+            address(uint160(newBurnAddress))
+        );
+    }
+
+    /// @notice Withdraws notes of `gr` like `multiTeleport`, but splits the minted delta between `payouts`.
+    /// @dev The proof commits to the ordered `payouts` list, which is recomputed here, so the split cannot change after
+    ///      proving. The proof also binds the total teleported to `gr` before this call, so the payouts plus `fee` add
+    ///      up to exactly the minted delta.
+    /// @param isGlobal Whether the proof references Hub-derived global roots.
+    /// @param numPayouts Payout slot capacity of the circuit that produced `proof` (e.g. 4 or 16).
+    /// @param rootHint Index into either `provedTransferRoots` or `globalTransferRoots`.
+    /// @param gr GeneralRecipient the consumed notes were burned to.
+    /// @param payouts Addresses and amounts in the order they were committed to.
+    /// @param proof ABI-encoded Groth16 proof blob consumed by `IPayoutVerifier`.
+    function payoutTeleport(
+        bool isGlobal,
+        uint8 numPayouts,
+        uint64 rootHint,
+        GeneralRecipientLib.GeneralRecipient calldata gr,
+        Payout[] calldata payouts,
+        bytes calldata proof
+    ) external whenNotPaused {
+        address payoutVerifier = isGlobal ? payoutGlobalVerifiers[numPayouts] : payoutLocalVerifiers[numPayouts];
+        require(payoutVerifier != address(0), PayoutVerifierNotSet(isGlobal, numPayouts));
+        uint256[8] memory pubSignals = _verifyPayoutProof(payoutVerifier, payouts, proof);
+
+        uint256 remaining = _settle(
+            isGlobal, rootHint, pubSignals[0], pubSignals[1], gr, pubSignals[2], pubSignals[3], pubSignals[4]
+        );
+        for (uint256 i = 0; i < payouts.length; ++i) {
+            // the proof makes the payouts sum to the settled amount; underflow would revert an over-mint anyway
+            remaining -= payouts[i].amount;
+            IzERC20(_token).teleport(payouts[i].recipient, payouts[i].amount);
+            emit Teleport(payouts[i].recipient, payouts[i].amount);
+        }
+    }
+
+    /// @dev Checks the payout proof's `teleportedBefore` and payout commitment inputs, then verifies it against `verifier`.
+    function _verifyPayoutProof(address verifier, Payout[] calldata payouts, bytes calldata proof)
+        internal
+        view
+        returns (uint256[8] memory pubSignals)
+    {
+        uint256[2] memory pA;
+        uint256[2][2] memory pB;
+        uint256[2] memory pC;
+        (pA, pB, pC, pubSignals) = abi.decode(proof, (uint256[2], uint256[2][2], uint256[2], uint256[8]));
+        uint256 teleportedBefore = totalTeleported[pubSignals[1]];
+        require(pubSignals[6] == teleportedBefore, TeleportedBeforeMismatch(teleportedBefore, pubSignals[6]));
+        uint256 commitment = payoutCommitment(payouts);
+        require(pubSignals[7] == commitment, PayoutCommitmentMismatch(commitment, pubSignals[7]));
+        _checkAssociationRoot(pubSignals[5]);
+        require(IPayoutVerifier(verifier).verifyProof(pA, pB, pC, pubSignals), InvalidProof());
+    }
+
+    /// @notice Commitment the payout circuit exposes for `payouts`:
+    ///         `c_0 = 0`, `c_{i+1} = poseidon(c_i, poseidon(recipient_i, amount_i))`.
+    /// @dev Amounts are range-checked to 31 bytes in the circuit, so larger values could only alias a committed amount.
+    function payoutCommitment(Payout[] calldata payouts) public pure returns (uint256 commitment) {
+        uint256[2] memory inputs;
+        for (uint256 i = 0; i < payouts.length; ++i) {
+            // a zero recipient marks an empty circuit slot and is not part of the commitment
+            require(payouts[i].recipient != address(0), ZeroAddress());
+            require(payouts[i].amount <= type(uint248).max, InvalidPayoutAmount(payouts[i].amount));
+            inputs[0] = uint256(uint160(payouts[i].recipient));
+            inputs[1] = payouts[i].amount;
+            inputs[1] = PoseidonT3.hash(inputs);
+            inputs[0] = commitment;
+            commitment = PoseidonT3.hash(inputs);
+        }
+    }
+
+    /// @dev Decodes and verifies a Groth16 withdraw proof against `verifier`, then forwards to `_teleport`.
+    function _groth16Teleport(
+        address verifier,
+        bool isGlobal,
+        uint64 rootHint,
+        GeneralRecipientLib.GeneralRecipient calldata gr,
+        bytes calldata proof
+    ) internal {
         // decode and verify proof
-        (uint256[2] memory pA, uint256[2][2] memory pB, uint256[2] memory pC, uint256[3] memory pubSignals) =
-            abi.decode(proof, (uint256[2], uint256[2][2], uint256[2], uint256[3]));
+        (uint256[2] memory pA, uint256[2][2] memory pB, uint256[2] memory pC, uint256[6] memory pubSignals) =
+            abi.decode(proof, (uint256[2], uint256[2][2], uint256[2], uint256[6]));
         uint256 transferRoot = pubSignals[0];
         uint256 recipient = pubSignals[1];
         uint256 value = pubSignals[2];
-        address singleWithdrawVerifier = isGlobal ? singleWithdrawGlobalVerifier : singleWithdrawLocalVerifier;
-        require(IWithdrawVerifier(singleWithdrawVerifier).verifyProof(pA, pB, pC, pubSignals), InvalidProof());
+        uint256 relayer = pubSignals[3];
+        uint256 fee = pubSignals[4];
+        _checkAssociationRoot(pubSignals[5]);
+        require(IWithdrawVerifier(verifier).verifyProof(pA, pB, pC, pubSignals), InvalidProof());
 
-        _teleport(isGlobal, rootHint, transferRoot, recipient, gr, value);
+        address to = address(uint160(uint256(gr.recipient)));
+        _teleport(isGlobal, rootHint, transferRoot, recipient, gr, value, relayer, fee, to);
     }
 
-    /// @dev Shared logic for Nova and Groth16 teleports:
-    ///      - Confirms the claimed root matches the hinted slot (local or global)
-    ///      - Recomputes the recipient hash and chain id binding
-    ///      - Mints only the delta above `totalTeleported[recipient]`.
+    /// @dev Zero means the withdrawal did not prove association-set membership; any other root must be published.
+    function _checkAssociationRoot(uint256 associationRoot) internal view {
+        require(associationRoot == 0 || associationRoots[associationRoot], UnknownAssociationRoot(associationRoot));
+    }
+
+    /// @dev Shared logic for Nova and Groth16 teleports: settles the withdrawal and mints what is left after the
+    ///      relayer fee to `to`.
     function _teleport(
         bool isGlobal,
         uint64 rootHint,
         uint256 transferRoot,
         uint256 recipient,
         GeneralRecipientLib.GeneralRecipient memory gr,
-        uint256 value
+        uint256 value,
+        uint256 relayer,
+        uint256 fee,
+        address to
     ) internal {
+        uint256 amount = _settle(isGlobal, rootHint, transferRoot, recipient, gr, value, relayer, fee);
+        IzERC20(_token).teleport(to, amount);
+        emit Teleport(to, amount);
+    }
+
+    /// @dev Validates and records a withdrawal without minting it:
+    ///      - Confirms the claimed root matches the hinted slot (local or global)
+    ///      - Recomputes the recipient hash and chain id binding
+    ///      - Books the delta above `totalTeleported[recipient]`, pays `fee` of it to `relayer` and returns the rest
+    function _settle(
+        bool isGlobal,
+        uint64 rootHint,
+        uint256 transferRoot,
+        uint256 recipient,
+        GeneralRecipientLib.GeneralRecipient memory gr,
+        uint256 value,
+        uint256 relayer,
+        uint256 fee
+    ) internal returns (uint256) {
         // verify root
         uint256 expectedRoot = isGlobal ? globalTransferRoots[rootHint] : provedTransferRoots[rootHint];
         require(expectedRoot != 0, ExpectedRootZero(rootHint));
@@ -294,14 +506,20 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
         require(recipient == expectedRecipient, RecipientMismatch(expectedRecipient, recipient));
         uint64 localChainId = uint64(block.chainid);
         require(gr.chainId == localChainId, InvalidRecipientChainId(gr.chainId, localChainId));
+        require(relayer <= type(uint160).max, InvalidRelayer(relayer));
 
         uint256 currentTotal = totalTeleported[recipient];
         require(value > currentTotal, NothingToWithdraw(currentTotal, value));
         uint256 diff = value - currentTotal;
+        require(fee <= diff, FeeExceedsWithdrawal(fee, diff));
         totalTeleported[recipient] += diff;
-        address recipientAddr = address(uint160(uint256(gr.recipient)));
-        IzERC20(_token).teleport(recipientAddr, diff);
-        emit Teleport(recipientAddr, diff);
+        if (fee > 0) {
+            address relayerAddr = address(uint160(relayer));
+            require(relayerAddr != address(0), ZeroAddress());
+            IzERC20(_token).teleport(relayerAddr, fee);
+            emit RelayerFeePaid(relayerAddr, fee);
+        }
+        return diff - fee;
     }
 
     /// -----------------------------------------------------------------------
@@ -416,4 +634,52 @@ contract Verifier is OAppUpgradeable, PausableUpgradeable, UUPSUpgradeable {
             singleWithdrawLocalVerifier
         );
     }
+
+    /// @notice Registers (or clears, with `address(0)`) the Groth16 verifier for `numLeaves`-leaf batch withdrawals.
+    /// @param isGlobal Whether the verifier targets the global (aggregation) tree depth.
+    /// @param numLeaves Leaf capacity of the circuit the verifier was generated for.
+    /// @param verifier Groth16 verifier contract address.
+    function setMultiWithdrawVerifier(bool isGlobal, uint8 numLeaves, address verifier) external onlyOwner {
+        if (isGlobal) {
+            multiWithdrawGlobalVerifiers[numLeaves] = verifier;
+        } else {
+            multiWithdrawLocalVerifiers[numLeaves] = verifier;
+        }
+        emit MultiWithdrawVerifierSet(isGlobal, numLeaves, verifier);
+    }
+
+    /// @notice Registers (or clears, with `address(0)`) the Groth16 verifier for `numLeaves`-leaf re-burns.
+    /// @param isGlobal Whether the verifier targets the global (aggregation) tree depth.
+    /// @param numLeaves Leaf capacity of the circuit the verifier was generated for.
+    /// @param verifier Groth16 verifier contract address.
+    function setReburnVerifier(bool isGlobal, uint8 numLeaves, address verifier) external onlyOwner {
+        if (isGlobal) {
+            reburnGlobalVerifiers[numLeaves] = verifier;
+        } else {
+            reburnLocalVerifiers[numLeaves] = verifier;
+        }
+        emit ReburnVerifierSet(isGlobal, numLeaves, verifier);
+    }
+
+    /// @notice Registers (or clears, with `address(0)`) the Groth16 verifier for payout withdrawals with `numPayouts` slots.
+    /// @param isGlobal Whether the verifier targets the global (aggregation) tree depth.
+    /// @param numPayouts Payout slot capacity of the circuit the verifier was generated for.
+    /// @param verifier Groth16 verifier contract address.
+    function setPayoutVerifier(bool isGlobal, uint8 numPayouts, address verifier) external onlyOwner {
+        if (isGlobal) {
+            payoutGlobalVerifiers[numPayouts] = verifier;
+        } else {
+            payoutLocalVerifiers[numPayouts] = verifier;
+        }
+        emit PayoutVerifierSet(isGlobal, numPayouts, verifier);
+    }
+
+    /// @notice Publishes (or retracts) an association-set root that withdrawals may prove membership in.
+    /// @dev Retracting a root only affects future teleports; already minted withdrawals are final.
+    /// @param root Root of an association tree mirroring the local or global transfer tree.
+    /// @param allowed Whether proofs referencing `root` are accepted.
+    function setAssociationRoot(uint256 root, bool allowed) external onlyOwner {
+        associationRoots[root] = allowed;
+        emit AssociationRootSet(root, allowed);
+    }
 }
@@ -6,5 +6,5 @@ interface IRootDecider {
 }
 
 interface IWithdrawDecider {
-    function verifyOpaqueNovaProof(uint256[34] calldata proof) external view returns (bool);
+    function verifyOpaqueNovaProof(uint256[40] calldata proof) external view returns (bool);
 }
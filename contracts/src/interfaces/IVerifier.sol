@@ -6,6 +6,24 @@ interface IWithdrawVerifier {
         uint256[2] calldata _pA,
         uint256[2][2] calldata _pB,
         uint256[2] calldata _pC,
-        uint256[3] calldata _pubSignals
+        uint256[6] calldata _pubSignals
+    ) external view returns (bool);
+}
+
+interface IReburnVerifier {
+    function verifyProof(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[7] calldata _pubSignals
+    ) external view returns (bool);
+}
+
+interface IPayoutVerifier {
+    function verifyProof(
+        uint256[2] calldata _pA,
+        uint256[2][2] calldata _pB,
+        uint256[2] calldata _pC,
+        uint256[8] calldata _pubSignals
     ) external view returns (bool);
 }
@@ -5,7 +5,9 @@ import {Vm} from "forge-std/Vm.sol";
 import {MessagingFee, MessagingReceipt} from "@layerzerolabs/lz-evm-oapp-v2/contracts/oapp/OAppSender.sol";
 import {Origin} from "@layerzerolabs/lz-evm-oapp-v2/contracts/oapp/OApp.sol";
 import {Verifier} from "../src/Verifier.sol";
+import {GeneralRecipientLib} from "../src/utils/GeneralRecipientLib.sol";
 import {ERC1967Proxy} from "@openzeppelin/contracts/proxy/ERC1967/ERC1967Proxy.sol";
+import {PoseidonT3} from "poseidon-solidity/contracts/PoseidonT3.sol";
 import {TestHelperOz5, EndpointV2Mock, MockSendLib} from "./utils/TestHelperOz5.sol";
 
 contract VerifierTest is TestHelperOz5 {
@@ -19,6 +21,7 @@ contract VerifierTest is TestHelperOz5 {
     address internal constant WITHDRAW_LOCAL_DECIDER = address(0x300);
     address internal constant SINGLE_WITHDRAW_GLOBAL_VERIFIER = address(0x400);
     address internal constant SINGLE_WITHDRAW_LOCAL_VERIFIER = address(0x500);
+    uint256 internal constant INITIAL_TRANSFER_ROOT = 0x1234;
 
     uint32 internal constant LOCAL_EID = 1;
     uint32 internal constant HUB_EID = 2;
@@ -59,6 +62,28 @@ contract VerifierTest is TestHelperOz5 {
             SINGLE_WITHDRAW_LOCAL_VERIFIER,
             "single withdraw local verifier mismatch"
         );
+        assertEq(verifier.provedTransferRoots(0), INITIAL_TRANSFER_ROOT, "initial transfer root mismatch");
+    }
+
+    function testInitializeZeroTransferRootReverts() public {
+        Verifier implementation = new Verifier();
+        bytes memory initData = abi.encodeCall(
+            Verifier.initialize,
+            (
+                TOKEN,
+                HUB_EID,
+                address(endpoint),
+                address(this),
+                ROOT_DECIDER,
+                WITHDRAW_GLOBAL_DECIDER,
+                WITHDRAW_LOCAL_DECIDER,
+                SINGLE_WITHDRAW_GLOBAL_VERIFIER,
+                SINGLE_WITHDRAW_LOCAL_VERIFIER,
+                0
+            )
+        );
+        vm.expectRevert(Verifier.ZeroTransferRoot.selector);
+        new ERC1967Proxy(address(implementation), initData);
     }
 
     function testSetVerifiers() public {
@@ -116,6 +141,117 @@ contract VerifierTest is TestHelperOz5 {
         );
     }
 
+    function testSetMultiWithdrawVerifier() public {
+        address localVerifier = address(0xb00);
+        address globalVerifier = address(0xc00);
+
+        verifier.setMultiWithdrawVerifier(false, 4, localVerifier);
+        verifier.setMultiWithdrawVerifier(true, 4, globalVerifier);
+
+        assertEq(verifier.multiWithdrawLocalVerifiers(4), localVerifier, "multi withdraw local verifier not set");
+        assertEq(verifier.multiWithdrawGlobalVerifiers(4), globalVerifier, "multi withdraw global verifier not set");
+        assertEq(verifier.multiWithdrawLocalVerifiers(2), address(0), "unexpected verifier for other size");
+    }
+
+    function testSetMultiWithdrawVerifierOnlyOwner() public {
+        vm.prank(address(0xBEEF));
+        vm.expectRevert("Ownable: caller is not the owner");
+        verifier.setMultiWithdrawVerifier(false, 2, address(0xb00));
+    }
+
+    function testMultiTeleportUnsetVerifierReverts() public {
+        GeneralRecipientLib.GeneralRecipient memory gr;
+        vm.expectRevert(abi.encodeWithSelector(Verifier.MultiWithdrawVerifierNotSet.selector, false, uint8(8)));
+        verifier.multiTeleport(false, 8, 0, gr, "");
+    }
+
+    function testSetReburnVerifier() public {
+        verifier.setReburnVerifier(false, 1, address(0xd00));
+        verifier.setReburnVerifier(true, 4, address(0xe00));
+
+        assertEq(verifier.reburnLocalVerifiers(1), address(0xd00), "reburn local verifier not set");
+        assertEq(verifier.reburnGlobalVerifiers(4), address(0xe00), "reburn global verifier not set");
+        assertEq(verifier.reburnLocalVerifiers(4), address(0), "unexpected verifier for other size");
+    }
+
+    function testSetReburnVerifierOnlyOwner() public {
+        vm.prank(address(0xBEEF));
+        vm.expectRevert("Ownable: caller is not the owner");
+        verifier.setReburnVerifier(false, 1, address(0xd00));
+    }
+
+    function testReburnUnsetVerifierReverts() public {
+        GeneralRecipientLib.GeneralRecipient memory gr;
+        vm.expectRevert(abi.encodeWithSelector(Verifier.ReburnVerifierNotSet.selector, true, uint8(4)));
+        verifier.reburn(true, 4, 0, gr, "");
+    }
+
+    function testSetPayoutVerifier() public {
+        verifier.setPayoutVerifier(false, 4, address(0xf00));
+        verifier.setPayoutVerifier(true, 16, address(0xf10));
+
+        assertEq(verifier.payoutLocalVerifiers(4), address(0xf00), "payout local verifier not set");
+        assertEq(verifier.payoutGlobalVerifiers(16), address(0xf10), "payout global verifier not set");
+        assertEq(verifier.payoutLocalVerifiers(16), address(0), "unexpected verifier for other size");
+    }
+
+    function testSetPayoutVerifierOnlyOwner() public {
+        vm.prank(address(0xBEEF));
+        vm.expectRevert("Ownable: caller is not the owner");
+        verifier.setPayoutVerifier(false, 4, address(0xf00));
+    }
+
+    function testPayoutTeleportUnsetVerifierReverts() public {
+        GeneralRecipientLib.GeneralRecipient memory gr;
+        vm.expectRevert(abi.encodeWithSelector(Verifier.PayoutVerifierNotSet.selector, true, uint8(16)));
+        verifier.payoutTeleport(true, 16, 0, gr, new Verifier.Payout[](0), "");
+    }
+
+    function testPayoutCommitmentMatchesCircuitEncoding() public view {
+        Verifier.Payout[] memory payouts = _payouts();
+        uint256 first = PoseidonT3.hash([uint256(uint160(payouts[0].recipient)), payouts[0].amount]);
+        uint256 second = PoseidonT3.hash([uint256(uint160(payouts[1].recipient)), payouts[1].amount]);
+        uint256 expected = PoseidonT3.hash([PoseidonT3.hash([uint256(0), first]), second]);
+        assertEq(verifier.payoutCommitment(payouts), expected, "payout commitment mismatch");
+        assertEq(verifier.payoutCommitment(new Verifier.Payout[](0)), 0, "empty split commits to zero");
+    }
+
+    function testPayoutCommitmentRejectsZeroRecipient() public {
+        Verifier.Payout[] memory payouts = _payouts();
+        payouts[1].recipient = address(0);
+        vm.expectRevert(Verifier.ZeroAddress.selector);
+        verifier.payoutCommitment(payouts);
+    }
+
+    function testPayoutTeleportBindsTeleportedBefore() public {
+        verifier.setPayoutVerifier(true, 4, address(0xf00));
+        GeneralRecipientLib.GeneralRecipient memory gr;
+        Verifier.Payout[] memory payouts = _payouts();
+        uint256[8] memory pubSignals;
+        pubSignals[6] = 1;
+        pubSignals[7] = verifier.payoutCommitment(payouts);
+
+        vm.expectRevert(abi.encodeWithSelector(Verifier.TeleportedBeforeMismatch.selector, 0, 1));
+        verifier.payoutTeleport(true, 4, 0, gr, payouts, _payoutProof(pubSignals));
+    }
+
+    function testPayoutTeleportBindsSplit() public {
+        verifier.setPayoutVerifier(true, 4, address(0xf00));
+        GeneralRecipientLib.GeneralRecipient memory gr;
+        Verifier.Payout[] memory payouts = _payouts();
+        uint256 commitment = verifier.payoutCommitment(payouts);
+        uint256[8] memory pubSignals;
+        pubSignals[7] = commitment;
+        payouts[0].amount += 1;
+
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                Verifier.PayoutCommitmentMismatch.selector, verifier.payoutCommitment(payouts), commitment
+            )
+        );
+        verifier.payoutTeleport(true, 4, 0, gr, payouts, _payoutProof(pubSignals));
+    }
+
     function testActivateEmergencyPausesContract() public {
         verifier.activateEmergency();
         assertTrue(verifier.paused(), "verifier should be paused");
@@ -213,7 +349,8 @@ contract VerifierTest is TestHelperOz5 {
                 WITHDRAW_GLOBAL_DECIDER,
                 WITHDRAW_LOCAL_DECIDER,
                 SINGLE_WITHDRAW_GLOBAL_VERIFIER,
-                SINGLE_WITHDRAW_LOCAL_VERIFIER
+                SINGLE_WITHDRAW_LOCAL_VERIFIER,
+                INITIAL_TRANSFER_ROOT
             )
         );
         ERC1967Proxy proxy = new ERC1967Proxy(address(implementation), initData);
@@ -257,6 +394,19 @@ contract VerifierTest is TestHelperOz5 {
         return bytes32(uint256(uint160(addr)));
     }
 
+    function _payouts() internal pure returns (Verifier.Payout[] memory payouts) {
+        payouts = new Verifier.Payout[](2);
+        payouts[0] = Verifier.Payout({recipient: address(0x1111), amount: 150});
+        payouts[1] = Verifier.Payout({recipient: address(0x2222), amount: 145});
+    }
+
+    function _payoutProof(uint256[8] memory pubSignals) internal pure returns (bytes memory) {
+        uint256[2] memory pA;
+        uint256[2][2] memory pB;
+        uint256[2] memory pC;
+        return abi.encode(pA, pB, pC, pubSignals);
+    }
+
     function _deployVerifier(address delegate) internal returns (Verifier) {
         Verifier implementation = new Verifier();
         bytes memory initData = abi.encodeCall(
@@ -270,7 +420,8 @@ contract VerifierTest is TestHelperOz5 {
                 WITHDRAW_GLOBAL_DECIDER,
                 WITHDRAW_LOCAL_DECIDER,
                 SINGLE_WITHDRAW_GLOBAL_VERIFIER,
-                SINGLE_WITHDRAW_LOCAL_VERIFIER
+                SINGLE_WITHDRAW_LOCAL_VERIFIER,
+                INITIAL_TRANSFER_ROOT
             )
         );
         ERC1967Proxy proxy = new ERC1967Proxy(address(implementation), initData);
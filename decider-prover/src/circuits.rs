@@ -1,40 +1,52 @@
-use std::{fs, io::Cursor, path::Path};
+use std::io::Cursor;
 
 use api_types::prover::CircuitKind;
 use ark_bn254::{Fr, G1Projective as G1};
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
 use ark_grumpkin::Projective as G2;
 use ark_serialize::CanonicalDeserialize;
 use folding_schemes::{folding::nova::IVCProof, frontend::FCircuit};
+use zkp::artifacts::{ArtifactDir, Mmap};
 use zkp::nova::{
-    constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+    constants::{
+        DEV_TRANSFER_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP,
+        STAGING_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT,
+    },
     params::{DeciderParams, FParams, NovaParams},
-    root_nova::RootCircuit,
-    withdraw_nova::WithdrawCircuit,
+    root_nova::{RootCircuit, root_artifact_prefix},
+    withdraw_nova::{WithdrawCircuit, WithdrawCircuitParams},
 };
 use zkp::utils::poseidon::utils::circom_poseidon_config;
 
 use crate::errors::ProverError;
 
 pub struct ProverEngine {
-    root: CircuitContext<RootCircuit<Fr>>,
+    root: RootContext,
     withdraw_local: Option<CircuitContext<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>>,
     withdraw_global: CircuitContext<WithdrawCircuit<Fr, GLOBAL_TRANSFER_TREE_HEIGHT>>,
 }
 
 impl ProverEngine {
-    pub fn load(artifacts_dir: &Path, enable_withdraw_local: bool) -> Result<Self, ProverError> {
+    pub fn load(
+        artifacts_dir: &ArtifactDir,
+        enable_withdraw_local: bool,
+        root_tree_height: usize,
+    ) -> Result<Self, ProverError> {
         let poseidon = circom_poseidon_config::<Fr>();
-        let root = CircuitContext::load("root", artifacts_dir, poseidon.clone())?;
+        let root = RootContext::load(root_tree_height, artifacts_dir, poseidon.clone())?;
+        let withdraw_params =
+            WithdrawCircuitParams::new(poseidon, artifacts_dir.manifest().pow_difficulty);
         let withdraw_local = if enable_withdraw_local {
             Some(CircuitContext::load(
                 "withdraw_local",
                 artifacts_dir,
-                poseidon.clone(),
+                withdraw_params.clone(),
             )?)
         } else {
             None
         };
-        let withdraw_global = CircuitContext::load("withdraw_global", artifacts_dir, poseidon)?;
+        let withdraw_global =
+            CircuitContext::load("withdraw_global", artifacts_dir, withdraw_params)?;
 
         Ok(Self {
             root,
@@ -64,6 +76,43 @@ impl ProverEngine {
     }
 }
 
+/// Root circuit instantiated for the transfer tree height the indexer runs with.
+enum RootContext {
+    Dev(CircuitContext<RootCircuit<Fr, DEV_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>>),
+    Staging(CircuitContext<RootCircuit<Fr, STAGING_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>>),
+    Production(CircuitContext<RootCircuit<Fr, TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP>>),
+}
+
+impl RootContext {
+    fn load(
+        height: usize,
+        dir: &ArtifactDir,
+        poseidon: PoseidonConfig<Fr>,
+    ) -> Result<Self, ProverError> {
+        let prefix = root_artifact_prefix(height);
+        match height {
+            DEV_TRANSFER_TREE_HEIGHT => CircuitContext::load(&prefix, dir, poseidon).map(Self::Dev),
+            STAGING_TRANSFER_TREE_HEIGHT => {
+                CircuitContext::load(&prefix, dir, poseidon).map(Self::Staging)
+            }
+            TRANSFER_TREE_HEIGHT => {
+                CircuitContext::load(&prefix, dir, poseidon).map(Self::Production)
+            }
+            _ => Err(ProverError::Config(format!(
+                "unsupported root tree height {height} (expected {DEV_TRANSFER_TREE_HEIGHT}, {STAGING_TRANSFER_TREE_HEIGHT} or {TRANSFER_TREE_HEIGHT})"
+            ))),
+        }
+    }
+
+    fn generate(&self, ivc_proof_bytes: &[u8]) -> Result<Vec<u8>, ProverError> {
+        match self {
+            Self::Dev(ctx) => ctx.generate(ivc_proof_bytes),
+            Self::Staging(ctx) => ctx.generate(ivc_proof_bytes),
+            Self::Production(ctx) => ctx.generate(ivc_proof_bytes),
+        }
+    }
+}
+
 struct CircuitContext<C>
 where
     C: FCircuit<Fr>,
@@ -78,14 +127,22 @@ where
     C: FCircuit<Fr>,
     FParams<C>: Clone,
 {
-    fn load(prefix: &str, dir: &Path, f_params: FParams<C>) -> Result<Self, ProverError> {
-        let nova_pp = read_artifact(dir, prefix, "nova_pp.bin")?;
-        let nova_vp = read_artifact(dir, prefix, "nova_vp.bin")?;
-        let decider_pp = read_artifact(dir, prefix, "decider_pp.bin")?;
-        let decider_vp = read_artifact(dir, prefix, "decider_vp.bin")?;
+    fn load(prefix: &str, dir: &ArtifactDir, f_params: FParams<C>) -> Result<Self, ProverError> {
+        let nova_pp = map_artifact(dir, prefix, "nova_pp.bin")?;
+        let nova_vp = map_artifact(dir, prefix, "nova_vp.bin")?;
+        let decider_pp = map_artifact(dir, prefix, "decider_pp.bin")?;
+        let decider_vp = map_artifact(dir, prefix, "decider_vp.bin")?;
 
-        let nova = NovaParams::<C>::from_bytes(f_params.clone(), nova_pp, nova_vp)?;
-        let decider = DeciderParams::<C>::from_bytes(decider_pp, decider_vp)?;
+        let (compress, validate) = (dir.compress(), dir.validate());
+        let nova = NovaParams::<C>::from_readers(
+            f_params,
+            &nova_pp[..],
+            &nova_vp[..],
+            compress,
+            validate,
+        )?;
+        let decider =
+            DeciderParams::<C>::from_readers(&decider_pp[..], &decider_vp[..], compress, validate)?;
 
         Ok(Self { nova, decider })
     }
@@ -118,12 +175,6 @@ where
         .map_err(Into::into)
 }
 
-fn read_artifact(dir: &Path, prefix: &str, suffix: &str) -> Result<Vec<u8>, ProverError> {
-    let path = dir.join(format!("{prefix}_{suffix}"));
-    fs::read(&path).map_err(|err| {
-        ProverError::Io(std::io::Error::new(
-            err.kind(),
-            format!("failed to read {}: {}", path.display(), err),
-        ))
-    })
+fn map_artifact(dir: &ArtifactDir, prefix: &str, suffix: &str) -> Result<Mmap, ProverError> {
+    dir.map(&format!("{prefix}_{suffix}")).map_err(Into::into)
 }
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
+use zkp::nova::constants::TRANSFER_TREE_HEIGHT;
 
 use crate::errors::ProverError;
 
@@ -21,6 +22,13 @@ pub struct AppConfig {
     pub job_ttl_seconds: u64,
     #[serde(default)]
     pub enable_withdraw_local: bool,
+    /// Must match the indexer's transfer tree height; selects the `root` artifact set.
+    #[serde(default = "default_root_tree_height")]
+    pub root_tree_height: usize,
+    /// SHA-256 of the expected `manifest.json`. When set, artifacts matching the pinned manifest
+    /// are loaded without re-validating every curve point.
+    #[serde(default)]
+    pub artifacts_manifest_sha256: Option<String>,
 }
 
 pub fn load_config() -> Result<AppConfig, ProverError> {
@@ -70,3 +78,7 @@ fn default_json_body_limit() -> usize {
 fn default_job_ttl_seconds() -> u64 {
     24 * 60 * 60
 }
+
+fn default_root_tree_height() -> usize {
+    TRANSFER_TREE_HEIGHT
+}
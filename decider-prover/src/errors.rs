@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use zkp::nova::params::NovaError;
+use zkp::{artifacts::ArtifactError, nova::params::NovaError};
 
 #[derive(Debug, Error)]
 pub enum ProverError {
@@ -16,6 +16,8 @@ pub enum ProverError {
     RedisPoolCreation(#[from] deadpool_redis::CreatePoolError),
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    #[error("artifact error: {0}")]
+    Artifact(#[from] ArtifactError),
     #[error("nova error: {0}")]
     Nova(#[from] NovaError),
     #[error("hex decode error: {0}")]
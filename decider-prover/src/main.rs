@@ -11,6 +11,7 @@ use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use deadpool_redis::Pool;
 use log::{error as log_error, info};
+use zkp::artifacts::ArtifactDir;
 
 use decider_prover::{
     CircuitKind, JobRequest, JobStatusResponse, ProverEngine, ProverError, SubmitJobResponse,
@@ -138,11 +139,24 @@ async fn main() -> anyhow::Result<()> {
 
 fn load_prover_engine(config: &AppConfig) -> Result<ProverEngine, ProverError> {
     let start = Instant::now();
-    let engine = ProverEngine::load(&config.artifacts_dir, config.enable_withdraw_local)?;
+    let artifacts = match &config.artifacts_manifest_sha256 {
+        Some(manifest_sha256) => ArtifactDir::open_trusted(&config.artifacts_dir, manifest_sha256)?,
+        None => ArtifactDir::open(&config.artifacts_dir)?,
+    };
+    let engine = ProverEngine::load(
+        &artifacts,
+        config.enable_withdraw_local,
+        config.root_tree_height,
+    )?;
     info!(
-        "loaded decider parameters from {} in {:.2?}",
+        "loaded decider parameters from {} in {:.2?} (point validation {})",
         config.artifacts_dir.display(),
-        start.elapsed()
+        start.elapsed(),
+        if artifacts.is_trusted() {
+            "skipped for pinned manifest"
+        } else {
+            "enabled"
+        }
     );
     Ok(engine)
 }
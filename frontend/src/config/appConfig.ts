@@ -29,6 +29,8 @@ export interface NovaArtifactLocationMap {
 
 export interface ArtifactPaths {
   basePath: string;
+  /** Generator `manifest.json`; the WASM provers check every artifact against it. */
+  manifest: string;
   single: ArtifactLocationMap;
   batch: NovaArtifactLocationMap;
 }
@@ -89,6 +91,8 @@ const envSchema = z.object({
     .catch('zUSD'),
 });
 
+const artifactManifestFile = 'manifest.json';
+
 const singleArtifactFiles: ArtifactLocationMap = {
   localPk: 'withdraw_local_groth16_pk.bin',
   localVk: 'withdraw_local_groth16_vk.bin',
@@ -145,6 +149,7 @@ export function resolveRuntimeConfig(env: ImportMetaEnv): RuntimeConfig {
     tokensCompressed: parsed.VITE_TOKENS_COMPRESSED,
     artifacts: {
       basePath,
+      manifest: joinUrl(basePath, artifactManifestFile),
       single: withBase(singleArtifactFiles),
       batch: withBase(batchArtifactFiles),
     },
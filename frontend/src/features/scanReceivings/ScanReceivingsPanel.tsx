@@ -625,8 +625,12 @@ export function ScanReceivingsPanel({
           if (!fields.localPk || !fields.localVk || !fields.globalPk || !fields.globalVk) {
             throw new Error('Upload all single teleport Groth16 artifacts before redeeming.');
           }
+          if (!artifacts.manifest) {
+            throw new Error('Upload the artifact manifest before redeeming.');
+          }
           const singleProof = await generateSingleTeleportProof({
             wasmArtifacts: {
+              manifest: artifacts.manifest,
               localPk: fields.localPk,
               localVk: fields.localVk,
               globalPk: fields.globalPk,
@@ -659,9 +663,13 @@ export function ScanReceivingsPanel({
           if (!fields.localPp || !fields.localVp || !fields.globalPp || !fields.globalVp) {
             throw new Error('Upload all batch teleport Nova artifacts before redeeming.');
           }
+          if (!artifacts.manifest) {
+            throw new Error('Upload the artifact manifest before redeeming.');
+          }
           const decider = createDeciderClient(config);
           const batchProof = await generateBatchTeleportProof({
             wasmArtifacts: {
+              manifest: artifacts.manifest,
               localPp: fields.localPp,
               localVp: fields.localVp,
               globalPp: fields.globalPp,
@@ -17,6 +17,14 @@ async function fetchBinary(url: string): Promise<Uint8Array> {
   return binaryCache.get(url) as Promise<Uint8Array>;
 }
 
+async function fetchManifest(url: string): Promise<string> {
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Failed to load artifact manifest from ${url} (${response.status})`);
+  }
+  return response.text();
+}
+
 async function loadArtifactGroup<T extends { [K in keyof T]: string }>(
   paths: T,
 ): Promise<Record<keyof T, Uint8Array>> {
@@ -30,12 +38,14 @@ async function loadArtifactGroup<T extends { [K in keyof T]: string }>(
 }
 
 export async function loadTeleportArtifacts(paths: ArtifactPaths): Promise<TeleportArtifacts> {
-  const [single, batch] = await Promise.all([
+  const [manifest, single, batch] = await Promise.all([
+    fetchManifest(paths.manifest),
     loadArtifactGroup(paths.single),
     loadArtifactGroup(paths.batch),
   ]);
 
   return {
+    manifest,
     single: single as SingleTeleportArtifacts,
     batch: batch as BatchTeleportArtifacts,
   };
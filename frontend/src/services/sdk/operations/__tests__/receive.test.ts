@@ -56,6 +56,7 @@ describe('generateSingleTeleportProof', () => {
 
     const result = await generateSingleTeleportProof({
       wasmArtifacts: {
+        manifest: '{}',
         localPk: new Uint8Array(),
         localVk: new Uint8Array(),
         globalPk: new Uint8Array(),
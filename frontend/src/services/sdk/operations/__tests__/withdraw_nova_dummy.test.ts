@@ -137,6 +137,7 @@ describe('generateBatchTeleportProof (dummy steps)', () => {
 
     const result = await generateBatchTeleportProof({
       wasmArtifacts: {
+        manifest: readFileSync(path.join(repoRoot, 'nova_artifacts', 'manifest.json'), 'utf8'),
         localPp: loadArtifact('withdraw_local_nova_pp.bin'),
         localVp: loadArtifact('withdraw_local_nova_vp.bin'),
         globalPp: loadArtifact('withdraw_global_nova_pp.bin'),
@@ -12,6 +12,7 @@ import {
 
 export interface NovaProverInput {
   wasmArtifacts: {
+    manifest: string;
     localPp: Uint8Array;
     localVp: Uint8Array;
     globalPp: Uint8Array;
@@ -51,6 +52,7 @@ export async function runNovaProver(params: NovaProverInput): Promise<NovaProver
   const sortedEvents = proofEventPairs.map((pair) => pair.event);
   const sortedProofs = proofEventPairs.map((pair) => pair.proof);
   const wasm: WithdrawNovaWasm = await createWithdrawNovaWasm(
+    params.wasmArtifacts.manifest,
     params.wasmArtifacts.localPp,
     params.wasmArtifacts.localVp,
     params.wasmArtifacts.globalPp,
@@ -61,6 +63,9 @@ export async function runNovaProver(params: NovaProverInput): Promise<NovaProver
     formatFieldElement(params.recipientFr, 'z0[1]'),
     formatFieldElement('0x0', 'z0[2]'),
     formatFieldElement('0x0', 'z0[3]'),
+    formatFieldElement('0x0', 'z0[4]'),
+    formatFieldElement('0x0', 'z0[5]'),
+    formatFieldElement('0x0', 'z0[6]'),
   ];
   const steps = sortedEvents.map((event, idx) => ({
     is_dummy: false,
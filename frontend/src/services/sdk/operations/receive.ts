@@ -173,6 +173,7 @@ export interface SingleTeleportArtifacts {
 
 export interface SingleTeleportParams {
   wasmArtifacts: {
+    manifest: string;
     localPk: Uint8Array;
     localVk: Uint8Array;
     globalPk: Uint8Array;
@@ -187,6 +188,7 @@ export interface SingleTeleportParams {
 
 export async function generateSingleTeleportProof(params: SingleTeleportParams): Promise<SingleTeleportArtifacts> {
   const wasm: SingleWithdrawWasm = await createSingleWithdrawWasm(
+    params.wasmArtifacts.manifest,
     params.wasmArtifacts.localPk,
     params.wasmArtifacts.localVk,
     params.wasmArtifacts.globalPk,
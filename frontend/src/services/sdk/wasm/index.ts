@@ -282,24 +282,28 @@ export async function aggregationMerkleProof(snapshot: readonly string[], index:
   return siblings.map((value) => normalizeHex(value));
 }
 
+/** Throws if `manifestJson` does not match this build or an artifact does not match its hash. */
 export async function createSingleWithdrawWasm(
+  manifestJson: string,
   localPk: Uint8Array,
   localVk: Uint8Array,
   globalPk: Uint8Array,
   globalVk: Uint8Array,
 ): Promise<SingleWithdrawWasm> {
   await ensureWasm();
-  return new SingleWithdrawWasm(localPk, localVk, globalPk, globalVk);
+  return new SingleWithdrawWasm(manifestJson, localPk, localVk, globalPk, globalVk);
 }
 
+/** Throws if `manifestJson` does not match this build or an artifact does not match its hash. */
 export async function createWithdrawNovaWasm(
+  manifestJson: string,
   localPp: Uint8Array,
   localVp: Uint8Array,
   globalPp: Uint8Array,
   globalVp: Uint8Array,
 ): Promise<WithdrawNovaWasm> {
   await ensureWasm();
-  return new WithdrawNovaWasm(localPp, localVp, globalPp, globalVp);
+  return new WithdrawNovaWasm(manifestJson, localPp, localVp, globalPp, globalVp);
 }
 
 export type { SingleWithdrawWasm, WithdrawNovaWasm };
@@ -21,6 +21,8 @@ export interface BatchTeleportArtifacts {
 }
 
 export interface TeleportArtifacts {
+  /** Raw `manifest.json` text passed to the WASM prover constructors. */
+  manifest?: string;
   single: SingleTeleportArtifacts;
   batch: BatchTeleportArtifacts;
 }
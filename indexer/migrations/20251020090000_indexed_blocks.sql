@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS indexed_blocks (
+    token_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    block_hash BYTEA NOT NULL,
+    PRIMARY KEY (token_id, block_number),
+    FOREIGN KEY (token_id) REFERENCES tokens (id)
+) PARTITION BY LIST (token_id);
@@ -0,0 +1,3 @@
+ALTER TABLE event_indexer_state
+    ADD COLUMN IF NOT EXISTS final_index BIGINT NOT NULL DEFAULT -1,
+    ADD COLUMN IF NOT EXISTS final_block BIGINT;
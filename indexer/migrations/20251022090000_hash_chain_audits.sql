@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS hash_chain_audits (
+    token_id BIGINT PRIMARY KEY,
+    audited_index BIGINT,
+    divergent_checkpoint TEXT,
+    divergent_index BIGINT,
+    onchain_hash_chain BYTEA,
+    indexed_hash_chain BYTEA,
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (token_id) REFERENCES tokens (id)
+);
@@ -12,7 +12,10 @@ use reqwest::Url;
 use serde::Deserialize;
 
 use crate::{
-    events::{BLOCK_SPAN_RECOMMENDED, EventIndexerConfig, FORWARD_SCAN_OVERLAP_RECOMMENDED},
+    events::{
+        BLOCK_SPAN_RECOMMENDED, EventIndexerConfig, FORWARD_SCAN_OVERLAP_RECOMMENDED,
+        MAX_REORG_DEPTH_RECOMMENDED,
+    },
     trees::{DbMerkleTreeConfig, HISTORY_WINDOW_RECOMMENDED},
 };
 use zkp::nova::constants::TRANSFER_TREE_HEIGHT;
@@ -21,6 +24,7 @@ const DEFAULT_EVENT_INTERVAL_MS: u64 = 5_000;
 const DEFAULT_TREE_INTERVAL_MS: u64 = 2_000;
 const DEFAULT_TREE_HEIGHT: u32 = TRANSFER_TREE_HEIGHT as u32;
 const DEFAULT_TREE_BATCH_SIZE: usize = 128;
+const DEFAULT_AUDIT_INTERVAL_MS: u64 = 60_000;
 const DEFAULT_ROOT_INTERVAL_MS: u64 = 5_000;
 const DEFAULT_ROOT_SUBMIT_INTERVAL_MS: u64 = 10_000;
 const DEFAULT_DECIDER_PROVER_TIMEOUT_SECS: u64 = 120;
@@ -32,6 +36,7 @@ pub struct IndexerConfig {
     pub tokens: Vec<TokenEntry>,
     pub event_indexer: EventJobConfig,
     pub tree: TreeJobConfig,
+    pub audit: AuditJobConfig,
     pub root: RootJobConfig,
 }
 
@@ -50,6 +55,7 @@ impl IndexerConfig {
             interval_ms: env.event_interval_ms,
             block_span: env.event_block_span,
             forward_scan_overlap: env.event_forward_scan_overlap,
+            max_reorg_depth: env.event_max_reorg_depth,
         };
         event_indexer
             .ensure_valid()
@@ -63,6 +69,13 @@ impl IndexerConfig {
         };
         tree.ensure_valid().context("invalid tree configuration")?;
 
+        let audit = AuditJobConfig {
+            interval_ms: env.audit_interval_ms,
+        };
+        audit
+            .ensure_valid()
+            .context("invalid hash chain audit configuration")?;
+
         let root = RootJobConfig::new(
             env.root_interval_ms,
             env.root_submit_interval_ms,
@@ -72,6 +85,7 @@ impl IndexerConfig {
             env.decider_prover_url,
             env.root_submitter_private_key,
             env.root_artifacts_dir,
+            env.root_artifacts_manifest_sha256,
         )
         .context("invalid root prover configuration")?;
 
@@ -80,6 +94,7 @@ impl IndexerConfig {
             tokens,
             event_indexer,
             tree,
+            audit,
             root,
         })
     }
@@ -94,12 +109,16 @@ struct EnvSettings {
     event_block_span: u64,
     #[serde(default = "default_forward_overlap")]
     event_forward_scan_overlap: u64,
+    #[serde(default = "default_max_reorg_depth")]
+    event_max_reorg_depth: u64,
     #[serde(default = "default_tree_interval_ms")]
     tree_interval_ms: u64,
     #[serde(default = "default_history_window")]
     tree_history_window: u64,
     #[serde(default = "default_tree_batch_size")]
     tree_batch_size: usize,
+    #[serde(default = "default_audit_interval_ms")]
+    audit_interval_ms: u64,
     #[serde(default = "default_root_interval_ms")]
     root_interval_ms: u64,
     #[serde(default = "default_root_submit_interval_ms")]
@@ -114,6 +133,8 @@ struct EnvSettings {
     root_submitter_private_key: String,
     #[serde(default)]
     root_artifacts_dir: Option<String>,
+    #[serde(default)]
+    root_artifacts_manifest_sha256: Option<String>,
 }
 
 impl EnvSettings {
@@ -130,6 +151,8 @@ pub struct EventJobConfig {
     pub block_span: u64,
     #[serde(default = "default_forward_overlap")]
     pub forward_scan_overlap: u64,
+    #[serde(default = "default_max_reorg_depth")]
+    pub max_reorg_depth: u64,
 }
 
 impl EventJobConfig {
@@ -137,8 +160,7 @@ impl EventJobConfig {
         if self.interval_ms == 0 {
             return Err(anyhow!("event job interval must be positive"));
         }
-        EventIndexerConfig::new(self.block_span, self.forward_scan_overlap)
-            .context("invalid event indexer configuration")?;
+        self.build_indexer_config()?;
         Ok(())
     }
 
@@ -148,6 +170,7 @@ impl EventJobConfig {
 
     pub fn build_indexer_config(&self) -> Result<EventIndexerConfig> {
         EventIndexerConfig::new(self.block_span, self.forward_scan_overlap)
+            .and_then(|config| config.with_max_reorg_depth(self.max_reorg_depth))
             .context("failed to construct EventIndexerConfig")
     }
 }
@@ -158,6 +181,7 @@ impl Default for EventJobConfig {
             interval_ms: default_event_interval_ms(),
             block_span: default_block_span(),
             forward_scan_overlap: default_forward_overlap(),
+            max_reorg_depth: default_max_reorg_depth(),
         }
     }
 }
@@ -211,6 +235,33 @@ impl Default for TreeJobConfig {
     }
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuditJobConfig {
+    #[serde(default = "default_audit_interval_ms")]
+    pub interval_ms: u64,
+}
+
+impl AuditJobConfig {
+    fn ensure_valid(&self) -> Result<()> {
+        if self.interval_ms == 0 {
+            return Err(anyhow!("hash chain audit interval must be positive"));
+        }
+        Ok(())
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+impl Default for AuditJobConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: default_audit_interval_ms(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RootJobConfig {
     pub interval_ms: u64,
@@ -221,6 +272,8 @@ pub struct RootJobConfig {
     pub prover_url: Url,
     pub submitter_private_key: B256,
     pub artifacts_dir: PathBuf,
+    /// SHA-256 of the expected `manifest.json`; artifacts matching it skip point validation.
+    pub artifacts_manifest_sha256: Option<String>,
 }
 
 impl RootJobConfig {
@@ -233,6 +286,7 @@ impl RootJobConfig {
         prover_url: String,
         submitter_private_key: String,
         artifacts_dir: Option<String>,
+        artifacts_manifest_sha256: Option<String>,
     ) -> Result<Self> {
         if interval_ms == 0 {
             return Err(anyhow!("root prover job interval must be positive"));
@@ -276,6 +330,7 @@ impl RootJobConfig {
             prover_url,
             submitter_private_key,
             artifacts_dir,
+            artifacts_manifest_sha256,
         })
     }
 
@@ -300,6 +355,10 @@ fn default_forward_overlap() -> u64 {
     FORWARD_SCAN_OVERLAP_RECOMMENDED
 }
 
+fn default_max_reorg_depth() -> u64 {
+    MAX_REORG_DEPTH_RECOMMENDED
+}
+
 fn default_tree_interval_ms() -> u64 {
     DEFAULT_TREE_INTERVAL_MS
 }
@@ -316,6 +375,10 @@ fn default_tree_batch_size() -> usize {
     DEFAULT_TREE_BATCH_SIZE
 }
 
+fn default_audit_interval_ms() -> u64 {
+    DEFAULT_AUDIT_INTERVAL_MS
+}
+
 fn default_root_interval_ms() -> u64 {
     DEFAULT_ROOT_INTERVAL_MS
 }
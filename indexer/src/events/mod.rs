@@ -1,19 +1,34 @@
+mod quorum;
+mod reorg;
+
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::num::NonZeroU64;
 
-use alloy::primitives::U256;
-use api_types::indexer::IndexedEvent;
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{B256, U256},
+};
+use log::warn;
 use sqlx::{FromRow, PgPool, Postgres, Transaction};
 use thiserror::Error;
 
-use client_common::contracts::{ContractError, z_erc20::ZErc20Contract};
-use client_common::tokens::TokenMetadata;
+use crate::trees::DbMerkleTreeError;
+use client_common::contracts::{
+    ContractError,
+    z_erc20::{IndexedTransferLog, ZErc20Contract},
+};
+use client_common::tokens::{Finality, TokenMetadata};
+
+pub use quorum::RpcQuorum;
 
 pub const BLOCK_SPAN_RECOMMENDED: u64 = 5_000;
 pub const FORWARD_SCAN_OVERLAP_RECOMMENDED: u64 = 10;
+pub const MAX_REORG_DEPTH_RECOMMENDED: u64 = 64;
 const VALUE_BYTES: usize = 32;
 const EVENTS_TABLE: &str = "indexed_transfer_events";
 const STATE_TABLE: &str = "event_indexer_state";
+const BLOCKS_TABLE: &str = "indexed_blocks";
 const TOKENS_TABLE: &str = "tokens";
 
 pub type Result<T> = std::result::Result<T, EventIndexerError>;
@@ -46,6 +61,29 @@ pub enum EventIndexerError {
         #[source]
         source: sqlx::Error,
     },
+    #[error("invalid block hash length for block {block}: expected 32, got {len}")]
+    InvalidBlockHash { block: u64, len: usize },
+    #[error("reorg reaches deeper than the {depth} tracked blocks; manual resync required")]
+    ReorgTooDeep { depth: u64 },
+    #[error("rpc quorum needs at least two providers, got {members}")]
+    QuorumTooSmall { members: usize },
+    #[error("rpc provider {provider} failed during {action}")]
+    QuorumProvider {
+        provider: String,
+        action: &'static str,
+        #[source]
+        source: ContractError,
+    },
+    #[error("rpc providers disagree on {what}; diverging: {}", divergent.join(", "))]
+    QuorumMismatch {
+        what: &'static str,
+        divergent: Vec<String>,
+    },
+    #[error("failed rolling back merkle state after reorg")]
+    Rollback {
+        #[source]
+        source: DbMerkleTreeError,
+    },
 }
 
 impl EventIndexerError {
@@ -62,6 +100,7 @@ impl EventIndexerError {
 pub struct EventIndexerConfig {
     block_span: NonZeroU64,
     forward_scan_overlap: u64,
+    max_reorg_depth: NonZeroU64,
 }
 
 impl EventIndexerConfig {
@@ -74,9 +113,23 @@ impl EventIndexerConfig {
         Ok(Self {
             block_span,
             forward_scan_overlap,
+            max_reorg_depth: NonZeroU64::new(MAX_REORG_DEPTH_RECOMMENDED)
+                .expect("recommended reorg depth is positive"),
         })
     }
 
+    /// How many blocks below the newest indexed block keep their hashes for reorg detection. A
+    /// reorg deeper than this stops the sync instead of rolling back.
+    pub fn with_max_reorg_depth(mut self, max_reorg_depth: u64) -> Result<Self> {
+        let Some(max_reorg_depth) = NonZeroU64::new(max_reorg_depth) else {
+            return Err(EventIndexerError::NonPositiveConfig {
+                label: "max_reorg_depth",
+            });
+        };
+        self.max_reorg_depth = max_reorg_depth;
+        Ok(self)
+    }
+
     pub fn block_span(&self) -> NonZeroU64 {
         self.block_span
     }
@@ -84,6 +137,10 @@ impl EventIndexerConfig {
     pub fn forward_scan_overlap(&self) -> u64 {
         self.forward_scan_overlap
     }
+
+    pub fn max_reorg_depth(&self) -> NonZeroU64 {
+        self.max_reorg_depth
+    }
 }
 
 pub struct EventIndexer {
@@ -92,6 +149,8 @@ pub struct EventIndexer {
     deployed_block_number: u64,
     partitions: EventIndexerPartitions,
     config: EventIndexerConfig,
+    finality: Finality,
+    quorum: Option<RpcQuorum>,
 }
 
 impl EventIndexer {
@@ -110,9 +169,27 @@ impl EventIndexer {
             deployed_block_number,
             partitions,
             config,
+            finality: Finality::default(),
+            quorum: None,
         })
     }
 
+    /// Events in blocks past the finality rule are indexed but only count towards the seen
+    /// index; the final index that feeds the tree stops at the last final block.
+    pub fn with_finality(mut self, finality: Finality) -> Self {
+        self.finality = finality;
+        self
+    }
+
+    /// Cross-checks the chain tip, the block hashes used for reorg detection, the final block,
+    /// `index()`, `hashChain()` and the `IndexedTransfer` logs against every member of `quorum`
+    /// instead of trusting the indexer's own contract handle.
+    /// A disagreement fails the sync before the contiguous index can move.
+    pub fn with_quorum(mut self, quorum: RpcQuorum) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
     pub async fn sync(&self) -> Result<()> {
         self.partitions.ensure(&self.pool).await?;
 
@@ -123,18 +200,43 @@ impl EventIndexer {
         )
         .await?;
 
-        let latest_block = self
-            .contract
-            .latest_block()
-            .await
-            .map_err(|err| EventIndexerError::contract("latest_block", err))?;
-        let contract_next_index = self
-            .contract
-            .index()
-            .await
-            .map_err(|err| EventIndexerError::contract("index", err))?;
+        let latest_block = self.latest_block().await?;
+        let contract_next_index = self.contract_next_index(latest_block).await?;
         let expected_last_index = contract_next_index.checked_sub(1);
 
+        let max_reorg_depth = self.config.max_reorg_depth().get();
+        if let Some(first_orphaned_block) = reorg::find_first_orphaned_block(
+            self,
+            &self.pool,
+            self.partitions.token_id(),
+            self.deployed_block_number,
+            max_reorg_depth,
+        )
+        .await?
+        {
+            let rollback = reorg::rollback_to_block(
+                &self.pool,
+                self.partitions.token_id(),
+                first_orphaned_block,
+            )
+            .await?;
+            warn!(
+                "reorg detected for token {}: rolled back from block {} (first orphaned event index {:?})",
+                self.partitions.token_id(),
+                rollback.first_orphaned_block,
+                rollback.first_orphaned_index
+            );
+            state = ensure_state_row(
+                &self.pool,
+                self.partitions.token_id(),
+                self.deployed_block_number,
+            )
+            .await?;
+        }
+
+        // fetched before any log so that a reorg racing the scan leaves a stale tip behind
+        let latest_hash = self.block_hash(latest_block).await?;
+
         let forward_start = state
             .last_synced_block
             .saturating_sub(self.config.forward_scan_overlap())
@@ -144,6 +246,22 @@ impl EventIndexer {
             self.scan_chunked(forward_start, latest_block).await?;
         }
 
+        if let Some(latest_hash) = latest_hash {
+            reorg::record_block_hashes(
+                &self.pool,
+                self.partitions.token_id(),
+                &[(latest_block, latest_hash)],
+            )
+            .await?;
+        }
+        reorg::prune_block_hashes(
+            &self.pool,
+            self.partitions.token_id(),
+            latest_block,
+            max_reorg_depth,
+        )
+        .await?;
+
         persist_sync_watermark(
             &self.pool,
             self.partitions.token_id(),
@@ -158,9 +276,81 @@ impl EventIndexer {
             .backfill_missing_indices(state, expected_last_index, latest_block)
             .await?;
 
+        if let Some(final_block) = self.final_block(latest_block).await? {
+            advance_final_index(&self.pool, self.partitions.token_id(), final_block).await?;
+        }
+
         Ok(())
     }
 
+    async fn latest_block(&self) -> Result<u64> {
+        match &self.quorum {
+            Some(quorum) => quorum.latest_block().await,
+            None => self
+                .contract
+                .latest_block()
+                .await
+                .map_err(|err| EventIndexerError::contract("latest_block", err)),
+        }
+    }
+
+    async fn contract_next_index(&self, latest_block: u64) -> Result<u64> {
+        match &self.quorum {
+            Some(quorum) => quorum.index_at(latest_block).await,
+            None => self
+                .contract
+                .index()
+                .await
+                .map_err(|err| EventIndexerError::contract("index", err)),
+        }
+    }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<B256>> {
+        match &self.quorum {
+            Some(quorum) => quorum.block_hash_at(number).await,
+            None => self
+                .contract
+                .block_hash(number)
+                .await
+                .map_err(|err| EventIndexerError::contract("block_hash", err)),
+        }
+    }
+
+    async fn indexed_transfer_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedTransferLog>> {
+        match &self.quorum {
+            Some(quorum) => quorum.indexed_transfer_logs(from_block, to_block).await,
+            None => self
+                .contract
+                .get_indexed_transfer_logs(from_block, to_block)
+                .await
+                .map_err(|err| EventIndexerError::contract("get_indexed_transfer_logs", err)),
+        }
+    }
+
+    async fn final_block(&self, latest_block: u64) -> Result<Option<u64>> {
+        let tag = match self.finality {
+            Finality::Latest => return Ok(Some(latest_block)),
+            Finality::Confirmations(confirmations) => {
+                return Ok(latest_block.checked_sub(confirmations));
+            }
+            Finality::Safe => BlockNumberOrTag::Safe,
+            Finality::Finalized => BlockNumberOrTag::Finalized,
+        };
+        let block = match &self.quorum {
+            Some(quorum) => quorum.block_number_at(tag).await?,
+            None => self
+                .contract
+                .block_number_at(tag)
+                .await
+                .map_err(|err| EventIndexerError::contract("block_number_at", err))?,
+        };
+        Ok(block.map(|block| block.min(latest_block)))
+    }
+
     async fn scan_chunked(&self, from_block: u64, to_block: u64) -> Result<()> {
         if from_block > to_block {
             return Ok(());
@@ -171,11 +361,7 @@ impl EventIndexer {
         let mut from = from_block;
         while from <= to_block {
             let to = to_block.min(from.saturating_add(block_span - 1));
-            let fetched = self
-                .contract
-                .get_indexed_transfer_events(from, to)
-                .await
-                .map_err(|err| EventIndexerError::contract("get_indexed_transfer_events", err))?;
+            let fetched = self.indexed_transfer_logs(from, to).await?;
 
             if !fetched.is_empty() {
                 insert_events(&self.pool, self.partitions.token_id(), &fetched).await?;
@@ -268,6 +454,7 @@ struct EventIndexerPartitions {
     token_id: i64,
     events_partition: String,
     state_partition: String,
+    blocks_partition: String,
 }
 
 impl EventIndexerPartitions {
@@ -278,10 +465,12 @@ impl EventIndexerPartitions {
         let suffix = format!("p{token_id}");
         let events_partition = format!("{EVENTS_TABLE}_{suffix}");
         let state_partition = format!("{STATE_TABLE}_{suffix}");
+        let blocks_partition = format!("{BLOCKS_TABLE}_{suffix}");
         Ok(Self {
             token_id,
             events_partition,
             state_partition,
+            blocks_partition,
         })
     }
 
@@ -312,6 +501,17 @@ impl EventIndexerPartitions {
             .await
             .map_err(|err| EventIndexerError::database("ensure state partition", err))?;
 
+        let blocks_sql = format!(
+            "CREATE TABLE IF NOT EXISTS {partition} PARTITION OF {parent} FOR VALUES IN ({value})",
+            partition = self.blocks_partition,
+            parent = BLOCKS_TABLE,
+            value = self.token_id,
+        );
+        sqlx::query(&blocks_sql)
+            .execute(pool)
+            .await
+            .map_err(|err| EventIndexerError::database("ensure blocks partition", err))?;
+
         Ok(())
     }
 }
@@ -528,6 +728,39 @@ async fn advance_contiguous_index(pool: &PgPool, token_id: i64) -> Result<Indexe
     })
 }
 
+/// Moves the final index to the last contiguous event mined at or below `final_block`.
+async fn advance_final_index(pool: &PgPool, token_id: i64, final_block: u64) -> Result<()> {
+    let final_block = to_i64(final_block, "final_block")?;
+    let update_sql = format!(
+        r#"
+        UPDATE {state_table} AS state
+        SET final_block = $2,
+            final_index = COALESCE(
+                (
+                    SELECT MAX(event_index)
+                    FROM {events_table}
+                    WHERE token_id = $1
+                      AND event_index <= state.contiguous_index
+                      AND eth_block_number <= $2
+                ),
+                -1
+            ),
+            updated_at = NOW()
+        WHERE token_id = $1
+        "#,
+        state_table = STATE_TABLE,
+        events_table = EVENTS_TABLE,
+    );
+    sqlx::query(&update_sql)
+        .bind(token_id)
+        .bind(final_block)
+        .execute(pool)
+        .await
+        .map_err(|err| EventIndexerError::database("update final index", err))?;
+
+    Ok(())
+}
+
 async fn find_gap_anchor(
     pool: &PgPool,
     token_id: i64,
@@ -587,8 +820,9 @@ async fn find_gap_anchor(
     }))
 }
 
-async fn insert_events(pool: &PgPool, token_id: i64, events: &[IndexedEvent]) -> Result<()> {
-    if events.is_empty() {
+/// Upserts the events and records the hash of every block that emitted one, in one transaction.
+async fn insert_events(pool: &PgPool, token_id: i64, logs: &[IndexedTransferLog]) -> Result<()> {
+    if logs.is_empty() {
         return Ok(());
     }
 
@@ -617,7 +851,9 @@ async fn insert_events(pool: &PgPool, token_id: i64, events: &[IndexedEvent]) ->
         events_table = EVENTS_TABLE,
     );
 
-    for event in events {
+    let mut block_hashes = BTreeMap::new();
+    for IndexedTransferLog { event, block_hash } in logs {
+        block_hashes.insert(event.eth_block_number, *block_hash);
         let index = to_i64(event.event_index, "event index")?;
         let block = to_i64(event.eth_block_number, "event block number")?;
         let from = event.from.as_slice();
@@ -639,6 +875,9 @@ async fn insert_events(pool: &PgPool, token_id: i64, events: &[IndexedEvent]) ->
             })?;
     }
 
+    let block_hashes: Vec<_> = block_hashes.into_iter().collect();
+    reorg::record_block_hashes(&mut *tx, token_id, &block_hashes).await?;
+
     tx.commit()
         .await
         .map_err(|err| EventIndexerError::database("commit events insert transaction", err))?;
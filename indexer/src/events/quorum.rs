@@ -0,0 +1,163 @@
+use std::future::Future;
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{B256, U256},
+};
+
+use client_common::contracts::{
+    ContractError,
+    z_erc20::{IndexedTransferLog, ZErc20Contract},
+};
+
+use super::{EventIndexerError, Result};
+
+/// Independently queried RPC providers of one token. Every read the event sync relies on must
+/// come back identical from all of them; otherwise the sync stops before touching the indexed
+/// state and names the providers that disagree with the majority.
+#[derive(Clone)]
+pub struct RpcQuorum {
+    members: Vec<QuorumMember>,
+}
+
+#[derive(Clone)]
+struct QuorumMember {
+    label: String,
+    contract: ZErc20Contract,
+}
+
+impl RpcQuorum {
+    /// `members` pairs each contract handle with the label used to report it; labels end up in
+    /// logs, so they should not carry RPC credentials.
+    pub fn new(members: Vec<(String, ZErc20Contract)>) -> Result<Self> {
+        if members.len() < 2 {
+            return Err(EventIndexerError::QuorumTooSmall {
+                members: members.len(),
+            });
+        }
+        let members = members
+            .into_iter()
+            .map(|(label, contract)| QuorumMember { label, contract })
+            .collect();
+        Ok(Self { members })
+    }
+
+    /// Highest block every member has reached, so a lagging provider holds the sync back instead
+    /// of answering for blocks it has not seen.
+    pub(super) async fn latest_block(&self) -> Result<u64> {
+        let tips = self
+            .fetch("latest_block", |contract| async move {
+                contract.latest_block().await
+            })
+            .await?;
+        Ok(tips.into_iter().min().expect("quorum has members"))
+    }
+
+    /// Agreed `index()` at `block`, after checking that the members also agree on `hashChain()`
+    /// there.
+    pub(super) async fn index_at(&self, block: u64) -> Result<u64> {
+        let hash_chains = self
+            .fetch("hash_chain_at", |contract| async move {
+                contract.hash_chain_at(block).await
+            })
+            .await?;
+        self.agree::<U256>("hashChain()", hash_chains)?;
+
+        let indices = self
+            .fetch("index_at", |contract| async move {
+                contract.index_at(block).await
+            })
+            .await?;
+        self.agree("index()", indices)
+    }
+
+    /// Agreed hash of the canonical block at `number`, `None` when the members agree that the
+    /// chain does not reach it.
+    pub(super) async fn block_hash_at(&self, number: u64) -> Result<Option<B256>> {
+        let hashes = self
+            .fetch("block_hash", |contract| async move {
+                contract.block_hash(number).await
+            })
+            .await?;
+        self.agree("block hash", hashes)
+    }
+
+    /// Agreed number of the block `tag` resolves to, e.g. the safe or finalized block.
+    pub(super) async fn block_number_at(&self, tag: BlockNumberOrTag) -> Result<Option<u64>> {
+        let blocks = self
+            .fetch("block_number_at", |contract| async move {
+                contract.block_number_at(tag).await
+            })
+            .await?;
+        self.agree("block number", blocks)
+    }
+
+    pub(super) async fn indexed_transfer_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedTransferLog>> {
+        let logs = self
+            .fetch("get_indexed_transfer_logs", |contract| async move {
+                contract
+                    .get_indexed_transfer_logs(from_block, to_block)
+                    .await
+            })
+            .await?;
+        self.agree("IndexedTransfer logs", logs)
+    }
+
+    async fn fetch<T, F, Fut>(&self, action: &'static str, read: F) -> Result<Vec<T>>
+    where
+        F: Fn(ZErc20Contract) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ContractError>>,
+    {
+        let mut values = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            let value = read(member.contract.clone()).await.map_err(|source| {
+                EventIndexerError::QuorumProvider {
+                    provider: member.label.clone(),
+                    action,
+                    source,
+                }
+            })?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn agree<T: PartialEq>(&self, what: &'static str, values: Vec<T>) -> Result<T> {
+        match divergent_members(&values) {
+            None => Ok(values.into_iter().next().expect("quorum has members")),
+            Some(divergent) => Err(EventIndexerError::QuorumMismatch {
+                what,
+                divergent: divergent
+                    .into_iter()
+                    .map(|position| self.members[position].label.clone())
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// Positions of the answers outside the strict majority, or of every answer when there is no
+/// majority to side with. `None` when all answers are equal.
+fn divergent_members<T: PartialEq>(values: &[T]) -> Option<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (position, value) in values.iter().enumerate() {
+        match groups.iter_mut().find(|group| values[group[0]] == *value) {
+            Some(group) => group.push(position),
+            None => groups.push(vec![position]),
+        }
+    }
+    if groups.len() <= 1 {
+        return None;
+    }
+
+    let majority = groups.iter().find(|group| group.len() * 2 > values.len());
+    Some(
+        (0..values.len())
+            .filter(|position| majority.is_none_or(|group| !group.contains(position)))
+            .collect(),
+    )
+}
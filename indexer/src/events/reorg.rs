@@ -0,0 +1,362 @@
+use alloy::primitives::B256;
+use sqlx::{Executor, FromRow, PgPool, Postgres, QueryBuilder, Transaction};
+
+use super::{
+    BLOCKS_TABLE, EVENTS_TABLE, EventIndexer, EventIndexerError, Result, STATE_TABLE, to_i64,
+    to_u64,
+};
+use crate::trees::rollback_merkle_state;
+
+const ROOT_PROOFS_TABLE: &str = "root_ivc_proofs";
+const ROOT_STATE_TABLE: &str = "root_prover_state";
+const BLOCK_HASH_BYTES: usize = 32;
+
+#[derive(FromRow)]
+struct BlockHashRow {
+    block_number: i64,
+    block_hash: Vec<u8>,
+}
+
+/// Outcome of rolling the indexed state back past a reorg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ReorgRollback {
+    /// Lowest block whose indexed contents were discarded.
+    pub first_orphaned_block: u64,
+    /// Lowest event index that was discarded, if any event sat on an orphaned block.
+    pub first_orphaned_index: Option<u64>,
+}
+
+pub(super) async fn record_block_hashes<'c>(
+    executor: impl Executor<'c, Database = Postgres>,
+    token_id: i64,
+    blocks: &[(u64, B256)],
+) -> Result<()> {
+    if blocks.is_empty() {
+        return Ok(());
+    }
+
+    let rows = blocks
+        .iter()
+        .map(|(number, hash)| Ok((to_i64(*number, "indexed block number")?, *hash)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut builder = QueryBuilder::<Postgres>::new(format!(
+        "INSERT INTO {BLOCKS_TABLE} (token_id, block_number, block_hash)"
+    ));
+    builder.push_values(&rows, |mut b, (number, hash)| {
+        b.push_bind(token_id);
+        b.push_bind(*number);
+        b.push_bind(hash.as_slice());
+    });
+    builder.push(
+        " ON CONFLICT (token_id, block_number) DO UPDATE SET block_hash = EXCLUDED.block_hash",
+    );
+    builder
+        .build()
+        .execute(executor)
+        .await
+        .map_err(|err| EventIndexerError::database("record indexed block hashes", err))?;
+
+    Ok(())
+}
+
+/// Keeps only the block hashes within `max_reorg_depth` of `newest_block`.
+pub(super) async fn prune_block_hashes(
+    pool: &PgPool,
+    token_id: i64,
+    newest_block: u64,
+    max_reorg_depth: u64,
+) -> Result<()> {
+    let floor = to_i64(
+        newest_block.saturating_sub(max_reorg_depth),
+        "block hash retention floor",
+    )?;
+    let sql = format!("DELETE FROM {BLOCKS_TABLE} WHERE token_id = $1 AND block_number < $2");
+    sqlx::query(&sql)
+        .bind(token_id)
+        .bind(floor)
+        .execute(pool)
+        .await
+        .map_err(|err| EventIndexerError::database("prune indexed block hashes", err))?;
+    Ok(())
+}
+
+/// Compares the recorded block hashes against the canonical chain and returns the lowest block
+/// whose indexed contents were orphaned, or `None` when the newest recorded block is still
+/// canonical.
+///
+/// Only the newest hash is fetched while the chain is stable. After a mismatch every retained hash
+/// is checked, because a reorg landing between two scans can leave orphaned logs below blocks that
+/// were recorded from the new chain. Canonical hashes are read through `indexer`, so they come
+/// from its RPC quorum when one is configured.
+pub(super) async fn find_first_orphaned_block(
+    indexer: &EventIndexer,
+    pool: &PgPool,
+    token_id: i64,
+    deployed_block_number: u64,
+    max_reorg_depth: u64,
+) -> Result<Option<u64>> {
+    let sql = format!(
+        r#"
+        SELECT block_number, block_hash
+        FROM {BLOCKS_TABLE}
+        WHERE token_id = $1
+        ORDER BY block_number DESC
+        "#
+    );
+    let rows = sqlx::query_as::<_, BlockHashRow>(&sql)
+        .bind(token_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|err| EventIndexerError::database("load indexed block hashes", err))?;
+
+    let Some(newest) = rows.first() else {
+        return Ok(None);
+    };
+    let newest_block = to_u64(newest.block_number, "newest indexed block")?;
+    if is_canonical(indexer, newest_block, &newest.block_hash).await? {
+        return Ok(None);
+    }
+
+    // everything above the highest canonical hash below the lowest mismatch is rescanned
+    let floor = newest_block
+        .saturating_sub(max_reorg_depth)
+        .max(deployed_block_number);
+    let mut checked = vec![(newest_block, false)];
+    for row in rows.iter().skip(1) {
+        let block = to_u64(row.block_number, "indexed block")?;
+        if block < floor {
+            break;
+        }
+        checked.push((block, is_canonical(indexer, block, &row.block_hash).await?));
+    }
+
+    let lowest_orphaned = checked
+        .iter()
+        .filter(|(_, canonical)| !canonical)
+        .map(|(block, _)| *block)
+        .min()
+        .unwrap_or(newest_block);
+    let anchor = checked
+        .iter()
+        .filter(|(block, canonical)| *canonical && *block < lowest_orphaned)
+        .map(|(block, _)| *block)
+        .max();
+
+    match anchor {
+        Some(anchor) => Ok(Some(anchor + 1)),
+        None if floor == deployed_block_number => Ok(Some(deployed_block_number)),
+        None => Err(EventIndexerError::ReorgTooDeep {
+            depth: max_reorg_depth,
+        }),
+    }
+}
+
+async fn is_canonical(indexer: &EventIndexer, block: u64, recorded: &[u8]) -> Result<bool> {
+    if recorded.len() != BLOCK_HASH_BYTES {
+        return Err(EventIndexerError::InvalidBlockHash {
+            block,
+            len: recorded.len(),
+        });
+    }
+    let canonical = indexer.block_hash(block).await?;
+    Ok(canonical.is_some_and(|hash| hash.as_slice() == recorded))
+}
+
+/// Discards everything indexed from `first_orphaned_block` onwards in one transaction: transfer
+/// events and their block hashes, the Merkle leaves they produced, and any root IVC proof that
+/// folded one of them. The sync watermark moves back so the next sync rescans the new branch.
+pub(super) async fn rollback_to_block(
+    pool: &PgPool,
+    token_id: i64,
+    first_orphaned_block: u64,
+) -> Result<ReorgRollback> {
+    let block_i64 = to_i64(first_orphaned_block, "first orphaned block")?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| EventIndexerError::database("begin reorg rollback transaction", err))?;
+
+    let lock_sql = format!(
+        r#"
+        SELECT contiguous_index, last_synced_block
+        FROM {STATE_TABLE}
+        WHERE token_id = $1
+        FOR UPDATE
+        "#
+    );
+    let (contiguous_index, last_synced_block) = sqlx::query_as::<_, (i64, i64)>(&lock_sql)
+        .bind(token_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| EventIndexerError::database("lock indexer state for rollback", err))?;
+
+    let orphan_sql = format!(
+        r#"
+        SELECT MIN(event_index)
+        FROM {EVENTS_TABLE}
+        WHERE token_id = $1 AND eth_block_number >= $2
+        "#
+    );
+    let first_orphaned_index: Option<i64> = sqlx::query_scalar(&orphan_sql)
+        .bind(token_id)
+        .bind(block_i64)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| EventIndexerError::database("locate first orphaned event", err))?;
+
+    let mut contiguous_index = contiguous_index;
+    if let Some(first_index) = first_orphaned_index {
+        let delete_sql =
+            format!("DELETE FROM {EVENTS_TABLE} WHERE token_id = $1 AND event_index >= $2");
+        sqlx::query(&delete_sql)
+            .bind(token_id)
+            .bind(first_index)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| EventIndexerError::database("delete orphaned events", err))?;
+
+        // the tree holds one leaf per event, so `first_index` leaves survive the reorg
+        let surviving_leaves = to_u64(first_index, "first orphaned event index")?;
+        rollback_merkle_state(&mut tx, token_id, surviving_leaves)
+            .await
+            .map_err(|source| EventIndexerError::Rollback { source })?;
+        rollback_root_proofs(&mut tx, token_id, first_index).await?;
+
+        contiguous_index = contiguous_index.min(first_index - 1);
+    }
+
+    let blocks_sql =
+        format!("DELETE FROM {BLOCKS_TABLE} WHERE token_id = $1 AND block_number >= $2");
+    sqlx::query(&blocks_sql)
+        .bind(token_id)
+        .bind(block_i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| EventIndexerError::database("delete orphaned block hashes", err))?;
+
+    let update_sql = format!(
+        r#"
+        UPDATE {STATE_TABLE}
+        SET contiguous_index = $1,
+            contiguous_block = (
+                SELECT eth_block_number
+                FROM {EVENTS_TABLE}
+                WHERE token_id = $3 AND event_index = $1
+            ),
+            last_synced_block = $2,
+            final_index = LEAST(final_index, $1),
+            final_block = LEAST(final_block, $2),
+            updated_at = NOW()
+        WHERE token_id = $3
+        "#
+    );
+    sqlx::query(&update_sql)
+        .bind(contiguous_index)
+        .bind(last_synced_block.min(block_i64 - 1).max(0))
+        .bind(token_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| EventIndexerError::database("rewind indexer state", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| EventIndexerError::database("commit reorg rollback", err))?;
+
+    Ok(ReorgRollback {
+        first_orphaned_block,
+        first_orphaned_index: first_orphaned_index
+            .map(|index| to_u64(index, "first orphaned event index"))
+            .transpose()?,
+    })
+}
+
+/// Drops root IVC proofs that folded a leaf at or after `first_index` and rewinds the prover
+/// state to the newest proof that survives. When the submitted base itself was orphaned the
+/// state row is removed, and the prover re-anchors on the verifier's `latestProvedIndex`.
+async fn rollback_root_proofs(
+    tx: &mut Transaction<'_, Postgres>,
+    token_id: i64,
+    first_index: i64,
+) -> Result<()> {
+    let state_sql = format!(
+        "SELECT base_index, last_compiled_index FROM {ROOT_STATE_TABLE} WHERE token_id = $1 FOR UPDATE"
+    );
+    let state = sqlx::query_as::<_, (i64, i64)>(&state_sql)
+        .bind(token_id)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(|err| EventIndexerError::database("lock root prover state for rollback", err))?;
+
+    if matches!(state, Some((base_index, _)) if base_index > first_index) {
+        let delete_proofs_sql = format!("DELETE FROM {ROOT_PROOFS_TABLE} WHERE token_id = $1");
+        sqlx::query(&delete_proofs_sql)
+            .bind(token_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(|err| EventIndexerError::database("delete orphaned root proofs", err))?;
+        let delete_state_sql = format!("DELETE FROM {ROOT_STATE_TABLE} WHERE token_id = $1");
+        sqlx::query(&delete_state_sql)
+            .bind(token_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(|err| EventIndexerError::database("reset root prover state", err))?;
+        return Ok(());
+    }
+
+    // a proof ending at `first_index` only folds surviving leaves
+    let proofs_sql =
+        format!("DELETE FROM {ROOT_PROOFS_TABLE} WHERE token_id = $1 AND end_index > $2");
+    sqlx::query(&proofs_sql)
+        .bind(token_id)
+        .bind(first_index)
+        .execute(tx.as_mut())
+        .await
+        .map_err(|err| EventIndexerError::database("delete orphaned root proofs", err))?;
+
+    let Some((base_index, last_compiled_index)) = state else {
+        return Ok(());
+    };
+    if last_compiled_index <= first_index {
+        return Ok(());
+    }
+
+    let resume_sql = format!(
+        "SELECT MAX(end_index) FROM {ROOT_PROOFS_TABLE} WHERE token_id = $1 AND end_index >= $2"
+    );
+    let resume_index: Option<i64> = sqlx::query_scalar(&resume_sql)
+        .bind(token_id)
+        .bind(base_index)
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(|err| EventIndexerError::database("locate surviving root proof", err))?;
+    let resume_index = resume_index.unwrap_or(base_index);
+
+    let update_sql = format!(
+        r#"
+        UPDATE {ROOT_STATE_TABLE}
+        SET last_compiled_index = $2,
+            last_submitted_index = LEAST(last_submitted_index, $2),
+            pending_reserved_hash_chain = CASE
+                WHEN pending_reserved_index > $3 THEN NULL
+                ELSE pending_reserved_hash_chain
+            END,
+            pending_reserved_index = CASE
+                WHEN pending_reserved_index > $3 THEN NULL
+                ELSE pending_reserved_index
+            END,
+            updated_at = NOW()
+        WHERE token_id = $1
+        "#
+    );
+    sqlx::query(&update_sql)
+        .bind(token_id)
+        .bind(resume_index)
+        .bind(first_index)
+        .execute(tx.as_mut())
+        .await
+        .map_err(|err| EventIndexerError::database("rewind root prover state", err))?;
+
+    Ok(())
+}
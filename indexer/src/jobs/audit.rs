@@ -0,0 +1,380 @@
+use std::{convert::TryFrom, fmt, time::Instant};
+
+use alloy::primitives::U256;
+use anyhow::{Context, Result};
+use api_types::indexer::HashChainDivergence;
+use log::{debug, error, info};
+use sqlx::{FromRow, PgPool};
+
+use crate::{
+    config::AuditJobConfig,
+    trees::{DbIncrementalMerkleTree, DbMerkleTreeConfig},
+};
+use client_common::{
+    contracts::{
+        utils::{get_provider, get_provider_with_fallback},
+        verifier::VerifierContract,
+        z_erc20::ZErc20Contract,
+    },
+    tokens::{TokenEntry, TokenMetadata},
+};
+
+const AUDIT_TABLE: &str = "hash_chain_audits";
+
+/// Periodically compares the hash chains stored in `merkle_snapshots` with the token's
+/// `hashChain()` at the last final block and with the verifier's reserved checkpoints. The outcome
+/// of the latest audit is kept per token: `/status` reports it and the root prover refuses to work
+/// on a token whose indexed hash chain diverges.
+pub struct HashChainAuditJob {
+    pool: PgPool,
+    tokens: Vec<AuditTokenContext>,
+    interval_ms: u64,
+    tree_height: u32,
+    tree_config: DbMerkleTreeConfig,
+    checkpoints_enabled: bool,
+}
+
+impl HashChainAuditJob {
+    pub async fn run_forever(&self) -> Result<()> {
+        loop {
+            let iteration_started = Instant::now();
+            self.run_once().await;
+            let elapsed = iteration_started.elapsed();
+            let interval = std::time::Duration::from_millis(self.interval_ms);
+            if elapsed < interval {
+                tokio::time::sleep(interval - elapsed).await;
+            }
+        }
+    }
+
+    pub async fn run_once(&self) {
+        for token in &self.tokens {
+            if let Err(err) = self.audit_token(token).await {
+                error!(
+                    "hash chain audit failed for token '{}': {err:?}",
+                    token.label
+                );
+            }
+        }
+    }
+
+    async fn audit_token(&self, token: &AuditTokenContext) -> Result<()> {
+        let Some(token_id) = lookup_token_id(&self.pool, &token.metadata).await? else {
+            debug!(
+                "token '{}' not yet registered in database; waiting for event sync",
+                token.label
+            );
+            return Ok(());
+        };
+
+        let tree = DbIncrementalMerkleTree::new(
+            self.pool.clone(),
+            token_id,
+            self.tree_height,
+            self.tree_config.clone(),
+        )
+        .await
+        .with_context(|| format!("failed to initialise merkle tree for '{}'", token.label))?;
+
+        let checkpoints = self
+            .onchain_checkpoints(token, token_id)
+            .await
+            .with_context(|| {
+                format!("failed to read on-chain checkpoints for '{}'", token.label)
+            })?;
+
+        let mut audited_index = None;
+        let mut divergence = None;
+        for checkpoint in checkpoints {
+            // not ingested yet, or already pruned from the history window
+            let Some(indexed) = tree.hash_chain_at(checkpoint.index).await? else {
+                continue;
+            };
+            audited_index = audited_index.max(Some(checkpoint.index));
+            if indexed != checkpoint.hash_chain {
+                divergence = Some(HashChainDivergence {
+                    checkpoint: checkpoint.kind.to_string(),
+                    index: checkpoint.index,
+                    onchain_hash_chain: checkpoint.hash_chain,
+                    indexed_hash_chain: indexed,
+                });
+                break;
+            }
+        }
+
+        record_audit(&self.pool, token_id, audited_index, divergence.as_ref()).await?;
+
+        match (&divergence, audited_index) {
+            (Some(divergence), _) => error!(
+                "indexed hash chain of '{}' diverges from {} at index {}: on-chain {}, indexed {}",
+                token.label,
+                divergence.checkpoint,
+                divergence.index,
+                divergence.onchain_hash_chain,
+                divergence.indexed_hash_chain
+            ),
+            (None, Some(index)) => info!(
+                "hash chain audit passed for '{}' up to index {}",
+                token.label, index
+            ),
+            (None, None) => debug!("no checkpoint to audit yet for '{}'", token.label),
+        }
+
+        Ok(())
+    }
+
+    async fn onchain_checkpoints(
+        &self,
+        token: &AuditTokenContext,
+        token_id: i64,
+    ) -> Result<Vec<Checkpoint>> {
+        let mut checkpoints = Vec::new();
+
+        // the tree never goes past the final block, so read the token there rather than at the tip
+        if let Some(final_block) = final_block(&self.pool, token_id).await? {
+            let index = token.token_contract.index_at(final_block).await?;
+            if index > 0 {
+                checkpoints.push(Checkpoint {
+                    kind: CheckpointKind::TokenHashChain,
+                    index,
+                    hash_chain: token.token_contract.hash_chain_at(final_block).await?,
+                });
+            }
+        }
+
+        if self.checkpoints_enabled {
+            let reserved = token.verifier_contract.latest_reserved_index().await?;
+            let proved = token.verifier_contract.latest_proved_index().await?;
+            let mut indices = vec![reserved, proved];
+            indices.dedup();
+            for index in indices.into_iter().filter(|index| *index > 0) {
+                checkpoints.push(Checkpoint {
+                    kind: CheckpointKind::ReservedHashChain,
+                    index,
+                    hash_chain: token.verifier_contract.reserved_hash_chain(index).await?,
+                });
+            }
+        }
+
+        Ok(checkpoints)
+    }
+}
+
+pub struct HashChainAuditJobBuilder {
+    pool: PgPool,
+    job_config: AuditJobConfig,
+    tree_config: DbMerkleTreeConfig,
+    tree_height: u32,
+    tokens: Vec<TokenEntry>,
+    checkpoints_enabled: bool,
+}
+
+impl HashChainAuditJobBuilder {
+    pub fn new(
+        pool: PgPool,
+        job_config: AuditJobConfig,
+        tree_config: DbMerkleTreeConfig,
+        tree_height: u32,
+        tokens: Vec<TokenEntry>,
+    ) -> Self {
+        Self {
+            pool,
+            job_config,
+            tree_config,
+            tree_height,
+            tokens,
+            checkpoints_enabled: true,
+        }
+    }
+
+    /// When disabled, only the token's `hashChain()` is audited and the verifier's reserved
+    /// checkpoints are skipped, e.g. when no verifier is deployed.
+    pub fn with_checkpoints_enabled(mut self, enabled: bool) -> Self {
+        self.checkpoints_enabled = enabled;
+        self
+    }
+
+    pub fn into_job(self) -> Result<HashChainAuditJob> {
+        let mut contexts = Vec::with_capacity(self.tokens.len());
+        for token in self.tokens {
+            let provider = if token.rpc_urls.len() == 1 {
+                get_provider(token.rpc_urls.first().expect("rpc urls not empty")).with_context(
+                    || format!("failed to build provider for token '{}'", token.label),
+                )?
+            } else {
+                get_provider_with_fallback(&token.rpc_urls).with_context(|| {
+                    format!(
+                        "failed to build fallback provider for token '{}'",
+                        token.label
+                    )
+                })?
+            };
+
+            contexts.push(AuditTokenContext {
+                label: token.label.clone(),
+                metadata: token.metadata(),
+                token_contract: ZErc20Contract::new(provider.clone(), token.token_address),
+                verifier_contract: VerifierContract::new(provider, token.verifier_address),
+            });
+        }
+
+        Ok(HashChainAuditJob {
+            pool: self.pool,
+            tokens: contexts,
+            interval_ms: self.job_config.interval_ms,
+            tree_height: self.tree_height,
+            tree_config: self.tree_config,
+            checkpoints_enabled: self.checkpoints_enabled,
+        })
+    }
+}
+
+struct AuditTokenContext {
+    label: String,
+    metadata: TokenMetadata,
+    token_contract: ZErc20Contract,
+    verifier_contract: VerifierContract,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckpointKind {
+    TokenHashChain,
+    ReservedHashChain,
+}
+
+impl fmt::Display for CheckpointKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TokenHashChain => f.write_str("hashChain"),
+            Self::ReservedHashChain => f.write_str("reservedHashChains"),
+        }
+    }
+}
+
+struct Checkpoint {
+    kind: CheckpointKind,
+    index: u64,
+    hash_chain: U256,
+}
+
+#[derive(FromRow)]
+struct DivergenceRow {
+    divergent_checkpoint: String,
+    divergent_index: i64,
+    onchain_hash_chain: Vec<u8>,
+    indexed_hash_chain: Vec<u8>,
+}
+
+/// Divergence found by the latest audit of the token, if any.
+pub(crate) async fn load_hash_chain_divergence(
+    pool: &PgPool,
+    token_id: i64,
+) -> Result<Option<HashChainDivergence>, sqlx::Error> {
+    let sql = format!(
+        r#"
+        SELECT divergent_checkpoint, divergent_index, onchain_hash_chain, indexed_hash_chain
+        FROM {audit_table}
+        WHERE token_id = $1 AND divergent_index IS NOT NULL
+        "#,
+        audit_table = AUDIT_TABLE,
+    );
+    let row = sqlx::query_as::<_, DivergenceRow>(&sql)
+        .bind(token_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|row| HashChainDivergence {
+        checkpoint: row.divergent_checkpoint,
+        index: row.divergent_index.max(0) as u64,
+        onchain_hash_chain: U256::from_be_slice(&row.onchain_hash_chain),
+        indexed_hash_chain: U256::from_be_slice(&row.indexed_hash_chain),
+    }))
+}
+
+async fn record_audit(
+    pool: &PgPool,
+    token_id: i64,
+    audited_index: Option<u64>,
+    divergence: Option<&HashChainDivergence>,
+) -> Result<()> {
+    let audited_index = audited_index
+        .map(i64::try_from)
+        .transpose()
+        .context("audited index exceeds i64 range")?;
+    let divergent_index = divergence
+        .map(|divergence| i64::try_from(divergence.index))
+        .transpose()
+        .context("divergent index exceeds i64 range")?;
+    let sql = format!(
+        r#"
+        INSERT INTO {audit_table} (
+            token_id,
+            audited_index,
+            divergent_checkpoint,
+            divergent_index,
+            onchain_hash_chain,
+            indexed_hash_chain,
+            checked_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, NOW())
+        ON CONFLICT (token_id) DO UPDATE
+        SET audited_index = EXCLUDED.audited_index,
+            divergent_checkpoint = EXCLUDED.divergent_checkpoint,
+            divergent_index = EXCLUDED.divergent_index,
+            onchain_hash_chain = EXCLUDED.onchain_hash_chain,
+            indexed_hash_chain = EXCLUDED.indexed_hash_chain,
+            checked_at = EXCLUDED.checked_at
+        "#,
+        audit_table = AUDIT_TABLE,
+    );
+    sqlx::query(&sql)
+        .bind(token_id)
+        .bind(audited_index)
+        .bind(divergence.map(|divergence| divergence.checkpoint.as_str()))
+        .bind(divergent_index)
+        .bind(
+            divergence.map(|divergence| divergence.onchain_hash_chain.to_be_bytes::<32>().to_vec()),
+        )
+        .bind(
+            divergence.map(|divergence| divergence.indexed_hash_chain.to_be_bytes::<32>().to_vec()),
+        )
+        .execute(pool)
+        .await
+        .context("failed to record hash chain audit")?;
+    Ok(())
+}
+
+async fn lookup_token_id(pool: &PgPool, metadata: &TokenMetadata) -> Result<Option<i64>> {
+    let chain_id = i64::try_from(metadata.chain_id)
+        .with_context(|| format!("chain id {} exceeds i64 range", metadata.chain_id))?;
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT id
+        FROM tokens
+        WHERE token_address = $1 AND chain_id = $2
+        "#,
+    )
+    .bind(metadata.token_address.as_slice())
+    .bind(chain_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to lookup token id")
+}
+
+async fn final_block(pool: &PgPool, token_id: i64) -> Result<Option<u64>> {
+    let block: Option<Option<i64>> = sqlx::query_scalar(
+        r#"
+        SELECT final_block
+        FROM event_indexer_state
+        WHERE token_id = $1
+        "#,
+    )
+    .bind(token_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to load final block")?;
+    block
+        .flatten()
+        .map(u64::try_from)
+        .transpose()
+        .context("final block is negative")
+}
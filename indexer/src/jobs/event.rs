@@ -2,18 +2,19 @@ use std::time::Instant;
 
 use anyhow::{Context, Result};
 use log::{debug, error, warn};
+use reqwest::Url;
 use sqlx::PgPool;
 
 use crate::{
     config::EventJobConfig,
-    events::{EventIndexer, EventIndexerConfig},
+    events::{EventIndexer, EventIndexerConfig, RpcQuorum},
 };
 use client_common::{
     contracts::{
         utils::{get_provider, get_provider_with_fallback},
         z_erc20::ZErc20Contract,
     },
-    tokens::{TokenEntry, TokenMetadata},
+    tokens::{Finality, TokenEntry, TokenMetadata},
 };
 
 use super::try_acquire_lock;
@@ -79,7 +80,12 @@ impl EventSyncJob {
             self.indexer_config,
         )
         .await
-        .with_context(|| format!("failed to initialise event indexer for '{}'", token.label))?;
+        .with_context(|| format!("failed to initialise event indexer for '{}'", token.label))?
+        .with_finality(token.finality);
+        let indexer = match &token.quorum {
+            Some(quorum) => indexer.with_quorum(quorum.clone()),
+            None => indexer,
+        };
 
         indexer
             .sync()
@@ -128,15 +134,22 @@ impl EventSyncJobBuilder {
                 })?
             };
 
-            let contract = ZErc20Contract::new(provider, token.token_address)
-                .with_legacy_tx(token.legacy_tx);
+            let contract =
+                ZErc20Contract::new(provider, token.token_address).with_legacy_tx(token.legacy_tx);
+            let quorum = if token.rpc_quorum {
+                Some(build_quorum(&token)?)
+            } else {
+                None
+            };
             let lock_key = token.lock_key_with_salt(EVENT_LOCK_SALT);
             let metadata = token.metadata();
             let context = EventTokenContext {
                 label: token.label.clone(),
                 metadata,
                 deployed_block_number: token.deployed_block_number,
+                finality: token.finality,
                 contract,
+                quorum,
                 lock_key,
             };
             contexts.push(context);
@@ -156,6 +169,31 @@ struct EventTokenContext {
     label: String,
     metadata: TokenMetadata,
     deployed_block_number: u64,
+    finality: Finality,
     contract: ZErc20Contract,
+    quorum: Option<RpcQuorum>,
     lock_key: i64,
 }
+
+/// One single-endpoint provider per RPC url. Members are labelled by position and origin so that
+/// API keys in the url path or query never reach the logs.
+fn build_quorum(token: &TokenEntry) -> Result<RpcQuorum> {
+    let mut members = Vec::with_capacity(token.rpc_urls.len());
+    for (position, rpc_url) in token.rpc_urls.iter().enumerate() {
+        let origin = Url::parse(rpc_url)
+            .with_context(|| format!("invalid rpc url #{position} for token '{}'", token.label))?
+            .origin()
+            .ascii_serialization();
+        let provider = get_provider(rpc_url).with_context(|| {
+            format!(
+                "failed to build quorum provider #{position} for token '{}'",
+                token.label
+            )
+        })?;
+        let contract =
+            ZErc20Contract::new(provider, token.token_address).with_legacy_tx(token.legacy_tx);
+        members.push((format!("#{position} ({origin})"), contract));
+    }
+    RpcQuorum::new(members)
+        .with_context(|| format!("invalid rpc quorum for token '{}'", token.label))
+}
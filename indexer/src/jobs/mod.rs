@@ -1,10 +1,13 @@
+mod audit;
 mod event;
 mod lock;
 mod root;
 mod tree;
 
+pub use audit::{HashChainAuditJob, HashChainAuditJobBuilder};
 pub use event::{EventSyncJob, EventSyncJobBuilder};
 pub use root::{RootProverJob, RootProverJobBuilder};
 pub use tree::{TreeIngestionJob, TreeIngestionJobBuilder};
 
+pub(crate) use audit::load_hash_chain_divergence;
 pub use lock::{LeaseGuard, try_acquire_lock};
@@ -24,7 +24,7 @@ use tokio::time::sleep;
 
 use crate::{
     config::RootJobConfig,
-    jobs::try_acquire_lock,
+    jobs::{load_hash_chain_divergence, try_acquire_lock},
     trees::{DbIncrementalMerkleTree, DbMerkleTreeConfig, HistoricalProof},
 };
 use client_common::{
@@ -37,32 +37,64 @@ use client_common::{
     tokens::{TokenEntry, TokenMetadata},
 };
 use zkp::{
+    artifacts::ArtifactDir,
     nova::{
-        constants::TRANSFER_TREE_HEIGHT,
-        params::{N, NovaParams},
-        root_nova::{RootCircuit, RootExternalInputs},
+        constants::{
+            DEV_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP, STAGING_TRANSFER_TREE_HEIGHT,
+            TRANSFER_TREE_HEIGHT,
+        },
+        decider_verifier::{decider_vp_from_bytes, verify_decider_calldata},
+        params::{DeciderVP, N, NovaParams},
+        root_nova::{RootCircuit, RootExternalInputs, RootStepInputs, root_artifact_prefix},
     },
     utils::{
         convertion::{address_to_fr, fr_to_u256, u256_to_fr},
         poseidon::utils::circom_poseidon_config,
         tree::gadgets::{hash_chain::hash_chain, leaf_hash::compute_leaf_hash},
     },
+    witness::validate_root_transfers,
 };
 
 const ROOT_LOCK_SALT: u64 = 0x524f4f54; // "ROOT"
 
-type RootNovaInstance = N<RootCircuit<Fr>>;
+type RootProverCircuit<const DEPTH: usize> = RootCircuit<Fr, DEPTH, ROOT_TRANSFERS_PER_STEP>;
+type RootNovaInstance<const DEPTH: usize> = N<RootProverCircuit<DEPTH>>;
 type RootIvcProof = IVCProof<G1, G2>;
 
-pub struct RootProverJob {
+/// Root prover job instantiated for the configured `TreeJobConfig.height`.
+pub enum RootProverJob {
+    Dev(RootProver<DEV_TRANSFER_TREE_HEIGHT>),
+    Staging(RootProver<STAGING_TRANSFER_TREE_HEIGHT>),
+    Production(RootProver<TRANSFER_TREE_HEIGHT>),
+}
+
+impl RootProverJob {
+    pub async fn run_forever(&self) -> Result<()> {
+        match self {
+            Self::Dev(job) => job.run_forever().await,
+            Self::Staging(job) => job.run_forever().await,
+            Self::Production(job) => job.run_forever().await,
+        }
+    }
+
+    pub async fn run_once(&self) -> Result<()> {
+        match self {
+            Self::Dev(job) => job.run_once().await,
+            Self::Staging(job) => job.run_once().await,
+            Self::Production(job) => job.run_once().await,
+        }
+    }
+}
+
+pub struct RootProver<const DEPTH: usize> {
     pool: PgPool,
     tokens: Vec<RootTokenContext>,
     tree_config: DbMerkleTreeConfig,
-    tree_height: u32,
     history_window: u64,
     compile_interval: Duration,
     submit_interval: Duration,
-    nova_params: Arc<NovaParams<RootCircuit<Fr>>>,
+    nova_params: Arc<NovaParams<RootProverCircuit<DEPTH>>>,
+    decider_vp: Arc<DeciderVP<RootProverCircuit<DEPTH>>>,
     prover: Arc<dyn DeciderClient>,
     submitter_private_key: B256,
     prover_timeout: Duration,
@@ -70,7 +102,7 @@ pub struct RootProverJob {
     submit_enabled: bool,
 }
 
-impl RootProverJob {
+impl<const DEPTH: usize> RootProver<DEPTH> {
     pub async fn run_forever(&self) -> Result<()> {
         let mut last_compile = Instant::now() - self.compile_interval;
         let mut last_submit = Instant::now() - self.submit_interval;
@@ -165,10 +197,14 @@ impl RootProverJob {
             }
         };
 
+        if self.halted_by_audit(token, token_id).await? {
+            return Ok(());
+        }
+
         let tree = DbIncrementalMerkleTree::new(
             self.pool.clone(),
             token_id,
-            self.tree_height,
+            DEPTH as u32,
             self.tree_config.clone(),
         )
         .await
@@ -278,8 +314,9 @@ impl RootProverJob {
         let mut rng = ChaCha20Rng::from_entropy();
         let mut current_index = state.last_compiled_index;
 
+        let mut contiguous = Vec::with_capacity(events.len());
         for event in events {
-            let expected_index = current_index;
+            let expected_index = current_index + contiguous.len() as u64;
             if event.event_index != expected_index {
                 warn!(
                     "encountered non-contiguous event for '{}': expected {}, got {}",
@@ -287,30 +324,54 @@ impl RootProverJob {
                 );
                 break;
             }
+            contiguous.push(event);
+        }
 
-            let proof = tree
-                .prove(event.event_index + 1, event.event_index)
-                .await
+        for chunk in contiguous.chunks(ROOT_TRANSFERS_PER_STEP) {
+            let mut transfers = Vec::with_capacity(chunk.len());
+            let mut proofs = Vec::with_capacity(chunk.len());
+            for event in chunk {
+                let proof = tree
+                    .prove(event.event_index + 1, event.event_index)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "failed to build merkle proof for '{}' at {}",
+                            token.label, event.event_index
+                        )
+                    })?;
+                transfers.push(to_external_inputs(event.address, event.value, &proof)?);
+                proofs.push(proof);
+            }
+            let step_inputs = RootStepInputs::padded(transfers).ok_or_else(|| {
+                anyhow!("root step holds more than {ROOT_TRANSFERS_PER_STEP} transfers")
+            })?;
+            let prev_root = nova.state().get(2).copied().unwrap_or_else(Fr::zero);
+            validate_root_transfers(current_index, prev_root, &step_inputs.transfers)
                 .with_context(|| {
                     format!(
-                        "failed to build merkle proof for '{}' at {}",
-                        token.label, event.event_index
+                        "invalid root witness for '{}' at index {}",
+                        token.label, current_index
                     )
                 })?;
 
-            let external_inputs = to_external_inputs(event.address, event.value, &proof)?;
-            nova.prove_step(&mut rng, external_inputs, None)
+            nova.prove_step(&mut rng, step_inputs, None)
                 .with_context(|| {
                     format!(
                         "failed to extend nova proof for '{}' at step {}",
-                        token.label, event.event_index
+                        token.label, current_index
                     )
                 })?;
 
-            current_index += 1;
+            current_index += chunk.len() as u64;
             let state_snapshot = nova.state();
             let ivc_proof = nova.ivc_proof();
             if let Err(err) = self.nova_params.verify(ivc_proof.clone()) {
+                // report against the last transfer folded in this step
+                let (event, proof) = chunk
+                    .last()
+                    .zip(proofs.last())
+                    .ok_or_else(|| anyhow!("empty root step"))?;
                 let state_index = state_snapshot.get(0).copied().unwrap_or_else(Fr::zero);
                 let state_hash_chain = state_snapshot.get(1).copied().unwrap_or_else(Fr::zero);
                 let state_root = state_snapshot.get(2).copied().unwrap_or_else(Fr::zero);
@@ -422,16 +483,19 @@ impl RootProverJob {
         )
         .await?;
 
-        let ivc_bytes = if target_index - state.base_index == 1 {
+        // the audit may have flagged the token while this cycle was compiling
+        if self.halted_by_audit(token, token_id).await? {
+            return Ok(state);
+        }
+
+        // The decider needs at least two folds. A range this short may have been compiled in a
+        // single step, so append an all-dummy step; it is a no-op if there already were two.
+        let ivc_bytes = if target_index - state.base_index <= ROOT_TRANSFERS_PER_STEP as u64 {
             // apply dummy step
             let mut nova = load_nova_from_ivc(&self.nova_params, &proof_record.ivc_proof)?;
             let mut rng = ChaCha20Rng::from_entropy();
-            let dummy = RootExternalInputs::<Fr> {
-                is_dummy: true,
-                address: Fr::zero(),
-                value: Fr::zero(),
-                siblings: [Fr::zero(); TRANSFER_TREE_HEIGHT],
-            };
+            let dummy =
+                RootStepInputs::padded(vec![]).context("failed to build dummy root step")?;
             nova.prove_step(&mut rng, dummy, None)
                 .context("failed to append dummy step before submission")?;
             serialize_ivc_proof(&nova.ivc_proof())?
@@ -493,6 +557,20 @@ impl RootProverJob {
             .await
             .context("root prover decider generation failed")?;
 
+        let ivc_proof = deserialize_ivc_proof(&ivc_bytes)?;
+        verify_decider_calldata::<RootProverCircuit<DEPTH>>(
+            &self.decider_vp,
+            &ivc_proof.z_0,
+            &ivc_proof.z_i,
+            &decider,
+        )
+        .with_context(|| {
+            format!(
+                "decider prover returned an invalid root proof for '{}' at index {}",
+                token.label, target_index
+            )
+        })?;
+
         let receipt = self
             .submit_transfer_root(token, &decider)
             .await
@@ -520,6 +598,22 @@ impl RootProverJob {
         Ok(state)
     }
 
+    /// Whether the hash-chain auditor currently flags the token. Proofs over a diverging tree
+    /// would be rejected on-chain, so neither compiling nor a decider job is worth starting.
+    async fn halted_by_audit(&self, token: &RootTokenContext, token_id: i64) -> Result<bool> {
+        let Some(divergence) = load_hash_chain_divergence(&self.pool, token_id)
+            .await
+            .context("failed to load hash chain audit")?
+        else {
+            return Ok(false);
+        };
+        warn!(
+            "root prover halted for '{}': indexed hash chain diverges from {} at index {}",
+            token.label, divergence.checkpoint, divergence.index
+        );
+        Ok(true)
+    }
+
     async fn reserve_hash_chain(&self, token: &RootTokenContext) -> Result<(u64, U256)> {
         let pending = token
             .verifier_contract
@@ -585,6 +679,28 @@ impl RootProverJobBuilder {
     }
 
     pub fn into_job(self) -> Result<RootProverJob> {
+        let height = self.tree_height as usize;
+        if height == DEV_TRANSFER_TREE_HEIGHT || height == STAGING_TRANSFER_TREE_HEIGHT {
+            warn!(
+                "root prover runs on a {height}-level transfer tree; withdraw artifacts only exist \
+                 for {TRANSFER_TREE_HEIGHT} levels, so teleports cannot be redeemed against these roots"
+            );
+        }
+        match height {
+            DEV_TRANSFER_TREE_HEIGHT => self.build().map(RootProverJob::Dev),
+            STAGING_TRANSFER_TREE_HEIGHT => self.build().map(RootProverJob::Staging),
+            TRANSFER_TREE_HEIGHT => self.build().map(RootProverJob::Production),
+            height => bail!(
+                "unsupported tree height {} for the root prover (expected {}, {} or {})",
+                height,
+                DEV_TRANSFER_TREE_HEIGHT,
+                STAGING_TRANSFER_TREE_HEIGHT,
+                TRANSFER_TREE_HEIGHT
+            ),
+        }
+    }
+
+    fn build<const DEPTH: usize>(self) -> Result<RootProver<DEPTH>> {
         let mut contexts = Vec::with_capacity(self.tokens.len());
         for token in self.tokens {
             let provider = if token.rpc_urls.len() == 1 {
@@ -616,7 +732,15 @@ impl RootProverJobBuilder {
             });
         }
 
-        let nova_params = Arc::new(load_root_nova_params(&self.root_config.artifacts_dir)?);
+        let artifacts = match &self.root_config.artifacts_manifest_sha256 {
+            Some(manifest_sha256) => {
+                ArtifactDir::open_trusted(&self.root_config.artifacts_dir, manifest_sha256)
+            }
+            None => ArtifactDir::open(&self.root_config.artifacts_dir),
+        }
+        .context("failed to open root artifacts")?;
+        let nova_params = Arc::new(load_root_nova_params::<DEPTH>(&artifacts)?);
+        let decider_vp = Arc::new(load_root_decider_vp::<DEPTH>(&artifacts)?);
         let prover: Arc<dyn DeciderClient> = match self.prover_override {
             Some(custom) => custom,
             None => Arc::new(HttpDeciderClient::new(
@@ -629,15 +753,15 @@ impl RootProverJobBuilder {
         let compile_interval = self.root_config.interval();
         let submit_interval = self.root_config.submit_interval();
 
-        Ok(RootProverJob {
+        Ok(RootProver {
             pool: self.pool,
             tokens: contexts,
             tree_config: self.tree_config,
-            tree_height: self.tree_height,
             history_window: self.root_config.history_window,
             compile_interval,
             submit_interval,
             nova_params,
+            decider_vp,
             prover,
             submitter_private_key: self.root_config.submitter_private_key,
             prover_timeout: self.root_config.prover_timeout,
@@ -1033,24 +1157,24 @@ async fn load_ivc_record(
     }))
 }
 
-fn to_external_inputs(
+fn to_external_inputs<const DEPTH: usize>(
     address: Address,
     value: U256,
     proof: &HistoricalProof,
-) -> Result<RootExternalInputs<Fr>> {
+) -> Result<RootExternalInputs<Fr, DEPTH>> {
     let siblings_vec = proof.proof.siblings.clone();
-    if siblings_vec.len() != TRANSFER_TREE_HEIGHT {
+    if siblings_vec.len() != DEPTH {
         bail!(
             "unexpected sibling path length: expected {}, got {}",
-            TRANSFER_TREE_HEIGHT,
+            DEPTH,
             siblings_vec.len()
         );
     }
-    let siblings_array: [Fr; TRANSFER_TREE_HEIGHT] = siblings_vec
+    let siblings_array: [Fr; DEPTH] = siblings_vec
         .try_into()
         .map_err(|_| anyhow!("failed to convert sibling path into array"))?;
 
-    Ok(RootExternalInputs::<Fr> {
+    Ok(RootExternalInputs::<Fr, DEPTH> {
         is_dummy: false,
         address: address_to_fr(address),
         value: u256_to_fr(value),
@@ -1072,14 +1196,14 @@ fn parse_u256(bytes: &[u8]) -> Result<U256> {
     Ok(U256::from_be_slice(bytes))
 }
 
-async fn initialise_nova(
-    nova_params: &Arc<NovaParams<RootCircuit<Fr>>>,
+async fn initialise_nova<const DEPTH: usize>(
+    nova_params: &Arc<NovaParams<RootProverCircuit<DEPTH>>>,
     token_id: i64,
     pool: &PgPool,
     tree: &DbIncrementalMerkleTree,
     base_index: u64,
     compiled_index: u64,
-) -> Result<RootNovaInstance> {
+) -> Result<RootNovaInstance<DEPTH>> {
     let z0 = load_state_vector(tree, base_index)
         .await
         .context("failed to load base state vector")?;
@@ -1128,16 +1252,34 @@ async fn load_state_vector(tree: &DbIncrementalMerkleTree, index: u64) -> Result
     Ok(vec![Fr::from(index), u256_to_fr(hash_chain), root])
 }
 
-fn load_root_nova_params(artifacts_dir: &std::path::Path) -> Result<NovaParams<RootCircuit<Fr>>> {
-    let pp_path = artifacts_dir.join("root_nova_pp.bin");
-    let vp_path = artifacts_dir.join("root_nova_vp.bin");
-    let pp_bytes =
-        std::fs::read(&pp_path).with_context(|| format!("failed to read {}", pp_path.display()))?;
-    let vp_bytes =
-        std::fs::read(&vp_path).with_context(|| format!("failed to read {}", vp_path.display()))?;
+fn load_root_nova_params<const DEPTH: usize>(
+    artifacts: &ArtifactDir,
+) -> Result<NovaParams<RootProverCircuit<DEPTH>>> {
+    let prefix = root_artifact_prefix(DEPTH);
+    let pp = artifacts.map(&format!("{prefix}_nova_pp.bin"))?;
+    let vp = artifacts.map(&format!("{prefix}_nova_vp.bin"))?;
     let f_params = circom_poseidon_config::<Fr>();
-    NovaParams::<RootCircuit<Fr>>::from_bytes(f_params, pp_bytes, vp_bytes)
-        .context("failed to load root nova parameters")
+    NovaParams::<RootProverCircuit<DEPTH>>::from_readers(
+        f_params,
+        &pp[..],
+        &vp[..],
+        artifacts.compress(),
+        artifacts.validate(),
+    )
+    .context("failed to load root nova parameters")
+}
+
+/// Every decider proof is verified against this VP before `proveTransferRoot` is sent, so the
+/// job refuses to start without it.
+fn load_root_decider_vp<const DEPTH: usize>(
+    artifacts: &ArtifactDir,
+) -> Result<DeciderVP<RootProverCircuit<DEPTH>>> {
+    let vp_name = format!("{}_decider_vp.bin", root_artifact_prefix(DEPTH));
+    let vp_bytes = artifacts
+        .read(&vp_name)
+        .with_context(|| format!("failed to load {vp_name}"))?;
+    decider_vp_from_bytes::<RootProverCircuit<DEPTH>>(&vp_bytes, artifacts.compress())
+        .context("failed to load root decider verifier parameters")
 }
 
 fn serialize_ivc_proof(proof: &RootIvcProof) -> Result<Vec<u8>> {
@@ -1153,10 +1295,10 @@ fn deserialize_ivc_proof(bytes: &[u8]) -> Result<RootIvcProof> {
     IVCProof::deserialize_uncompressed(&mut cursor).context("failed to deserialize ivc proof")
 }
 
-fn load_nova_from_ivc(
-    nova_params: &Arc<NovaParams<RootCircuit<Fr>>>,
+fn load_nova_from_ivc<const DEPTH: usize>(
+    nova_params: &Arc<NovaParams<RootProverCircuit<DEPTH>>>,
     ivc_bytes: &[u8],
-) -> Result<RootNovaInstance> {
+) -> Result<RootNovaInstance<DEPTH>> {
     let ivc = deserialize_ivc_proof(ivc_bytes)?;
     nova_params
         .nova_from_ivc_proof(ivc)
@@ -101,15 +101,15 @@ impl TreeIngestionJob {
         .with_context(|| format!("failed to initialise merkle tree for '{}'", token.label))?;
 
         let processed = latest_tree_index(&self.pool, token_id).await?;
-        let contiguous_index = contiguous_event_index(&self.pool, token_id).await?;
-        let target_event_count = match contiguous_index {
+        let final_index = final_event_index(&self.pool, token_id).await?;
+        let target_event_count = match final_index {
             None => 0,
             Some(idx) => idx + 1,
         };
 
         if processed > target_event_count {
             warn!(
-                "tree state ahead of events for '{}': processed={}, contiguous_events={}",
+                "tree state ahead of events for '{}': processed={}, final_events={}",
                 token.label, processed, target_event_count
             );
             return Ok(());
@@ -311,10 +311,12 @@ async fn latest_tree_index(pool: &PgPool, token_id: i64) -> Result<u64> {
     Ok(latest.max(0) as u64)
 }
 
-async fn contiguous_event_index(pool: &PgPool, token_id: i64) -> Result<Option<u64>> {
+/// Only events at or below the final index are appended, so a root never commits to an event
+/// that finality could still take back.
+async fn final_event_index(pool: &PgPool, token_id: i64) -> Result<Option<u64>> {
     let row: Option<i64> = sqlx::query_scalar(
         r#"
-        SELECT contiguous_index
+        SELECT final_index
         FROM event_indexer_state
         WHERE token_id = $1
         "#,
@@ -322,7 +324,7 @@ async fn contiguous_event_index(pool: &PgPool, token_id: i64) -> Result<Option<u
     .bind(token_id)
     .fetch_optional(pool)
     .await
-    .context("failed to query contiguous event index")?;
+    .context("failed to query final event index")?;
     match row {
         Some(value) if value >= 0 => Ok(Some(value as u64)),
         _ => Ok(None),
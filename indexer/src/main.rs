@@ -7,7 +7,10 @@ use sqlx::{Executor, postgres::PgPoolOptions};
 use tokio::task::JoinError;
 use tree_indexer::{
     config::IndexerConfig,
-    jobs::{EventSyncJobBuilder, RootProverJobBuilder, TreeIngestionJobBuilder},
+    jobs::{
+        EventSyncJobBuilder, HashChainAuditJobBuilder, RootProverJobBuilder,
+        TreeIngestionJobBuilder,
+    },
     server,
 };
 
@@ -72,6 +75,16 @@ async fn main() -> Result<()> {
         .build_tree_config()
         .context("failed to build merkle tree config for root prover job")?;
 
+    let audit_job = HashChainAuditJobBuilder::new(
+        pool.clone(),
+        config.audit.clone(),
+        tree_config.clone(),
+        config.tree.height,
+        config.tokens.clone(),
+    )
+    .into_job()
+    .context("failed to construct hash chain audit job")?;
+
     let root_job = RootProverJobBuilder::new(
         pool.clone(),
         config.root.clone(),
@@ -86,6 +99,7 @@ async fn main() -> Result<()> {
         if run_sync {
             event_job.run_once().await;
             tree_job.run_once().await;
+            audit_job.run_once().await;
             root_job.run_once().await?;
         } else {
             info!("IS_SYNC is not set to 'true'; skipping job execution in --once mode");
@@ -108,6 +122,7 @@ async fn main() -> Result<()> {
         );
         let event_handle = tokio::spawn(async move { event_job.run_forever().await });
         let tree_handle = tokio::spawn(async move { tree_job.run_forever().await });
+        let audit_handle = tokio::spawn(async move { audit_job.run_forever().await });
         let root_handle = tokio::spawn(async move { root_job.run_forever().await });
 
         tokio::select! {
@@ -120,6 +135,9 @@ async fn main() -> Result<()> {
             res = tree_handle => {
                 handle_job_exit("tree ingestion", res)?;
             }
+            res = audit_handle => {
+                handle_job_exit("hash chain audit", res)?;
+            }
             res = root_handle => {
                 handle_job_exit("root prover", res)?;
             }
@@ -9,20 +9,26 @@ use actix_web::{
 use alloy::primitives::{Address, U256};
 use anyhow::{Context, Result, anyhow};
 use api_types::indexer::{
-    EventsQuery, HistoricalProof, IndexedEvent, ProveManyRequest, TokenStatusResponse,
-    TreeIndexQuery, TreeIndexResponse,
+    EventsBatchRequest, EventsQuery, HistoricalProof, IndexedEvent, ProveManyRequest,
+    RecipientEvents, TokenStatusResponse, TreeIndexQuery, TreeIndexResponse,
 };
 use ark_bn254::Fr;
 use ark_ff::{BigInteger, PrimeField};
 use log::{error, warn};
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Row, postgres::PgRow};
 
-use crate::trees::{DbIncrementalMerkleTree, DbMerkleTreeConfig, DbMerkleTreeError};
+use crate::{
+    jobs::load_hash_chain_divergence,
+    trees::{DbIncrementalMerkleTree, DbMerkleTreeConfig, DbMerkleTreeError},
+};
 use client_common::{
     contracts::{utils::get_provider_with_fallback, verifier::VerifierContract},
     tokens::{TokenEntry, TokenMetadata},
 };
 
+/// Upper bound on recipients per `POST /events/batch`, keeping a single request's scan bounded.
+const MAX_BATCH_RECIPIENTS: usize = 256;
+
 #[derive(Clone)]
 pub struct AppState {
     pool: PgPool,
@@ -138,6 +144,7 @@ pub async fn run_http_server(
             .route("/healthz", web::get().to(health))
             .route("/status", web::get().to(tokens_status))
             .route("/events", web::get().to(events_by_recipient))
+            .route("/events/batch", web::post().to(events_by_recipients))
             .route("/proofs", web::post().to(prove_many))
             .route("/tree-index", web::get().to(tree_index_by_root))
     })
@@ -161,7 +168,7 @@ async fn tokens_status(state: Data<AppState>) -> actix_web::Result<Json<Vec<Toke
     for token in contexts {
         let (reserved_index, proved_index) = fetch_onchain_indices(&token).await;
 
-        let events_synced_index = fetch_events_synced_index(&state.pool, token.id)
+        let (events_synced_index, events_final_index) = fetch_event_indices(&state.pool, token.id)
             .await
             .map_err(|err| {
                 error!(
@@ -191,6 +198,16 @@ async fn tokens_status(state: Data<AppState>) -> actix_web::Result<Json<Vec<Toke
                 ErrorInternalServerError("failed to load ivc index")
             })?;
 
+        let hash_chain_divergence = load_hash_chain_divergence(&state.pool, token.id)
+            .await
+            .map_err(|err| {
+                error!(
+                    "failed to load hash chain audit for token '{}': {err:?}",
+                    token.label
+                );
+                ErrorInternalServerError("failed to load hash chain audit")
+            })?;
+
         statuses.push(TokenStatusResponse {
             label: token.label.clone(),
             chain_id: token.chain_id,
@@ -199,8 +216,11 @@ async fn tokens_status(state: Data<AppState>) -> actix_web::Result<Json<Vec<Toke
             onchain_reserved_index: reserved_index,
             onchain_proved_index: proved_index,
             events_synced_index,
+            events_final_index,
             tree_synced_index,
             ivc_generated_index,
+            healthy: hash_chain_divergence.is_none(),
+            hash_chain_divergence,
         });
     }
 
@@ -250,44 +270,143 @@ async fn events_by_recipient(
         ErrorInternalServerError("failed to fetch events")
     })?;
 
-    let mut events = Vec::with_capacity(rows.len());
-    for row in rows {
-        let event_index: i64 = row.try_get("event_index").map_err(|_| {
-            ErrorInternalServerError("invalid event_index value retrieved from database")
-        })?;
-        let from_bytes: Vec<u8> = row.try_get("from_address").map_err(|_| {
-            ErrorInternalServerError("invalid from_address value retrieved from database")
-        })?;
-        let to_bytes: Vec<u8> = row.try_get("to_address").map_err(|_| {
-            ErrorInternalServerError("invalid to_address value retrieved from database")
-        })?;
-        let value_bytes: Vec<u8> = row
-            .try_get("value")
-            .map_err(|_| ErrorInternalServerError("invalid value retrieved from database"))?;
-        let block_number: i64 = row.try_get("eth_block_number").map_err(|_| {
-            ErrorInternalServerError("invalid eth_block_number retrieved from database")
+    let events = rows
+        .iter()
+        .map(indexed_event_from_row)
+        .collect::<actix_web::Result<Vec<_>>>()?;
+
+    Ok(Json(events))
+}
+
+async fn events_by_recipients(
+    state: Data<AppState>,
+    request: Json<EventsBatchRequest>,
+) -> actix_web::Result<Json<Vec<RecipientEvents>>> {
+    let request = request.into_inner();
+    let token = state
+        .token(request.chain_id, &request.token_address)
+        .ok_or_else(|| {
+            ErrorNotFound(format!(
+                "token not configured for chain_id {} and address {}",
+                request.chain_id, request.token_address
+            ))
         })?;
 
-        let event_index = u64::try_from(event_index)
-            .map_err(|_| ErrorInternalServerError("event_index does not fit into u64"))?;
-        let block_number = u64::try_from(block_number)
-            .map_err(|_| ErrorInternalServerError("block number does not fit into u64"))?;
-
-        let from = address_from_bytes(&from_bytes)?;
-        let to = address_from_bytes(&to_bytes)?;
-        let value = bytes32_to_u256(&value_bytes)
-            .map_err(|_| ErrorInternalServerError("stored value must be 32 bytes"))?;
-
-        events.push(IndexedEvent {
-            event_index,
-            from,
-            to,
-            value,
-            eth_block_number: block_number,
-        });
+    if request.recipients.len() > MAX_BATCH_RECIPIENTS {
+        return Err(ErrorBadRequest(format!(
+            "at most {MAX_BATCH_RECIPIENTS} recipients per request, got {}",
+            request.recipients.len()
+        )));
+    }
+    if request.recipients.is_empty() {
+        return Ok(Json(Vec::new()));
     }
 
-    Ok(Json(events))
+    let limit = request.limit.unwrap_or(100).min(1_000);
+    let limit_i64 = i64::try_from(limit).map_err(|_| ErrorBadRequest("limit is too large"))?;
+
+    let addresses: Vec<Vec<u8>> = request
+        .recipients
+        .iter()
+        .map(|recipient| recipient.to.to_vec())
+        .collect();
+    let cursors = request
+        .recipients
+        .iter()
+        .map(|recipient| i64::try_from(recipient.from_event_index.unwrap_or(0)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ErrorBadRequest("from_event_index is too large"))?;
+
+    // one index range scan on (token_id, to_address, event_index) per recipient
+    let rows = sqlx::query(
+        r#"
+        SELECT recipient.position, event.event_index, event.from_address, event.to_address,
+               event.value, event.eth_block_number
+        FROM UNNEST($2::BYTEA[], $3::BIGINT[]) WITH ORDINALITY
+            AS recipient (to_address, from_event_index, position)
+        CROSS JOIN LATERAL (
+            SELECT event_index, from_address, to_address, value, eth_block_number
+            FROM indexed_transfer_events
+            WHERE token_id = $1
+              AND to_address = recipient.to_address
+              AND event_index >= recipient.from_event_index
+            ORDER BY event_index ASC
+            LIMIT $4
+        ) AS event
+        ORDER BY recipient.position, event.event_index
+        "#,
+    )
+    .bind(token.id)
+    .bind(&addresses)
+    .bind(&cursors)
+    .bind(limit_i64)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|err| {
+        error!(
+            "failed to fetch events for token '{}' and {} recipients: {err:?}",
+            token.label,
+            addresses.len()
+        );
+        ErrorInternalServerError("failed to fetch events")
+    })?;
+
+    let mut grouped: Vec<RecipientEvents> = request
+        .recipients
+        .iter()
+        .map(|recipient| RecipientEvents {
+            to: recipient.to,
+            events: Vec::new(),
+        })
+        .collect();
+    for row in &rows {
+        let position: i64 = row
+            .try_get("position")
+            .map_err(|_| ErrorInternalServerError("invalid recipient position"))?;
+        let group = usize::try_from(position - 1)
+            .ok()
+            .and_then(|index| grouped.get_mut(index))
+            .ok_or_else(|| ErrorInternalServerError("recipient position out of range"))?;
+        group.events.push(indexed_event_from_row(row)?);
+    }
+
+    Ok(Json(grouped))
+}
+
+fn indexed_event_from_row(row: &PgRow) -> actix_web::Result<IndexedEvent> {
+    let event_index: i64 = row.try_get("event_index").map_err(|_| {
+        ErrorInternalServerError("invalid event_index value retrieved from database")
+    })?;
+    let from_bytes: Vec<u8> = row.try_get("from_address").map_err(|_| {
+        ErrorInternalServerError("invalid from_address value retrieved from database")
+    })?;
+    let to_bytes: Vec<u8> = row.try_get("to_address").map_err(|_| {
+        ErrorInternalServerError("invalid to_address value retrieved from database")
+    })?;
+    let value_bytes: Vec<u8> = row
+        .try_get("value")
+        .map_err(|_| ErrorInternalServerError("invalid value retrieved from database"))?;
+    let block_number: i64 = row.try_get("eth_block_number").map_err(|_| {
+        ErrorInternalServerError("invalid eth_block_number retrieved from database")
+    })?;
+
+    let event_index = u64::try_from(event_index)
+        .map_err(|_| ErrorInternalServerError("event_index does not fit into u64"))?;
+    let block_number = u64::try_from(block_number)
+        .map_err(|_| ErrorInternalServerError("block number does not fit into u64"))?;
+
+    let from = address_from_bytes(&from_bytes)?;
+    let to = address_from_bytes(&to_bytes)?;
+    let value = bytes32_to_u256(&value_bytes)
+        .map_err(|_| ErrorInternalServerError("stored value must be 32 bytes"))?;
+
+    Ok(IndexedEvent {
+        event_index,
+        from,
+        to,
+        value,
+        eth_block_number: block_number,
+    })
 }
 
 async fn prove_many(
@@ -463,8 +582,8 @@ async fn fetch_onchain_indices(token: &TokenContext) -> (Option<u64>, Option<u64
         }
     };
 
-    let contract = VerifierContract::new(provider, token.verifier_address)
-        .with_legacy_tx(token.legacy_tx);
+    let contract =
+        VerifierContract::new(provider, token.verifier_address).with_legacy_tx(token.legacy_tx);
 
     let reserved_index = match contract.latest_reserved_index().await {
         Ok(value) => Some(value),
@@ -491,13 +610,14 @@ async fn fetch_onchain_indices(token: &TokenContext) -> (Option<u64>, Option<u64
     (reserved_index, proved_index)
 }
 
-async fn fetch_events_synced_index(
+/// Returns the seen (contiguous) and final event indices.
+async fn fetch_event_indices(
     pool: &PgPool,
     token_id: i64,
-) -> Result<Option<u64>, sqlx::Error> {
-    let value: Option<Option<i64>> = sqlx::query_scalar::<_, Option<i64>>(
+) -> Result<(Option<u64>, Option<u64>), sqlx::Error> {
+    let value: Option<(i64, i64)> = sqlx::query_as::<_, (i64, i64)>(
         r#"
-        SELECT contiguous_index
+        SELECT contiguous_index, final_index
         FROM event_indexer_state
         WHERE token_id = $1
         "#,
@@ -506,9 +626,10 @@ async fn fetch_events_synced_index(
     .fetch_optional(pool)
     .await?;
 
-    Ok(value
-        .flatten()
-        .and_then(|v| if v >= 0 { Some(v as u64) } else { None }))
+    let non_negative = |v: i64| if v >= 0 { Some(v as u64) } else { None };
+    Ok(value.map_or((None, None), |(seen, last_final)| {
+        (non_negative(seen), non_negative(last_final))
+    }))
 }
 
 async fn fetch_tree_synced_index(pool: &PgPool, token_id: i64) -> Result<Option<u64>, sqlx::Error> {
@@ -67,6 +67,10 @@ pub enum DbMerkleTreeError {
     InvalidBitPathBytes { len: usize },
     #[error("{message}")]
     InvalidConfig { message: &'static str },
+    #[error(
+        "cannot roll back to index {target}: node updates are only retained after index {retained}"
+    )]
+    RollbackBeyondHistory { target: u64, retained: u64 },
 }
 
 impl DbMerkleTreeError {
@@ -763,6 +767,99 @@ impl DbIncrementalMerkleTree {
     }
 }
 
+/// Rewinds the tree of `token_id` to `index` leaves inside the caller's transaction: every node
+/// touched after `index` gets back the `old_hash` of its first later update, and the later update
+/// rows and snapshots are dropped. Fails when the history window has already pruned the updates
+/// needed to restore the nodes.
+pub(crate) async fn rollback_merkle_state(
+    tx: &mut Transaction<'_, Postgres>,
+    token_id: i64,
+    index: u64,
+) -> Result<()> {
+    let index_i64 = i64::try_from(index).map_err(|_| DbMerkleTreeError::U64ToI64 {
+        label: "merkle rollback index",
+        value: index,
+    })?;
+
+    sqlx::query("SELECT id FROM tokens WHERE id = $1 FOR UPDATE")
+        .bind(token_id)
+        .execute(tx.as_mut())
+        .await
+        .map_err(|err| DbMerkleTreeError::database("lock token row for merkle rollback", err))?;
+
+    let latest: i64 = sqlx::query_scalar(&format!(
+        "SELECT COALESCE(MAX(tree_index), 0) FROM {table} WHERE token_id = $1",
+        table = MERKLE_SNAPSHOTS_TABLE,
+    ))
+    .bind(token_id)
+    .fetch_one(tx.as_mut())
+    .await
+    .map_err(|err| DbMerkleTreeError::database("query latest merkle index for rollback", err))?;
+    if latest <= index_i64 {
+        return Ok(());
+    }
+
+    // every append writes at least the root, so a complete history starts right after `index`
+    let oldest_update: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT MIN(tree_index) FROM {table} WHERE token_id = $1 AND tree_index > $2",
+        table = MERKLE_UPDATES_TABLE,
+    ))
+    .bind(token_id)
+    .bind(index_i64)
+    .fetch_one(tx.as_mut())
+    .await
+    .map_err(|err| DbMerkleTreeError::database("query oldest merkle update for rollback", err))?;
+    if oldest_update != Some(index_i64 + 1) {
+        return Err(DbMerkleTreeError::RollbackBeyondHistory {
+            target: index,
+            retained: oldest_update.map_or(latest, |oldest| oldest - 1) as u64,
+        });
+    }
+
+    sqlx::query(&format!(
+        "UPDATE {nodes} AS nodes
+         SET hash = restored.old_hash,
+             updated_at_index = $2,
+             updated_at = NOW()
+         FROM (
+             SELECT DISTINCT ON (node_path) node_path, old_hash
+             FROM {updates}
+             WHERE token_id = $1 AND tree_index > $2
+             ORDER BY node_path, tree_index ASC
+         ) AS restored
+         WHERE nodes.token_id = $1 AND nodes.node_path = restored.node_path",
+        nodes = MERKLE_NODES_TABLE,
+        updates = MERKLE_UPDATES_TABLE,
+    ))
+    .bind(token_id)
+    .bind(index_i64)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|err| DbMerkleTreeError::database("restore merkle nodes for rollback", err))?;
+
+    sqlx::query(&format!(
+        "DELETE FROM {table} WHERE token_id = $1 AND tree_index > $2",
+        table = MERKLE_UPDATES_TABLE,
+    ))
+    .bind(token_id)
+    .bind(index_i64)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|err| DbMerkleTreeError::database("delete rolled back merkle updates", err))?;
+
+    sqlx::query(&format!(
+        "DELETE FROM {table} WHERE token_id = $1 AND tree_index > $2",
+        table = MERKLE_SNAPSHOTS_TABLE,
+    ))
+    .bind(token_id)
+    .bind(index_i64)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|err| DbMerkleTreeError::database("delete rolled back merkle snapshots", err))?;
+
+    Ok(())
+}
+
 fn compute_zero_hashes(height: u32) -> Vec<Fr> {
     let mut hashes = Vec::with_capacity(height as usize + 1);
     let mut current = Fr::from(0u64);
@@ -4,3 +4,5 @@ pub use db::{
     AppendResult, DbIncrementalMerkleTree, DbMerkleTreeConfig, DbMerkleTreeError,
     HISTORY_WINDOW_RECOMMENDED, HistoricalProof,
 };
+
+pub(crate) use db::rollback_merkle_state;
@@ -2,14 +2,17 @@ mod common;
 
 use std::{convert::TryFrom, path::Path};
 
-use alloy::primitives::{Address, U256};
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::Provider,
+};
 use anyhow::{Context, Result};
 use client_common::{
     contracts::{
-        utils::{get_address_from_private_key, get_provider},
+        utils::{NormalProvider, get_address_from_private_key, get_provider},
         z_erc20::ZErc20Contract,
     },
-    tokens::TokenMetadata,
+    tokens::{Finality, TokenMetadata},
 };
 use common::{
     TestDatabase,
@@ -18,10 +21,17 @@ use common::{
         is_binary_available, parse_private_key, wait_for_anvil,
     },
 };
-use sqlx::migrate::Migrator;
-use tree_indexer::events::{
-    BLOCK_SPAN_RECOMMENDED, EventIndexer, EventIndexerConfig, FORWARD_SCAN_OVERLAP_RECOMMENDED,
+use sqlx::{PgPool, migrate::Migrator};
+use tree_indexer::{
+    events::{
+        BLOCK_SPAN_RECOMMENDED, EventIndexer, EventIndexerConfig, EventIndexerError,
+        FORWARD_SCAN_OVERLAP_RECOMMENDED, RpcQuorum,
+    },
+    trees::{DbIncrementalMerkleTree, DbMerkleTreeConfig, HISTORY_WINDOW_RECOMMENDED},
 };
+use zkp::utils::tree::incremental_merkle_tree::IncrementalMerkleTree;
+
+const TREE_HEIGHT: u32 = 16;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn event_indexer_syncs_against_anvil() -> Result<()> {
@@ -202,8 +212,371 @@ async fn event_indexer_syncs_against_anvil() -> Result<()> {
         "second sync should append the new mint event"
     );
 
+    // the final index trails the seen index until the events are deep enough
+    let deep_finality = EventIndexer::new(
+        contract.clone(),
+        database.pool().clone(),
+        0,
+        metadata,
+        indexer_config,
+    )
+    .await?
+    .with_finality(Finality::Confirmations(1_000));
+    deep_finality.sync().await?;
+    assert_eq!(event_indices(database.pool(), token_id).await?, (3, -1));
+
+    indexer.sync().await?;
+    assert_eq!(event_indices(database.pool(), token_id).await?, (3, 3));
+
     database.cleanup().await?;
     anvil.stop().await?;
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn event_indexer_rolls_back_orphaned_events() -> Result<()> {
+    let anvil_bin = std::env::var("ANVIL_BIN").unwrap_or_else(|_| "anvil".to_string());
+    if !is_binary_available(&anvil_bin).await {
+        eprintln!("skipping test: anvil binary not found ({anvil_bin})");
+        return Ok(());
+    }
+
+    let port = match find_unused_port() {
+        Ok(port) => port,
+        Err(err) => {
+            eprintln!("skipping test: failed to allocate free TCP port for anvil ({err:?})");
+            return Ok(());
+        }
+    };
+    let anvil = AnvilInstance::spawn(&anvil_bin, port, DEFAULT_ANVIL_CHAIN_ID).await?;
+
+    let provider = get_provider(&anvil.rpc_url())?;
+    wait_for_anvil(&provider).await?;
+
+    let database = match TestDatabase::create("idx_reorg").await {
+        Ok(db) => db,
+        Err(err) => {
+            eprintln!("skipping test: failed to start postgres container ({err:?})");
+            return Ok(());
+        }
+    };
+    let migrator = Migrator::new(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/migrations"
+    )))
+    .await
+    .context("failed to load embedded migrations")?;
+    migrator
+        .run(database.pool())
+        .await
+        .context("failed to run migrations for test database")?;
+
+    let deployer_key =
+        parse_private_key("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")?;
+    let deployer_address = get_address_from_private_key(deployer_key);
+    let contract = ZErc20Contract::deploy(
+        provider.clone(),
+        deployer_key,
+        "TestToken".to_string(),
+        "TT".to_string(),
+        deployer_address,
+    )
+    .await
+    .context("failed to deploy zERC20 contract")?;
+    await_receipt(contract.set_minter(deployer_key, deployer_address).await?).await?;
+    await_receipt(
+        contract
+            .mint(deployer_key, deployer_address, U256::from(1_000u64))
+            .await?,
+    )
+    .await?;
+
+    let snapshot: U256 = provider
+        .raw_request("evm_snapshot".into(), ())
+        .await
+        .context("evm_snapshot failed")?;
+
+    let recipient_a = Address::from_slice(&[0xAA; 20]);
+    let recipient_b = Address::from_slice(&[0xBB; 20]);
+    await_receipt(
+        contract
+            .transfer(deployer_key, recipient_a, U256::from(250u64))
+            .await?,
+    )
+    .await?;
+    await_receipt(
+        contract
+            .transfer(deployer_key, recipient_b, U256::from(125u64))
+            .await?,
+    )
+    .await?;
+
+    let metadata = TokenMetadata {
+        token_address: contract.address(),
+        verifier_address: deployer_address,
+        chain_id: DEFAULT_ANVIL_CHAIN_ID,
+    };
+    let indexer = EventIndexer::new(
+        contract.clone(),
+        database.pool().clone(),
+        0,
+        metadata,
+        EventIndexerConfig::new(BLOCK_SPAN_RECOMMENDED, FORWARD_SCAN_OVERLAP_RECOMMENDED)?,
+    )
+    .await?;
+    indexer.sync().await?;
+
+    let token_id: i64 = sqlx::query_scalar("SELECT id FROM tokens WHERE token_address = $1")
+        .bind(contract.address().as_slice())
+        .fetch_one(database.pool())
+        .await?;
+
+    // mirror the tree ingestion job so the rollback has merkle state to rewind
+    let tree = DbIncrementalMerkleTree::new(
+        database.pool().clone(),
+        token_id,
+        TREE_HEIGHT,
+        DbMerkleTreeConfig::new(HISTORY_WINDOW_RECOMMENDED)?,
+    )
+    .await?;
+    for (to, value) in indexed_events(database.pool(), token_id).await? {
+        tree.append_leaf(to, value).await?;
+    }
+    assert_eq!(tree.latest_index().await?, 3);
+    let root_before_fork = {
+        let mut prefix = IncrementalMerkleTree::new(TREE_HEIGHT as usize);
+        prefix.insert(deployer_address, U256::from(1_000u64));
+        prefix.get_root()
+    };
+
+    let reverted: bool = provider
+        .raw_request("evm_revert".into(), (snapshot,))
+        .await
+        .context("evm_revert failed")?;
+    assert!(reverted, "anvil should revert to the snapshot");
+    await_receipt(
+        contract
+            .transfer(deployer_key, recipient_b, U256::from(300u64))
+            .await?,
+    )
+    .await?;
+
+    indexer.sync().await?;
+
+    assert_eq!(
+        indexed_events(database.pool(), token_id).await?,
+        vec![
+            (deployer_address, U256::from(1_000u64)),
+            (recipient_b, U256::from(300u64)),
+        ],
+        "orphaned transfers should be replaced by the new branch"
+    );
+    let contiguous_index: i64 =
+        sqlx::query_scalar("SELECT contiguous_index FROM event_indexer_state WHERE token_id = $1")
+            .bind(token_id)
+            .fetch_one(database.pool())
+            .await?;
+    assert_eq!(contiguous_index, 1);
+
+    assert_eq!(
+        tree.latest_index().await?,
+        1,
+        "leaves of orphaned events should be rolled back"
+    );
+    assert_eq!(tree.root_at(1).await?, Some(root_before_fork));
+    let appended = tree
+        .append_leaf(recipient_b, U256::from(300u64))
+        .await
+        .context("append after rollback")?;
+    let mut expected = IncrementalMerkleTree::new(TREE_HEIGHT as usize);
+    expected.insert(deployer_address, U256::from(1_000u64));
+    expected.insert(recipient_b, U256::from(300u64));
+    assert_eq!(appended.root, expected.get_root());
+
+    database.cleanup().await?;
+    anvil.stop().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn event_indexer_quorum_rejects_divergent_provider() -> Result<()> {
+    let anvil_bin = std::env::var("ANVIL_BIN").unwrap_or_else(|_| "anvil".to_string());
+    if !is_binary_available(&anvil_bin).await {
+        eprintln!("skipping test: anvil binary not found ({anvil_bin})");
+        return Ok(());
+    }
+
+    let port_a = match find_unused_port() {
+        Ok(port) => port,
+        Err(err) => {
+            eprintln!("skipping test: failed to allocate free TCP port for anvil ({err:?})");
+            return Ok(());
+        }
+    };
+    let anvil_a = AnvilInstance::spawn(&anvil_bin, port_a, DEFAULT_ANVIL_CHAIN_ID).await?;
+    let provider_a = get_provider(&anvil_a.rpc_url())?;
+    wait_for_anvil(&provider_a).await?;
+
+    // allocated once the first node listens so both cannot land on the same port
+    let port_b = find_unused_port()?;
+    let anvil_b = AnvilInstance::spawn(&anvil_bin, port_b, DEFAULT_ANVIL_CHAIN_ID).await?;
+    let provider_b = get_provider(&anvil_b.rpc_url())?;
+    wait_for_anvil(&provider_b).await?;
+
+    let database = match TestDatabase::create("idx_quorum").await {
+        Ok(db) => db,
+        Err(err) => {
+            eprintln!("skipping test: failed to start postgres container ({err:?})");
+            return Ok(());
+        }
+    };
+    let migrator = Migrator::new(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/migrations"
+    )))
+    .await
+    .context("failed to load embedded migrations")?;
+    migrator
+        .run(database.pool())
+        .await
+        .context("failed to run migrations for test database")?;
+
+    let deployer_key =
+        parse_private_key("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")?;
+    let deployer_address = get_address_from_private_key(deployer_key);
+
+    // same deployer and nonces on both nodes, so the token lands at the same address; the second
+    // node then plays a provider that lies about the first mint
+    let contract_a = deploy_with_mint(provider_a, deployer_key, U256::from(1_000u64)).await?;
+    let contract_b = deploy_with_mint(provider_b, deployer_key, U256::from(999u64)).await?;
+    assert_eq!(contract_a.address(), contract_b.address());
+
+    let metadata = TokenMetadata {
+        token_address: contract_a.address(),
+        verifier_address: deployer_address,
+        chain_id: DEFAULT_ANVIL_CHAIN_ID,
+    };
+    let indexer_config =
+        EventIndexerConfig::new(BLOCK_SPAN_RECOMMENDED, FORWARD_SCAN_OVERLAP_RECOMMENDED)?;
+
+    assert!(matches!(
+        RpcQuorum::new(vec![("a".to_string(), contract_a.clone())]),
+        Err(EventIndexerError::QuorumTooSmall { members: 1 })
+    ));
+
+    let agreeing = EventIndexer::new(
+        contract_a.clone(),
+        database.pool().clone(),
+        0,
+        metadata,
+        indexer_config,
+    )
+    .await?
+    .with_quorum(RpcQuorum::new(vec![
+        ("a".to_string(), contract_a.clone()),
+        ("a-mirror".to_string(), contract_a.clone()),
+    ])?);
+    agreeing.sync().await?;
+
+    let token_id: i64 = sqlx::query_scalar("SELECT id FROM tokens WHERE token_address = $1")
+        .bind(contract_a.address().as_slice())
+        .fetch_one(database.pool())
+        .await?;
+    assert_eq!(event_indices(database.pool(), token_id).await?.0, 0);
+
+    await_receipt(
+        contract_a
+            .mint(deployer_key, deployer_address, U256::from(50u64))
+            .await?,
+    )
+    .await?;
+
+    let split = EventIndexer::new(
+        contract_a.clone(),
+        database.pool().clone(),
+        0,
+        metadata,
+        indexer_config,
+    )
+    .await?
+    .with_quorum(RpcQuorum::new(vec![
+        ("a".to_string(), contract_a.clone()),
+        ("a-mirror".to_string(), contract_a.clone()),
+        ("b".to_string(), contract_b.clone()),
+    ])?);
+    match split.sync().await {
+        Err(EventIndexerError::QuorumMismatch { divergent, .. }) => {
+            assert_eq!(divergent, vec!["b".to_string()]);
+        }
+        other => panic!("expected a quorum mismatch, got {other:?}"),
+    }
+    assert_eq!(
+        event_indices(database.pool(), token_id).await?.0,
+        0,
+        "a disagreeing quorum must not advance the contiguous index"
+    );
+
+    agreeing.sync().await?;
+    assert_eq!(event_indices(database.pool(), token_id).await?.0, 1);
+
+    database.cleanup().await?;
+    anvil_a.stop().await?;
+    anvil_b.stop().await?;
+
+    Ok(())
+}
+
+async fn deploy_with_mint(
+    provider: NormalProvider,
+    deployer_key: B256,
+    amount: U256,
+) -> Result<ZErc20Contract> {
+    let deployer_address = get_address_from_private_key(deployer_key);
+    let contract = ZErc20Contract::deploy(
+        provider,
+        deployer_key,
+        "TestToken".to_string(),
+        "TT".to_string(),
+        deployer_address,
+    )
+    .await
+    .context("failed to deploy zERC20 contract")?;
+    await_receipt(contract.set_minter(deployer_key, deployer_address).await?).await?;
+    await_receipt(
+        contract
+            .mint(deployer_key, deployer_address, amount)
+            .await?,
+    )
+    .await?;
+    Ok(contract)
+}
+
+async fn indexed_events(pool: &PgPool, token_id: i64) -> Result<Vec<(Address, U256)>> {
+    let rows = sqlx::query_as::<_, (Vec<u8>, Vec<u8>)>(
+        r#"
+        SELECT to_address, value
+        FROM indexed_transfer_events
+        WHERE token_id = $1
+        ORDER BY event_index
+        "#,
+    )
+    .bind(token_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(to, value)| (Address::from_slice(&to), U256::from_be_slice(&value)))
+        .collect())
+}
+
+async fn event_indices(pool: &PgPool, token_id: i64) -> Result<(i64, i64)> {
+    let indices = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT contiguous_index, final_index FROM event_indexer_state WHERE token_id = $1",
+    )
+    .bind(token_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(indices)
+}
@@ -0,0 +1,277 @@
+mod common;
+
+use std::{path::Path, time::Duration};
+
+use alloy::primitives::{Address, U256};
+use anyhow::{Context, Result, bail};
+use client_common::{
+    contracts::{
+        utils::{get_address_from_private_key, get_provider},
+        z_erc20::ZErc20Contract,
+    },
+    indexer::{EventsBatchRecipient, HttpIndexerClient, IndexedEvent, IndexerClient},
+    teleport::events::fetch_transfer_events,
+    tokens::{Finality, TokenEntry},
+};
+use common::{
+    TestDatabase,
+    anvil::{
+        AnvilInstance, DEFAULT_ANVIL_CHAIN_ID, DEFAULT_ANVIL_HOST, await_receipt, find_unused_port,
+        is_binary_available, parse_private_key, wait_for_anvil,
+    },
+};
+use reqwest::Url;
+use sqlx::migrate::Migrator;
+use tree_indexer::{
+    events::{
+        BLOCK_SPAN_RECOMMENDED, EventIndexer, EventIndexerConfig, FORWARD_SCAN_OVERLAP_RECOMMENDED,
+    },
+    server::run_http_server,
+    trees::{DbMerkleTreeConfig, HISTORY_WINDOW_RECOMMENDED},
+};
+use zkp::circuits::constants::POW_DIFFICULTY;
+
+const TREE_HEIGHT: u32 = 16;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_batch_matches_per_recipient_queries() -> Result<()> {
+    let anvil_bin = std::env::var("ANVIL_BIN").unwrap_or_else(|_| "anvil".to_string());
+    if !is_binary_available(&anvil_bin).await {
+        eprintln!("skipping test: anvil binary not found ({anvil_bin})");
+        return Ok(());
+    }
+
+    let port = match find_unused_port() {
+        Ok(port) => port,
+        Err(err) => {
+            eprintln!("skipping test: failed to allocate free TCP port for anvil ({err:?})");
+            return Ok(());
+        }
+    };
+    let anvil = AnvilInstance::spawn(&anvil_bin, port, DEFAULT_ANVIL_CHAIN_ID).await?;
+
+    let rpc_url = anvil.rpc_url();
+    let provider = get_provider(&rpc_url)?;
+    wait_for_anvil(&provider).await?;
+
+    let database = match TestDatabase::create("idx_batch").await {
+        Ok(db) => db,
+        Err(err) => {
+            eprintln!("skipping test: failed to start postgres container ({err:?})");
+            return Ok(());
+        }
+    };
+    let migrator = Migrator::new(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/migrations"
+    )))
+    .await
+    .context("failed to load embedded migrations")?;
+    migrator
+        .run(database.pool())
+        .await
+        .context("failed to run migrations for test database")?;
+
+    let deployer_key =
+        parse_private_key("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")?;
+    let deployer_address = get_address_from_private_key(deployer_key);
+
+    let contract = ZErc20Contract::deploy(
+        provider.clone(),
+        deployer_key,
+        "TestToken".to_string(),
+        "TT".to_string(),
+        deployer_address,
+    )
+    .await
+    .context("failed to deploy zERC20 contract")?;
+    await_receipt(
+        contract
+            .set_minter(deployer_key, deployer_address)
+            .await
+            .context("set_minter transaction failed to submit")?,
+    )
+    .await?;
+    await_receipt(
+        contract
+            .mint(deployer_key, deployer_address, U256::from(1_000u64))
+            .await
+            .context("mint transaction failed to submit")?,
+    )
+    .await?;
+
+    // interleaved so that each recipient's events are not contiguous in the global index
+    let recipient_a = Address::from_slice(&[0xAA; 20]);
+    let recipient_b = Address::from_slice(&[0xBB; 20]);
+    let recipient_empty = Address::from_slice(&[0xCC; 20]);
+    for (recipient, value) in [
+        (recipient_a, 10u64),
+        (recipient_b, 20),
+        (recipient_a, 30),
+        (recipient_b, 40),
+        (recipient_a, 50),
+    ] {
+        await_receipt(
+            contract
+                .transfer(deployer_key, recipient, U256::from(value))
+                .await
+                .context("transfer failed to submit")?,
+        )
+        .await?;
+    }
+
+    let token_entry = TokenEntry {
+        label: "anvil-test".to_string(),
+        token_address: contract.address(),
+        verifier_address: deployer_address,
+        minter_address: None,
+        chain_id: DEFAULT_ANVIL_CHAIN_ID,
+        deployed_block_number: 0,
+        rpc_urls: vec![rpc_url.clone()],
+        legacy_tx: false,
+        pow_difficulty: POW_DIFFICULTY,
+        finality: Finality::Latest,
+        rpc_quorum: false,
+    };
+
+    let indexer_config =
+        EventIndexerConfig::new(BLOCK_SPAN_RECOMMENDED, FORWARD_SCAN_OVERLAP_RECOMMENDED)?;
+    EventIndexer::new(
+        contract.clone(),
+        database.pool().clone(),
+        0,
+        token_entry.metadata(),
+        indexer_config,
+    )
+    .await?
+    .sync()
+    .await?;
+
+    let server_port = find_unused_port()?;
+    let bind_addr = format!("{DEFAULT_ANVIL_HOST}:{server_port}");
+    let tokens = [token_entry.clone()];
+    let server = run_http_server(
+        &bind_addr,
+        database.pool().clone(),
+        &tokens,
+        DbMerkleTreeConfig::new(HISTORY_WINDOW_RECOMMENDED)?,
+        TREE_HEIGHT,
+    );
+    let client = HttpIndexerClient::new(Url::parse(&format!("http://{bind_addr}"))?)?;
+
+    let checks = async {
+        wait_for_server(&client, &token_entry).await?;
+
+        let single = |to: Address, limit: Option<usize>| {
+            client.events_by_recipient(token_entry.chain_id, token_entry.token_address, to, limit)
+        };
+        let all_a = single(recipient_a, None).await?;
+        let all_b = single(recipient_b, None).await?;
+        assert_eq!(values(&all_a), vec![10, 30, 50]);
+        assert_eq!(values(&all_b), vec![20, 40]);
+
+        let recipients: Vec<_> = [recipient_a, recipient_b, recipient_empty]
+            .into_iter()
+            .map(|to| EventsBatchRecipient {
+                to,
+                from_event_index: None,
+            })
+            .collect();
+
+        for limit in [None, Some(1), Some(2)] {
+            let groups = client
+                .events_by_recipients(
+                    token_entry.chain_id,
+                    token_entry.token_address,
+                    &recipients,
+                    limit,
+                )
+                .await?;
+            assert_eq!(
+                groups.iter().map(|group| group.to).collect::<Vec<_>>(),
+                vec![recipient_a, recipient_b, recipient_empty],
+                "groups follow the request order"
+            );
+            for group in &groups {
+                let expected = single(group.to, limit).await?;
+                assert_eq!(
+                    group.events, expected,
+                    "batch events of {} with limit {limit:?} differ from /events",
+                    group.to
+                );
+                if let Some(limit) = limit {
+                    assert!(group.events.len() <= limit, "limit applies per recipient");
+                }
+                assert!(
+                    group
+                        .events
+                        .windows(2)
+                        .all(|pair| pair[0].event_index < pair[1].event_index),
+                    "events of {} are ordered by event index",
+                    group.to
+                );
+            }
+        }
+
+        let cursor = [EventsBatchRecipient {
+            to: recipient_a,
+            from_event_index: Some(all_a[1].event_index),
+        }];
+        let paged = client
+            .events_by_recipients(
+                token_entry.chain_id,
+                token_entry.token_address,
+                &cursor,
+                None,
+            )
+            .await?;
+        assert_eq!(paged[0].events, all_a[1..], "cursor skips earlier events");
+
+        // recipients without a balance are skipped before the indexer is queried
+        let fetched = fetch_transfer_events(
+            &client,
+            None,
+            std::slice::from_ref(&token_entry),
+            std::slice::from_ref(&contract),
+            &[recipient_a, recipient_b, recipient_empty],
+        )
+        .await?;
+        let expected: Vec<IndexedEvent> = all_a.iter().chain(&all_b).cloned().collect();
+        assert_eq!(
+            fetched.get(&token_entry.chain_id),
+            Some(&expected),
+            "fetch_transfer_events matches per-recipient /events calls"
+        );
+
+        anyhow::Ok(())
+    };
+
+    tokio::select! {
+        result = server => {
+            result?;
+            bail!("HTTP server stopped before the checks finished");
+        }
+        result = checks => result?,
+    }
+
+    anvil.stop().await?;
+    Ok(())
+}
+
+async fn wait_for_server(client: &HttpIndexerClient, token: &TokenEntry) -> Result<()> {
+    for _ in 0..40 {
+        if client
+            .events_by_recipient(token.chain_id, token.token_address, Address::ZERO, Some(1))
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    bail!("indexer HTTP server did not become ready in time")
+}
+
+fn values(events: &[IndexedEvent]) -> Vec<u64> {
+    events.iter().map(|event| event.value.to::<u64>()).collect()
+}
@@ -20,7 +20,7 @@ use client_common::{
         z_erc20::ZErc20Contract,
     },
     prover::{DeciderClient, DeciderResult},
-    tokens::TokenEntry,
+    tokens::{Finality, TokenEntry},
 };
 use common::{
     TestDatabase,
@@ -32,10 +32,14 @@ use common::{
 use reqwest::Url;
 use sqlx::{PgPool, migrate::Migrator};
 use tree_indexer::{
-    config::{EventJobConfig, RootJobConfig, TreeJobConfig},
-    jobs::{EventSyncJobBuilder, RootProverJobBuilder, TreeIngestionJobBuilder},
+    config::{AuditJobConfig, EventJobConfig, RootJobConfig, TreeJobConfig},
+    jobs::{
+        EventSyncJobBuilder, HashChainAuditJobBuilder, RootProverJobBuilder,
+        TreeIngestionJobBuilder,
+    },
     trees::HISTORY_WINDOW_RECOMMENDED,
 };
+use zkp::circuits::constants::POW_DIFFICULTY;
 struct MockDeciderClient {
     calls: AtomicUsize,
 }
@@ -152,6 +156,9 @@ async fn event_and_tree_jobs_ingest_transfers() -> Result<()> {
         deployed_block_number: 0,
         rpc_urls: vec![rpc_url.clone()],
         legacy_tx: false,
+        pow_difficulty: POW_DIFFICULTY,
+        finality: Finality::Latest,
+        rpc_quorum: false,
     };
 
     let tree_job_config = TreeJobConfig::default();
@@ -185,10 +192,22 @@ async fn event_and_tree_jobs_ingest_transfers() -> Result<()> {
         prover_url: Url::parse("http://127.0.0.1:8080").expect("hardcoded prover url should parse"),
         submitter_private_key: deployer_key,
         artifacts_dir,
+        artifacts_manifest_sha256: None,
     };
     let tree_db_config = tree_job_config
         .build_tree_config()
         .context("failed to build tree config for root job")?;
+    let audit_job = HashChainAuditJobBuilder::new(
+        database.pool().clone(),
+        AuditJobConfig::default(),
+        tree_db_config.clone(),
+        tree_job_config.height,
+        vec![token_entry.clone()],
+    )
+    // the deployer stands in for the verifier, so there are no reserved checkpoints to read
+    .with_checkpoints_enabled(false)
+    .into_job()
+    .context("failed to construct audit job")?;
     let mock_prover = Arc::new(MockDeciderClient::new());
     let root_job = RootProverJobBuilder::new(
         database.pool().clone(),
@@ -264,6 +283,41 @@ async fn event_and_tree_jobs_ingest_transfers() -> Result<()> {
         "mock prover should be exercised by the root job"
     );
 
+    audit_job.run_once().await;
+    assert_eq!(
+        fetch_audit(database.pool(), token_id).await?,
+        (Some(final_count), None),
+        "stored hash chain should match the token's hashChain()"
+    );
+
+    sqlx::query(
+        "UPDATE merkle_snapshots SET hash_chain = $3 WHERE token_id = $1 AND tree_index = $2",
+    )
+    .bind(token_id)
+    .bind(final_count)
+    .bind(U256::from(1u64).to_be_bytes::<32>().as_slice())
+    .execute(database.pool())
+    .await
+    .context("failed to corrupt the latest snapshot")?;
+
+    audit_job.run_once().await;
+    assert_eq!(
+        fetch_audit(database.pool(), token_id).await?,
+        (Some(final_count), Some("hashChain".to_string())),
+        "audit should flag the corrupted snapshot"
+    );
+
+    let calls_before_halt = mock_prover.calls();
+    root_job
+        .run_once()
+        .await
+        .context("root job run failed while halted")?;
+    assert_eq!(
+        mock_prover.calls(),
+        calls_before_halt,
+        "root prover must not start decider jobs for a diverging token"
+    );
+
     database.cleanup().await?;
     anvil.stop().await?;
 
@@ -287,6 +341,20 @@ async fn fetch_token_id(pool: &PgPool, token_address: Address, chain_id: u64) ->
     .context("token metadata row missing after initial sync")
 }
 
+async fn fetch_audit(pool: &PgPool, token_id: i64) -> Result<(Option<i64>, Option<String>)> {
+    sqlx::query_as(
+        r#"
+        SELECT audited_index, divergent_checkpoint
+        FROM hash_chain_audits
+        WHERE token_id = $1
+        "#,
+    )
+    .bind(token_id)
+    .fetch_one(pool)
+    .await
+    .context("hash chain audit row missing")
+}
+
 async fn assert_tree_matches_events(pool: &PgPool, token_id: i64) -> Result<i64> {
     let event_count: i64 = sqlx::query_scalar(
         r#"
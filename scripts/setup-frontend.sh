@@ -10,6 +10,7 @@ WASM_SRC_DIR="$ROOT_DIR/wasm/pkg"
 ABI_SRC_DIR="$ROOT_DIR/client-common/abi"
 
 ARTIFACT_FILES=(
+  manifest.json
   withdraw_local_nova_pp.bin
   withdraw_local_nova_vp.bin
   withdraw_global_nova_pp.bin
@@ -4,25 +4,34 @@ use ark_bn254::Fr;
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
 use client_common::payment::{burn_address::FullBurnAddress, invoice::SecretAndTweak};
-use folding_schemes::FoldingScheme;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 use web_time::Instant;
 use zkp::{
-    groth16::{params::Groth16Params, withdraw::SingleWithdrawCircuit},
+    artifacts::{ArtifactCompression, ArtifactManifest},
+    circuits::{burn_address::check_pow_difficulty, constants::POW_DIFFICULTY},
+    groth16::{
+        multi_withdraw::{MULTI_WITHDRAW_SIZES, MultiWithdrawCircuit, MultiWithdrawLeaf},
+        params::Groth16Params,
+        withdraw::SingleWithdrawCircuit,
+    },
     nova::{
         constants::{AGGREGATION_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
         params::NovaParams,
-        withdraw_nova::{WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawExternalInputs},
+        withdraw_nova::{
+            WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawCircuitParams, WithdrawExternalInputs,
+        },
+        withdraw_session::{WithdrawSession, checkpoint_depth},
     },
     utils::{
         convertion::{fr_to_b256, u256_to_fr},
-        general_recipient::GeneralRecipient,
+        general_recipient::{GeneralRecipient, RecipientAccount},
         poseidon::utils::circom_poseidon_config,
         tree::merkle_tree::MerkleTree,
     },
+    witness::validate_single_withdraw,
 };
 
 use rand::{SeedableRng, rngs::StdRng};
@@ -38,6 +47,9 @@ pub struct JsExternalInput {
     #[serde(rename = "leafIndex")]
     pub leaf_index: String,
     pub siblings: Vec<String>,
+    /// Association tree path; may be omitted when the association root is zero.
+    #[serde(default, rename = "associationSiblings")]
+    pub association_siblings: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +61,16 @@ struct JsProveResult {
     steps: usize,
 }
 
+#[derive(Debug, Serialize)]
+struct JsSessionProgress {
+    checkpoint: String,
+    #[serde(rename = "stepsDone")]
+    steps_done: usize,
+    #[serde(rename = "totalSteps")]
+    total_steps: usize,
+    result: Option<JsProveResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsSingleWithdrawInput {
     #[serde(rename = "merkleRoot")]
@@ -56,12 +78,37 @@ pub struct JsSingleWithdrawInput {
     pub recipient: String,
     #[serde(rename = "withdrawValue")]
     pub withdraw_value: String,
+    #[serde(default)]
+    pub relayer: Option<String>,
+    #[serde(default)]
+    pub fee: Option<String>,
+    #[serde(default, rename = "associationRoot")]
+    pub association_root: Option<String>,
     pub value: String,
     pub delta: String,
     pub secret: String,
     #[serde(rename = "leafIndex")]
     pub leaf_index: String,
     pub siblings: Vec<String>,
+    #[serde(default, rename = "associationSiblings")]
+    pub association_siblings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsMultiWithdrawInput {
+    #[serde(rename = "merkleRoot")]
+    pub merkle_root: String,
+    pub recipient: String,
+    #[serde(rename = "withdrawValue")]
+    pub withdraw_value: String,
+    #[serde(default)]
+    pub relayer: Option<String>,
+    #[serde(default)]
+    pub fee: Option<String>,
+    #[serde(default, rename = "associationRoot")]
+    pub association_root: Option<String>,
+    pub delta: String,
+    pub leaves: Vec<JsExternalInput>,
 }
 
 #[derive(Debug, Serialize)]
@@ -84,6 +131,10 @@ struct JsSecretTweak {
 struct JsGeneralRecipient {
     #[serde(rename = "chainId")]
     chain_id: u64,
+    /// Address family namespace, e.g. `eip155` or `solana`.
+    family: String,
+    /// `<namespace>:<chain id>:<address>` form accepted by the `recipient` parameters.
+    account: String,
     address: String,
     tweak: String,
     #[serde(rename = "fr")]
@@ -112,15 +163,24 @@ pub struct WithdrawNovaWasm {
 
 #[wasm_bindgen]
 impl WithdrawNovaWasm {
+    /// `manifest_json` is the generator's `manifest.json`; every file is checked against it and
+    /// the circuits use its `pow_difficulty`.
     #[wasm_bindgen(constructor)]
     pub fn new(
+        manifest_json: &str,
         local_pp_bytes: Vec<u8>,
         local_vp_bytes: Vec<u8>,
         global_pp_bytes: Vec<u8>,
         global_vp_bytes: Vec<u8>,
     ) -> Result<WithdrawNovaWasm, JsValue> {
         console_error_panic_hook::set_once();
-        let f_params = circom_poseidon_config();
+        let manifest = open_manifest(manifest_json)?;
+        verify_file(&manifest, "withdraw_local_nova_pp.bin", &local_pp_bytes)?;
+        verify_file(&manifest, "withdraw_local_nova_vp.bin", &local_vp_bytes)?;
+        verify_file(&manifest, "withdraw_global_nova_pp.bin", &global_pp_bytes)?;
+        verify_file(&manifest, "withdraw_global_nova_vp.bin", &global_vp_bytes)?;
+        let f_params =
+            WithdrawCircuitParams::new(circom_poseidon_config(), manifest.pow_difficulty);
         let local = NovaParams::from_bytes(f_params.clone(), local_pp_bytes, local_vp_bytes)
             .map_err(|err| JsValue::from_str(&err.to_string()))?;
         let global = NovaParams::from_bytes(f_params, global_pp_bytes, global_vp_bytes)
@@ -131,36 +191,8 @@ impl WithdrawNovaWasm {
     #[wasm_bindgen]
     pub fn prove(&self, z0: JsValue, steps: JsValue) -> Result<JsValue, JsValue> {
         console_error_panic_hook::set_once();
-
-        let z0_hex: Vec<String> = serde_wasm_bindgen::from_value(z0)
-            .map_err(|err| JsValue::from_str(&err.to_string()))?;
-        let step_inputs: Vec<JsExternalInput> = serde_wasm_bindgen::from_value(steps)
-            .map_err(|err| JsValue::from_str(&err.to_string()))?;
-        if z0_hex.len() != WITHDRAW_STATE_LEN {
-            return Err(JsValue::from_str(
-                "z0 must contain exactly four field elements",
-            ));
-        }
-        let z0_fields = z0_hex
-            .iter()
-            .map(|value| hex_to_fr(value))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(anyhow_to_js_error)?;
-        let tree_height = step_inputs
-            .first()
-            .map(|input| input.siblings.len())
-            .unwrap_or(TRANSFER_TREE_HEIGHT);
-
-        if step_inputs
-            .iter()
-            .any(|input| input.siblings.len() != tree_height)
-        {
-            return Err(JsValue::from_str(
-                "all steps must have sibling paths with uniform length",
-            ));
-        }
-
-        match tree_height {
+        let (z0_fields, step_inputs) = parse_withdraw_request(z0, steps)?;
+        match steps_tree_height(&step_inputs)? {
             TRANSFER_TREE_HEIGHT => {
                 prove_with_depth::<TRANSFER_TREE_HEIGHT>(&self.local, z0_fields, &step_inputs)
             }
@@ -169,35 +201,101 @@ impl WithdrawNovaWasm {
                 z0_fields,
                 &step_inputs,
             ),
-            _ => Err(JsValue::from_str(&format!(
-                "unsupported sibling path length: {tree_height} (expected {TRANSFER_TREE_HEIGHT} or {GLOBAL_TRANSFER_TREE_HEIGHT})"
-            ))),
+            tree_height => Err(unsupported_tree_height(tree_height)),
         }
     }
+
+    /// Validates `steps` like `prove` and returns a hex checkpoint of the not yet started proof.
+    /// Pass it to `resumeSession` to fold the steps in chunks; persisting every returned
+    /// checkpoint lets a reloaded page continue where it stopped. Checkpoints contain the burn
+    /// secrets of all steps.
+    #[wasm_bindgen(js_name = startSession)]
+    pub fn start_session(&self, z0: JsValue, steps: JsValue) -> Result<String, JsValue> {
+        console_error_panic_hook::set_once();
+        let (z0_fields, step_inputs) = parse_withdraw_request(z0, steps)?;
+        let checkpoint = match steps_tree_height(&step_inputs)? {
+            TRANSFER_TREE_HEIGHT => {
+                start_with_depth::<TRANSFER_TREE_HEIGHT>(&self.local, z0_fields, &step_inputs)?
+                    .checkpoint()
+            }
+            GLOBAL_TRANSFER_TREE_HEIGHT => start_with_depth::<GLOBAL_TRANSFER_TREE_HEIGHT>(
+                &self.global,
+                z0_fields,
+                &step_inputs,
+            )?
+            .checkpoint(),
+            tree_height => return Err(unsupported_tree_height(tree_height)),
+        }
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        Ok(format!("0x{}", hex::encode(checkpoint)))
+    }
+
+    /// Folds up to `max_steps` further steps of the session in `checkpoint` and returns
+    /// `{ checkpoint, stepsDone, totalSteps, result }`, where `result` is the `prove` output once
+    /// every step is proven and `null` before that.
+    #[wasm_bindgen(js_name = resumeSession)]
+    pub fn resume_session(&self, checkpoint: &str, max_steps: usize) -> Result<JsValue, JsValue> {
+        console_error_panic_hook::set_once();
+        let bytes = hex::decode(normalize_hex(checkpoint.trim()))
+            .map_err(|err| JsValue::from_str(&format!("invalid checkpoint hex: {err}")))?;
+        let depth = checkpoint_depth(&bytes).map_err(|err| JsValue::from_str(&err.to_string()))?;
+        let progress = match depth {
+            TRANSFER_TREE_HEIGHT => {
+                resume_with_depth::<TRANSFER_TREE_HEIGHT>(&self.local, &bytes, max_steps)?
+            }
+            GLOBAL_TRANSFER_TREE_HEIGHT => {
+                resume_with_depth::<GLOBAL_TRANSFER_TREE_HEIGHT>(&self.global, &bytes, max_steps)?
+            }
+            depth => return Err(unsupported_tree_height(depth)),
+        };
+        serde_wasm_bindgen::to_value(&progress).map_err(|err| JsValue::from_str(&err.to_string()))
+    }
 }
 
 #[wasm_bindgen]
 pub struct SingleWithdrawWasm {
     local: Groth16Params,
     global: Groth16Params,
+    pow_difficulty: usize,
 }
 
 #[wasm_bindgen]
 impl SingleWithdrawWasm {
+    /// `manifest_json` is the generator's `manifest.json`; every file is checked against it and
+    /// the circuits use its `pow_difficulty`.
     #[wasm_bindgen(constructor)]
     pub fn new(
+        manifest_json: &str,
         local_pk_bytes: Vec<u8>,
         local_vk_bytes: Vec<u8>,
         global_pk_bytes: Vec<u8>,
         global_vk_bytes: Vec<u8>,
     ) -> Result<SingleWithdrawWasm, JsValue> {
         console_error_panic_hook::set_once();
+        let manifest = open_manifest(manifest_json)?;
+        verify_file(&manifest, "withdraw_local_groth16_pk.bin", &local_pk_bytes)?;
+        verify_file(&manifest, "withdraw_local_groth16_vk.bin", &local_vk_bytes)?;
+        verify_file(
+            &manifest,
+            "withdraw_global_groth16_pk.bin",
+            &global_pk_bytes,
+        )?;
+        verify_file(
+            &manifest,
+            "withdraw_global_groth16_vk.bin",
+            &global_vk_bytes,
+        )?;
+        let pow_difficulty = manifest.pow_difficulty;
         let local = Groth16Params::from_bytes(local_pk_bytes, local_vk_bytes)
             .map_err(|err| JsValue::from_str(&err.to_string()))?;
         let global = Groth16Params::from_bytes(global_pk_bytes, global_vk_bytes)
             .map_err(|err| JsValue::from_str(&err.to_string()))?;
 
-        Ok(SingleWithdrawWasm { local, global })
+        Ok(SingleWithdrawWasm {
+            local,
+            global,
+            pow_difficulty,
+        })
     }
 
     #[wasm_bindgen]
@@ -208,12 +306,125 @@ impl SingleWithdrawWasm {
 
         let tree_depth = witness.siblings.len();
         match tree_depth {
-            TRANSFER_TREE_HEIGHT => {
-                prove_single_with_depth::<TRANSFER_TREE_HEIGHT>(&self.local, witness)
-            }
-            GLOBAL_TRANSFER_TREE_HEIGHT => {
-                prove_single_with_depth::<GLOBAL_TRANSFER_TREE_HEIGHT>(&self.global, witness)
-            }
+            TRANSFER_TREE_HEIGHT => prove_single_with_depth::<TRANSFER_TREE_HEIGHT>(
+                &self.local,
+                self.pow_difficulty,
+                witness,
+            ),
+            GLOBAL_TRANSFER_TREE_HEIGHT => prove_single_with_depth::<GLOBAL_TRANSFER_TREE_HEIGHT>(
+                &self.global,
+                self.pow_difficulty,
+                witness,
+            ),
+            _ => Err(JsValue::from_str(&format!(
+                "unsupported sibling path length: {tree_depth} (expected {TRANSFER_TREE_HEIGHT} or {GLOBAL_TRANSFER_TREE_HEIGHT})"
+            ))),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct MultiWithdrawWasm {
+    num_leaves: usize,
+    local: Groth16Params,
+    global: Groth16Params,
+    pow_difficulty: usize,
+}
+
+#[wasm_bindgen]
+impl MultiWithdrawWasm {
+    /// `manifest_json` is the generator's `manifest.json`; every file is checked against it and
+    /// the circuits use its `pow_difficulty`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        manifest_json: &str,
+        num_leaves: usize,
+        local_pk_bytes: Vec<u8>,
+        local_vk_bytes: Vec<u8>,
+        global_pk_bytes: Vec<u8>,
+        global_vk_bytes: Vec<u8>,
+    ) -> Result<MultiWithdrawWasm, JsValue> {
+        console_error_panic_hook::set_once();
+        if !MULTI_WITHDRAW_SIZES.contains(&num_leaves) {
+            return Err(JsValue::from_str(&format!(
+                "unsupported multi withdraw size: {num_leaves} (expected one of {MULTI_WITHDRAW_SIZES:?})"
+            )));
+        }
+        let manifest = open_manifest(manifest_json)?;
+        for (scope, pk, vk) in [
+            ("local", &local_pk_bytes, &local_vk_bytes),
+            ("global", &global_pk_bytes, &global_vk_bytes),
+        ] {
+            let prefix = format!("withdraw_{scope}_multi{num_leaves}");
+            verify_file(&manifest, &format!("{prefix}_groth16_pk.bin"), pk)?;
+            verify_file(&manifest, &format!("{prefix}_groth16_vk.bin"), vk)?;
+        }
+        let pow_difficulty = manifest.pow_difficulty;
+        let local = Groth16Params::from_bytes(local_pk_bytes, local_vk_bytes)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        let global = Groth16Params::from_bytes(global_pk_bytes, global_vk_bytes)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        Ok(MultiWithdrawWasm {
+            num_leaves,
+            local,
+            global,
+            pow_difficulty,
+        })
+    }
+
+    #[wasm_bindgen(getter, js_name = numLeaves)]
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Proves up to `numLeaves` leaves at once. Missing slots are padded with dummy leaves placed
+    /// after the last provided leaf index.
+    #[wasm_bindgen]
+    pub fn prove(&self, witness: JsValue) -> Result<JsValue, JsValue> {
+        console_error_panic_hook::set_once();
+        let witness: JsMultiWithdrawInput = serde_wasm_bindgen::from_value(witness)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        let tree_depth = witness
+            .leaves
+            .first()
+            .map(|leaf| leaf.siblings.len())
+            .ok_or_else(|| JsValue::from_str("multi withdraw requires at least one leaf"))?;
+        match (tree_depth, self.num_leaves) {
+            (TRANSFER_TREE_HEIGHT, 2) => prove_multi_with_depth::<TRANSFER_TREE_HEIGHT, 2>(
+                &self.local,
+                self.pow_difficulty,
+                witness,
+            ),
+            (TRANSFER_TREE_HEIGHT, 4) => prove_multi_with_depth::<TRANSFER_TREE_HEIGHT, 4>(
+                &self.local,
+                self.pow_difficulty,
+                witness,
+            ),
+            (TRANSFER_TREE_HEIGHT, 8) => prove_multi_with_depth::<TRANSFER_TREE_HEIGHT, 8>(
+                &self.local,
+                self.pow_difficulty,
+                witness,
+            ),
+            (GLOBAL_TRANSFER_TREE_HEIGHT, 2) => prove_multi_with_depth::<
+                GLOBAL_TRANSFER_TREE_HEIGHT,
+                2,
+            >(
+                &self.global, self.pow_difficulty, witness
+            ),
+            (GLOBAL_TRANSFER_TREE_HEIGHT, 4) => prove_multi_with_depth::<
+                GLOBAL_TRANSFER_TREE_HEIGHT,
+                4,
+            >(
+                &self.global, self.pow_difficulty, witness
+            ),
+            (GLOBAL_TRANSFER_TREE_HEIGHT, 8) => prove_multi_with_depth::<
+                GLOBAL_TRANSFER_TREE_HEIGHT,
+                8,
+            >(
+                &self.global, self.pow_difficulty, witness
+            ),
             _ => Err(JsValue::from_str(&format!(
                 "unsupported sibling path length: {tree_depth} (expected {TRANSFER_TREE_HEIGHT} or {GLOBAL_TRANSFER_TREE_HEIGHT})"
             ))),
@@ -221,20 +432,76 @@ impl SingleWithdrawWasm {
     }
 }
 
+fn parse_withdraw_request(
+    z0: JsValue,
+    steps: JsValue,
+) -> Result<(Vec<Fr>, Vec<JsExternalInput>), JsValue> {
+    let z0_hex: Vec<String> =
+        serde_wasm_bindgen::from_value(z0).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let step_inputs: Vec<JsExternalInput> =
+        serde_wasm_bindgen::from_value(steps).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    if z0_hex.len() != WITHDRAW_STATE_LEN {
+        return Err(JsValue::from_str(
+            "z0 must contain exactly seven field elements",
+        ));
+    }
+    let z0_fields = z0_hex
+        .iter()
+        .map(|value| hex_to_fr(value))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(anyhow_to_js_error)?;
+    Ok((z0_fields, step_inputs))
+}
+
+fn steps_tree_height(step_inputs: &[JsExternalInput]) -> Result<usize, JsValue> {
+    let tree_height = step_inputs
+        .first()
+        .map(|input| input.siblings.len())
+        .unwrap_or(TRANSFER_TREE_HEIGHT);
+
+    if step_inputs
+        .iter()
+        .any(|input| input.siblings.len() != tree_height)
+    {
+        return Err(JsValue::from_str(
+            "all steps must have sibling paths with uniform length",
+        ));
+    }
+    Ok(tree_height)
+}
+
+fn unsupported_tree_height(tree_height: usize) -> JsValue {
+    JsValue::from_str(&format!(
+        "unsupported sibling path length: {tree_height} (expected {TRANSFER_TREE_HEIGHT} or {GLOBAL_TRANSFER_TREE_HEIGHT})"
+    ))
+}
+
 fn prove_with_depth<const DEPTH: usize>(
     params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
     z0_fields: Vec<Fr>,
     step_inputs: &[JsExternalInput],
 ) -> Result<JsValue, JsValue> {
     let prove_start = Instant::now();
-    let mut nova = params
-        .initial_nova(z0_fields)
-        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let mut session = start_with_depth(params, z0_fields, step_inputs)?;
+    prove_session_steps(&mut session, usize::MAX)?;
 
-    let mut rng = rand::thread_rng();
+    log_timing(&format!(
+        "WithdrawNovaWasm::prove total {:.2} ms for {} steps",
+        prove_start.elapsed().as_secs_f64() * 1_000.0,
+        step_inputs.len()
+    ));
 
-    for (idx, external) in step_inputs.iter().enumerate() {
-        let step_start = Instant::now();
+    let result = finish_session(params, &session)?;
+    serde_wasm_bindgen::to_value(&result).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+fn start_with_depth<const DEPTH: usize>(
+    params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+    z0_fields: Vec<Fr>,
+    step_inputs: &[JsExternalInput],
+) -> Result<WithdrawSession<DEPTH>, JsValue> {
+    let mut ext_inputs = Vec::with_capacity(step_inputs.len());
+    for external in step_inputs {
         let siblings_vec = external
             .siblings
             .iter()
@@ -253,11 +520,13 @@ fn prove_with_depth<const DEPTH: usize>(
             .try_into()
             .map_err(|_| JsValue::from_str("failed to convert siblings into fixed-size array"))?;
 
+        let association_siblings =
+            parse_association_siblings::<DEPTH>(&external.association_siblings)?;
         let leaf_index = parse_u64(&external.leaf_index).map_err(anyhow_to_js_error)?;
         let value_fr = hex_to_fr(&external.value).map_err(anyhow_to_js_error)?;
         let secret_fr = hex_to_fr(&external.secret).map_err(anyhow_to_js_error)?;
 
-        let ext_input = WithdrawExternalInputs {
+        ext_inputs.push(WithdrawExternalInputs {
             is_dummy: if external.is_dummy {
                 Fr::from(1u64)
             } else {
@@ -267,25 +536,66 @@ fn prove_with_depth<const DEPTH: usize>(
             secret: secret_fr,
             leaf_index: Fr::from(leaf_index),
             siblings,
-        };
+            association_siblings,
+        });
+    }
+    WithdrawSession::new(params, z0_fields, ext_inputs)
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
 
-        nova.prove_step(&mut rng, ext_input, None)
-            .map_err(|err| JsValue::from_str(&format!("prove_step failed at {idx}: {err}")))?;
+fn resume_with_depth<const DEPTH: usize>(
+    params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+    checkpoint: &[u8],
+    max_steps: usize,
+) -> Result<JsSessionProgress, JsValue> {
+    let mut session = WithdrawSession::<DEPTH>::resume(params, checkpoint)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    prove_session_steps(&mut session, max_steps)?;
+    let checkpoint = session
+        .checkpoint()
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let result = if session.is_complete() {
+        Some(finish_session(params, &session)?)
+    } else {
+        None
+    };
+    Ok(JsSessionProgress {
+        checkpoint: format!("0x{}", hex::encode(checkpoint)),
+        steps_done: session.steps_done(),
+        total_steps: session.total_steps(),
+        result,
+    })
+}
+
+fn prove_session_steps<const DEPTH: usize>(
+    session: &mut WithdrawSession<DEPTH>,
+    max_steps: usize,
+) -> Result<(), JsValue> {
+    let mut rng = rand::thread_rng();
+    for _ in 0..max_steps {
+        if session.is_complete() {
+            break;
+        }
+        let idx = session.steps_done();
+        let step_start = Instant::now();
+        session
+            .prove_next_step(&mut rng)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
         log_timing(&format!(
             "WithdrawNovaWasm::prove_step[{idx}] {duration:.2} ms",
             duration = step_start.elapsed().as_secs_f64() * 1_000.0
         ));
     }
+    Ok(())
+}
 
-    log_timing(&format!(
-        "WithdrawNovaWasm::prove total {:.2} ms for {} steps",
-        prove_start.elapsed().as_secs_f64() * 1_000.0,
-        step_inputs.len()
-    ));
-
-    let state = nova.state();
+fn finish_session<const DEPTH: usize>(
+    params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+    session: &WithdrawSession<DEPTH>,
+) -> Result<JsProveResult, JsValue> {
+    let state = session.state();
     let final_state = state.iter().map(fr_to_hex).collect::<Vec<_>>();
-    let ivc_proof = nova.ivc_proof();
+    let ivc_proof = session.ivc_proof();
     params
         .verify(ivc_proof.clone())
         .map_err(|err| JsValue::from_str(&err.to_string()))?;
@@ -293,16 +603,16 @@ fn prove_with_depth<const DEPTH: usize>(
     ivc_proof
         .serialize_uncompressed(&mut proof_bytes)
         .map_err(|err| JsValue::from_str(&err.to_string()))?;
-    let result = JsProveResult {
+    Ok(JsProveResult {
         final_state,
         ivc_proof: format!("0x{}", hex::encode(proof_bytes)),
-        steps: step_inputs.len(),
-    };
-    serde_wasm_bindgen::to_value(&result).map_err(|err| JsValue::from_str(&err.to_string()))
+        steps: session.total_steps(),
+    })
 }
 
 fn prove_single_with_depth<const DEPTH: usize>(
     params: &Groth16Params,
+    pow_difficulty: usize,
     witness: JsSingleWithdrawInput,
 ) -> Result<JsValue, JsValue> {
     let prove_start = Instant::now();
@@ -310,11 +620,15 @@ fn prove_single_with_depth<const DEPTH: usize>(
         merkle_root,
         recipient,
         withdraw_value,
+        relayer,
+        fee,
+        association_root,
         value,
         delta,
         secret,
         leaf_index,
         siblings,
+        association_siblings,
     } = witness;
 
     if siblings.len() != DEPTH {
@@ -329,6 +643,11 @@ fn prove_single_with_depth<const DEPTH: usize>(
     let merkle_root_fr = hex_to_fr(&merkle_root).map_err(anyhow_to_js_error)?;
     let recipient_fr = hex_to_fr(&recipient).map_err(anyhow_to_js_error)?;
     let withdraw_value_fr = hex_to_fr(&withdraw_value).map_err(anyhow_to_js_error)?;
+    let relayer_fr = optional_hex_to_fr(relayer.as_deref()).map_err(anyhow_to_js_error)?;
+    let fee_fr = optional_hex_to_fr(fee.as_deref()).map_err(anyhow_to_js_error)?;
+    let association_root_fr =
+        optional_hex_to_fr(association_root.as_deref()).map_err(anyhow_to_js_error)?;
+    let association_siblings_arr = parse_association_siblings::<DEPTH>(&association_siblings)?;
     let value_fr = hex_to_fr(&value).map_err(anyhow_to_js_error)?;
     let delta_fr = hex_to_fr(&delta).map_err(anyhow_to_js_error)?;
     let secret_fr = hex_to_fr(&secret).map_err(anyhow_to_js_error)?;
@@ -345,15 +664,22 @@ fn prove_single_with_depth<const DEPTH: usize>(
 
     let circuit = SingleWithdrawCircuit::<Fr, DEPTH> {
         poseidon_params,
+        pow_difficulty,
         merkle_root: Some(merkle_root_fr),
         recipient: Some(recipient_fr),
         withdraw_value: Some(withdraw_value_fr),
+        relayer: Some(relayer_fr),
+        fee: Some(fee_fr),
+        association_root: Some(association_root_fr),
         value: Some(value_fr),
         delta: Some(delta_fr),
         secret: Some(secret_fr),
         leaf_index: Some(leaf_index_u64),
         siblings: siblings_arr.map(Some),
+        association_siblings: association_siblings_arr.map(Some),
     };
+    validate_single_withdraw(&circuit)
+        .map_err(|err| JsValue::from_str(&format!("invalid single withdraw witness: {err}")))?;
 
     let public_inputs = circuit
         .public_inputs()
@@ -379,6 +705,104 @@ fn prove_single_with_depth<const DEPTH: usize>(
     serde_wasm_bindgen::to_value(&result).map_err(|err| JsValue::from_str(&err.to_string()))
 }
 
+fn prove_multi_with_depth<const DEPTH: usize, const N: usize>(
+    params: &Groth16Params,
+    pow_difficulty: usize,
+    witness: JsMultiWithdrawInput,
+) -> Result<JsValue, JsValue> {
+    let prove_start = Instant::now();
+    let JsMultiWithdrawInput {
+        merkle_root,
+        recipient,
+        withdraw_value,
+        relayer,
+        fee,
+        association_root,
+        delta,
+        leaves,
+    } = witness;
+
+    if leaves.len() > N {
+        return Err(JsValue::from_str(&format!(
+            "too many leaves for a {N}-leaf withdraw: {}",
+            leaves.len()
+        )));
+    }
+
+    let mut circuit =
+        MultiWithdrawCircuit::<Fr, DEPTH, N>::new(circom_poseidon_config(), pow_difficulty);
+    circuit.merkle_root = Some(hex_to_fr(&merkle_root).map_err(anyhow_to_js_error)?);
+    circuit.recipient = Some(hex_to_fr(&recipient).map_err(anyhow_to_js_error)?);
+    circuit.withdraw_value = Some(hex_to_fr(&withdraw_value).map_err(anyhow_to_js_error)?);
+    circuit.relayer = Some(optional_hex_to_fr(relayer.as_deref()).map_err(anyhow_to_js_error)?);
+    circuit.fee = Some(optional_hex_to_fr(fee.as_deref()).map_err(anyhow_to_js_error)?);
+    circuit.association_root =
+        Some(optional_hex_to_fr(association_root.as_deref()).map_err(anyhow_to_js_error)?);
+    circuit.delta = Some(hex_to_fr(&delta).map_err(anyhow_to_js_error)?);
+
+    let mut next_index = 0u64;
+    for (slot, leaf) in leaves.iter().enumerate() {
+        let siblings_vec = leaf
+            .siblings
+            .iter()
+            .map(|sibling| hex_to_fr(sibling))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow_to_js_error)?;
+        if siblings_vec.len() != DEPTH {
+            return Err(JsValue::from_str(&format!(
+                "Merkle path must have {DEPTH} siblings, found {}",
+                siblings_vec.len()
+            )));
+        }
+        let siblings: [Fr; DEPTH] = siblings_vec
+            .try_into()
+            .map_err(|_| JsValue::from_str("failed to convert siblings into fixed-size array"))?;
+
+        let leaf_index = parse_u64(&leaf.leaf_index).map_err(anyhow_to_js_error)?;
+        circuit.leaves[slot] = if leaf.is_dummy {
+            MultiWithdrawLeaf::dummy(leaf_index)
+        } else {
+            MultiWithdrawLeaf::new(
+                hex_to_fr(&leaf.value).map_err(anyhow_to_js_error)?,
+                hex_to_fr(&leaf.secret).map_err(anyhow_to_js_error)?,
+                leaf_index,
+                siblings,
+            )
+            .with_association_siblings(parse_association_siblings::<DEPTH>(
+                &leaf.association_siblings,
+            )?)
+        };
+        next_index = leaf_index + 1;
+    }
+    for slot in leaves.len()..N {
+        circuit.leaves[slot] = MultiWithdrawLeaf::dummy(next_index);
+        next_index += 1;
+    }
+
+    let public_inputs = circuit
+        .public_inputs()
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let proof_bytes = params
+        .generate_proof(&mut rng, circuit, &public_inputs)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    log_timing(&format!(
+        "MultiWithdrawWasm::prove total {:.2} ms (depth {DEPTH}, {N} leaves)",
+        prove_start.elapsed().as_secs_f64() * 1_000.0
+    ));
+
+    let public_inputs_hex = public_inputs.iter().map(fr_to_hex).collect::<Vec<_>>();
+    let result = JsGroth16ProveResult {
+        proof_calldata: format!("0x{}", hex::encode(proof_bytes)),
+        public_inputs: public_inputs_hex,
+        tree_depth: DEPTH,
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
 fn parse_u64(value: &str) -> anyhow::Result<u64> {
     let normalized = value.trim();
     if normalized.is_empty() {
@@ -401,6 +825,37 @@ pub fn hex_to_fr(value: &str) -> anyhow::Result<Fr> {
     Ok(Fr::from_be_bytes_mod_order(&bytes))
 }
 
+/// PoW difficulty passed from JS, falling back to `POW_DIFFICULTY` when omitted.
+fn parse_pow_difficulty(pow_difficulty: Option<u32>) -> anyhow::Result<usize> {
+    let pow_difficulty = pow_difficulty.map_or(POW_DIFFICULTY, |difficulty| difficulty as usize);
+    check_pow_difficulty(pow_difficulty)?;
+    Ok(pow_difficulty)
+}
+
+fn optional_hex_to_fr(value: Option<&str>) -> anyhow::Result<Fr> {
+    value.map(hex_to_fr).unwrap_or(Ok(Fr::from(0u64)))
+}
+
+/// An empty list stands for a withdraw without an association set.
+fn parse_association_siblings<const DEPTH: usize>(
+    siblings: &[String],
+) -> Result<[Fr; DEPTH], JsValue> {
+    if siblings.is_empty() {
+        return Ok([Fr::from(0u64); DEPTH]);
+    }
+    let siblings_vec = siblings
+        .iter()
+        .map(|sibling| hex_to_fr(sibling))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(anyhow_to_js_error)?;
+    siblings_vec.try_into().map_err(|siblings: Vec<Fr>| {
+        JsValue::from_str(&format!(
+            "Association path must have {DEPTH} siblings, found {}",
+            siblings.len()
+        ))
+    })
+}
+
 pub fn fr_to_hex(value: &Fr) -> String {
     use ark_ff::BigInteger;
 
@@ -413,6 +868,28 @@ pub fn fr_to_hex(value: &Fr) -> String {
     format!("0x{}", hex::encode(bytes))
 }
 
+/// Parses a `manifest.json` and checks that it matches the circuits compiled into this build.
+fn open_manifest(manifest_json: &str) -> Result<ArtifactManifest, JsValue> {
+    let manifest = ArtifactManifest::from_json(manifest_json)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    manifest
+        .ensure_compatible()
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    if manifest.compression != ArtifactCompression::Uncompressed {
+        return Err(JsValue::from_str(&format!(
+            "the WASM prover loads uncompressed artifacts, but the manifest lists {} files",
+            manifest.compression
+        )));
+    }
+    Ok(manifest)
+}
+
+fn verify_file(manifest: &ArtifactManifest, file_name: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    manifest
+        .verify(file_name, bytes)
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
 fn anyhow_to_js_error(err: anyhow::Error) -> JsValue {
     JsValue::from_str(&err.to_string())
 }
@@ -448,6 +925,25 @@ fn parse_address_hex(value: &str) -> anyhow::Result<Address> {
     Address::from_str(value).context("invalid recipient address")
 }
 
+/// Accepts either a bare EVM address or a `<namespace>:<chain id>:<address>` account, whose chain
+/// id must then match `chain_id`.
+fn parse_recipient(chain_id: u64, value: &str) -> anyhow::Result<RecipientAccount> {
+    if !value.contains(':') {
+        return Ok(RecipientAccount::new_evm(
+            chain_id,
+            parse_address_hex(value)?,
+        ));
+    }
+    let account = RecipientAccount::from_str(value).context("invalid recipient account")?;
+    anyhow::ensure!(
+        account.chain_id == chain_id,
+        "recipient account is on chain {}, expected {}",
+        account.chain_id,
+        chain_id
+    );
+    Ok(account)
+}
+
 fn b256_to_hex_string(value: B256) -> String {
     format!("0x{}", hex::encode(value.as_slice()))
 }
@@ -471,19 +967,27 @@ fn general_recipient_to_js(gr: &GeneralRecipient) -> JsGeneralRecipient {
     let fr_value = gr.to_fr();
     let fr_hex = fr_to_hex_checked(fr_value);
     let u256_hex = b256_to_hex_string(fr_to_b256(fr_value));
-    let recipient_address = Address::from_word(gr.address);
+    let address = match gr.account().evm_address() {
+        Some(recipient_address) => address_to_hex_string(recipient_address),
+        None => gr.family.format_address(&gr.address),
+    };
     JsGeneralRecipient {
         chain_id: gr.chain_id,
-        address: address_to_hex_string(recipient_address),
+        family: gr.family.namespace().to_string(),
+        account: gr.account().to_string(),
+        address,
         tweak: b256_to_hex_string(gr.tweak),
         fr: fr_hex,
         u256: u256_hex,
     }
 }
 
-fn build_burn_artifacts(burn: &FullBurnAddress) -> anyhow::Result<JsBurnArtifacts> {
+fn build_burn_artifacts(
+    burn: &FullBurnAddress,
+    pow_difficulty: usize,
+) -> anyhow::Result<JsBurnArtifacts> {
     let burn_address = burn
-        .burn_address()
+        .burn_address(pow_difficulty)
         .context("failed to derive burn address from payload")?;
     Ok(JsBurnArtifacts {
         burn_address: address_to_hex_string(burn_address),
@@ -493,6 +997,21 @@ fn build_burn_artifacts(burn: &FullBurnAddress) -> anyhow::Result<JsBurnArtifact
     })
 }
 
+/// Checks downloaded artifact bytes against the generator's `manifest.json`, e.g. to reject a bad
+/// download before it is cached. The prover constructors above run the same check themselves.
+/// Fails if the manifest was produced for a different circuit version, tree heights or Poseidon
+/// config, or if `bytes` do not match its hash.
+#[wasm_bindgen]
+pub fn verify_artifact(manifest_json: &str, file_name: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    let manifest = ArtifactManifest::from_json(manifest_json)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    manifest
+        .ensure_compatible()
+        .and_then(|_| manifest.verify(file_name, bytes))
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
 #[wasm_bindgen]
 pub fn seed_message() -> String {
     SEED_MESSAGE.to_string()
@@ -504,17 +1023,16 @@ pub fn derive_payment_advice(
     payment_advice_id_hex: &str,
     recipient_chain_id: u64,
     recipient_address_hex: &str,
+    pow_difficulty: Option<u32>,
 ) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
+    let pow_difficulty = parse_pow_difficulty(pow_difficulty).map_err(anyhow_to_js_error)?;
     let seed = parse_b256_hex(seed_hex).map_err(anyhow_to_js_error)?;
     let payment_advice_id = parse_b256_hex(payment_advice_id_hex).map_err(anyhow_to_js_error)?;
-    let recipient_address = parse_address_hex(recipient_address_hex).map_err(anyhow_to_js_error)?;
-    let secret_and_tweak = SecretAndTweak::payment_advice(
-        payment_advice_id,
-        seed,
-        recipient_chain_id,
-        recipient_address,
-    );
+    let recipient =
+        parse_recipient(recipient_chain_id, recipient_address_hex).map_err(anyhow_to_js_error)?;
+    let secret_and_tweak =
+        SecretAndTweak::payment_advice(payment_advice_id, seed, recipient, pow_difficulty);
     let result = secret_tweak_to_js(secret_and_tweak);
     serde_wasm_bindgen::to_value(&result).map_err(serde_error_to_js)
 }
@@ -525,13 +1043,16 @@ pub fn derive_invoice_single(
     invoice_id_hex: &str,
     recipient_chain_id: u64,
     recipient_address_hex: &str,
+    pow_difficulty: Option<u32>,
 ) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
+    let pow_difficulty = parse_pow_difficulty(pow_difficulty).map_err(anyhow_to_js_error)?;
     let seed = parse_b256_hex(seed_hex).map_err(anyhow_to_js_error)?;
     let invoice_id = parse_b256_hex(invoice_id_hex).map_err(anyhow_to_js_error)?;
-    let recipient_address = parse_address_hex(recipient_address_hex).map_err(anyhow_to_js_error)?;
+    let recipient =
+        parse_recipient(recipient_chain_id, recipient_address_hex).map_err(anyhow_to_js_error)?;
     let secret_and_tweak =
-        SecretAndTweak::single_invoice(invoice_id, seed, recipient_chain_id, recipient_address);
+        SecretAndTweak::single_invoice(invoice_id, seed, recipient, pow_difficulty);
     let result = secret_tweak_to_js(secret_and_tweak);
     serde_wasm_bindgen::to_value(&result).map_err(serde_error_to_js)
 }
@@ -543,18 +1064,16 @@ pub fn derive_invoice_batch(
     sub_id: u32,
     recipient_chain_id: u64,
     recipient_address_hex: &str,
+    pow_difficulty: Option<u32>,
 ) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
+    let pow_difficulty = parse_pow_difficulty(pow_difficulty).map_err(anyhow_to_js_error)?;
     let seed = parse_b256_hex(seed_hex).map_err(anyhow_to_js_error)?;
     let invoice_id = parse_b256_hex(invoice_id_hex).map_err(anyhow_to_js_error)?;
-    let recipient_address = parse_address_hex(recipient_address_hex).map_err(anyhow_to_js_error)?;
-    let secret_and_tweak = SecretAndTweak::batch_invoice(
-        invoice_id,
-        sub_id,
-        seed,
-        recipient_chain_id,
-        recipient_address,
-    );
+    let recipient =
+        parse_recipient(recipient_chain_id, recipient_address_hex).map_err(anyhow_to_js_error)?;
+    let secret_and_tweak =
+        SecretAndTweak::batch_invoice(invoice_id, sub_id, seed, recipient, pow_difficulty);
     let result = secret_tweak_to_js(secret_and_tweak);
     serde_wasm_bindgen::to_value(&result).map_err(serde_error_to_js)
 }
@@ -565,27 +1084,34 @@ pub fn build_full_burn_address(
     recipient_address_hex: &str,
     secret_hex: &str,
     tweak_hex: &str,
+    pow_difficulty: Option<u32>,
 ) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
-    let recipient_address = parse_address_hex(recipient_address_hex).map_err(anyhow_to_js_error)?;
+    let pow_difficulty = parse_pow_difficulty(pow_difficulty).map_err(anyhow_to_js_error)?;
+    let recipient =
+        parse_recipient(recipient_chain_id, recipient_address_hex).map_err(anyhow_to_js_error)?;
     let secret = parse_b256_hex(secret_hex).map_err(anyhow_to_js_error)?;
     let tweak = parse_b256_hex(tweak_hex).map_err(anyhow_to_js_error)?;
     let secret_and_tweak = SecretAndTweak { secret, tweak };
-    let burn = FullBurnAddress::new(recipient_chain_id, recipient_address, &secret_and_tweak)
+    let burn = FullBurnAddress::new(recipient, &secret_and_tweak, pow_difficulty)
         .map_err(anyhow_to_js_error)?;
-    let artifacts = build_burn_artifacts(&burn).map_err(anyhow_to_js_error)?;
+    let artifacts = build_burn_artifacts(&burn, pow_difficulty).map_err(anyhow_to_js_error)?;
     serde_wasm_bindgen::to_value(&artifacts).map_err(serde_error_to_js)
 }
 
 #[wasm_bindgen]
-pub fn decode_full_burn_address(full_burn_address_hex: &str) -> Result<JsValue, JsValue> {
+pub fn decode_full_burn_address(
+    full_burn_address_hex: &str,
+    pow_difficulty: Option<u32>,
+) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
+    let pow_difficulty = parse_pow_difficulty(pow_difficulty).map_err(anyhow_to_js_error)?;
     let payload_hex = normalize_hex(full_burn_address_hex);
     let bytes = hex::decode(payload_hex)
         .context("invalid FullBurnAddress hex")
         .map_err(anyhow_to_js_error)?;
     let burn = FullBurnAddress::from_bytes(&bytes).map_err(anyhow_to_js_error)?;
-    let artifacts = build_burn_artifacts(&burn).map_err(anyhow_to_js_error)?;
+    let artifacts = build_burn_artifacts(&burn, pow_difficulty).map_err(anyhow_to_js_error)?;
     serde_wasm_bindgen::to_value(&artifacts).map_err(serde_error_to_js)
 }
 
@@ -596,9 +1122,9 @@ pub fn general_recipient_fr(
     tweak_hex: &str,
 ) -> Result<String, JsValue> {
     console_error_panic_hook::set_once();
-    let recipient_address = parse_address_hex(recipient_address_hex).map_err(anyhow_to_js_error)?;
+    let recipient = parse_recipient(chain_id, recipient_address_hex).map_err(anyhow_to_js_error)?;
     let tweak = parse_b256_hex(tweak_hex).map_err(anyhow_to_js_error)?;
-    let gr = GeneralRecipient::new_evm(chain_id, recipient_address, tweak);
+    let gr = recipient.with_tweak(tweak);
     Ok(fr_to_hex_checked(gr.to_fr()))
 }
 
@@ -611,22 +1137,25 @@ fn parse_u256_hex(value: &str) -> anyhow::Result<U256> {
     U256::from_str_radix(normalized, 16).context("invalid hex-encoded U256")
 }
 
+fn aggregation_tree_from_snapshot(snapshot: &[String]) -> anyhow::Result<MerkleTree> {
+    let mut leaves = Vec::new();
+    for (idx, hex) in snapshot.iter().enumerate() {
+        let value = parse_u256_hex(hex)?;
+        if !value.is_zero() {
+            leaves.push((idx as u64, u256_to_fr(value)));
+        }
+    }
+    let mut tree = MerkleTree::new(AGGREGATION_TREE_HEIGHT);
+    tree.update_leaves(leaves);
+    Ok(tree)
+}
+
 #[wasm_bindgen]
 pub fn aggregation_root(snapshot_hex: JsValue) -> Result<String, JsValue> {
     console_error_panic_hook::set_once();
     let snapshot: Vec<String> = serde_wasm_bindgen::from_value(snapshot_hex)
         .map_err(|err| JsValue::from_str(&err.to_string()))?;
-    let mut tree = MerkleTree::new(AGGREGATION_TREE_HEIGHT);
-    for (idx, hex) in snapshot.iter().enumerate() {
-        let value = match parse_u256_hex(hex) {
-            Ok(val) => val,
-            Err(err) => return Err(anyhow_to_js_error(err)),
-        };
-        if value.is_zero() {
-            continue;
-        }
-        tree.update_leaf(idx as u64, u256_to_fr(value));
-    }
+    let tree = aggregation_tree_from_snapshot(&snapshot).map_err(anyhow_to_js_error)?;
     Ok(fr_to_hex(&tree.get_root()))
 }
 
@@ -635,17 +1164,7 @@ pub fn aggregation_merkle_proof(snapshot_hex: JsValue, index: u32) -> Result<JsV
     console_error_panic_hook::set_once();
     let snapshot: Vec<String> = serde_wasm_bindgen::from_value(snapshot_hex)
         .map_err(|err| JsValue::from_str(&err.to_string()))?;
-    let mut tree = MerkleTree::new(AGGREGATION_TREE_HEIGHT);
-    for (idx, hex) in snapshot.iter().enumerate() {
-        let value = match parse_u256_hex(hex) {
-            Ok(val) => val,
-            Err(err) => return Err(anyhow_to_js_error(err)),
-        };
-        if value.is_zero() {
-            continue;
-        }
-        tree.update_leaf(idx as u64, u256_to_fr(value));
-    }
+    let tree = aggregation_tree_from_snapshot(&snapshot).map_err(anyhow_to_js_error)?;
     if index as usize >= (1usize << AGGREGATION_TREE_HEIGHT) {
         return Err(JsValue::from_str("aggregation index exceeds tree capacity"));
     }
@@ -669,8 +1188,8 @@ mod tests {
         let secret_and_tweak = SecretAndTweak::payment_advice(
             parse_b256_hex(advice).expect("advice hex"),
             parse_b256_hex(seed).expect("seed hex"),
-            chain_id,
-            recipient_address,
+            RecipientAccount::new_evm(chain_id, recipient_address),
+            POW_DIFFICULTY,
         );
         let js_value = secret_tweak_to_js(secret_and_tweak);
 
@@ -693,12 +1212,13 @@ mod tests {
         let recipient_address =
             Address::from_str("0x1111111111111111111111111111111111111111").unwrap();
         let chain_id = 42161u64;
+        let recipient = RecipientAccount::new_evm(chain_id, recipient_address);
         let secret_and_tweak =
-            SecretAndTweak::single_invoice(invoice, seed, chain_id, recipient_address);
+            SecretAndTweak::single_invoice(invoice, seed, recipient, POW_DIFFICULTY);
 
-        let burn = FullBurnAddress::new(chain_id, recipient_address, &secret_and_tweak)?;
-        let artifacts = build_burn_artifacts(&burn)?;
-        let expected_burn_address = address_to_hex_string(burn.burn_address()?);
+        let burn = FullBurnAddress::new(recipient, &secret_and_tweak, POW_DIFFICULTY)?;
+        let artifacts = build_burn_artifacts(&burn, POW_DIFFICULTY)?;
+        let expected_burn_address = address_to_hex_string(burn.burn_address(POW_DIFFICULTY)?);
 
         assert_eq!(artifacts.burn_address, expected_burn_address);
         assert_eq!(
@@ -715,6 +1235,34 @@ mod tests {
             artifacts.general_recipient.fr,
             fr_to_hex_checked(burn.gr.to_fr())
         );
+        assert_eq!(
+            artifacts.general_recipient.address,
+            address_to_hex_string(recipient_address)
+        );
+        assert_eq!(artifacts.general_recipient.family, "eip155");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_recipient_accepts_addresses_and_accounts() -> Result<()> {
+        let address = "0x1111111111111111111111111111111111111111";
+        let evm = parse_recipient(10, address)?;
+        assert_eq!(
+            evm,
+            RecipientAccount::new_evm(10, Address::from_str(address)?)
+        );
+        assert_eq!(parse_recipient(10, &evm.to_string())?, evm);
+
+        let solana = RecipientAccount::new_solana(900, [0x44; 32]);
+        assert_eq!(parse_recipient(900, &solana.to_string())?, solana);
+        assert!(parse_recipient(901, &solana.to_string()).is_err());
+
+        let secret_and_tweak =
+            SecretAndTweak::payment_advice(B256::ZERO, B256::ZERO, solana, POW_DIFFICULTY);
+        let burn = FullBurnAddress::new(solana, &secret_and_tweak, POW_DIFFICULTY)?;
+        let artifacts = build_burn_artifacts(&burn, POW_DIFFICULTY)?;
+        assert_eq!(artifacts.general_recipient.family, "solana");
+        assert_eq!(artifacts.general_recipient.account, solana.to_string());
         Ok(())
     }
 
@@ -729,10 +1277,11 @@ mod tests {
         let recipient_address =
             Address::from_str("0x8888888888888888888888888888888888888888").unwrap();
         let chain_id = 10u64;
+        let recipient = RecipientAccount::new_evm(chain_id, recipient_address);
         let secret_and_tweak =
-            SecretAndTweak::single_invoice(invoice, seed, chain_id, recipient_address);
+            SecretAndTweak::single_invoice(invoice, seed, recipient, POW_DIFFICULTY);
 
-        let burn = FullBurnAddress::new(chain_id, recipient_address, &secret_and_tweak)?;
+        let burn = FullBurnAddress::new(recipient, &secret_and_tweak, POW_DIFFICULTY)?;
         let encoded = burn.to_bytes();
         let decoded = FullBurnAddress::from_bytes(&encoded)?;
 
@@ -742,4 +1291,11 @@ mod tests {
         assert_eq!(decoded.secret, burn.secret);
         Ok(())
     }
+
+    #[test]
+    fn pow_difficulty_defaults_and_is_bounded() {
+        assert_eq!(parse_pow_difficulty(None).unwrap(), POW_DIFFICULTY);
+        assert_eq!(parse_pow_difficulty(Some(8)).unwrap(), 8);
+        assert!(parse_pow_difficulty(Some(64)).is_err());
+    }
 }
@@ -8,7 +8,10 @@ use wasm_bindgen::JsValue;
 use wasm_bindgen_test::*;
 use zkerc20_wasm::{JsSingleWithdrawInput, SingleWithdrawWasm, fr_to_hex};
 use zkp::{
-    circuits::burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+    circuits::{
+        burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+        constants::POW_DIFFICULTY,
+    },
     nova::constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
     utils::{
         convertion::{address_to_fr, fr_to_address, u256_to_fr},
@@ -36,7 +39,7 @@ fn test_single_withdraw_wasm_prove() {
         Address::from_str("0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1").unwrap();
     let recipient_fr = address_to_fr(recipient_address);
     let secret_seed = Fr::from(123_456u64);
-    let nonce = find_pow_nonce(recipient_fr, secret_seed);
+    let nonce = find_pow_nonce(recipient_fr, secret_seed, POW_DIFFICULTY);
     let secret_fr = secret_from_nonce(secret_seed, nonce);
     let value_u256 = U256::from(5_000u64);
     let delta_fr = Fr::from(1_000u64);
@@ -61,7 +64,10 @@ fn test_single_withdraw_wasm_prove() {
     let global_pk = include_bytes!("../../nova_artifacts/withdraw_global_groth16_pk.bin").to_vec();
     let global_vk = include_bytes!("../../nova_artifacts/withdraw_global_groth16_vk.bin").to_vec();
 
-    let prover = SingleWithdrawWasm::new(local_pk, local_vk, global_pk, global_vk).unwrap();
+    let manifest = include_str!("../../nova_artifacts/manifest.json");
+
+    let prover =
+        SingleWithdrawWasm::new(manifest, local_pk, local_vk, global_pk, global_vk).unwrap();
 
     let local_js: JsValue = serde_wasm_bindgen::to_value(&local_witness).unwrap();
     let local_result_js = prover.prove(local_js).unwrap();
@@ -101,8 +107,8 @@ fn build_witness_input(
 ) -> JsSingleWithdrawInput {
     let mut tree = IncrementalMerkleTree::new(tree_height);
 
-    let leaf_address_fr =
-        compute_burn_address_from_secret(recipient_fr, secret_fr).expect("secret satisfies PoW");
+    let leaf_address_fr = compute_burn_address_from_secret(recipient_fr, secret_fr, POW_DIFFICULTY)
+        .expect("secret satisfies PoW");
     let leaf_address = fr_to_address(leaf_address_fr);
 
     let index = tree.insert(leaf_address, value_u256);
@@ -117,10 +123,14 @@ fn build_witness_input(
         merkle_root: fr_to_hex(&root),
         recipient: fr_to_hex(&recipient_fr),
         withdraw_value: fr_to_hex(&withdraw_value_fr),
+        relayer: None,
+        fee: None,
+        association_root: None,
         value: fr_to_hex(&value_fr),
         delta: fr_to_hex(&delta_fr),
         secret: fr_to_hex(&secret_fr),
         leaf_index: index.to_string(),
         siblings: proof.siblings.iter().map(fr_to_hex).collect(),
+        association_siblings: vec![],
     }
 }
@@ -10,11 +10,16 @@ use wasm_bindgen_test::*;
 use web_time::Instant;
 use zkerc20_wasm::{JsExternalInput, WithdrawNovaWasm, fr_to_hex, hex_to_fr, log_timing};
 use zkp::{
-    circuits::burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+    circuits::{
+        burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+        constants::POW_DIFFICULTY,
+    },
     nova::{
         constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
         params::NovaParams,
-        withdraw_nova::{WITHDRAW_STATE_LEN, WithdrawCircuit, dummy_withdraw_ext_input},
+        withdraw_nova::{
+            WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawCircuitParams, dummy_withdraw_ext_input,
+        },
     },
     utils::{
         convertion::{address_to_fr, fr_to_address, u256_to_fr},
@@ -34,6 +39,16 @@ struct ProveResult {
     steps: usize,
 }
 
+#[derive(Deserialize)]
+struct SessionProgress {
+    checkpoint: String,
+    #[serde(rename = "stepsDone")]
+    steps_done: usize,
+    #[serde(rename = "totalSteps")]
+    total_steps: usize,
+    result: Option<ProveResult>,
+}
+
 #[wasm_bindgen_test]
 fn test_withdraw_nova_wasm_prove() {
     console_error_panic_hook::set_once();
@@ -49,9 +64,9 @@ fn test_withdraw_nova_wasm_prove() {
     let secrets_and_addresses = secret_seeds
         .iter()
         .map(|seed| {
-            let nonce = find_pow_nonce(recipient, *seed);
+            let nonce = find_pow_nonce(recipient, *seed, POW_DIFFICULTY);
             let secret = secret_from_nonce(*seed, nonce);
-            let address = compute_burn_address_from_secret(recipient, secret)
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
                 .expect("nonce should satisfy PoW");
             (secret, address)
         })
@@ -81,7 +96,15 @@ fn test_withdraw_nova_wasm_prove() {
     }
     let root = tree.get_root();
 
-    let z0_state = vec![root, recipient, Fr::ZERO, Fr::ZERO];
+    let z0_state = vec![
+        root,
+        recipient,
+        Fr::ZERO,
+        Fr::ZERO,
+        Fr::ZERO,
+        Fr::ZERO,
+        Fr::ZERO,
+    ];
     assert_eq!(z0_state.len(), WITHDRAW_STATE_LEN);
 
     let mut wasm_steps: Vec<JsExternalInput> = Vec::new();
@@ -97,6 +120,7 @@ fn test_withdraw_nova_wasm_prove() {
             secret: fr_to_hex(&secrets[i]),
             leaf_index: leaf_index.to_string(),
             siblings: sibling_hexes,
+            association_siblings: vec![],
         };
         wasm_steps.push(js_step);
     }
@@ -113,11 +137,12 @@ fn test_withdraw_nova_wasm_prove() {
             secret: fr_to_hex(&ext_input.secret),
             leaf_index: leaf_index.to_string(),
             siblings: sibling_hexes,
+            association_siblings: vec![],
         };
         wasm_steps.push(js_step);
     }
 
-    let f_params = circom_poseidon_config::<Fr>();
+    let f_params = WithdrawCircuitParams::new(circom_poseidon_config::<Fr>(), POW_DIFFICULTY);
 
     let local_nova_pp_bytes = include_bytes!("../../nova_artifacts/withdraw_local_nova_pp.bin");
     let local_nova_vp_bytes = include_bytes!("../../nova_artifacts/withdraw_local_nova_vp.bin");
@@ -146,8 +171,14 @@ fn test_withdraw_nova_wasm_prove() {
         .unwrap();
 
     let load_start = Instant::now();
-    let prover =
-        WithdrawNovaWasm::new(local_pp_vec, local_vp_vec, global_pp_vec, global_vp_vec).unwrap();
+    let prover = WithdrawNovaWasm::new(
+        include_str!("../../nova_artifacts/manifest.json"),
+        local_pp_vec,
+        local_vp_vec,
+        global_pp_vec,
+        global_vp_vec,
+    )
+    .unwrap();
     log_timing(&format!(
         "WithdrawNovaWasm::new {:.2} ms",
         load_start.elapsed().as_secs_f64() * 1_000.0
@@ -173,4 +204,21 @@ fn test_withdraw_nova_wasm_prove() {
         .collect();
     assert_eq!(final_state.len(), WITHDRAW_STATE_LEN);
     assert_eq!(final_state[0], root);
+
+    // the same proof, folded three steps at a time through persisted checkpoints
+    let z0_js: JsValue = serde_wasm_bindgen::to_value(&z0_hex).unwrap();
+    let steps_js: JsValue = serde_wasm_bindgen::to_value(&wasm_steps).unwrap();
+    let mut checkpoint = prover.start_session(z0_js, steps_js).unwrap();
+    let session_result = loop {
+        let progress: SessionProgress =
+            serde_wasm_bindgen::from_value(prover.resume_session(&checkpoint, 3).unwrap()).unwrap();
+        assert_eq!(progress.total_steps, wasm_steps.len());
+        checkpoint = progress.checkpoint;
+        if let Some(result) = progress.result {
+            assert_eq!(progress.steps_done, wasm_steps.len());
+            break result;
+        }
+    };
+    assert_eq!(session_result.steps, result.steps);
+    assert_eq!(session_result.final_state, result.final_state);
 }
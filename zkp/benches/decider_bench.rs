@@ -5,11 +5,14 @@ use ark_bn254::Fr;
 use ark_ff::Zero;
 use folding_schemes::FoldingScheme;
 use rand::{SeedableRng, rngs::StdRng};
+use zkp::circuits::constants::POW_DIFFICULTY;
 use zkp::nova::{
     constants::TRANSFER_TREE_HEIGHT,
     params::{DeciderParams, NovaParams},
-    root_nova::{RootCircuit, RootExternalInputs},
-    withdraw_nova::{WITHDRAW_STATE_LEN, WithdrawCircuit, dummy_withdraw_ext_input},
+    root_nova::{RootCircuit, RootExternalInputs, RootStepInputs},
+    withdraw_nova::{
+        WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawCircuitParams, dummy_withdraw_ext_input,
+    },
 };
 use zkp::utils::poseidon::utils::circom_poseidon_config;
 
@@ -31,12 +34,8 @@ fn bench_root_decider() -> f64 {
         .expect("root nova initialization");
 
     let mut step_rng = StdRng::seed_from_u64(0xF00D_FACE);
-    let external_input = RootExternalInputs::<Fr> {
-        is_dummy: true,
-        address: Fr::zero(),
-        value: Fr::zero(),
-        siblings: [Fr::zero(); TRANSFER_TREE_HEIGHT],
-    };
+    let external_input =
+        RootStepInputs::<Fr, TRANSFER_TREE_HEIGHT, 1>::from(RootExternalInputs::dummy());
     nova.prove_step(&mut step_rng, external_input.clone(), None)
         .expect("root nova step proof");
     nova.prove_step(&mut step_rng, external_input, None)
@@ -56,7 +55,7 @@ fn bench_withdraw_decider() -> f64 {
     let poseidon_params = circom_poseidon_config::<Fr>();
     let mut setup_rng = StdRng::seed_from_u64(0xA11C_EDA5);
     let nova_params = NovaParams::<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>::rand(
-        poseidon_params.clone(),
+        WithdrawCircuitParams::new(poseidon_params.clone(), POW_DIFFICULTY),
         &mut setup_rng,
     )
     .expect("withdraw nova params");
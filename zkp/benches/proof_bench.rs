@@ -8,16 +8,18 @@ use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
 use folding_schemes::FoldingScheme;
 use rand::{SeedableRng, rngs::StdRng};
 
-use zkp::circuits::burn_address::{
-    compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+use zkp::circuits::{
+    burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+    constants::POW_DIFFICULTY,
 };
 use zkp::groth16::{params::Groth16Params, withdraw::SingleWithdrawCircuit};
 use zkp::nova::{
     constants::TRANSFER_TREE_HEIGHT,
     params::NovaParams,
-    root_nova::{RootCircuit, RootExternalInputs},
+    root_nova::{RootCircuit, RootExternalInputs, RootStepInputs},
     withdraw_nova::{
-        WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawExternalInputs, dummy_withdraw_ext_input,
+        WITHDRAW_STATE_LEN, WithdrawCircuit, WithdrawCircuitParams, WithdrawExternalInputs,
+        dummy_withdraw_ext_input,
     },
 };
 use zkp::utils::{
@@ -35,12 +37,8 @@ fn bench_root_nova_step(c: &mut Criterion) {
         .initial_nova(vec![Fr::zero(); state_len])
         .expect("root nova initialization");
     let base_rng = StdRng::seed_from_u64(0xBAD5EED);
-    let external_input = RootExternalInputs::<Fr> {
-        is_dummy: true,
-        address: Fr::zero(),
-        value: Fr::zero(),
-        siblings: [Fr::zero(); TRANSFER_TREE_HEIGHT],
-    };
+    let external_input =
+        RootStepInputs::<Fr, TRANSFER_TREE_HEIGHT, 1>::from(RootExternalInputs::dummy());
 
     c.bench_function("root_nova_step_dummy", |b| {
         b.iter_batched(
@@ -58,7 +56,7 @@ fn bench_withdraw_nova_step(c: &mut Criterion) {
     let poseidon_params = circom_poseidon_config::<Fr>();
     let mut setup_rng = StdRng::seed_from_u64(0xA11CE5ED);
     let nova_params = NovaParams::<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>::rand(
-        poseidon_params.clone(),
+        WithdrawCircuitParams::new(poseidon_params.clone(), POW_DIFFICULTY),
         &mut setup_rng,
     )
     .expect("withdraw nova params");
@@ -114,10 +112,10 @@ fn build_single_withdraw_circuit<const DEPTH: usize>(
 ) -> SingleWithdrawCircuit<Fr, DEPTH> {
     let recipient = Fr::from(321u64);
     let secret_seed = Fr::from(654u64);
-    let nonce = find_pow_nonce(recipient, secret_seed);
+    let nonce = find_pow_nonce(recipient, secret_seed, POW_DIFFICULTY);
     let secret = secret_from_nonce(secret_seed, nonce);
-    let address =
-        compute_burn_address_from_secret(recipient, secret).expect("nonce should satisfy PoW");
+    let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+        .expect("nonce should satisfy PoW");
     let value = Fr::from(1_000u64);
     let delta = Fr::from(123u64);
     let leaf_index: u64 = 5;
@@ -134,14 +132,19 @@ fn build_single_withdraw_circuit<const DEPTH: usize>(
 
     SingleWithdrawCircuit {
         poseidon_params: poseidon_config.clone(),
+        pow_difficulty: POW_DIFFICULTY,
         merkle_root: Some(merkle_root),
         recipient: Some(recipient),
         withdraw_value: Some(withdraw_value),
+        relayer: Some(Fr::zero()),
+        fee: Some(Fr::zero()),
+        association_root: Some(Fr::zero()),
         value: Some(value),
         delta: Some(delta),
         secret: Some(secret),
         leaf_index: Some(leaf_index),
         siblings: siblings.map(Some),
+        association_siblings: [Some(Fr::zero()); DEPTH],
     }
 }
 
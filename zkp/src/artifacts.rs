@@ -0,0 +1,551 @@
+//! Manifest written by `generate_circuit_artifacts` next to the `*.bin`/`*.sol` artifacts. It
+//! records what the artifacts were generated for (circuit version, tree heights, Poseidon config,
+//! burn-address PoW difficulty) and a SHA-256 of every file, so loaders can refuse stale or tampered artifact directories
+//! instead of failing deep inside deserialization or proving.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use memmap2::Mmap;
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_serialize::{CanonicalSerialize, Compress, Validate};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    circuits::constants::{MAX_POW_DIFFICULTY, POW_DIFFICULTY},
+    nova::constants::{
+        AGGREGATION_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP,
+        TRANSFER_TREE_HEIGHT,
+    },
+    utils::poseidon::utils::circom_poseidon_config,
+};
+
+/// Bump whenever a circuit changes in a way that invalidates previously generated artifacts.
+pub const CIRCUIT_VERSION: u32 = 2;
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error(
+        "artifact manifest {0} not found; regenerate the artifacts with generate_circuit_artifacts"
+    )]
+    ManifestMissing(PathBuf),
+
+    #[error("invalid artifact manifest {path}: {source}")]
+    InvalidManifest {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("artifacts were generated for circuit version {found}, expected {expected}")]
+    CircuitVersionMismatch { expected: u32, found: u32 },
+
+    #[error("artifacts were generated for tree heights {found:?}, expected {expected:?}")]
+    TreeHeightMismatch {
+        expected: TreeHeights,
+        found: TreeHeights,
+    },
+
+    #[error("artifacts were generated for {found} root transfers per step, expected {expected}")]
+    RootTransfersPerStepMismatch { expected: usize, found: usize },
+
+    #[error(
+        "artifacts were generated for a different Poseidon config ({found}, expected {expected})"
+    )]
+    PoseidonConfigMismatch { expected: String, found: String },
+
+    #[error("artifacts were generated for PoW difficulty {found}, at most {max} is supported")]
+    UnsupportedPowDifficulty { found: usize, max: usize },
+
+    #[error(
+        "{path} does not match the pinned manifest hash (sha256 {actual}, expected {expected})"
+    )]
+    UntrustedManifest {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("{0} is not listed in the artifact manifest")]
+    UnknownFile(String),
+
+    #[error("{file} does not match the artifact manifest (sha256 {actual}, expected {expected})")]
+    HashMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("JSON Error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeHeights {
+    pub transfer: usize,
+    pub global: usize,
+    pub aggregation: usize,
+}
+
+impl TreeHeights {
+    pub fn current() -> Self {
+        Self {
+            transfer: TRANSFER_TREE_HEIGHT,
+            global: GLOBAL_TRANSFER_TREE_HEIGHT,
+            aggregation: AGGREGATION_TREE_HEIGHT,
+        }
+    }
+}
+
+/// Point encoding used for every parameter file in an artifact directory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactCompression {
+    #[default]
+    Uncompressed,
+    /// Roughly halves the files at the cost of point decompression on load.
+    Compressed,
+}
+
+impl From<ArtifactCompression> for Compress {
+    fn from(compression: ArtifactCompression) -> Self {
+        match compression {
+            ArtifactCompression::Uncompressed => Compress::No,
+            ArtifactCompression::Compressed => Compress::Yes,
+        }
+    }
+}
+
+impl fmt::Display for ArtifactCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactCompression::Uncompressed => f.write_str("uncompressed"),
+            ArtifactCompression::Compressed => f.write_str("compressed"),
+        }
+    }
+}
+
+impl FromStr for ArtifactCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uncompressed" => Ok(ArtifactCompression::Uncompressed),
+            "compressed" => Ok(ArtifactCompression::Compressed),
+            _ => Err(format!(
+                "unknown artifact compression '{s}' (expected uncompressed or compressed)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    pub circuit_version: u32,
+    pub tree_heights: TreeHeights,
+    pub root_transfers_per_step: usize,
+    pub poseidon_config_hash: String,
+    #[serde(default)]
+    pub compression: ArtifactCompression,
+    /// Leading zero bits required of every burn-address PoW hash. Baked into the withdraw
+    /// circuits at key generation, so provers and burn-address derivation must use this value.
+    #[serde(default = "default_pow_difficulty")]
+    pub pow_difficulty: usize,
+    /// SHA-256 (hex) of every parameter file, keyed by file name.
+    pub files: BTreeMap<String, String>,
+    /// SHA-256 (hex) of every generated Solidity verifier, keyed by file name.
+    pub verifier_contracts: BTreeMap<String, String>,
+}
+
+impl Default for ArtifactManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArtifactManifest {
+    /// Empty manifest describing the circuits compiled into this build.
+    pub fn new() -> Self {
+        Self {
+            circuit_version: CIRCUIT_VERSION,
+            tree_heights: TreeHeights::current(),
+            root_transfers_per_step: ROOT_TRANSFERS_PER_STEP,
+            poseidon_config_hash: poseidon_config_hash(&circom_poseidon_config()),
+            compression: ArtifactCompression::default(),
+            pow_difficulty: POW_DIFFICULTY,
+            files: BTreeMap::new(),
+            verifier_contracts: BTreeMap::new(),
+        }
+    }
+
+    /// Records the hash of a generated file; `.sol` files are tracked as verifier contracts.
+    pub fn record(&mut self, file_name: &str, bytes: &[u8]) {
+        let entries = if file_name.ends_with(".sol") {
+            &mut self.verifier_contracts
+        } else {
+            &mut self.files
+        };
+        entries.insert(file_name.to_string(), sha256_hex(bytes));
+    }
+
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.files.contains_key(file_name) || self.verifier_contracts.contains_key(file_name)
+    }
+
+    /// Checks that the manifest was produced for the circuits compiled into this build.
+    pub fn ensure_compatible(&self) -> Result<(), ArtifactError> {
+        let expected = Self::new();
+        if self.circuit_version != expected.circuit_version {
+            return Err(ArtifactError::CircuitVersionMismatch {
+                expected: expected.circuit_version,
+                found: self.circuit_version,
+            });
+        }
+        if self.tree_heights != expected.tree_heights {
+            return Err(ArtifactError::TreeHeightMismatch {
+                expected: expected.tree_heights,
+                found: self.tree_heights.clone(),
+            });
+        }
+        if self.root_transfers_per_step != expected.root_transfers_per_step {
+            return Err(ArtifactError::RootTransfersPerStepMismatch {
+                expected: expected.root_transfers_per_step,
+                found: self.root_transfers_per_step,
+            });
+        }
+        if self.poseidon_config_hash != expected.poseidon_config_hash {
+            return Err(ArtifactError::PoseidonConfigMismatch {
+                expected: expected.poseidon_config_hash,
+                found: self.poseidon_config_hash.clone(),
+            });
+        }
+        if self.pow_difficulty > MAX_POW_DIFFICULTY {
+            return Err(ArtifactError::UnsupportedPowDifficulty {
+                found: self.pow_difficulty,
+                max: MAX_POW_DIFFICULTY,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks `bytes` against the recorded hash of `file_name`.
+    pub fn verify(&self, file_name: &str, bytes: &[u8]) -> Result<(), ArtifactError> {
+        let expected = self
+            .files
+            .get(file_name)
+            .or_else(|| self.verifier_contracts.get(file_name))
+            .ok_or_else(|| ArtifactError::UnknownFile(file_name.to_string()))?;
+        let actual = sha256_hex(bytes);
+        if &actual != expected {
+            return Err(ArtifactError::HashMismatch {
+                file: file_name.to_string(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String, ArtifactError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArtifactError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, ArtifactError> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Err(ArtifactError::ManifestMissing(path));
+        }
+        let json = fs::read_to_string(&path).map_err(|source| ArtifactError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        serde_json::from_str(&json)
+            .map_err(|source| ArtifactError::InvalidManifest { path, source })
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), ArtifactError> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        fs::write(&path, self.to_json()? + "\n")
+            .map_err(|source| ArtifactError::Io { path, source })
+    }
+}
+
+/// An artifact directory whose manifest matches this build. Every read is checked against the
+/// manifest hash.
+#[derive(Clone, Debug)]
+pub struct ArtifactDir {
+    dir: PathBuf,
+    manifest: ArtifactManifest,
+    trusted: bool,
+}
+
+impl ArtifactDir {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, ArtifactError> {
+        let dir = dir.as_ref().to_path_buf();
+        let manifest = ArtifactManifest::load(&dir)?;
+        manifest.ensure_compatible()?;
+        Ok(Self {
+            dir,
+            manifest,
+            trusted: false,
+        })
+    }
+
+    /// Opens `dir` only if its `manifest.json` hashes to `manifest_sha256` (hex, as printed by
+    /// `sha256sum`). The file hashes are then trusted too, so [`Self::validate`] lets loaders
+    /// skip the curve/subgroup checks on every point, which dominate start-up time.
+    pub fn open_trusted(
+        dir: impl AsRef<Path>,
+        manifest_sha256: &str,
+    ) -> Result<Self, ArtifactError> {
+        let dir = dir.as_ref().to_path_buf();
+        let path = dir.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Err(ArtifactError::ManifestMissing(path));
+        }
+        let json = fs::read(&path).map_err(|source| ArtifactError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let expected = manifest_sha256
+            .trim()
+            .trim_start_matches("0x")
+            .to_ascii_lowercase();
+        let actual = sha256_hex(&json);
+        if actual != expected {
+            return Err(ArtifactError::UntrustedManifest {
+                path,
+                expected,
+                actual,
+            });
+        }
+        let manifest: ArtifactManifest = serde_json::from_slice(&json)
+            .map_err(|source| ArtifactError::InvalidManifest { path, source })?;
+        manifest.ensure_compatible()?;
+        Ok(Self {
+            dir,
+            manifest,
+            trusted: true,
+        })
+    }
+
+    /// Whether the manifest was pinned through [`Self::open_trusted`].
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+
+    /// Validation mode for the `from_readers` loaders: points from a pinned manifest have
+    /// already been validated when the artifacts were generated.
+    pub fn validate(&self) -> Validate {
+        if self.trusted {
+            Validate::No
+        } else {
+            Validate::Yes
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &ArtifactManifest {
+        &self.manifest
+    }
+
+    /// Serialization mode to pass to the `*_with_mode` loaders.
+    pub fn compress(&self) -> Compress {
+        self.manifest.compression.into()
+    }
+
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.manifest.contains(file_name)
+    }
+
+    pub fn read(&self, file_name: &str) -> Result<Vec<u8>, ArtifactError> {
+        if !self.manifest.contains(file_name) {
+            return Err(ArtifactError::UnknownFile(file_name.to_string()));
+        }
+        let path = self.dir.join(file_name);
+        let bytes = fs::read(&path).map_err(|source| ArtifactError::Io { path, source })?;
+        self.manifest.verify(file_name, &bytes)?;
+        Ok(bytes)
+    }
+
+    /// Memory-maps `file_name` and checks it against the manifest hash. Unlike [`Self::read`]
+    /// the contents stay in the page cache instead of a heap buffer, so the multi-hundred-MB
+    /// prover params can be deserialized straight from the mapping.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn map(&self, file_name: &str) -> Result<Mmap, ArtifactError> {
+        if !self.manifest.contains(file_name) {
+            return Err(ArtifactError::UnknownFile(file_name.to_string()));
+        }
+        let path = self.dir.join(file_name);
+        let io_error = |source| ArtifactError::Io {
+            path: path.clone(),
+            source,
+        };
+        let file = fs::File::open(&path).map_err(io_error)?;
+        // SAFETY: artifact directories are written once by the generator and only read
+        // afterwards; the hash check below rejects a file that was replaced before mapping.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+        self.manifest.verify(file_name, &mmap)?;
+        Ok(mmap)
+    }
+}
+
+fn default_pow_difficulty() -> usize {
+    POW_DIFFICULTY
+}
+
+/// SHA-256 over the compressed encoding of every Poseidon parameter.
+pub fn poseidon_config_hash(config: &PoseidonConfig<Fr>) -> String {
+    let mut bytes = Vec::new();
+    for value in [
+        config.full_rounds as u64,
+        config.partial_rounds as u64,
+        config.alpha,
+        config.rate as u64,
+        config.capacity as u64,
+    ] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    for element in config.ark.iter().chain(config.mds.iter()).flatten() {
+        element
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+    }
+    sha256_hex(&bytes)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_verifies_recorded_files() {
+        let mut manifest = ArtifactManifest::new();
+        manifest.record("withdraw_local_nova_pp.bin", b"pp");
+        manifest.record("WithdrawLocalNovaDecider.sol", b"contract");
+        assert!(
+            manifest
+                .verifier_contracts
+                .contains_key("WithdrawLocalNovaDecider.sol")
+        );
+
+        let manifest = ArtifactManifest::from_json(&manifest.to_json().unwrap()).unwrap();
+        manifest.ensure_compatible().unwrap();
+        manifest
+            .verify("withdraw_local_nova_pp.bin", b"pp")
+            .unwrap();
+        assert!(matches!(
+            manifest.verify("withdraw_local_nova_pp.bin", b"stale"),
+            Err(ArtifactError::HashMismatch { .. })
+        ));
+        assert!(matches!(
+            manifest.verify("withdraw_global_nova_pp.bin", b"pp"),
+            Err(ArtifactError::UnknownFile(_))
+        ));
+    }
+
+    #[test]
+    fn compression_defaults_to_uncompressed() {
+        let mut value = serde_json::to_value(ArtifactManifest::new()).unwrap();
+        value.as_object_mut().unwrap().remove("compression");
+        value.as_object_mut().unwrap().remove("pow_difficulty");
+        let manifest: ArtifactManifest = serde_json::from_value(value).unwrap();
+        assert_eq!(manifest.compression, ArtifactCompression::Uncompressed);
+        assert_eq!(manifest.pow_difficulty, POW_DIFFICULTY);
+        assert_eq!(
+            "compressed".parse::<ArtifactCompression>(),
+            Ok(ArtifactCompression::Compressed)
+        );
+    }
+
+    #[test]
+    fn open_trusted_pins_manifest_hash() {
+        let dir = std::env::temp_dir().join(format!("zkp-artifacts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut manifest = ArtifactManifest::new();
+        manifest.record("withdraw_local_nova_vp.bin", b"vp");
+        manifest.write(&dir).unwrap();
+        fs::write(dir.join("withdraw_local_nova_vp.bin"), b"vp").unwrap();
+        let pinned = sha256_hex(&fs::read(dir.join(MANIFEST_FILE_NAME)).unwrap());
+
+        let trusted = ArtifactDir::open_trusted(&dir, &pinned.to_uppercase()).unwrap();
+        assert_eq!(trusted.validate(), Validate::No);
+        assert_eq!(
+            &trusted.map("withdraw_local_nova_vp.bin").unwrap()[..],
+            b"vp"
+        );
+        assert_eq!(ArtifactDir::open(&dir).unwrap().validate(), Validate::Yes);
+        assert!(matches!(
+            ArtifactDir::open_trusted(&dir, &sha256_hex(b"other")),
+            Err(ArtifactError::UntrustedManifest { .. })
+        ));
+
+        fs::write(dir.join("withdraw_local_nova_vp.bin"), b"tampered").unwrap();
+        assert!(matches!(
+            trusted.map("withdraw_local_nova_vp.bin"),
+            Err(ArtifactError::HashMismatch { .. })
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn manifest_rejects_other_circuits() {
+        let mut manifest = ArtifactManifest::new();
+        manifest.circuit_version += 1;
+        assert!(matches!(
+            manifest.ensure_compatible(),
+            Err(ArtifactError::CircuitVersionMismatch { .. })
+        ));
+
+        let mut manifest = ArtifactManifest::new();
+        manifest.tree_heights.transfer = 16;
+        assert!(matches!(
+            manifest.ensure_compatible(),
+            Err(ArtifactError::TreeHeightMismatch { .. })
+        ));
+
+        let mut manifest = ArtifactManifest::new();
+        manifest.poseidon_config_hash = sha256_hex(b"other");
+        assert!(matches!(
+            manifest.ensure_compatible(),
+            Err(ArtifactError::PoseidonConfigMismatch { .. })
+        ));
+
+        let mut manifest = ArtifactManifest::new();
+        manifest.pow_difficulty = 8;
+        manifest.ensure_compatible().unwrap();
+        manifest.pow_difficulty = MAX_POW_DIFFICULTY + 1;
+        assert!(matches!(
+            manifest.ensure_compatible(),
+            Err(ArtifactError::UnsupportedPowDifficulty { .. })
+        ));
+    }
+}
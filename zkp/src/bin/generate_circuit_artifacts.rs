@@ -1,158 +1,469 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use ark_bn254::Fr;
 use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_relations::gr1cs::ConstraintSynthesizer;
+use clap::{Parser, ValueEnum};
 use folding_schemes::frontend::FCircuit;
 use rand::{SeedableRng, rngs::StdRng};
 
 use zkp::{
-    groth16::{params::Groth16Params, withdraw::SingleWithdrawCircuit},
+    artifacts::{ArtifactCompression, ArtifactDir, ArtifactManifest, MANIFEST_FILE_NAME},
+    circuits::burn_address::check_pow_difficulty,
+    groth16::{
+        disclosure::{DisclosureCircuit, disclosure_artifact_prefix},
+        multi_withdraw::MultiWithdrawCircuit,
+        params::{Groth16Params, groth16_verifier_solidity_code, verifying_key_from_bytes},
+        payout::{PAYOUT_LEAVES, PayoutWithdrawCircuit, payout_artifact_prefix},
+        reburn::ReburnCircuit,
+        withdraw::SingleWithdrawCircuit,
+    },
     nova::{
-        constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
-        params::{DeciderParams, FParams, NovaParams},
-        root_nova::RootCircuit,
-        withdraw_nova::WithdrawCircuit,
+        constants::{
+            DEV_TRANSFER_TREE_HEIGHT, GLOBAL_TRANSFER_TREE_HEIGHT, ROOT_TRANSFERS_PER_STEP,
+            STAGING_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT,
+        },
+        decider_verifier::decider_vp_from_bytes,
+        params::{DeciderParams, FParams, NovaParams, decider_verifier_solidity_code},
+        root_nova::{RootCircuit, root_artifact_prefix},
+        withdraw_nova::{WithdrawCircuit, WithdrawCircuitParams},
+    },
+    utils::{
+        convertion::fr_to_u256, poseidon::utils::circom_poseidon_config,
+        tree::merkle_tree::MerkleTree,
     },
-    utils::poseidon::utils::circom_poseidon_config,
 };
 
+#[derive(Parser, Debug)]
+#[command(about = "Generate Nova/Groth16 parameters and Solidity verifiers")]
+struct Args {
+    /// Circuits to generate (comma separated). Defaults to all of them.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    circuits: Vec<CircuitSet>,
+
+    /// Output directory. Defaults to <workspace>/nova_artifacts.
+    #[arg(long, value_name = "PATH")]
+    output_dir: Option<PathBuf>,
+
+    /// Point encoding of the parameter files: `uncompressed` or `compressed`. A full run
+    /// defaults to `uncompressed`; partial runs keep the existing manifest's mode.
+    #[arg(long)]
+    compression: Option<ArtifactCompression>,
+
+    /// Leading zero bits required of burn-address PoW hashes. A full run defaults to
+    /// `POW_DIFFICULTY`; partial runs keep the existing manifest's difficulty.
+    #[arg(long)]
+    pow_difficulty: Option<usize>,
+
+    /// Seed for the setup RNG. Every circuit is set up from a fresh RNG with this seed, so
+    /// regenerating a subset yields the same files as a full run. Only for dev artifacts.
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Also generate `root_h{height}_*` artifacts for a dev/staging transfer tree height. Only
+    /// the root prover is built for that height: withdraw circuits, Solidity verifiers and the
+    /// manifest's `tree_heights` stay at the production heights, so such a deployment can prove
+    /// roots but cannot redeem against them.
+    #[arg(long, env = "ROOT_TREE_HEIGHT")]
+    root_tree_height: Option<usize>,
+
+    /// Re-render the Solidity verifiers from the verifier params already in the output
+    /// directory instead of running the setup.
+    #[arg(long)]
+    verifiers_only: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CircuitSet {
+    /// `withdraw_local` Groth16 and Nova artifacts.
+    WithdrawLocal,
+    /// `withdraw_global` Groth16 and Nova artifacts.
+    WithdrawGlobal,
+    /// `withdraw_{local,global}_multi{N}` Groth16 artifacts for every `MULTI_WITHDRAW_SIZES`.
+    MultiWithdraw,
+    /// `reburn_{local,global}_{N}` Groth16 artifacts for every `REBURN_SIZES`.
+    Reburn,
+    /// Root Nova artifacts (plus `--root-tree-height`, if set).
+    Root,
+    /// `disclosure_{local,global}_{N}` Groth16 keys for every `DISCLOSURE_SIZES`. Disclosures
+    /// are verified off-chain, so no Solidity verifier is rendered.
+    Disclosure,
+    /// `payout_{local,global}_{M}` Groth16 artifacts for every `PAYOUT_SIZES`.
+    Payout,
+}
+
 fn main() -> Result<()> {
-    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .parent()
-        .context("failed to locate workspace root directory")?
-        .to_path_buf();
+    let args = Args::parse();
+    let circuits = if args.circuits.is_empty() {
+        CircuitSet::value_variants().to_vec()
+    } else {
+        args.circuits.clone()
+    };
+    let full_run = !args.verifiers_only && circuits.len() == CircuitSet::value_variants().len();
 
-    let artifacts_dir = workspace_root.join("nova_artifacts");
+    let artifacts_dir = match &args.output_dir {
+        Some(dir) => dir.clone(),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .context("failed to locate workspace root directory")?
+            .join("nova_artifacts"),
+    };
     fs::create_dir_all(&artifacts_dir)
         .with_context(|| format!("failed to create {}", artifacts_dir.display()))?;
 
+    let mut generator = Generator::new(&artifacts_dir, &args, full_run)?;
     let poseidon_config = circom_poseidon_config::<Fr>();
+    let pow_difficulty = generator.manifest.pow_difficulty;
+    let withdraw_params = WithdrawCircuitParams::new(poseidon_config.clone(), pow_difficulty);
 
-    generate_groth16_artifacts::<TRANSFER_TREE_HEIGHT>(
-        "withdraw_local",
-        &artifacts_dir,
-        &poseidon_config,
-    )?;
-    println!("Generated local withdraw groth16 artifacts");
-
-    generate_groth16_artifacts::<GLOBAL_TRANSFER_TREE_HEIGHT>(
-        "withdraw_global",
-        &artifacts_dir,
-        &poseidon_config,
-    )?;
-    println!("Generated global withdraw nova artifacts");
-
-    generate_nova_artifacts::<RootCircuit<Fr>>("root", &artifacts_dir, poseidon_config.clone())?;
-    println!("Generated root nova artifacts");
-
-    generate_nova_artifacts::<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>(
-        "withdraw_local",
-        &artifacts_dir,
-        poseidon_config.clone(),
-    )?;
-    println!("Generated local withdraw nova artifacts");
-
-    generate_nova_artifacts::<WithdrawCircuit<Fr, GLOBAL_TRANSFER_TREE_HEIGHT>>(
-        "withdraw_global",
-        &artifacts_dir,
-        poseidon_config.clone(),
-    )?;
-
-    println!("Generated global withdraw groth16 artifacts");
+    for circuit in circuits {
+        match circuit {
+            CircuitSet::WithdrawLocal => {
+                generator.groth16(
+                    "withdraw_local",
+                    SingleWithdrawCircuit::<Fr, TRANSFER_TREE_HEIGHT>::new(
+                        poseidon_config.clone(),
+                        pow_difficulty,
+                    ),
+                )?;
+                generator.nova::<WithdrawCircuit<Fr, TRANSFER_TREE_HEIGHT>>(
+                    "withdraw_local",
+                    withdraw_params.clone(),
+                )?;
+                println!("Generated local withdraw artifacts");
+            }
+            CircuitSet::WithdrawGlobal => {
+                generator.groth16(
+                    "withdraw_global",
+                    SingleWithdrawCircuit::<Fr, GLOBAL_TRANSFER_TREE_HEIGHT>::new(
+                        poseidon_config.clone(),
+                        pow_difficulty,
+                    ),
+                )?;
+                generator.nova::<WithdrawCircuit<Fr, GLOBAL_TRANSFER_TREE_HEIGHT>>(
+                    "withdraw_global",
+                    withdraw_params.clone(),
+                )?;
+                println!("Generated global withdraw artifacts");
+            }
+            CircuitSet::MultiWithdraw => {
+                // Keep in sync with `MULTI_WITHDRAW_SIZES`.
+                generator.multi_withdraw::<2>(&poseidon_config)?;
+                generator.multi_withdraw::<4>(&poseidon_config)?;
+                generator.multi_withdraw::<8>(&poseidon_config)?;
+                println!("Generated multi-leaf withdraw groth16 artifacts");
+            }
+            CircuitSet::Reburn => {
+                // Keep in sync with `REBURN_SIZES`.
+                generator.reburn::<1>(&poseidon_config)?;
+                generator.reburn::<4>(&poseidon_config)?;
+                println!("Generated re-burn groth16 artifacts");
+            }
+            CircuitSet::Root => {
+                generator.root(TRANSFER_TREE_HEIGHT, &poseidon_config)?;
+                println!("Generated root nova artifacts");
+
+                // Dev/staging deployments run the root prover against a shallower transfer tree.
+                if let Some(height) = args
+                    .root_tree_height
+                    .filter(|&height| height != TRANSFER_TREE_HEIGHT)
+                {
+                    generator.root(height, &poseidon_config)?;
+                    println!("Generated root nova artifacts for tree height {height}");
+                }
+            }
+            CircuitSet::Disclosure => {
+                // Keep in sync with `DISCLOSURE_SIZES`.
+                generator.disclosure::<1>(&poseidon_config)?;
+                generator.disclosure::<4>(&poseidon_config)?;
+                generator.disclosure::<8>(&poseidon_config)?;
+                println!("Generated disclosure groth16 artifacts");
+            }
+            CircuitSet::Payout => {
+                // Keep in sync with `PAYOUT_SIZES`.
+                generator.payout::<4>(&poseidon_config)?;
+                generator.payout::<16>(&poseidon_config)?;
+                println!("Generated payout withdraw groth16 artifacts");
+            }
+        }
+    }
 
+    generator
+        .manifest
+        .write(&artifacts_dir)
+        .with_context(|| format!("failed to write {MANIFEST_FILE_NAME}"))?;
     println!("All artifacts saved under {}", artifacts_dir.display());
 
     Ok(())
 }
 
-fn generate_nova_artifacts<C>(
-    prefix: &str,
-    output_dir: &std::path::Path,
-    f_params: FParams<C>,
-) -> Result<()>
-where
-    C: FCircuit<Fr>,
-    FParams<C>: Clone,
-{
-    let mut rng = StdRng::seed_from_u64(42);
-
-    let nova_params = NovaParams::<C>::rand(f_params.clone(), &mut rng)?;
-    let state_len = nova_params.state_len()?;
-    let decider_params = DeciderParams::<C>::rand(&mut rng, &nova_params)?;
-
-    let (nova_pp_bytes, nova_vp_bytes) = nova_params.to_bytes()?;
-    let (decider_pp_bytes, decider_vp_bytes) = decider_params.to_bytes()?;
-    let pascal_case_prefix = to_pascal_case(prefix);
-    let contract_name = format!("{}NovaDecider", pascal_case_prefix);
-    let solidity = decider_params
-        .verifier_solidity_code(state_len)
-        .replace("NovaDecider", &contract_name);
-
-    write_bytes(
-        &output_dir.join(format!("{prefix}_nova_pp.bin")),
-        &nova_pp_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{prefix}_nova_vp.bin")),
-        &nova_vp_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{prefix}_decider_pp.bin")),
-        &decider_pp_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{prefix}_decider_vp.bin")),
-        &decider_vp_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{pascal_case_prefix}NovaDecider.sol")),
-        solidity.as_bytes(),
-    )?;
-
-    Ok(())
+struct Generator {
+    output_dir: PathBuf,
+    manifest: ArtifactManifest,
+    seed: u64,
+    /// Set with `--verifiers-only`: verifier params are read from here instead of running setup.
+    existing: Option<ArtifactDir>,
 }
 
-fn generate_groth16_artifacts<const DEPTH: usize>(
-    prefix: &str,
-    output_dir: &std::path::Path,
-    poseidon_config: &PoseidonConfig<Fr>,
-) -> Result<()> {
-    let mut rng = StdRng::seed_from_u64(42);
-    let circuit = SingleWithdrawCircuit::<Fr, DEPTH>::new(poseidon_config.clone());
-    let params = Groth16Params::rand(&mut rng, circuit.clone())
-        .with_context(|| format!("failed groth16 setup for {prefix}"))?;
-
-    let (pk_bytes, vk_bytes) = params
-        .to_bytes()
-        .with_context(|| format!("failed to serialize groth16 params for {prefix}"))?;
-
-    let pascal_case_prefix = to_pascal_case(prefix);
-    let contract_name = format!("{}Groth16Verifier", pascal_case_prefix);
-    let solidity = params
-        .verifier_solidity_code()
-        .with_context(|| format!("failed to render solidity verifier for {prefix}"))?
-        .replace("Groth16Verifier", &contract_name);
-
-    write_bytes(
-        &output_dir.join(format!("{prefix}_groth16_pk.bin")),
-        &pk_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{prefix}_groth16_vk.bin")),
-        &vk_bytes,
-    )?;
-    write_bytes(
-        &output_dir.join(format!("{pascal_case_prefix}Groth16Verifier.sol")),
-        solidity.as_bytes(),
-    )?;
+impl Generator {
+    /// A full run starts a fresh manifest; anything else updates the one in `output_dir`, which
+    /// must match this build and the requested compression so the directory stays consistent.
+    fn new(output_dir: &Path, args: &Args, full_run: bool) -> Result<Self> {
+        let existing = if args.verifiers_only {
+            Some(ArtifactDir::open(output_dir).with_context(|| {
+                format!(
+                    "--verifiers-only needs existing artifacts in {}",
+                    output_dir.display()
+                )
+            })?)
+        } else {
+            None
+        };
 
-    Ok(())
+        let mut manifest = if let Some(existing) = &existing {
+            existing.manifest().clone()
+        } else if full_run || !output_dir.join(MANIFEST_FILE_NAME).exists() {
+            ArtifactManifest::new()
+        } else {
+            let manifest = ArtifactManifest::load(output_dir)?;
+            manifest.ensure_compatible().with_context(|| {
+                format!(
+                    "existing {MANIFEST_FILE_NAME} does not match this build; regenerate all circuits"
+                )
+            })?;
+            manifest
+        };
+        match args.compression {
+            Some(compression) if full_run => manifest.compression = compression,
+            Some(compression) if compression != manifest.compression => bail!(
+                "artifacts in {} are {}; regenerate all circuits to switch to {compression}",
+                output_dir.display(),
+                manifest.compression
+            ),
+            _ => {}
+        }
+        match args.pow_difficulty {
+            Some(pow_difficulty) if full_run => manifest.pow_difficulty = pow_difficulty,
+            Some(pow_difficulty) if pow_difficulty != manifest.pow_difficulty => bail!(
+                "artifacts in {} use PoW difficulty {}; regenerate all circuits to switch to {pow_difficulty}",
+                output_dir.display(),
+                manifest.pow_difficulty
+            ),
+            _ => {}
+        }
+        check_pow_difficulty(manifest.pow_difficulty)?;
+
+        Ok(Self {
+            output_dir: output_dir.to_path_buf(),
+            manifest,
+            seed: args.seed,
+            existing,
+        })
+    }
+
+    fn nova<C>(&mut self, prefix: &str, f_params: FParams<C>) -> Result<()>
+    where
+        C: FCircuit<Fr>,
+        FParams<C>: Clone,
+    {
+        let compress = self.manifest.compression.into();
+        let contract_name = format!("{}NovaDecider", to_pascal_case(prefix));
+
+        let solidity = if let Some(existing) = &self.existing {
+            let state_len = C::new(f_params)
+                .map_err(|e| anyhow::anyhow!("failed to initialize {prefix} circuit: {e}"))?
+                .state_len();
+            let vp_bytes = existing.read(&format!("{prefix}_decider_vp.bin"))?;
+            let vp = decider_vp_from_bytes::<C>(&vp_bytes, compress)?;
+            decider_verifier_solidity_code::<C>(&vp, state_len)
+        } else {
+            let mut rng = StdRng::seed_from_u64(self.seed);
+            let nova_params = NovaParams::<C>::rand(f_params, &mut rng)?;
+            let state_len = nova_params.state_len()?;
+            let decider_params = DeciderParams::<C>::rand(&mut rng, &nova_params)?;
+
+            let (nova_pp_bytes, nova_vp_bytes) = nova_params.to_bytes_with_mode(compress)?;
+            let (decider_pp_bytes, decider_vp_bytes) =
+                decider_params.to_bytes_with_mode(compress)?;
+            self.write(&format!("{prefix}_nova_pp.bin"), &nova_pp_bytes)?;
+            self.write(&format!("{prefix}_nova_vp.bin"), &nova_vp_bytes)?;
+            self.write(&format!("{prefix}_decider_pp.bin"), &decider_pp_bytes)?;
+            self.write(&format!("{prefix}_decider_vp.bin"), &decider_vp_bytes)?;
+            decider_params.verifier_solidity_code(state_len)
+        };
+
+        self.write(
+            &format!("{contract_name}.sol"),
+            solidity.replace("NovaDecider", &contract_name).as_bytes(),
+        )
+    }
+
+    fn groth16<C>(&mut self, prefix: &str, circuit: C) -> Result<()>
+    where
+        C: ConstraintSynthesizer<Fr>,
+    {
+        let compress = self.manifest.compression.into();
+        let contract_name = format!("{}Groth16Verifier", to_pascal_case(prefix));
+
+        let solidity = if let Some(existing) = &self.existing {
+            let vk_bytes = existing.read(&format!("{prefix}_groth16_vk.bin"))?;
+            let vk = verifying_key_from_bytes(&vk_bytes, compress)?;
+            groth16_verifier_solidity_code(&vk)
+        } else {
+            self.groth16_keys(prefix, circuit)?.verifier_solidity_code()
+        }
+        .with_context(|| format!("failed to render solidity verifier for {prefix}"))?;
+
+        self.write(
+            &format!("{contract_name}.sol"),
+            solidity
+                .replace("Groth16Verifier", &contract_name)
+                .as_bytes(),
+        )
+    }
+
+    fn groth16_keys<C>(&mut self, prefix: &str, circuit: C) -> Result<Groth16Params>
+    where
+        C: ConstraintSynthesizer<Fr>,
+    {
+        let compress = self.manifest.compression.into();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let params = Groth16Params::rand(&mut rng, circuit)
+            .with_context(|| format!("failed groth16 setup for {prefix}"))?;
+        let (pk_bytes, vk_bytes) = params
+            .to_bytes_with_mode(compress)
+            .with_context(|| format!("failed to serialize groth16 params for {prefix}"))?;
+        self.write(&format!("{prefix}_groth16_pk.bin"), &pk_bytes)?;
+        self.write(&format!("{prefix}_groth16_vk.bin"), &vk_bytes)?;
+        Ok(params)
+    }
+
+    fn root(&mut self, height: usize, poseidon_config: &PoseidonConfig<Fr>) -> Result<()> {
+        let prefix = root_artifact_prefix(height);
+        match height {
+            DEV_TRANSFER_TREE_HEIGHT => self.nova::<RootCircuit<
+                Fr,
+                DEV_TRANSFER_TREE_HEIGHT,
+                ROOT_TRANSFERS_PER_STEP,
+            >>(&prefix, poseidon_config.clone())?,
+            STAGING_TRANSFER_TREE_HEIGHT => self.nova::<RootCircuit<
+                Fr,
+                STAGING_TRANSFER_TREE_HEIGHT,
+                ROOT_TRANSFERS_PER_STEP,
+            >>(&prefix, poseidon_config.clone())?,
+            TRANSFER_TREE_HEIGHT => self.nova::<RootCircuit<
+                Fr,
+                TRANSFER_TREE_HEIGHT,
+                ROOT_TRANSFERS_PER_STEP,
+            >>(&prefix, poseidon_config.clone())?,
+            _ => bail!(
+                "unsupported root tree height {height} (expected {DEV_TRANSFER_TREE_HEIGHT}, {STAGING_TRANSFER_TREE_HEIGHT} or {TRANSFER_TREE_HEIGHT})"
+            ),
+        }
+        // Verifier.initialize seeds the transfer root history with the empty root at this height.
+        println!(
+            "Initial transfer root for tree height {height}: {}",
+            fr_to_u256(MerkleTree::new(height).get_root())
+        );
+        Ok(())
+    }
+
+    fn multi_withdraw<const N: usize>(
+        &mut self,
+        poseidon_config: &PoseidonConfig<Fr>,
+    ) -> Result<()> {
+        self.groth16(
+            &format!("withdraw_local_multi{N}"),
+            MultiWithdrawCircuit::<Fr, TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )?;
+        self.groth16(
+            &format!("withdraw_global_multi{N}"),
+            MultiWithdrawCircuit::<Fr, GLOBAL_TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )
+    }
+
+    fn reburn<const N: usize>(&mut self, poseidon_config: &PoseidonConfig<Fr>) -> Result<()> {
+        self.groth16(
+            &format!("reburn_local_{N}"),
+            ReburnCircuit::<Fr, TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )?;
+        self.groth16(
+            &format!("reburn_global_{N}"),
+            ReburnCircuit::<Fr, GLOBAL_TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )
+    }
+
+    fn payout<const M: usize>(&mut self, poseidon_config: &PoseidonConfig<Fr>) -> Result<()> {
+        self.groth16(
+            &payout_prefix(TRANSFER_TREE_HEIGHT, M)?,
+            PayoutWithdrawCircuit::<Fr, TRANSFER_TREE_HEIGHT, PAYOUT_LEAVES, M>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )?;
+        self.groth16(
+            &payout_prefix(GLOBAL_TRANSFER_TREE_HEIGHT, M)?,
+            PayoutWithdrawCircuit::<Fr, GLOBAL_TRANSFER_TREE_HEIGHT, PAYOUT_LEAVES, M>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )
+    }
+
+    /// Disclosure keys have no verifier to re-render, so `--verifiers-only` skips them.
+    fn disclosure<const N: usize>(&mut self, poseidon_config: &PoseidonConfig<Fr>) -> Result<()> {
+        if self.existing.is_some() {
+            return Ok(());
+        }
+        self.groth16_keys(
+            &disclosure_prefix(TRANSFER_TREE_HEIGHT, N)?,
+            DisclosureCircuit::<Fr, TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )?;
+        self.groth16_keys(
+            &disclosure_prefix(GLOBAL_TRANSFER_TREE_HEIGHT, N)?,
+            DisclosureCircuit::<Fr, GLOBAL_TRANSFER_TREE_HEIGHT, N>::new(
+                poseidon_config.clone(),
+                self.manifest.pow_difficulty,
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn write(&mut self, file_name: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.output_dir.join(file_name);
+        fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
+        self.manifest.record(file_name, bytes);
+        Ok(())
+    }
 }
 
-fn write_bytes(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
-    fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
-    Ok(())
+fn disclosure_prefix(tree_height: usize, num_slots: usize) -> Result<String> {
+    disclosure_artifact_prefix(tree_height, num_slots)
+        .with_context(|| format!("no disclosure artifacts for tree height {tree_height}"))
+}
+
+fn payout_prefix(tree_height: usize, num_payouts: usize) -> Result<String> {
+    payout_artifact_prefix(tree_height, num_payouts)
+        .with_context(|| format!("no payout artifacts for tree height {tree_height}"))
 }
 
 fn to_pascal_case(s: &str) -> String {
@@ -0,0 +1,120 @@
+use crate::utils::poseidon::gadgets::CircomCRHParametersVar;
+use crate::utils::tree::gadgets::merkle::merkle_root_from_leaf;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{FieldVar, fp::FpVar},
+};
+use ark_relations::gr1cs::SynthesisError;
+use core::ops::Not;
+
+/// Proves that `leaf_hash` also sits at `index_bits` in the association tree committed to by
+/// `association_root`. An association tree mirrors the transfer tree it vets: approved leaves keep
+/// their leaf hash and position, every other slot is left empty.
+///
+/// A zero `association_root` opts out of the check, and so does `is_constrained = false` (dummy
+/// steps). Circuits that never carry an association set pass a constant zero root and pay no
+/// constraints for it.
+pub fn enforce_association_membership<F: PrimeField + Absorb>(
+    poseidon_params: &CircomCRHParametersVar<F>,
+    association_root: &FpVar<F>,
+    leaf_hash: &FpVar<F>,
+    index_bits: &[Boolean<F>],
+    siblings: &[FpVar<F>],
+    is_constrained: &Boolean<F>,
+) -> Result<(), SynthesisError> {
+    assert_eq!(siblings.len(), index_bits.len());
+    if matches!(association_root, FpVar::Constant(root) if root.is_zero()) {
+        return Ok(());
+    }
+
+    let computed_root = merkle_root_from_leaf(poseidon_params, leaf_hash, index_bits, siblings)?;
+
+    let is_enabled: FpVar<F> = association_root.is_zero()?.not().into();
+    let is_constrained: FpVar<F> = is_constrained.clone().into();
+    let diff = association_root.clone() - computed_root;
+    (diff * is_enabled * is_constrained).enforce_equal(&FpVar::<F>::zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::enforce_association_membership;
+    use crate::test_utils::merkle_root_from_path;
+    use crate::utils::poseidon::gadgets::CircomCRHParametersVar;
+    use crate::utils::poseidon::utils::circom_poseidon_config;
+    use crate::utils::tree::gadgets::merkle::to_bits_le_limited;
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+    use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar};
+    use ark_relations::gr1cs::{ConstraintSystem, SynthesisError};
+    use ark_relations::ns;
+
+    const DEPTH: usize = 4;
+
+    fn membership_satisfied(
+        association_root: Fr,
+        leaf_hash: Fr,
+        siblings: &[Fr; DEPTH],
+        is_constrained: bool,
+    ) -> Result<bool, SynthesisError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params =
+            CircomCRHParametersVar::new_constant(ns!(cs, "params"), &circom_poseidon_config())?;
+        let root = FpVar::<Fr>::new_input(ns!(cs, "association_root"), || Ok(association_root))?;
+        let leaf = FpVar::<Fr>::new_witness(ns!(cs, "leaf_hash"), || Ok(leaf_hash))?;
+        let index = FpVar::<Fr>::new_witness(ns!(cs, "leaf_index"), || Ok(Fr::from(5u64)))?;
+        let index_bits = to_bits_le_limited(&index, DEPTH)?;
+        let siblings = siblings
+            .iter()
+            .map(|s| FpVar::<Fr>::new_witness(ns!(cs, "sibling"), || Ok(*s)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_constrained =
+            Boolean::new_witness(ns!(cs, "is_constrained"), || Ok(is_constrained))?;
+        enforce_association_membership(
+            &params,
+            &root,
+            &leaf,
+            &index_bits,
+            &siblings,
+            &is_constrained,
+        )?;
+        cs.is_satisfied()
+    }
+
+    #[test]
+    fn association_membership_checks_root_unless_disabled() -> Result<(), SynthesisError> {
+        let siblings = [3u64, 1, 4, 1].map(Fr::from);
+        let leaf_hash = Fr::from(42u64);
+        let association_root =
+            merkle_root_from_path(&circom_poseidon_config(), 5, leaf_hash, &siblings);
+
+        assert!(membership_satisfied(
+            association_root,
+            leaf_hash,
+            &siblings,
+            true
+        )?);
+        assert!(!membership_satisfied(
+            association_root,
+            leaf_hash + Fr::one(),
+            &siblings,
+            true
+        )?);
+        // dummy steps and the zero root skip the check entirely
+        assert!(membership_satisfied(
+            association_root,
+            leaf_hash + Fr::one(),
+            &siblings,
+            false
+        )?);
+        assert!(membership_satisfied(
+            Fr::zero(),
+            leaf_hash + Fr::one(),
+            &siblings,
+            true
+        )?);
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    circuits::constants::{ADDRESS_BIT_LENGTH, POW_DIFFICULTY},
+    circuits::constants::{ADDRESS_BIT_LENGTH, MAX_POW_DIFFICULTY},
     utils::poseidon::{
         gadgets::{CircomCRHGadget, CircomCRHParametersVar},
         utils::poseidon2,
@@ -25,20 +25,37 @@ pub enum BurnAddressError {
         "poseidon hash does not satisfy the required PoW difficulty of {difficulty} leading zero bits"
     )]
     PowDifficultyUnsatisfied { difficulty: usize },
+    #[error("PoW difficulty {difficulty} exceeds the maximum of {max} bits")]
+    UnsupportedPowDifficulty { difficulty: usize, max: usize },
 }
 
-pub fn compute_burn_address_from_secret(recipient: Fr, secret: Fr) -> Result<Fr, BurnAddressError> {
+pub fn check_pow_difficulty(pow_difficulty: usize) -> Result<(), BurnAddressError> {
+    if pow_difficulty > MAX_POW_DIFFICULTY {
+        return Err(BurnAddressError::UnsupportedPowDifficulty {
+            difficulty: pow_difficulty,
+            max: MAX_POW_DIFFICULTY,
+        });
+    }
+    Ok(())
+}
+
+pub fn compute_burn_address_from_secret(
+    recipient: Fr,
+    secret: Fr,
+    pow_difficulty: usize,
+) -> Result<Fr, BurnAddressError> {
+    check_pow_difficulty(pow_difficulty)?;
     let hash_bigint = poseidon2(recipient, secret).into_bigint();
     let hash_bits = hash_bigint.to_bits_le();
 
     if hash_bits
         .iter()
         .skip(ADDRESS_BIT_LENGTH)
-        .take(POW_DIFFICULTY)
+        .take(pow_difficulty)
         .any(|bit| *bit)
     {
         return Err(BurnAddressError::PowDifficultyUnsatisfied {
-            difficulty: POW_DIFFICULTY,
+            difficulty: pow_difficulty,
         });
     }
 
@@ -48,10 +65,13 @@ pub fn compute_burn_address_from_secret(recipient: Fr, secret: Fr) -> Result<Fr,
     Ok(address.into())
 }
 
-pub fn find_pow_nonce(recipient: Fr, secret_seed: Fr) -> u64 {
+/// Expected to take `2^pow_difficulty` Poseidon hashes. Panics if `pow_difficulty` exceeds
+/// `MAX_POW_DIFFICULTY`.
+pub fn find_pow_nonce(recipient: Fr, secret_seed: Fr, pow_difficulty: usize) -> u64 {
+    check_pow_difficulty(pow_difficulty).expect("unsupported PoW difficulty");
     for nonce in 0u64.. {
         let candidate = secret_seed + Fr::from(nonce);
-        if compute_burn_address_from_secret(recipient, candidate).is_ok() {
+        if compute_burn_address_from_secret(recipient, candidate, pow_difficulty).is_ok() {
             return nonce;
         }
     }
@@ -66,17 +86,22 @@ pub fn compute_burn_address_for_nonce(
     recipient: Fr,
     secret_seed: Fr,
     nonce: u64,
+    pow_difficulty: usize,
 ) -> Result<Fr, BurnAddressError> {
     let secret = secret_from_nonce(secret_seed, nonce);
-    compute_burn_address_from_secret(recipient, secret)
+    compute_burn_address_from_secret(recipient, secret, pow_difficulty)
 }
 
+/// `pow_difficulty` is fixed when the circuit is synthesized, so keys generated for one difficulty
+/// cannot prove burns of another.
 pub fn burn_address_var<F: PrimeField + Absorb>(
     poseidon_params: &CircomCRHParametersVar<F>,
     recipient: &FpVar<F>,
     secret: &FpVar<F>,
     is_constrained: &Boolean<F>,
+    pow_difficulty: usize,
 ) -> Result<FpVar<F>, SynthesisError> {
+    check_pow_difficulty(pow_difficulty).map_err(|_| SynthesisError::Unsatisfiable)?;
     let poseidon =
         CircomCRHGadget::<F>::evaluate(poseidon_params, &[recipient.clone(), secret.clone()])?;
     let poseidon_bits = poseidon.to_bits_le()?;
@@ -86,7 +111,7 @@ pub fn burn_address_var<F: PrimeField + Absorb>(
     for bit in poseidon_bits
         .iter()
         .skip(ADDRESS_BIT_LENGTH)
-        .take(POW_DIFFICULTY)
+        .take(pow_difficulty)
     {
         let bit_fp: FpVar<F> = bit.clone().into();
         (bit_fp * is_constrained_fp.clone()).enforce_equal(&zero)?;
@@ -101,6 +126,7 @@ mod tests {
     use super::{
         burn_address_var, compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
     };
+    use crate::circuits::constants::{MAX_POW_DIFFICULTY, POW_DIFFICULTY};
     use crate::test_utils::truncate_to_160_bits;
     use crate::utils::poseidon::gadgets::CircomCRHParametersVar;
     use crate::utils::poseidon::utils::{circom_poseidon_config, circom_poseidon_hash};
@@ -133,10 +159,11 @@ mod tests {
 
         let recipient_value = sample_address_field(&mut rng);
         let secret_seed = sample_secret_seed(&mut rng);
-        let nonce = find_pow_nonce(recipient_value, secret_seed);
+        let nonce = find_pow_nonce(recipient_value, secret_seed, POW_DIFFICULTY);
         let secret_value = secret_from_nonce(secret_seed, nonce);
-        let expected_address = compute_burn_address_from_secret(recipient_value, secret_value)
-            .expect("nonce should satisfy PoW");
+        let expected_address =
+            compute_burn_address_from_secret(recipient_value, secret_value, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
 
         let config = circom_poseidon_config();
         let params = CircomCRHParametersVar::new_constant(ns!(cs, "params"), &config)?;
@@ -146,7 +173,13 @@ mod tests {
         let expected_var = FpVar::<Fr>::new_input(ns!(cs, "expected"), || Ok(expected_address))?;
 
         let should_constrain = Boolean::constant(true);
-        let actual = burn_address_var(&params, &recipient, &secret, &should_constrain)?;
+        let actual = burn_address_var(
+            &params,
+            &recipient,
+            &secret,
+            &should_constrain,
+            POW_DIFFICULTY,
+        )?;
         actual.enforce_equal(&expected_var)?;
 
         assert!(cs.is_satisfied().unwrap());
@@ -164,10 +197,11 @@ mod tests {
 
         let recipient_value = Fr::from(123_456_789u64);
         let secret_seed = Fr::from(1_000u64);
-        let nonce = find_pow_nonce(recipient_value, secret_seed);
+        let nonce = find_pow_nonce(recipient_value, secret_seed, POW_DIFFICULTY);
         let secret_value = secret_from_nonce(secret_seed, nonce);
-        let pow_expected_field = compute_burn_address_from_secret(recipient_value, secret_value)
-            .expect("nonce should satisfy PoW");
+        let pow_expected_field =
+            compute_burn_address_from_secret(recipient_value, secret_value, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
 
         let config = circom_poseidon_config();
         let params = CircomCRHParametersVar::new_constant(ns!(cs, "params"), &config)?;
@@ -188,7 +222,13 @@ mod tests {
         let expected_var = FpVar::<Fr>::new_input(ns!(cs, "expected"), || Ok(expected_field))?;
 
         let should_constrain = Boolean::constant(true);
-        let actual = burn_address_var(&params, &recipient, &secret, &should_constrain)?;
+        let actual = burn_address_var(
+            &params,
+            &recipient,
+            &secret,
+            &should_constrain,
+            POW_DIFFICULTY,
+        )?;
         actual.enforce_equal(&expected_var)?;
 
         assert!(cs.is_satisfied().unwrap());
@@ -199,11 +239,43 @@ mod tests {
     fn compute_burn_address_enforces_pow() {
         let recipient = Fr::from(42u64);
         let secret = Fr::from(17u64);
-        match compute_burn_address_from_secret(recipient, secret) {
-            Ok(_) => panic!("secret should not satisfy PoW"),
+        match compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY) {
             Err(super::BurnAddressError::PowDifficultyUnsatisfied { difficulty }) => {
-                assert_eq!(difficulty, super::POW_DIFFICULTY);
+                assert_eq!(difficulty, POW_DIFFICULTY);
             }
+            other => panic!("secret should not satisfy PoW, got {other:?}"),
         }
+        assert!(matches!(
+            compute_burn_address_from_secret(recipient, secret, MAX_POW_DIFFICULTY + 1),
+            Err(super::BurnAddressError::UnsupportedPowDifficulty { .. })
+        ));
+    }
+
+    #[test]
+    fn pow_difficulty_is_a_circuit_parameter() -> Result<(), SynthesisError> {
+        let recipient_value = Fr::from(123_456_789u64);
+        // a secret that satisfies a 4-bit PoW but not the default one
+        let secret_value = (0u64..)
+            .map(|nonce| secret_from_nonce(Fr::from(1_000u64), nonce))
+            .find(|secret| {
+                compute_burn_address_from_secret(recipient_value, *secret, 4).is_ok()
+                    && compute_burn_address_from_secret(recipient_value, *secret, POW_DIFFICULTY)
+                        .is_err()
+            })
+            .unwrap();
+
+        let satisfied = |difficulty: usize| -> Result<bool, SynthesisError> {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let config = circom_poseidon_config();
+            let params = CircomCRHParametersVar::new_constant(ns!(cs, "params"), &config)?;
+            let recipient = FpVar::<Fr>::new_witness(ns!(cs, "recipient"), || Ok(recipient_value))?;
+            let secret = FpVar::<Fr>::new_witness(ns!(cs, "secret"), || Ok(secret_value))?;
+            let should_constrain = Boolean::constant(true);
+            burn_address_var(&params, &recipient, &secret, &should_constrain, difficulty)?;
+            cs.is_satisfied()
+        };
+        assert!(satisfied(4)?);
+        assert!(!satisfied(POW_DIFFICULTY)?);
+        Ok(())
     }
 }
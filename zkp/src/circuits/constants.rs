@@ -1,3 +1,6 @@
 pub const BYTES31_BIT_LENGTH: usize = 248;
 pub const ADDRESS_BIT_LENGTH: usize = 160;
+/// Default number of zero bits required above the burn address. Deployments may pick another
+/// difficulty up to `MAX_POW_DIFFICULTY`; it is recorded in the artifact manifest and token config.
 pub const POW_DIFFICULTY: usize = 12;
+pub const MAX_POW_DIFFICULTY: usize = 32;
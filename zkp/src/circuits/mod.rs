@@ -1,3 +1,4 @@
+pub mod association;
 pub mod burn_address;
 pub mod constants;
 pub mod root_transition;
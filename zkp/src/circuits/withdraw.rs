@@ -1,4 +1,8 @@
-use crate::circuits::{burn_address::burn_address_var, constants::BYTES31_BIT_LENGTH};
+use crate::circuits::{
+    association::enforce_association_membership,
+    burn_address::burn_address_var,
+    constants::{ADDRESS_BIT_LENGTH, BYTES31_BIT_LENGTH},
+};
 use crate::utils::poseidon::gadgets::CircomCRHParametersVar;
 use crate::utils::tree::gadgets::{
     leaf_hash::leaf_hash_var,
@@ -18,18 +22,22 @@ use core::ops::Not;
 
 pub fn single_withdraw<F, const DEPTH: usize>(
     poseidon_params: &CircomCRHParametersVar<F>,
+    pow_difficulty: usize,
     merkle_root: &FpVar<F>,
     recipient: &FpVar<F>,
+    association_root: &FpVar<F>,
     value: &FpVar<F>,
     delta: &FpVar<F>,
     secret: &FpVar<F>,
     leaf_index: &FpVar<F>,
     siblings: &[FpVar<F>],
+    association_siblings: &[FpVar<F>],
 ) -> Result<FpVar<F>, SynthesisError>
 where
     F: PrimeField + Absorb,
 {
     assert_eq!(siblings.len(), DEPTH);
+    assert_eq!(association_siblings.len(), DEPTH);
 
     enforce_bit_length(leaf_index, DEPTH)?;
     enforce_bit_length(value, BYTES31_BIT_LENGTH)?;
@@ -38,11 +46,25 @@ where
     let index_bits = to_bits_le_limited(leaf_index, DEPTH)?;
 
     let enforce_pow = Boolean::constant(true);
-    let leaf_address = burn_address_var(poseidon_params, recipient, secret, &enforce_pow)?;
+    let leaf_address = burn_address_var(
+        poseidon_params,
+        recipient,
+        secret,
+        &enforce_pow,
+        pow_difficulty,
+    )?;
     let leaf_hash = leaf_hash_var(poseidon_params, &leaf_address, value)?;
     let computed_root = merkle_root_from_leaf(poseidon_params, &leaf_hash, &index_bits, siblings)?;
 
     merkle_root.enforce_equal(&computed_root)?;
+    enforce_association_membership(
+        poseidon_params,
+        association_root,
+        &leaf_hash,
+        &index_bits,
+        association_siblings,
+        &enforce_pow,
+    )?;
 
     let withdraw_value = value.clone() - delta.clone();
     enforce_bit_length(&withdraw_value, BYTES31_BIT_LENGTH)?;
@@ -50,22 +72,39 @@ where
     Ok(withdraw_value)
 }
 
+/// Binds the relayer address and fee into the proof. The fee is paid out of `withdraw_value`,
+/// so it must not exceed it.
+pub fn enforce_relayer_fee<F: PrimeField>(
+    relayer: &FpVar<F>,
+    fee: &FpVar<F>,
+    withdraw_value: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+    enforce_bit_length(relayer, ADDRESS_BIT_LENGTH)?;
+    enforce_bit_length(fee, BYTES31_BIT_LENGTH)?;
+    let net_value = withdraw_value.clone() - fee.clone();
+    enforce_bit_length(&net_value, BYTES31_BIT_LENGTH)
+}
+
 pub fn withdraw_step<F, const DEPTH: usize>(
     poseidon_params: &CircomCRHParametersVar<F>,
+    pow_difficulty: usize,
     merkle_root: &FpVar<F>,
     recipient: &FpVar<F>,
     prev_leaf_index_with_offset: &FpVar<F>,
     prev_total_value: &FpVar<F>,
+    association_root: &FpVar<F>,
     is_dummy: &Boolean<F>,
     value: &FpVar<F>,
     secret: &FpVar<F>,
     leaf_index: &FpVar<F>,
     siblings: &[FpVar<F>],
+    association_siblings: &[FpVar<F>],
 ) -> Result<(FpVar<F>, FpVar<F>, FpVar<F>, FpVar<F>), SynthesisError>
 where
     F: PrimeField + Absorb,
 {
     assert_eq!(siblings.len(), DEPTH);
+    assert_eq!(association_siblings.len(), DEPTH);
 
     // input range checks
     let one = FpVar::<F>::constant(F::one());
@@ -84,7 +123,13 @@ where
     let index_bits = to_bits_le_limited(leaf_index, DEPTH)?;
 
     let should_constrain = is_dummy.clone().not();
-    let leaf_address = burn_address_var(poseidon_params, recipient, secret, &should_constrain)?;
+    let leaf_address = burn_address_var(
+        poseidon_params,
+        recipient,
+        secret,
+        &should_constrain,
+        pow_difficulty,
+    )?;
     let leaf_hash = leaf_hash_var(poseidon_params, &leaf_address, value)?;
     let computed_root = merkle_root_from_leaf(poseidon_params, &leaf_hash, &index_bits, siblings)?;
 
@@ -93,6 +138,14 @@ where
 
     let diff = merkle_root.clone() - computed_root;
     (diff * is_real_fp).enforce_equal(&FpVar::<F>::constant(F::zero()))?;
+    enforce_association_membership(
+        poseidon_params,
+        association_root,
+        &leaf_hash,
+        &index_bits,
+        association_siblings,
+        &should_constrain,
+    )?;
 
     let two = F::from(2u64);
     let factor = one - is_dummy_fp.clone() * FpVar::<F>::constant(two);
@@ -109,16 +162,22 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{single_withdraw, withdraw_step};
+    use super::{enforce_relayer_fee, single_withdraw, withdraw_step};
     use crate::circuits::burn_address::{
         compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
     };
+    use crate::circuits::constants::POW_DIFFICULTY;
     use crate::test_utils::{merkle_root_from_path, truncate_to_160_bits};
     use crate::utils::poseidon::gadgets::CircomCRHParametersVar;
     use crate::utils::poseidon::utils::{circom_poseidon_config, circom_poseidon_hash};
     use ark_bn254::Fr;
     use ark_ff::{PrimeField, Zero};
-    use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar};
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        boolean::Boolean,
+        eq::EqGadget,
+        fields::{FieldVar, fp::FpVar},
+    };
     use ark_relations::gr1cs::{ConstraintSystem, SynthesisError};
     use ark_relations::ns;
     use ark_std::vec::Vec;
@@ -132,10 +191,10 @@ mod tests {
         "0x0911ce40509a628649e9857657bf47883d8e532cc9968313a3f431e0255bb4b8";
 
     fn find_pow_secret(recipient: Fr, seed: Fr) -> (Fr, Fr) {
-        let nonce = find_pow_nonce(recipient, seed);
+        let nonce = find_pow_nonce(recipient, seed, POW_DIFFICULTY);
         let secret = secret_from_nonce(seed, nonce);
-        let address =
-            compute_burn_address_from_secret(recipient, secret).expect("nonce should satisfy PoW");
+        let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+            .expect("nonce should satisfy PoW");
         (secret, address)
     }
 
@@ -186,13 +245,16 @@ mod tests {
 
         let new_value = single_withdraw::<Fr, DEPTH>(
             &params,
+            POW_DIFFICULTY,
             &merkle_root,
             &recipient,
+            &FpVar::zero(),
             &value,
             &delta,
             &secret,
             &leaf_index,
             &siblings,
+            &siblings,
         )?;
 
         let expected_new_value =
@@ -203,6 +265,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn relayer_fee_bounded_by_withdraw_value() -> Result<(), SynthesisError> {
+        let relayer_value = Fr::from_be_bytes_mod_order(&[0xffu8; 20]);
+        for (fee_value, expected) in [(0u64, true), (75, true), (76, false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let relayer = FpVar::<Fr>::new_input(ns!(cs, "relayer"), || Ok(relayer_value))?;
+            let fee = FpVar::<Fr>::new_input(ns!(cs, "fee"), || Ok(Fr::from(fee_value)))?;
+            let withdraw_value =
+                FpVar::<Fr>::new_witness(ns!(cs, "withdraw_value"), || Ok(Fr::from(75u64)))?;
+            enforce_relayer_fee(&relayer, &fee, &withdraw_value)?;
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let relayer = FpVar::<Fr>::new_input(ns!(cs, "relayer"), || {
+            Ok(Fr::from_be_bytes_mod_order(&[0x01u8; 21]))
+        })?;
+        let fee = FpVar::<Fr>::new_input(ns!(cs, "fee"), || Ok(Fr::zero()))?;
+        let withdraw_value =
+            FpVar::<Fr>::new_witness(ns!(cs, "withdraw_value"), || Ok(Fr::from(75u64)))?;
+        enforce_relayer_fee(&relayer, &fee, &withdraw_value)?;
+        assert!(!cs.is_satisfied().unwrap());
+        Ok(())
+    }
+
     #[test]
     fn withdraw_real_leaf_updates_total() -> Result<(), SynthesisError> {
         let cs = ConstraintSystem::<Fr>::new_ref();
@@ -276,15 +363,18 @@ mod tests {
         let (out_root, out_recipient, out_leaf_index_with_offset, out_total) =
             withdraw_step::<Fr, DEPTH>(
                 &params,
+                POW_DIFFICULTY,
                 &merkle_root,
                 &recipient,
                 &prev_leaf_index_with_offset,
                 &prev_total_value,
+                &FpVar::zero(),
                 &is_dummy,
                 &value,
                 &secret,
                 &leaf_index,
                 &siblings,
+                &siblings,
             )?;
 
         let out_root_exp = FpVar::<Fr>::new_input(ns!(cs, "out_root"), || Ok(merkle_root_value))?;
@@ -364,15 +454,18 @@ mod tests {
         let (out_root, out_recipient, out_leaf_index_with_offset, out_total) =
             withdraw_step::<Fr, DEPTH>(
                 &params,
+                POW_DIFFICULTY,
                 &merkle_root,
                 &recipient,
                 &prev_leaf_index_with_offset,
                 &prev_total_value,
+                &FpVar::zero(),
                 &is_dummy,
                 &value,
                 &secret,
                 &leaf_index,
                 &siblings,
+                &siblings,
             )?;
 
         let out_root_exp = FpVar::<Fr>::new_input(ns!(cs, "out_root"), || Ok(merkle_root_value))?;
@@ -438,15 +531,18 @@ mod tests {
         let (out_root, out_recipient, out_leaf_index_with_offset, out_total) =
             withdraw_step::<Fr, DEPTH>(
                 &params,
+                POW_DIFFICULTY,
                 &merkle_root,
                 &recipient,
                 &prev_leaf_index_with_offset,
                 &prev_total_value,
+                &FpVar::zero(),
                 &is_dummy,
                 &value,
                 &secret,
                 &leaf_index,
                 &siblings,
+                &siblings,
             )?;
 
         let out_root_exp = FpVar::<Fr>::new_input(ns!(cs, "out_root"), || Ok(merkle_root_value))?;
@@ -468,6 +564,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn withdraw_dummy_rejects_delta_exceeding_total() -> Result<(), SynthesisError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let poseidon_config = circom_poseidon_config();
+        let params = CircomCRHParametersVar::new_constant(ns!(cs, "params"), &poseidon_config)?;
+
+        let recipient_value = Fr::from(10u64);
+        let secret_value = Fr::zero();
+        let value_value = Fr::from(51u64);
+        let prev_leaf_index_with_offset_value = Fr::from(2u64);
+        let leaf_index_value = Fr::from(2u64);
+        let prev_total_value_value = Fr::from(50u64);
+        let is_dummy_value = true;
+
+        let siblings_values = vec![Fr::zero(); DEPTH];
+        let merkle_root_value = Fr::from(999_999_999u64);
+
+        let merkle_root =
+            FpVar::<Fr>::new_witness(ns!(cs, "merkle_root"), || Ok(merkle_root_value))?;
+        let recipient = FpVar::<Fr>::new_witness(ns!(cs, "recipient"), || Ok(recipient_value))?;
+        let prev_leaf_index_with_offset =
+            FpVar::<Fr>::new_witness(ns!(cs, "prev_leaf_index_with_offset"), || {
+                Ok(prev_leaf_index_with_offset_value)
+            })?;
+        let prev_total_value =
+            FpVar::<Fr>::new_witness(ns!(cs, "prev_total_value"), || Ok(prev_total_value_value))?;
+        let is_dummy = Boolean::new_witness(ns!(cs, "is_dummy"), || Ok(is_dummy_value))?;
+        let value = FpVar::<Fr>::new_witness(ns!(cs, "value"), || Ok(value_value))?;
+        let secret = FpVar::<Fr>::new_witness(ns!(cs, "secret"), || Ok(secret_value))?;
+        let leaf_index = FpVar::<Fr>::new_witness(ns!(cs, "leaf_index"), || Ok(leaf_index_value))?;
+        let siblings = siblings_values
+            .iter()
+            .map(|s| FpVar::<Fr>::new_witness(ns!(cs, "sibling"), || Ok(*s)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        withdraw_step::<Fr, DEPTH>(
+            &params,
+            POW_DIFFICULTY,
+            &merkle_root,
+            &recipient,
+            &prev_leaf_index_with_offset,
+            &prev_total_value,
+            &FpVar::zero(),
+            &is_dummy,
+            &value,
+            &secret,
+            &leaf_index,
+            &siblings,
+            &siblings,
+        )?;
+
+        assert!(!cs.is_satisfied().unwrap());
+        Ok(())
+    }
+
     #[test]
     fn withdraw_rejects_non_increasing_index() -> Result<(), SynthesisError> {
         let cs = ConstraintSystem::<Fr>::new_ref();
@@ -509,15 +661,18 @@ mod tests {
         let (out_root, out_recipient, out_leaf_index_with_offset, out_total) =
             withdraw_step::<Fr, DEPTH>(
                 &params,
+                POW_DIFFICULTY,
                 &merkle_root,
                 &recipient,
                 &prev_leaf_index_with_offset,
                 &prev_total_value,
+                &FpVar::zero(),
                 &is_dummy,
                 &value,
                 &secret,
                 &leaf_index,
                 &siblings,
+                &siblings,
             )?;
 
         let out_root_exp = FpVar::<Fr>::new_input(ns!(cs, "out_root"), || Ok(merkle_root_value))?;
@@ -0,0 +1,339 @@
+use crate::{
+    circuits::withdraw::withdraw_step,
+    groth16::{
+        multi_withdraw::MultiWithdrawLeaf,
+        params::{Groth16Error, Groth16Params},
+    },
+    nova::constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+    utils::poseidon::gadgets::CircomCRHParametersVar,
+};
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::sponge::{Absorb, poseidon::PoseidonConfig};
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{FieldVar, fp::FpVar},
+};
+use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_relations::ns;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+
+/// Slot counts for which `generate_circuit_artifacts` produces disclosure Groth16 keys.
+pub const DISCLOSURE_SIZES: [usize; 3] = [1, 4, 8];
+
+/// Artifact file prefix for the disclosure circuit with `num_slots` leaves over a
+/// `tree_height`-level tree, or `None` for trees no deployment uses.
+pub fn disclosure_artifact_prefix(tree_height: usize, num_slots: usize) -> Option<String> {
+    let scope = match tree_height {
+        TRANSFER_TREE_HEIGHT => "local",
+        GLOBAL_TRANSFER_TREE_HEIGHT => "global",
+        _ => return None,
+    };
+    Some(format!("disclosure_{scope}_{num_slots}"))
+}
+
+/// Selective-disclosure statement for audits: the leaves at the disclosed indices of the tree
+/// with root `merkle_root` hold burns to addresses derived from `recipient`, and their values sum
+/// to `total_value`. Secrets and Merkle paths stay private, and nothing is revealed about other
+/// leaves of the tree.
+///
+/// Public inputs are `[merkle_root, recipient, total_value, disclosed_0, .., disclosed_{N-1}]`
+/// where `disclosed_i` is `leaf_index + 1` for a real slot and `0` for a dummy one. Slots use the
+/// `withdraw_step` ordering rules, so disclosed indices are strictly increasing and a leaf cannot
+/// be counted twice; dummy slots must carry a zero value.
+#[derive(Clone)]
+pub struct DisclosureCircuit<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> {
+    pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
+
+    // ---- public inputs ----
+    pub merkle_root: Option<F>,
+    pub recipient: Option<F>,
+    pub total_value: Option<F>,
+
+    // ---- witness ----
+    pub leaves: [MultiWithdrawLeaf<F, DEPTH>; N],
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> DisclosureCircuit<F, DEPTH, N> {
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
+        Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root: None,
+            recipient: None,
+            total_value: None,
+            leaves: [(); N].map(|_| MultiWithdrawLeaf::default()),
+        }
+    }
+
+    pub fn public_inputs(&self) -> Result<Vec<F>, SynthesisError> {
+        let mut inputs = vec![
+            self.merkle_root.ok_or(SynthesisError::AssignmentMissing)?,
+            self.recipient.ok_or(SynthesisError::AssignmentMissing)?,
+            self.total_value.ok_or(SynthesisError::AssignmentMissing)?,
+        ];
+        for leaf in &self.leaves {
+            inputs.push(disclosed_index(leaf).ok_or(SynthesisError::AssignmentMissing)?);
+        }
+        Ok(inputs)
+    }
+}
+
+/// Rebuilds the public inputs of a `num_slots` disclosure from the real leaf indices, in the
+/// order they were proven; the remaining slots are dummies.
+pub fn disclosure_public_inputs(
+    merkle_root: Fr,
+    recipient: Fr,
+    total_value: Fr,
+    leaf_indices: &[u64],
+    num_slots: usize,
+) -> Vec<Fr> {
+    let mut inputs = vec![merkle_root, recipient, total_value];
+    inputs.extend(
+        leaf_indices
+            .iter()
+            .map(|&index| Fr::from(index) + Fr::from(1u64)),
+    );
+    inputs.resize(3 + num_slots.max(leaf_indices.len()), Fr::from(0u64));
+    inputs
+}
+
+fn disclosed_index<F: PrimeField, const DEPTH: usize>(
+    leaf: &MultiWithdrawLeaf<F, DEPTH>,
+) -> Option<F> {
+    match (leaf.is_dummy?, leaf.leaf_index?) {
+        (true, _) => Some(F::zero()),
+        (false, index) => Some(F::from(index) + F::one()),
+    }
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> ConstraintSynthesizer<F>
+    for DisclosureCircuit<F, DEPTH, N>
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root,
+            recipient,
+            total_value,
+            leaves,
+        } = self;
+
+        let poseidon_params =
+            CircomCRHParametersVar::new_constant(ns!(cs, "poseidon_params"), &poseidon_params)?;
+
+        // ---- public inputs ----
+        let merkle_root = FpVar::<F>::new_input(ns!(cs, "merkle_root"), || {
+            merkle_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let recipient = FpVar::<F>::new_input(ns!(cs, "recipient"), || {
+            recipient.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let expected_total_value = FpVar::<F>::new_input(ns!(cs, "total_value"), || {
+            total_value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let disclosed_indices = leaves
+            .iter()
+            .map(|leaf| {
+                FpVar::<F>::new_input(ns!(cs, "disclosed_index"), || {
+                    disclosed_index(leaf).ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // ---- witness ----
+        // disclosures are not tied to an association set; the constant zero root skips the check
+        let no_association_root = FpVar::<F>::zero();
+        let no_association_siblings = vec![FpVar::<F>::zero(); DEPTH];
+        let mut prev_leaf_index_with_offset = FpVar::<F>::zero();
+        let mut total_value = FpVar::<F>::zero();
+        for (leaf, disclosed_index) in leaves.into_iter().zip(disclosed_indices) {
+            let is_dummy = Boolean::new_witness(ns!(cs, "is_dummy"), || {
+                leaf.is_dummy.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let value = FpVar::<F>::new_witness(ns!(cs, "value"), || {
+                leaf.value.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let secret = FpVar::<F>::new_witness(ns!(cs, "secret"), || {
+                leaf.secret.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let leaf_index = FpVar::<F>::new_witness(ns!(cs, "leaf_index"), || {
+                leaf.leaf_index
+                    .map(F::from)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let sibling_vars = leaf
+                .siblings
+                .into_iter()
+                .map(|sibling| {
+                    FpVar::<F>::new_witness(ns!(cs, "sibling"), || {
+                        sibling.ok_or(SynthesisError::AssignmentMissing)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // a dummy slot would otherwise subtract its value and understate the total
+            value.conditional_enforce_equal(&FpVar::<F>::zero(), &is_dummy)?;
+
+            let (_, _, leaf_index_with_offset, new_total_value) = withdraw_step::<F, DEPTH>(
+                &poseidon_params,
+                pow_difficulty,
+                &merkle_root,
+                &recipient,
+                &prev_leaf_index_with_offset,
+                &total_value,
+                &no_association_root,
+                &is_dummy,
+                &value,
+                &secret,
+                &leaf_index,
+                sibling_vars.as_slice(),
+                &no_association_siblings,
+            )?;
+
+            let is_real: FpVar<F> = (!is_dummy).into();
+            disclosed_index.enforce_equal(&(leaf_index_with_offset.clone() * is_real))?;
+
+            prev_leaf_index_with_offset = leaf_index_with_offset;
+            total_value = new_total_value;
+        }
+
+        expected_total_value.enforce_equal(&total_value)?;
+
+        Ok(())
+    }
+}
+
+/// Proves `circuit` and returns the compressed Groth16 proof. Disclosures are checked off-chain,
+/// so the proof is kept in arkworks encoding rather than as Solidity calldata.
+pub fn prove_disclosure<C, R>(
+    params: &Groth16Params,
+    rng: &mut R,
+    circuit: C,
+    public_inputs: &[Fr],
+) -> Result<Vec<u8>, Groth16Error>
+where
+    C: ConstraintSynthesizer<Fr>,
+    R: RngCore + CryptoRng,
+{
+    let proof = Groth16::<Bn254>::prove(&params.pk, circuit, rng)
+        .map_err(|e| Groth16Error::ProofGenerationError(e.to_string()))?;
+    let mut proof_bytes = Vec::new();
+    proof
+        .serialize_compressed(&mut proof_bytes)
+        .map_err(|e| Groth16Error::SerializationError(format!("Disclosure Proof: {}", e)))?;
+    verify_disclosure(&params.vk, public_inputs, &proof_bytes)?;
+    Ok(proof_bytes)
+}
+
+pub fn verify_disclosure(
+    vk: &VerifyingKey<Bn254>,
+    public_inputs: &[Fr],
+    proof: &[u8],
+) -> Result<(), Groth16Error> {
+    let proof = Proof::<Bn254>::deserialize_compressed(proof)
+        .map_err(|e| Groth16Error::DeserializationError(format!("Disclosure Proof: {}", e)))?;
+    let verified = Groth16::<Bn254>::verify(vk, public_inputs, &proof).map_err(|e| {
+        Groth16Error::ProofVerificationInternalError(format!("Disclosure verification: {}", e))
+    })?;
+    if !verified {
+        return Err(Groth16Error::ProofVerificationError(
+            "Disclosure proof verification failed".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DisclosureCircuit, disclosure_public_inputs, prove_disclosure, verify_disclosure};
+    use crate::circuits::burn_address::{
+        compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+    };
+    use crate::circuits::constants::POW_DIFFICULTY;
+    use crate::groth16::{multi_withdraw::MultiWithdrawLeaf, params::Groth16Params};
+    use crate::utils::{
+        convertion::{fr_to_address, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::incremental_merkle_tree::IncrementalMerkleTree,
+    };
+    use alloy::primitives::U256;
+    use ark_bn254::Fr;
+    use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::rand::{SeedableRng, rngs::StdRng};
+
+    const DEPTH: usize = 4;
+
+    fn build_circuit<const N: usize>(values: &[u64]) -> DisclosureCircuit<Fr, DEPTH, N> {
+        let recipient = Fr::from(4242u64);
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        // an unrelated transfer that stays undisclosed
+        tree.insert(fr_to_address(Fr::from(1u64)), U256::from(999u64));
+        let mut circuit =
+            DisclosureCircuit::<Fr, DEPTH, N>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        let mut leaves = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let seed = Fr::from(30 + i as u64);
+            let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
+            let index = tree.insert(fr_to_address(address), U256::from(*value));
+            leaves.push((index, secret, *value));
+        }
+        for (slot, (index, secret, value)) in leaves.iter().enumerate() {
+            let siblings = tree.prove(*index).siblings.try_into().unwrap();
+            circuit.leaves[slot] =
+                MultiWithdrawLeaf::new(u256_to_fr(U256::from(*value)), *secret, *index, siblings);
+        }
+        for slot in leaves.len()..N {
+            circuit.leaves[slot] = MultiWithdrawLeaf::dummy(8 + slot as u64);
+        }
+        circuit.merkle_root = Some(tree.get_root());
+        circuit.recipient = Some(recipient);
+        circuit.total_value = Some(Fr::from(values.iter().sum::<u64>()));
+        circuit
+    }
+
+    #[test]
+    fn disclosure_proof_verifies_against_rebuilt_inputs() {
+        let circuit = build_circuit::<4>(&[100, 250]);
+        let public_inputs = circuit.public_inputs().unwrap();
+        assert_eq!(
+            public_inputs,
+            disclosure_public_inputs(
+                circuit.merkle_root.unwrap(),
+                circuit.recipient.unwrap(),
+                Fr::from(350u64),
+                &[1, 2],
+                4,
+            )
+        );
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let setup_circuit =
+            DisclosureCircuit::<Fr, DEPTH, 4>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        let params = Groth16Params::rand(&mut rng, setup_circuit).expect("setup");
+        let proof = prove_disclosure(&params, &mut rng, circuit, &public_inputs).expect("prove");
+
+        let mut overstated = public_inputs.clone();
+        overstated[2] = Fr::from(351u64);
+        assert!(verify_disclosure(&params.vk, &overstated, &proof).is_err());
+    }
+
+    #[test]
+    fn disclosure_rejects_valued_dummy() {
+        let mut circuit = build_circuit::<2>(&[100]);
+        circuit.leaves[1].value = Some(Fr::from(40u64));
+        circuit.total_value = Some(Fr::from(60u64));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -1,2 +1,6 @@
+pub mod disclosure;
+pub mod multi_withdraw;
 pub mod params;
+pub mod payout;
+pub mod reburn;
 pub mod withdraw;
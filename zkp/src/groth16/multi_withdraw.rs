@@ -0,0 +1,349 @@
+use crate::{
+    circuits::{
+        constants::BYTES31_BIT_LENGTH,
+        withdraw::{enforce_relayer_fee, withdraw_step},
+    },
+    utils::{poseidon::gadgets::CircomCRHParametersVar, tree::gadgets::merkle::enforce_bit_length},
+};
+use ark_crypto_primitives::sponge::{Absorb, poseidon::PoseidonConfig};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{FieldVar, fp::FpVar},
+};
+use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_relations::ns;
+
+/// Batch sizes for which `generate_circuit_artifacts` produces multi-leaf Groth16 artifacts.
+pub const MULTI_WITHDRAW_SIZES: [usize; 3] = [2, 4, 8];
+
+#[derive(Clone, Debug)]
+pub struct MultiWithdrawLeaf<F: PrimeField, const DEPTH: usize> {
+    pub is_dummy: Option<bool>,
+    pub value: Option<F>,
+    pub secret: Option<F>,
+    pub leaf_index: Option<u64>,
+    pub siblings: [Option<F>; DEPTH],
+    pub association_siblings: [Option<F>; DEPTH],
+}
+
+impl<F: PrimeField, const DEPTH: usize> Default for MultiWithdrawLeaf<F, DEPTH> {
+    fn default() -> Self {
+        Self {
+            is_dummy: None,
+            value: None,
+            secret: None,
+            leaf_index: None,
+            siblings: [(); DEPTH].map(|_| None),
+            association_siblings: [(); DEPTH].map(|_| None),
+        }
+    }
+}
+
+impl<F: PrimeField, const DEPTH: usize> MultiWithdrawLeaf<F, DEPTH> {
+    pub fn new(value: F, secret: F, leaf_index: u64, siblings: [F; DEPTH]) -> Self {
+        Self {
+            is_dummy: Some(false),
+            value: Some(value),
+            secret: Some(secret),
+            leaf_index: Some(leaf_index),
+            siblings: siblings.map(Some),
+            association_siblings: [(); DEPTH].map(|_| Some(F::zero())),
+        }
+    }
+
+    /// Path of the leaf in the association tree, required when the circuit's `association_root`
+    /// is non-zero.
+    pub fn with_association_siblings(mut self, association_siblings: [F; DEPTH]) -> Self {
+        self.association_siblings = association_siblings.map(Some);
+        self
+    }
+
+    /// Padding slot for batches smaller than `N`. `leaf_index` must still be strictly greater
+    /// than the index of the preceding slot.
+    pub fn dummy(leaf_index: u64) -> Self {
+        Self {
+            is_dummy: Some(true),
+            value: Some(F::zero()),
+            secret: Some(F::zero()),
+            leaf_index: Some(leaf_index),
+            siblings: [(); DEPTH].map(|_| Some(F::zero())),
+            association_siblings: [(); DEPTH].map(|_| Some(F::zero())),
+        }
+    }
+}
+
+/// Groth16 counterpart of the Nova `WithdrawCircuit` for small batches: proves `N` leaves
+/// (real or dummy, in strictly increasing index order) against one Merkle root in a single
+/// proof. The public input layout matches `SingleWithdrawCircuit`, and a non-zero
+/// `association_root` applies to every real leaf.
+#[derive(Clone)]
+pub struct MultiWithdrawCircuit<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> {
+    pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
+
+    // ---- public inputs ----
+    pub merkle_root: Option<F>,
+    pub recipient: Option<F>,
+    pub withdraw_value: Option<F>,
+    pub relayer: Option<F>,
+    pub fee: Option<F>,
+    pub association_root: Option<F>,
+
+    // ---- witness ----
+    pub delta: Option<F>,
+    pub leaves: [MultiWithdrawLeaf<F, DEPTH>; N],
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> MultiWithdrawCircuit<F, DEPTH, N> {
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
+        Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root: None,
+            recipient: None,
+            withdraw_value: None,
+            relayer: None,
+            fee: None,
+            association_root: None,
+            delta: None,
+            leaves: [(); N].map(|_| MultiWithdrawLeaf::default()),
+        }
+    }
+
+    pub fn public_inputs(&self) -> Result<Vec<F>, SynthesisError> {
+        Ok(vec![
+            self.merkle_root.ok_or(SynthesisError::AssignmentMissing)?,
+            self.recipient.ok_or(SynthesisError::AssignmentMissing)?,
+            self.withdraw_value
+                .ok_or(SynthesisError::AssignmentMissing)?,
+            self.relayer.ok_or(SynthesisError::AssignmentMissing)?,
+            self.fee.ok_or(SynthesisError::AssignmentMissing)?,
+            self.association_root
+                .ok_or(SynthesisError::AssignmentMissing)?,
+        ])
+    }
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> ConstraintSynthesizer<F>
+    for MultiWithdrawCircuit<F, DEPTH, N>
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root,
+            recipient,
+            withdraw_value,
+            relayer,
+            fee,
+            association_root,
+            delta,
+            leaves,
+        } = self;
+
+        let poseidon_params =
+            CircomCRHParametersVar::new_constant(ns!(cs, "poseidon_params"), &poseidon_params)?;
+
+        // ---- public inputs ----
+        let merkle_root = FpVar::<F>::new_input(ns!(cs, "merkle_root"), || {
+            merkle_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let recipient = FpVar::<F>::new_input(ns!(cs, "recipient"), || {
+            recipient.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let expected_withdraw_value = FpVar::<F>::new_input(ns!(cs, "withdraw_value"), || {
+            withdraw_value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let relayer = FpVar::<F>::new_input(ns!(cs, "relayer"), || {
+            relayer.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let fee = FpVar::<F>::new_input(ns!(cs, "fee"), || {
+            fee.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let association_root = FpVar::<F>::new_input(ns!(cs, "association_root"), || {
+            association_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        // ---- witness ----
+        let delta = FpVar::<F>::new_witness(ns!(cs, "delta"), || {
+            delta.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let total_value = sum_withdraw_leaves(
+            cs,
+            &poseidon_params,
+            pow_difficulty,
+            &merkle_root,
+            &recipient,
+            &association_root,
+            leaves,
+        )?;
+
+        enforce_bit_length(&delta, BYTES31_BIT_LENGTH)?;
+        let computed_withdraw_value = total_value - delta;
+        enforce_bit_length(&computed_withdraw_value, BYTES31_BIT_LENGTH)?;
+
+        expected_withdraw_value.enforce_equal(&computed_withdraw_value)?;
+        enforce_relayer_fee(&relayer, &fee, &expected_withdraw_value)?;
+
+        Ok(())
+    }
+}
+
+/// Allocates `leaves` and runs each through `withdraw_step`, returning the total value of the
+/// real ones. Leaves must be in strictly increasing index order, which rules out proving the same
+/// leaf twice.
+pub(crate) fn sum_withdraw_leaves<F: PrimeField + Absorb, const DEPTH: usize>(
+    cs: ConstraintSystemRef<F>,
+    poseidon_params: &CircomCRHParametersVar<F>,
+    pow_difficulty: usize,
+    merkle_root: &FpVar<F>,
+    recipient: &FpVar<F>,
+    association_root: &FpVar<F>,
+    leaves: impl IntoIterator<Item = MultiWithdrawLeaf<F, DEPTH>>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut prev_leaf_index_with_offset = FpVar::<F>::zero();
+    let mut total_value = FpVar::<F>::zero();
+    for leaf in leaves {
+        let is_dummy = Boolean::new_witness(ns!(cs, "is_dummy"), || {
+            leaf.is_dummy.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let value = FpVar::<F>::new_witness(ns!(cs, "value"), || {
+            leaf.value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let secret = FpVar::<F>::new_witness(ns!(cs, "secret"), || {
+            leaf.secret.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let leaf_index = FpVar::<F>::new_witness(ns!(cs, "leaf_index"), || {
+            leaf.leaf_index
+                .map(F::from)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let sibling_vars = leaf
+            .siblings
+            .into_iter()
+            .map(|sibling| {
+                FpVar::<F>::new_witness(ns!(cs, "sibling"), || {
+                    sibling.ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let association_sibling_vars = leaf
+            .association_siblings
+            .into_iter()
+            .map(|sibling| {
+                FpVar::<F>::new_witness(ns!(cs, "association_sibling"), || {
+                    sibling.ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (_, _, leaf_index_with_offset, new_total_value) = withdraw_step::<F, DEPTH>(
+            poseidon_params,
+            pow_difficulty,
+            merkle_root,
+            recipient,
+            &prev_leaf_index_with_offset,
+            &total_value,
+            association_root,
+            &is_dummy,
+            &value,
+            &secret,
+            &leaf_index,
+            sibling_vars.as_slice(),
+            association_sibling_vars.as_slice(),
+        )?;
+        prev_leaf_index_with_offset = leaf_index_with_offset;
+        total_value = new_total_value;
+    }
+    Ok(total_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultiWithdrawCircuit, MultiWithdrawLeaf};
+    use crate::circuits::burn_address::{
+        compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+    };
+    use crate::circuits::constants::POW_DIFFICULTY;
+    use crate::groth16::params::Groth16Params;
+    use crate::utils::{
+        convertion::{fr_to_address, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::incremental_merkle_tree::IncrementalMerkleTree,
+    };
+    use alloy::primitives::U256;
+    use ark_bn254::Fr;
+    use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::rand::{SeedableRng, rngs::StdRng};
+
+    const DEPTH: usize = 4;
+
+    fn build_circuit<const N: usize>(
+        values: &[u64],
+        duplicate_first: bool,
+    ) -> MultiWithdrawCircuit<Fr, DEPTH, N> {
+        let recipient = Fr::from(777u64);
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let mut leaves = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let seed = Fr::from(10 + i as u64);
+            let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
+            let index = tree.insert(fr_to_address(address), U256::from(*value));
+            leaves.push((index, secret, *value));
+        }
+        if duplicate_first {
+            leaves[1] = leaves[0];
+        }
+
+        let mut circuit =
+            MultiWithdrawCircuit::<Fr, DEPTH, N>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        for (slot, (index, secret, value)) in leaves.iter().enumerate() {
+            let siblings = tree.prove(*index).siblings.try_into().unwrap();
+            circuit.leaves[slot] =
+                MultiWithdrawLeaf::new(u256_to_fr(U256::from(*value)), *secret, *index, siblings);
+        }
+        for slot in leaves.len()..N {
+            circuit.leaves[slot] = MultiWithdrawLeaf::dummy(8 + slot as u64);
+        }
+
+        let delta = 15u64;
+        let total = leaves.iter().map(|(_, _, value)| value).sum::<u64>();
+        circuit.merkle_root = Some(tree.get_root());
+        circuit.recipient = Some(recipient);
+        circuit.withdraw_value = Some(Fr::from(total - delta));
+        circuit.relayer = Some(Fr::from(0xbeefu64));
+        circuit.fee = Some(Fr::from(5u64));
+        circuit.association_root = Some(Fr::from(0u64));
+        circuit.delta = Some(Fr::from(delta));
+        circuit
+    }
+
+    #[test]
+    fn test_multi_withdraw_circuit() {
+        let circuit = build_circuit::<4>(&[100, 200, 300], false);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let setup_circuit =
+            MultiWithdrawCircuit::<Fr, DEPTH, 4>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        let params = Groth16Params::rand(&mut rng, setup_circuit).expect("setup");
+        let public_inputs = circuit.public_inputs().expect("public inputs");
+        assert_eq!(public_inputs[2], Fr::from(585u64));
+        params
+            .generate_proof(&mut rng, circuit, &public_inputs)
+            .expect("groth16 proof");
+    }
+
+    #[test]
+    fn multi_withdraw_rejects_repeated_leaf() {
+        let circuit = build_circuit::<2>(&[100, 200], true);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
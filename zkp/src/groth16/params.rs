@@ -1,9 +1,9 @@
-use std::io::Cursor;
+use std::io::Read;
 
 use ark_bn254::{Bn254, Fr};
 use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
 use ark_relations::gr1cs::ConstraintSynthesizer;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use rand::{CryptoRng, RngCore};
 use solidity_verifiers::utils::eth::ToEth;
@@ -45,32 +45,58 @@ impl Groth16Params {
     }
 
     pub fn from_bytes(pk_bytes: Vec<u8>, vk_bytes: Vec<u8>) -> Result<Self, Groth16Error> {
-        let pk = {
-            let mut rd = Cursor::new(&pk_bytes);
-            ProvingKey::<Bn254>::deserialize_uncompressed(&mut rd).map_err(|e| {
-                Groth16Error::DeserializationError(format!("Groth16 Proving Key: {}", e))
-            })?
-        };
+        Self::from_bytes_with_mode(pk_bytes, vk_bytes, Compress::No)
+    }
+
+    pub fn from_bytes_with_mode(
+        pk_bytes: Vec<u8>,
+        vk_bytes: Vec<u8>,
+        compress: Compress,
+    ) -> Result<Self, Groth16Error> {
+        Self::from_readers(
+            pk_bytes.as_slice(),
+            vk_bytes.as_slice(),
+            compress,
+            Validate::Yes,
+        )
+    }
 
-        let vk = {
-            let mut rd = Cursor::new(&vk_bytes);
-            VerifyingKey::<Bn254>::deserialize_uncompressed(&mut rd).map_err(|e| {
+    /// Deserializes the keys straight from `pk`/`vk` without buffering them. Only pass
+    /// `Validate::No` for bytes whose hash matched a trusted manifest.
+    pub fn from_readers(
+        pk: impl Read,
+        vk: impl Read,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, Groth16Error> {
+        let pk =
+            ProvingKey::<Bn254>::deserialize_with_mode(pk, compress, validate).map_err(|e| {
+                Groth16Error::DeserializationError(format!("Groth16 Proving Key: {}", e))
+            })?;
+        let vk =
+            VerifyingKey::<Bn254>::deserialize_with_mode(vk, compress, validate).map_err(|e| {
                 Groth16Error::DeserializationError(format!("Groth16 Verifying Key: {}", e))
-            })?
-        };
+            })?;
 
         Ok(Self { vk, pk })
     }
 
     pub fn to_bytes(&self) -> Result<(Vec<u8>, Vec<u8>), Groth16Error> {
+        self.to_bytes_with_mode(Compress::No)
+    }
+
+    pub fn to_bytes_with_mode(
+        &self,
+        compress: Compress,
+    ) -> Result<(Vec<u8>, Vec<u8>), Groth16Error> {
         let mut pk_bytes = Vec::new();
         self.pk
-            .serialize_with_mode(&mut pk_bytes, Compress::No)
+            .serialize_with_mode(&mut pk_bytes, compress)
             .map_err(|e| Groth16Error::SerializationError(format!("Groth16 Proving Key: {}", e)))?;
 
         let mut vk_bytes = Vec::new();
         self.vk
-            .serialize_with_mode(&mut vk_bytes, Compress::No)
+            .serialize_with_mode(&mut vk_bytes, compress)
             .map_err(|e| {
                 Groth16Error::SerializationError(format!("Groth16 Verifying Key: {}", e))
             })?;
@@ -79,10 +105,7 @@ impl Groth16Params {
     }
 
     pub fn verifier_solidity_code(&self) -> Result<String, Groth16Error> {
-        let verifier_key = Groth16VerifierKey::from(self.vk.clone());
-        let bytes = verifier_key.render_as_template(None);
-        String::from_utf8(bytes)
-            .map_err(|e| Groth16Error::SolidityExportError(format!("UTF-8 Error: {}", e)))
+        groth16_verifier_solidity_code(&self.vk)
     }
 
     pub fn generate_proof<C, R>(
@@ -114,3 +137,20 @@ impl Groth16Params {
         Ok([proof.to_eth(), public_inputs.to_eth()].concat())
     }
 }
+
+pub fn verifying_key_from_bytes(
+    vk_bytes: &[u8],
+    compress: Compress,
+) -> Result<VerifyingKey<Bn254>, Groth16Error> {
+    VerifyingKey::<Bn254>::deserialize_with_mode(vk_bytes, compress, Validate::Yes)
+        .map_err(|e| Groth16Error::DeserializationError(format!("Groth16 Verifying Key: {}", e)))
+}
+
+/// Renders the Solidity verifier from the verifying key alone, so verifiers can be regenerated
+/// from a `*_groth16_vk.bin` without loading the proving key.
+pub fn groth16_verifier_solidity_code(vk: &VerifyingKey<Bn254>) -> Result<String, Groth16Error> {
+    let verifier_key = Groth16VerifierKey::from(vk.clone());
+    let bytes = verifier_key.render_as_template(None);
+    String::from_utf8(bytes)
+        .map_err(|e| Groth16Error::SolidityExportError(format!("UTF-8 Error: {}", e)))
+}
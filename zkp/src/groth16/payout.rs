@@ -0,0 +1,420 @@
+use crate::{
+    circuits::{
+        constants::{ADDRESS_BIT_LENGTH, BYTES31_BIT_LENGTH},
+        withdraw::enforce_relayer_fee,
+    },
+    groth16::multi_withdraw::{MultiWithdrawLeaf, sum_withdraw_leaves},
+    nova::constants::{GLOBAL_TRANSFER_TREE_HEIGHT, TRANSFER_TREE_HEIGHT},
+    utils::{
+        poseidon::{
+            gadgets::{CircomCRHGadget, CircomCRHParametersVar},
+            utils::poseidon2,
+        },
+        tree::gadgets::merkle::enforce_bit_length,
+    },
+};
+use ark_bn254::Fr;
+use ark_crypto_primitives::{
+    crh::CRHSchemeGadget,
+    sponge::{Absorb, poseidon::PoseidonConfig},
+};
+use ark_ff::{AdditiveGroup, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{FieldVar, fp::FpVar},
+    select::CondSelectGadget,
+};
+use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_relations::ns;
+
+/// Payout slot counts for which payout withdraw circuits are instantiated.
+pub const PAYOUT_SIZES: [usize; 2] = [4, 16];
+
+/// Leaves proven by every payout withdraw circuit; only the payout slot count varies.
+pub const PAYOUT_LEAVES: usize = 8;
+
+/// Artifact file prefix for the payout withdraw circuit with `num_payouts` slots over a
+/// `tree_height`-level tree, or `None` for trees no deployment uses.
+pub fn payout_artifact_prefix(tree_height: usize, num_payouts: usize) -> Option<String> {
+    let scope = match tree_height {
+        TRANSFER_TREE_HEIGHT => "local",
+        GLOBAL_TRANSFER_TREE_HEIGHT => "global",
+        _ => return None,
+    };
+    Some(format!("payout_{scope}_{num_payouts}"))
+}
+
+/// Commitment to an ordered list of `(recipient, amount)` payouts:
+/// `c_0 = 0`, `c_{i+1} = poseidon(c_i, poseidon(recipient_i, amount_i))`. Entries with a zero
+/// recipient are empty slots and do not change the commitment, so it does not depend on how many
+/// slots the circuit has.
+pub fn payout_commitment(payouts: &[(Fr, Fr)]) -> Fr {
+    payouts
+        .iter()
+        .filter(|(recipient, _)| *recipient != Fr::ZERO)
+        .fold(Fr::ZERO, |commitment, (recipient, amount)| {
+            poseidon2(commitment, poseidon2(*recipient, *amount))
+        })
+}
+
+/// In-circuit `payout_commitment`, also returning the sum of the amounts. Recipients are
+/// range-checked to 160 bits and amounts to 31 bytes, and an empty slot must carry a zero amount.
+pub fn payout_commitment_var<F: PrimeField + Absorb>(
+    poseidon_params: &CircomCRHParametersVar<F>,
+    payouts: &[(FpVar<F>, FpVar<F>)],
+) -> Result<(FpVar<F>, FpVar<F>), SynthesisError> {
+    let zero = FpVar::<F>::zero();
+    let mut commitment = FpVar::<F>::zero();
+    let mut total = FpVar::<F>::zero();
+    for (recipient, amount) in payouts {
+        enforce_bit_length(recipient, ADDRESS_BIT_LENGTH)?;
+        enforce_bit_length(amount, BYTES31_BIT_LENGTH)?;
+
+        let is_empty = recipient.is_zero()?;
+        let is_empty_fp: FpVar<F> = is_empty.clone().into();
+        (amount.clone() * is_empty_fp).enforce_equal(&zero)?;
+
+        let payout_hash =
+            CircomCRHGadget::<F>::evaluate(poseidon_params, &[recipient.clone(), amount.clone()])?;
+        let next =
+            CircomCRHGadget::<F>::evaluate(poseidon_params, &[commitment.clone(), payout_hash])?;
+        commitment = FpVar::conditionally_select(&is_empty, &commitment, &next)?;
+        total += amount;
+    }
+    Ok((commitment, total))
+}
+
+#[derive(Clone, Debug)]
+pub struct PayoutSlot<F: PrimeField> {
+    pub recipient: Option<F>,
+    pub amount: Option<F>,
+}
+
+impl<F: PrimeField> Default for PayoutSlot<F> {
+    fn default() -> Self {
+        Self {
+            recipient: None,
+            amount: None,
+        }
+    }
+}
+
+impl<F: PrimeField> PayoutSlot<F> {
+    pub fn new(recipient: F, amount: F) -> Self {
+        Self {
+            recipient: Some(recipient),
+            amount: Some(amount),
+        }
+    }
+
+    /// Unused slot for splits with fewer than `M` payouts.
+    pub fn empty() -> Self {
+        Self::new(F::zero(), F::zero())
+    }
+}
+
+/// Variant of `MultiWithdrawCircuit` for payroll-style redemptions: the `N` leaves are proven
+/// exactly as in a multi withdraw, but the proven amount is split between up to `M` addresses.
+/// Instead of paying out to the recipient, the circuit exposes `payout_commitment` over the
+/// `(recipient, amount)` pairs. The verifier only mints `withdraw_value` minus what was already
+/// teleported to the recipient, so the amounts plus the relayer `fee` must sum to that delta:
+/// `teleported_before` is public and checked on-chain against `totalTeleported`.
+///
+/// Public inputs follow `MultiWithdrawCircuit` with `teleported_before` and `payout_commitment`
+/// appended.
+#[derive(Clone)]
+pub struct PayoutWithdrawCircuit<
+    F: PrimeField + Absorb,
+    const DEPTH: usize,
+    const N: usize,
+    const M: usize,
+> {
+    pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
+
+    // ---- public inputs ----
+    pub merkle_root: Option<F>,
+    pub recipient: Option<F>,
+    pub withdraw_value: Option<F>,
+    pub relayer: Option<F>,
+    pub fee: Option<F>,
+    pub association_root: Option<F>,
+    pub teleported_before: Option<F>,
+    pub payout_commitment: Option<F>,
+
+    // ---- witness ----
+    pub delta: Option<F>,
+    pub leaves: [MultiWithdrawLeaf<F, DEPTH>; N],
+    pub payouts: [PayoutSlot<F>; M],
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize, const M: usize>
+    PayoutWithdrawCircuit<F, DEPTH, N, M>
+{
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
+        Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root: None,
+            recipient: None,
+            withdraw_value: None,
+            relayer: None,
+            fee: None,
+            association_root: None,
+            teleported_before: None,
+            payout_commitment: None,
+            delta: None,
+            leaves: [(); N].map(|_| MultiWithdrawLeaf::default()),
+            payouts: [(); M].map(|_| PayoutSlot::default()),
+        }
+    }
+
+    pub fn public_inputs(&self) -> Result<Vec<F>, SynthesisError> {
+        Ok(vec![
+            self.merkle_root.ok_or(SynthesisError::AssignmentMissing)?,
+            self.recipient.ok_or(SynthesisError::AssignmentMissing)?,
+            self.withdraw_value
+                .ok_or(SynthesisError::AssignmentMissing)?,
+            self.relayer.ok_or(SynthesisError::AssignmentMissing)?,
+            self.fee.ok_or(SynthesisError::AssignmentMissing)?,
+            self.association_root
+                .ok_or(SynthesisError::AssignmentMissing)?,
+            self.teleported_before
+                .ok_or(SynthesisError::AssignmentMissing)?,
+            self.payout_commitment
+                .ok_or(SynthesisError::AssignmentMissing)?,
+        ])
+    }
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize, const M: usize>
+    ConstraintSynthesizer<F> for PayoutWithdrawCircuit<F, DEPTH, N, M>
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root,
+            recipient,
+            withdraw_value,
+            relayer,
+            fee,
+            association_root,
+            teleported_before,
+            payout_commitment,
+            delta,
+            leaves,
+            payouts,
+        } = self;
+
+        let poseidon_params =
+            CircomCRHParametersVar::new_constant(ns!(cs, "poseidon_params"), &poseidon_params)?;
+
+        // ---- public inputs ----
+        let merkle_root = FpVar::<F>::new_input(ns!(cs, "merkle_root"), || {
+            merkle_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let recipient = FpVar::<F>::new_input(ns!(cs, "recipient"), || {
+            recipient.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let expected_withdraw_value = FpVar::<F>::new_input(ns!(cs, "withdraw_value"), || {
+            withdraw_value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let relayer = FpVar::<F>::new_input(ns!(cs, "relayer"), || {
+            relayer.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let fee = FpVar::<F>::new_input(ns!(cs, "fee"), || {
+            fee.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let association_root = FpVar::<F>::new_input(ns!(cs, "association_root"), || {
+            association_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let teleported_before = FpVar::<F>::new_input(ns!(cs, "teleported_before"), || {
+            teleported_before.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let expected_payout_commitment =
+            FpVar::<F>::new_input(ns!(cs, "payout_commitment"), || {
+                payout_commitment.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+
+        // ---- witness ----
+        let delta = FpVar::<F>::new_witness(ns!(cs, "delta"), || {
+            delta.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let payout_vars = payouts
+            .into_iter()
+            .map(|payout| {
+                let recipient = FpVar::<F>::new_witness(ns!(cs, "payout_recipient"), || {
+                    payout.recipient.ok_or(SynthesisError::AssignmentMissing)
+                })?;
+                let amount = FpVar::<F>::new_witness(ns!(cs, "payout_amount"), || {
+                    payout.amount.ok_or(SynthesisError::AssignmentMissing)
+                })?;
+                Ok((recipient, amount))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        let total_value = sum_withdraw_leaves(
+            cs,
+            &poseidon_params,
+            pow_difficulty,
+            &merkle_root,
+            &recipient,
+            &association_root,
+            leaves,
+        )?;
+
+        enforce_bit_length(&delta, BYTES31_BIT_LENGTH)?;
+        let computed_withdraw_value = total_value - delta;
+        enforce_bit_length(&computed_withdraw_value, BYTES31_BIT_LENGTH)?;
+
+        expected_withdraw_value.enforce_equal(&computed_withdraw_value)?;
+        enforce_relayer_fee(&relayer, &fee, &expected_withdraw_value)?;
+
+        let (computed_payout_commitment, payout_total) =
+            payout_commitment_var(&poseidon_params, &payout_vars)?;
+        expected_payout_commitment.enforce_equal(&computed_payout_commitment)?;
+        // the split covers only what this withdraw mints on top of the earlier teleports
+        enforce_bit_length(&teleported_before, BYTES31_BIT_LENGTH)?;
+        (payout_total + fee + teleported_before).enforce_equal(&expected_withdraw_value)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PayoutSlot, PayoutWithdrawCircuit, payout_commitment};
+    use crate::circuits::burn_address::{
+        compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+    };
+    use crate::circuits::constants::POW_DIFFICULTY;
+    use crate::groth16::{multi_withdraw::MultiWithdrawLeaf, params::Groth16Params};
+    use crate::utils::{
+        convertion::{fr_to_address, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::incremental_merkle_tree::IncrementalMerkleTree,
+    };
+    use alloy::primitives::U256;
+    use ark_bn254::Fr;
+    use ark_ff::One;
+    use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::rand::{SeedableRng, rngs::StdRng};
+
+    const DEPTH: usize = 4;
+
+    fn build_circuit<const N: usize, const M: usize>(
+        values: &[u64],
+        teleported_before: u64,
+        payouts: &[(u64, u64)],
+    ) -> PayoutWithdrawCircuit<Fr, DEPTH, N, M> {
+        let recipient = Fr::from(777u64);
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let mut circuit =
+            PayoutWithdrawCircuit::<Fr, DEPTH, N, M>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        for (slot, value) in values.iter().enumerate() {
+            let seed = Fr::from(10 + slot as u64);
+            let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
+            let index = tree.insert(fr_to_address(address), U256::from(*value));
+            let siblings = tree.prove(index).siblings.try_into().unwrap();
+            circuit.leaves[slot] =
+                MultiWithdrawLeaf::new(u256_to_fr(U256::from(*value)), secret, index, siblings);
+        }
+        for slot in values.len()..N {
+            circuit.leaves[slot] = MultiWithdrawLeaf::dummy(8 + slot as u64);
+        }
+
+        let payouts = payouts
+            .iter()
+            .map(|(recipient, amount)| (Fr::from(*recipient), Fr::from(*amount)))
+            .collect::<Vec<_>>();
+        for slot in 0..M {
+            circuit.payouts[slot] = payouts
+                .get(slot)
+                .map_or_else(PayoutSlot::empty, |(recipient, amount)| {
+                    PayoutSlot::new(*recipient, *amount)
+                });
+        }
+
+        let total = values.iter().sum::<u64>();
+        circuit.merkle_root = Some(tree.get_root());
+        circuit.recipient = Some(recipient);
+        circuit.withdraw_value = Some(Fr::from(total));
+        circuit.relayer = Some(Fr::from(0xbeefu64));
+        circuit.fee = Some(Fr::from(5u64));
+        circuit.association_root = Some(Fr::from(0u64));
+        circuit.teleported_before = Some(Fr::from(teleported_before));
+        circuit.payout_commitment = Some(payout_commitment(&payouts));
+        circuit.delta = Some(Fr::from(0u64));
+        circuit
+    }
+
+    fn is_satisfied<const N: usize, const M: usize>(
+        circuit: PayoutWithdrawCircuit<Fr, DEPTH, N, M>,
+    ) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_payout_withdraw_circuit() {
+        let circuit = build_circuit::<2, 4>(&[100, 200], 0, &[(0xa1, 150), (0xa2, 145)]);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let setup_circuit =
+            PayoutWithdrawCircuit::<Fr, DEPTH, 2, 4>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        let params = Groth16Params::rand(&mut rng, setup_circuit).expect("setup");
+        let public_inputs = circuit.public_inputs().expect("public inputs");
+        params
+            .generate_proof(&mut rng, circuit, &public_inputs)
+            .expect("groth16 proof");
+    }
+
+    #[test]
+    fn payouts_must_sum_to_withdraw_value() {
+        assert!(is_satisfied(build_circuit::<2, 4>(
+            &[100, 200],
+            0,
+            &[(0xa1, 150), (0xa2, 145)]
+        )));
+        assert!(!is_satisfied(build_circuit::<2, 4>(
+            &[100, 200],
+            0,
+            &[(0xa1, 150), (0xa2, 146)]
+        )));
+    }
+
+    #[test]
+    fn payouts_cover_only_the_minted_delta() {
+        // 100 of the 300 were teleported earlier, so the split and fee share the other 200
+        assert!(is_satisfied(build_circuit::<2, 4>(
+            &[100, 200],
+            100,
+            &[(0xa1, 150), (0xa2, 45)]
+        )));
+        assert!(!is_satisfied(build_circuit::<2, 4>(
+            &[100, 200],
+            100,
+            &[(0xa1, 150), (0xa2, 145)]
+        )));
+    }
+
+    #[test]
+    fn payout_commitment_binds_split() {
+        let mut circuit = build_circuit::<2, 4>(&[100, 200], 0, &[(0xa1, 150), (0xa2, 145)]);
+        circuit.payout_commitment = Some(payout_commitment(&[
+            (Fr::from(0xa1u64), Fr::from(145u64)),
+            (Fr::from(0xa2u64), Fr::from(150u64)),
+        ]));
+        assert!(!is_satisfied(circuit));
+
+        // an empty slot cannot hide value from the commitment
+        let mut circuit = build_circuit::<2, 4>(&[100, 200], 0, &[(0xa1, 150), (0xa2, 144)]);
+        circuit.payouts[3] = PayoutSlot::new(Fr::from(0u64), Fr::one());
+        assert!(!is_satisfied(circuit));
+    }
+}
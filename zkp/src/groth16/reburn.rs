@@ -0,0 +1,275 @@
+use crate::{
+    circuits::{
+        burn_address::burn_address_var, constants::BYTES31_BIT_LENGTH,
+        withdraw::enforce_relayer_fee,
+    },
+    groth16::multi_withdraw::{MultiWithdrawLeaf, sum_withdraw_leaves},
+    utils::{poseidon::gadgets::CircomCRHParametersVar, tree::gadgets::merkle::enforce_bit_length},
+};
+use ark_crypto_primitives::sponge::{Absorb, poseidon::PoseidonConfig};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_relations::ns;
+
+/// Batch sizes for which `generate_circuit_artifacts` produces re-burn Groth16 artifacts.
+pub const REBURN_SIZES: [usize; 2] = [1, 4];
+
+/// Private re-burn (note refresh): consumes up to `N` burned leaves of `recipient`, exactly like
+/// `MultiWithdrawCircuit`, but the proven total is minted to `new_burn_address` instead of the
+/// recipient's public address. `new_burn_address` is shown to be a PoW-valid burn address of a
+/// private `(new_recipient, new_secret)`, so the refreshed funds land in a fresh leaf that only
+/// the new owner can withdraw and never sit in a public balance.
+///
+/// Public inputs follow `MultiWithdrawCircuit` with `new_burn_address` appended. The verifier
+/// still tracks the consumed total under `recipient`, which prevents the same leaves from being
+/// re-burned or withdrawn twice.
+#[derive(Clone)]
+pub struct ReburnCircuit<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> {
+    pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
+
+    // ---- public inputs ----
+    pub merkle_root: Option<F>,
+    pub recipient: Option<F>,
+    pub reburn_value: Option<F>,
+    pub relayer: Option<F>,
+    pub fee: Option<F>,
+    pub association_root: Option<F>,
+    pub new_burn_address: Option<F>,
+
+    // ---- witness ----
+    pub delta: Option<F>,
+    pub leaves: [MultiWithdrawLeaf<F, DEPTH>; N],
+    pub new_recipient: Option<F>,
+    pub new_secret: Option<F>,
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> ReburnCircuit<F, DEPTH, N> {
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
+        Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root: None,
+            recipient: None,
+            reburn_value: None,
+            relayer: None,
+            fee: None,
+            association_root: None,
+            new_burn_address: None,
+            delta: None,
+            leaves: [(); N].map(|_| MultiWithdrawLeaf::default()),
+            new_recipient: None,
+            new_secret: None,
+        }
+    }
+
+    pub fn public_inputs(&self) -> Result<Vec<F>, SynthesisError> {
+        Ok(vec![
+            self.merkle_root.ok_or(SynthesisError::AssignmentMissing)?,
+            self.recipient.ok_or(SynthesisError::AssignmentMissing)?,
+            self.reburn_value.ok_or(SynthesisError::AssignmentMissing)?,
+            self.relayer.ok_or(SynthesisError::AssignmentMissing)?,
+            self.fee.ok_or(SynthesisError::AssignmentMissing)?,
+            self.association_root
+                .ok_or(SynthesisError::AssignmentMissing)?,
+            self.new_burn_address
+                .ok_or(SynthesisError::AssignmentMissing)?,
+        ])
+    }
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const N: usize> ConstraintSynthesizer<F>
+    for ReburnCircuit<F, DEPTH, N>
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let Self {
+            poseidon_params,
+            pow_difficulty,
+            merkle_root,
+            recipient,
+            reburn_value,
+            relayer,
+            fee,
+            association_root,
+            new_burn_address,
+            delta,
+            leaves,
+            new_recipient,
+            new_secret,
+        } = self;
+
+        let poseidon_params =
+            CircomCRHParametersVar::new_constant(ns!(cs, "poseidon_params"), &poseidon_params)?;
+
+        // ---- public inputs ----
+        let merkle_root = FpVar::<F>::new_input(ns!(cs, "merkle_root"), || {
+            merkle_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let recipient = FpVar::<F>::new_input(ns!(cs, "recipient"), || {
+            recipient.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let expected_reburn_value = FpVar::<F>::new_input(ns!(cs, "reburn_value"), || {
+            reburn_value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let relayer = FpVar::<F>::new_input(ns!(cs, "relayer"), || {
+            relayer.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let fee = FpVar::<F>::new_input(ns!(cs, "fee"), || {
+            fee.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let association_root = FpVar::<F>::new_input(ns!(cs, "association_root"), || {
+            association_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let new_burn_address = FpVar::<F>::new_input(ns!(cs, "new_burn_address"), || {
+            new_burn_address.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        // ---- witness ----
+        let delta = FpVar::<F>::new_witness(ns!(cs, "delta"), || {
+            delta.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let new_recipient = FpVar::<F>::new_witness(ns!(cs, "new_recipient"), || {
+            new_recipient.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let new_secret = FpVar::<F>::new_witness(ns!(cs, "new_secret"), || {
+            new_secret.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let total_value = sum_withdraw_leaves(
+            cs,
+            &poseidon_params,
+            pow_difficulty,
+            &merkle_root,
+            &recipient,
+            &association_root,
+            leaves,
+        )?;
+
+        enforce_bit_length(&delta, BYTES31_BIT_LENGTH)?;
+        let computed_reburn_value = total_value - delta;
+        enforce_bit_length(&computed_reburn_value, BYTES31_BIT_LENGTH)?;
+
+        expected_reburn_value.enforce_equal(&computed_reburn_value)?;
+        enforce_relayer_fee(&relayer, &fee, &expected_reburn_value)?;
+
+        let computed_burn_address = burn_address_var(
+            &poseidon_params,
+            &new_recipient,
+            &new_secret,
+            &Boolean::constant(true),
+            pow_difficulty,
+        )?;
+        new_burn_address.enforce_equal(&computed_burn_address)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReburnCircuit;
+    use crate::circuits::burn_address::{
+        compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+    };
+    use crate::circuits::constants::POW_DIFFICULTY;
+    use crate::groth16::{multi_withdraw::MultiWithdrawLeaf, params::Groth16Params};
+    use crate::test_utils::truncate_to_160_bits;
+    use crate::utils::{
+        convertion::{fr_to_address, u256_to_fr},
+        poseidon::utils::{circom_poseidon_config, poseidon2},
+        tree::incremental_merkle_tree::IncrementalMerkleTree,
+    };
+    use alloy::primitives::U256;
+    use ark_bn254::Fr;
+    use ark_ff::One;
+    use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::rand::{SeedableRng, rngs::StdRng};
+
+    const DEPTH: usize = 4;
+
+    fn pow_secret(recipient: Fr, seed: u64) -> (Fr, Fr) {
+        let seed = Fr::from(seed);
+        let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+        let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+            .expect("nonce should satisfy PoW");
+        (secret, address)
+    }
+
+    fn build_circuit<const N: usize>(values: &[u64]) -> ReburnCircuit<Fr, DEPTH, N> {
+        let recipient = Fr::from(777u64);
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let mut leaves = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let (secret, address) = pow_secret(recipient, 10 + i as u64);
+            let index = tree.insert(fr_to_address(address), U256::from(*value));
+            leaves.push((index, secret, *value));
+        }
+
+        let mut circuit =
+            ReburnCircuit::<Fr, DEPTH, N>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        for (slot, (index, secret, value)) in leaves.iter().enumerate() {
+            let siblings = tree.prove(*index).siblings.try_into().unwrap();
+            circuit.leaves[slot] =
+                MultiWithdrawLeaf::new(u256_to_fr(U256::from(*value)), *secret, *index, siblings);
+        }
+        for slot in leaves.len()..N {
+            circuit.leaves[slot] = MultiWithdrawLeaf::dummy(8 + slot as u64);
+        }
+
+        let new_recipient = Fr::from(888u64);
+        let (new_secret, new_burn_address) = pow_secret(new_recipient, 99);
+        let total = leaves.iter().map(|(_, _, value)| value).sum::<u64>();
+        circuit.merkle_root = Some(tree.get_root());
+        circuit.recipient = Some(recipient);
+        circuit.reburn_value = Some(Fr::from(total));
+        circuit.relayer = Some(Fr::from(0xbeefu64));
+        circuit.fee = Some(Fr::from(5u64));
+        circuit.association_root = Some(Fr::from(0u64));
+        circuit.new_burn_address = Some(new_burn_address);
+        circuit.delta = Some(Fr::from(0u64));
+        circuit.new_recipient = Some(new_recipient);
+        circuit.new_secret = Some(new_secret);
+        circuit
+    }
+
+    #[test]
+    fn test_reburn_circuit() {
+        let circuit = build_circuit::<4>(&[100, 200, 300]);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let setup_circuit =
+            ReburnCircuit::<Fr, DEPTH, 4>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        let params = Groth16Params::rand(&mut rng, setup_circuit).expect("setup");
+        let public_inputs = circuit.public_inputs().expect("public inputs");
+        assert_eq!(public_inputs[2], Fr::from(600u64));
+        params
+            .generate_proof(&mut rng, circuit, &public_inputs)
+            .expect("groth16 proof");
+    }
+
+    #[test]
+    fn reburn_binds_new_burn_address() {
+        let mut circuit = build_circuit::<2>(&[100, 200]);
+        circuit.new_burn_address = circuit.new_burn_address.map(|address| address + Fr::one());
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        // the refreshed address must carry the same PoW as any other burn address
+        let mut circuit = build_circuit::<2>(&[100, 200]);
+        let new_recipient = circuit.new_recipient.unwrap();
+        let weak_secret = (1u64..)
+            .map(|offset| circuit.new_secret.unwrap() + Fr::from(offset))
+            .find(|secret| {
+                compute_burn_address_from_secret(new_recipient, *secret, POW_DIFFICULTY).is_err()
+            })
+            .unwrap();
+        circuit.new_secret = Some(weak_secret);
+        circuit.new_burn_address =
+            Some(truncate_to_160_bits(poseidon2(new_recipient, weak_secret)));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    circuits::withdraw::single_withdraw, utils::poseidon::gadgets::CircomCRHParametersVar,
+    circuits::withdraw::{enforce_relayer_fee, single_withdraw},
+    utils::poseidon::gadgets::CircomCRHParametersVar,
 };
 use ark_crypto_primitives::sponge::{Absorb, poseidon::PoseidonConfig};
 use ark_ff::PrimeField;
@@ -10,11 +11,16 @@ use ark_relations::ns;
 #[derive(Clone)]
 pub struct SingleWithdrawCircuit<F: PrimeField + Absorb, const DEPTH: usize> {
     pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
 
     // ---- public inputs ----
     pub merkle_root: Option<F>,
     pub recipient: Option<F>,
     pub withdraw_value: Option<F>,
+    pub relayer: Option<F>,
+    pub fee: Option<F>,
+    /// Zero withdraws without an association set.
+    pub association_root: Option<F>,
 
     // ---- witness ----
     pub value: Option<F>,
@@ -22,20 +28,26 @@ pub struct SingleWithdrawCircuit<F: PrimeField + Absorb, const DEPTH: usize> {
     pub secret: Option<F>,
     pub leaf_index: Option<u64>,
     pub siblings: [Option<F>; DEPTH],
+    pub association_siblings: [Option<F>; DEPTH],
 }
 
 impl<F: PrimeField + Absorb, const DEPTH: usize> SingleWithdrawCircuit<F, DEPTH> {
-    pub fn new(poseidon_params: PoseidonConfig<F>) -> Self {
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
         Self {
             poseidon_params,
+            pow_difficulty,
             merkle_root: None,
             recipient: None,
             withdraw_value: None,
+            relayer: None,
+            fee: None,
+            association_root: None,
             value: None,
             delta: None,
             secret: None,
             leaf_index: None,
             siblings: [(); DEPTH].map(|_| None),
+            association_siblings: [(); DEPTH].map(|_| None),
         }
     }
 
@@ -45,6 +57,10 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> SingleWithdrawCircuit<F, DEPTH>
             self.recipient.ok_or(SynthesisError::AssignmentMissing)?,
             self.withdraw_value
                 .ok_or(SynthesisError::AssignmentMissing)?,
+            self.relayer.ok_or(SynthesisError::AssignmentMissing)?,
+            self.fee.ok_or(SynthesisError::AssignmentMissing)?,
+            self.association_root
+                .ok_or(SynthesisError::AssignmentMissing)?,
         ])
     }
 }
@@ -55,14 +71,19 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> ConstraintSynthesizer<F>
     fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
         let Self {
             poseidon_params,
+            pow_difficulty,
             merkle_root,
             recipient,
             withdraw_value,
+            relayer,
+            fee,
+            association_root,
             value,
             delta,
             secret,
             leaf_index,
             siblings,
+            association_siblings,
         } = self;
 
         let poseidon_params =
@@ -78,6 +99,15 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> ConstraintSynthesizer<F>
         let expected_withdraw_value = FpVar::<F>::new_input(ns!(cs, "withdraw_value"), || {
             withdraw_value.ok_or(SynthesisError::AssignmentMissing)
         })?;
+        let relayer = FpVar::<F>::new_input(ns!(cs, "relayer"), || {
+            relayer.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let fee = FpVar::<F>::new_input(ns!(cs, "fee"), || {
+            fee.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let association_root = FpVar::<F>::new_input(ns!(cs, "association_root"), || {
+            association_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
 
         // ---- witness ----
         let value = FpVar::<F>::new_witness(ns!(cs, "value"), || {
@@ -102,19 +132,31 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> ConstraintSynthesizer<F>
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
+        let association_sibling_vars = association_siblings
+            .into_iter()
+            .map(|sibling| {
+                FpVar::<F>::new_witness(ns!(cs, "association_sibling"), || {
+                    sibling.ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         let computed_withdraw_value = single_withdraw::<F, DEPTH>(
             &poseidon_params,
+            pow_difficulty,
             &merkle_root,
             &recipient,
+            &association_root,
             &value,
             &delta,
             &secret,
             &leaf_index,
             sibling_vars.as_slice(),
+            association_sibling_vars.as_slice(),
         )?;
 
         expected_withdraw_value.enforce_equal(&computed_withdraw_value)?;
+        enforce_relayer_fee(&relayer, &fee, &expected_withdraw_value)?;
 
         Ok(())
     }
@@ -126,6 +168,7 @@ mod tests {
     use crate::circuits::burn_address::{
         compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
     };
+    use crate::circuits::constants::POW_DIFFICULTY;
     use crate::groth16::params::Groth16Params;
     use crate::test_utils::merkle_root_from_path;
     use crate::utils::poseidon::utils::circom_poseidon_config;
@@ -143,10 +186,11 @@ mod tests {
 
         let recipient_value = Fr::from(321u64);
         let secret_seed = Fr::from(654u64);
-        let nonce = find_pow_nonce(recipient_value, secret_seed);
+        let nonce = find_pow_nonce(recipient_value, secret_seed, POW_DIFFICULTY);
         let secret_value = secret_from_nonce(secret_seed, nonce);
-        let address_value = compute_burn_address_from_secret(recipient_value, secret_value)
-            .expect("nonce should satisfy PoW");
+        let address_value =
+            compute_burn_address_from_secret(recipient_value, secret_value, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
         let value_value = Fr::from(100u64);
         let delta_value = Fr::from(25u64);
         let withdraw_value_value = value_value - delta_value;
@@ -165,17 +209,29 @@ mod tests {
             leaf_value,
             &siblings_values,
         );
+        let association_siblings_values: [Fr; DEPTH] = [9u64, 10, 11, 12].map(Fr::from);
+        let association_root_value = merkle_root_from_path(
+            &poseidon_config,
+            leaf_index_value,
+            leaf_value,
+            &association_siblings_values,
+        );
 
         let circuit = SingleWithdrawCircuit::<Fr, DEPTH> {
             poseidon_params: poseidon_config.clone(),
+            pow_difficulty: POW_DIFFICULTY,
             merkle_root: Some(merkle_root_value),
             recipient: Some(recipient_value),
             withdraw_value: Some(withdraw_value_value),
+            relayer: Some(Fr::from(0xbeefu64)),
+            fee: Some(Fr::from(5u64)),
+            association_root: Some(association_root_value),
             value: Some(value_value),
             delta: Some(delta_value),
             secret: Some(secret_value),
             leaf_index: Some(leaf_index_value),
             siblings: siblings_values.map(Some),
+            association_siblings: association_siblings_values.map(Some),
         };
 
         let mut rng = StdRng::seed_from_u64(42);
@@ -1,6 +1,9 @@
+pub mod artifacts;
 pub mod circuits;
 pub mod groth16;
 pub mod nova;
+pub mod shape;
 #[cfg(test)]
 pub mod test_utils;
 pub mod utils;
+pub mod witness;
@@ -1,3 +1,8 @@
 pub const AGGREGATION_TREE_HEIGHT: usize = 6;
 pub const TRANSFER_TREE_HEIGHT: usize = 40;
+/// Smaller transfer trees supported by the root prover for dev and staging deployments.
+pub const DEV_TRANSFER_TREE_HEIGHT: usize = 16;
+pub const STAGING_TRANSFER_TREE_HEIGHT: usize = 20;
 pub const GLOBAL_TRANSFER_TREE_HEIGHT: usize = TRANSFER_TREE_HEIGHT + AGGREGATION_TREE_HEIGHT;
+/// Transfers folded per `RootCircuit` step by the root prover and its deployed decider.
+pub const ROOT_TRANSFERS_PER_STEP: usize = 4;
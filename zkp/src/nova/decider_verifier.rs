@@ -0,0 +1,418 @@
+//! Native verification of the decider calldata produced by `DeciderParams::generate_decider_proof`
+//! (and returned by the decider prover), so callers can reject a bad proof before paying gas for
+//! `proveTransferRoot`/`teleport`.
+
+use std::io::Cursor;
+
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G1Projective as G1, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use folding_schemes::{Decider, frontend::FCircuit};
+
+use crate::nova::params::{D, DeciderParams, DeciderProof, DeciderVP, FParams, NovaError};
+
+const WORD: usize = 32;
+const G1_LEN: usize = 2 * WORD;
+const G2_LEN: usize = 4 * WORD;
+const GROTH16_PROOF_LEN: usize = 2 * G1_LEN + G2_LEN;
+
+/// Length of the decider calldata for a circuit with `state_len` state elements:
+/// `i, z_0, z_i, U_i.cmW, U_i.cmE, u_i.cmW, cmT, r, pA, pB, pC, challenge_W, challenge_E,
+/// eval_W, eval_E, W kzg_proof, E kzg_proof`.
+pub fn decider_calldata_len(state_len: usize) -> usize {
+    WORD * (1 + 2 * state_len) + 4 * G1_LEN + WORD + GROTH16_PROOF_LEN + 4 * WORD + 2 * G1_LEN
+}
+
+impl<C: FCircuit<Fr>> DeciderParams<C>
+where
+    FParams<C>: Clone,
+{
+    /// Verifies decider `calldata` against the expected initial and final IVC states.
+    pub fn verify_decider_proof(
+        &self,
+        z_0: &[Fr],
+        z_i: &[Fr],
+        calldata: &[u8],
+    ) -> Result<(), NovaError> {
+        verify_decider_calldata::<C>(&self.vp, z_0, z_i, calldata)
+    }
+}
+
+/// Deserializes a `*_decider_vp.bin` artifact written with `compress`.
+pub fn decider_vp_from_bytes<C: FCircuit<Fr>>(
+    vp_bytes: &[u8],
+    compress: Compress,
+) -> Result<DeciderVP<C>, NovaError>
+where
+    FParams<C>: Clone,
+{
+    DeciderVP::<C>::deserialize_with_mode(&mut Cursor::new(vp_bytes), compress, Validate::Yes)
+        .map_err(|e| NovaError::DeserializationError(format!("Decider Verifier Params: {}", e)))
+}
+
+/// Verifies decider `calldata` with the verifier params serialized in `vp_bytes`.
+pub fn verify_decider_proof<C: FCircuit<Fr>>(
+    vp_bytes: &[u8],
+    compress: Compress,
+    z_0: &[Fr],
+    z_i: &[Fr],
+    calldata: &[u8],
+) -> Result<(), NovaError>
+where
+    FParams<C>: Clone,
+{
+    // reject malformed calldata before paying for the verifier params
+    let decoded = DeciderCalldata::decode(z_0, z_i, calldata)?;
+    let vp = decider_vp_from_bytes::<C>(vp_bytes, compress)?;
+    decoded.verify::<C>(&vp, z_0, z_i)
+}
+
+/// Decodes `calldata`, checks that it carries `z_0`/`z_i`, and runs the decider verifier natively.
+pub fn verify_decider_calldata<C: FCircuit<Fr>>(
+    vp: &DeciderVP<C>,
+    z_0: &[Fr],
+    z_i: &[Fr],
+    calldata: &[u8],
+) -> Result<(), NovaError>
+where
+    FParams<C>: Clone,
+{
+    DeciderCalldata::decode(z_0, z_i, calldata)?.verify::<C>(vp, z_0, z_i)
+}
+
+struct DeciderCalldata {
+    i: Fr,
+    running_cm_w: G1Affine,
+    running_cm_e: G1Affine,
+    incoming_cm_w: G1Affine,
+    /// Canonical encoding of the decider proof:
+    /// `snark_proof, kzg_proofs[2] (eval, proof), cmT, r, kzg_challenges[2]`.
+    proof_bytes: Vec<u8>,
+}
+
+impl DeciderCalldata {
+    fn decode(z_0: &[Fr], z_i: &[Fr], calldata: &[u8]) -> Result<Self, NovaError> {
+        if z_0.len() != z_i.len() {
+            return Err(NovaError::InvalidDeciderCalldata(format!(
+                "z_0 has {} elements but z_i has {}",
+                z_0.len(),
+                z_i.len()
+            )));
+        }
+        let state_len = z_0.len();
+        let expected_len = decider_calldata_len(state_len);
+        if calldata.len() != expected_len {
+            return Err(NovaError::InvalidDeciderCalldata(format!(
+                "expected {expected_len} bytes for state length {state_len}, got {}",
+                calldata.len()
+            )));
+        }
+
+        let mut rd = CalldataReader { calldata, pos: 0 };
+        let i = rd.fr("i")?;
+        let proof_z_0 = (0..state_len)
+            .map(|_| rd.fr("z_0"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let proof_z_i = (0..state_len)
+            .map(|_| rd.fr("z_i"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if proof_z_0 != z_0 {
+            return Err(NovaError::InvalidDeciderCalldata(
+                "z_0 does not match the expected initial state".to_string(),
+            ));
+        }
+        if proof_z_i != z_i {
+            return Err(NovaError::InvalidDeciderCalldata(
+                "z_i does not match the expected final state".to_string(),
+            ));
+        }
+
+        let running_cm_w = rd.g1("U_i.cmW")?;
+        let running_cm_e = rd.g1("U_i.cmE")?;
+        let incoming_cm_w = rd.g1("u_i.cmW")?;
+        let cm_t = rd.g1("cmT")?;
+        let r = rd.fr("r")?;
+        let snark_a = rd.g1("pA")?;
+        let snark_b = rd.g2("pB")?;
+        let snark_c = rd.g1("pC")?;
+        let challenge_w = rd.fr("challenge_W")?;
+        let challenge_e = rd.fr("challenge_E")?;
+        let eval_w = rd.fr("eval_W")?;
+        let eval_e = rd.fr("eval_E")?;
+        let kzg_proof_w = rd.g1("W kzg_proof")?;
+        let kzg_proof_e = rd.g1("E kzg_proof")?;
+
+        // The decider proof only exposes accessors, so it is rebuilt from its canonical encoding.
+        let mut proof_bytes = Vec::new();
+        serialize_into(&mut proof_bytes, &snark_a)?;
+        serialize_into(&mut proof_bytes, &snark_b)?;
+        serialize_into(&mut proof_bytes, &snark_c)?;
+        serialize_into(&mut proof_bytes, &eval_w)?;
+        serialize_into(&mut proof_bytes, &kzg_proof_w)?;
+        serialize_into(&mut proof_bytes, &eval_e)?;
+        serialize_into(&mut proof_bytes, &kzg_proof_e)?;
+        serialize_into(&mut proof_bytes, &cm_t)?;
+        serialize_into(&mut proof_bytes, &r)?;
+        serialize_into(&mut proof_bytes, &challenge_w)?;
+        serialize_into(&mut proof_bytes, &challenge_e)?;
+
+        Ok(Self {
+            i,
+            running_cm_w,
+            running_cm_e,
+            incoming_cm_w,
+            proof_bytes,
+        })
+    }
+
+    fn verify<C: FCircuit<Fr>>(
+        &self,
+        vp: &DeciderVP<C>,
+        z_0: &[Fr],
+        z_i: &[Fr],
+    ) -> Result<(), NovaError>
+    where
+        FParams<C>: Clone,
+    {
+        let proof =
+            DeciderProof::<C>::deserialize_uncompressed(&mut Cursor::new(&self.proof_bytes))
+                .map_err(|e| NovaError::DeserializationError(format!("Decider Proof: {}", e)))?;
+
+        // incoming instances always carry a zero error commitment
+        let running_commitments: Vec<G1> = vec![self.running_cm_w.into(), self.running_cm_e.into()];
+        let incoming_commitments: Vec<G1> = vec![self.incoming_cm_w.into(), G1::zero()];
+        let verified = D::<C>::verify(
+            vp.clone(),
+            self.i,
+            z_0.to_vec(),
+            z_i.to_vec(),
+            &running_commitments,
+            &incoming_commitments,
+            &proof,
+        )
+        .map_err(|e| NovaError::DeciderVerificationError(format!("Decider Verification: {}", e)))?;
+        if !verified {
+            return Err(NovaError::DeciderVerificationError(
+                "Decider Proof Verification Failed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn serialize_into<T: CanonicalSerialize>(bytes: &mut Vec<u8>, value: &T) -> Result<(), NovaError> {
+    value
+        .serialize_uncompressed(bytes)
+        .map_err(|e| NovaError::SerializationError(format!("Decider Proof: {}", e)))
+}
+
+/// Reads the big-endian words emitted by `ToEth`.
+struct CalldataReader<'a> {
+    calldata: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CalldataReader<'a> {
+    fn word(&mut self) -> &'a [u8] {
+        let word = &self.calldata[self.pos..self.pos + WORD];
+        self.pos += WORD;
+        word
+    }
+
+    fn field<P: PrimeField>(&mut self, name: &str) -> Result<P, NovaError> {
+        let word = self.word();
+        let value = P::from_be_bytes_mod_order(word);
+        if value.into_bigint().to_bytes_be() != word {
+            return Err(NovaError::InvalidDeciderCalldata(format!(
+                "{name} is not a canonical field element"
+            )));
+        }
+        Ok(value)
+    }
+
+    fn fr(&mut self, name: &str) -> Result<Fr, NovaError> {
+        self.field::<Fr>(name)
+    }
+
+    fn g1(&mut self, name: &str) -> Result<G1Affine, NovaError> {
+        let x = self.field::<Fq>(name)?;
+        let y = self.field::<Fq>(name)?;
+        // the EVM encodes the point at infinity as (0, 0)
+        if x.is_zero() && y.is_zero() {
+            return Ok(<G1Affine as AffineRepr>::zero());
+        }
+        let point = G1Affine::new_unchecked(x, y);
+        if !point.is_on_curve() {
+            return Err(NovaError::InvalidDeciderCalldata(format!(
+                "{name} is not on the BN254 G1 curve"
+            )));
+        }
+        Ok(point)
+    }
+
+    fn g2(&mut self, name: &str) -> Result<G2Affine, NovaError> {
+        // `ToEth` writes the Fq2 coordinates as (c1, c0)
+        let x_c1 = self.field::<Fq>(name)?;
+        let x_c0 = self.field::<Fq>(name)?;
+        let y_c1 = self.field::<Fq>(name)?;
+        let y_c0 = self.field::<Fq>(name)?;
+        let (x, y) = (Fq2::new(x_c0, x_c1), Fq2::new(y_c0, y_c1));
+        if x.is_zero() && y.is_zero() {
+            return Ok(<G2Affine as AffineRepr>::zero());
+        }
+        let point = G2Affine::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(NovaError::InvalidDeciderCalldata(format!(
+                "{name} is not a valid BN254 G2 point"
+            )));
+        }
+        Ok(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nova::{
+            params::NovaParams,
+            root_nova::{RootCircuit, RootExternalInputs},
+        },
+        utils::{
+            convertion::{address_to_fr, u256_to_fr},
+            poseidon::utils::circom_poseidon_config,
+            tree::incremental_merkle_tree::IncrementalMerkleTree,
+        },
+    };
+    use alloy::primitives::{Address, U256};
+    use ark_ff::{One, Zero};
+    use folding_schemes::FoldingScheme;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn decode_err(z_0: &[Fr], z_i: &[Fr], calldata: &[u8]) -> NovaError {
+        match DeciderCalldata::decode(z_0, z_i, calldata) {
+            Ok(_) => panic!("calldata should be rejected"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn calldata_len_matches_solidity_layout() {
+        // i, z_0[3], z_i[3], 3 commitments, cmT, r, pA, pB, pC, challenges[2], evals[2],
+        // kzg proofs[2]
+        assert_eq!(
+            decider_calldata_len(3),
+            32 * 7 + 64 * 4 + 32 + 256 + 64 + 64 + 128
+        );
+    }
+
+    #[test]
+    fn decode_rejects_mismatched_state_and_length() {
+        let state = vec![Fr::zero(); 3];
+        let err = decode_err(&state, &state[..2], &[]);
+        assert!(matches!(err, NovaError::InvalidDeciderCalldata(_)));
+
+        let err = decode_err(&state, &state, &[0u8; 10]);
+        assert!(matches!(err, NovaError::InvalidDeciderCalldata(_)));
+    }
+
+    #[test]
+    fn decode_rejects_calldata_for_another_statement() {
+        let z_0 = vec![Fr::zero(); 3];
+        let mut z_i = z_0.clone();
+        z_i[0] = Fr::one();
+
+        // all-zero calldata claims z_i == z_0
+        let calldata = vec![0u8; decider_calldata_len(3)];
+        assert!(DeciderCalldata::decode(&z_0, &z_0, &calldata).is_ok());
+        let err = decode_err(&z_0, &z_i, &calldata);
+        assert!(matches!(err, NovaError::InvalidDeciderCalldata(_)));
+    }
+
+    #[test]
+    fn decode_rejects_non_canonical_and_off_curve_words() {
+        let state = vec![Fr::zero(); 3];
+        let mut calldata = vec![0u8; decider_calldata_len(3)];
+        calldata[..WORD].fill(0xff);
+        let err = decode_err(&state, &state, &calldata);
+        assert!(matches!(err, NovaError::InvalidDeciderCalldata(_)));
+
+        let mut calldata = vec![0u8; decider_calldata_len(3)];
+        // U_i.cmW = (0, 1) is not on the curve
+        calldata[7 * WORD + G1_LEN - 1] = 1;
+        let err = decode_err(&state, &state, &calldata);
+        assert!(matches!(err, NovaError::InvalidDeciderCalldata(_)));
+    }
+
+    #[test]
+    fn generated_calldata_round_trips() {
+        const DEPTH: usize = 4;
+        let mut rng = StdRng::seed_from_u64(4);
+
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let z_0 = vec![
+            Fr::from(tree.index),
+            u256_to_fr(tree.hash_chain),
+            tree.get_root(),
+        ];
+        let nova_params =
+            NovaParams::<RootCircuit<Fr, DEPTH>>::rand(circom_poseidon_config(), &mut rng).unwrap();
+        let decider_params = DeciderParams::rand(&mut rng, &nova_params).unwrap();
+
+        let mut nova = nova_params.initial_nova(z_0.clone()).unwrap();
+        for i in 0..2u8 {
+            let address = Address::repeat_byte(i + 1);
+            let value = U256::from(100 + i as u64);
+            let siblings = tree.prove(tree.index).siblings.try_into().unwrap();
+            tree.insert(address, value);
+            let transfer = RootExternalInputs::<Fr, DEPTH> {
+                is_dummy: false,
+                address: address_to_fr(address),
+                value: u256_to_fr(value),
+                siblings,
+            };
+            nova.prove_step(&mut rng, transfer.into(), None).unwrap();
+        }
+        let z_i = nova.state();
+        assert_eq!(z_i[2], tree.get_root());
+
+        let calldata = decider_params.generate_decider_proof(nova).unwrap();
+        assert_eq!(calldata.len(), decider_calldata_len(z_0.len()));
+        verify_decider_calldata::<RootCircuit<Fr, DEPTH>>(
+            &decider_params.vp,
+            &z_0,
+            &z_i,
+            &calldata,
+        )
+        .expect("calldata from generate_decider_proof must verify");
+
+        // flip the lowest bit of `r`: the calldata still decodes but no longer verifies
+        let r_offset = WORD * (1 + 2 * z_0.len()) + 4 * G1_LEN;
+        let mut tampered = calldata.clone();
+        tampered[r_offset + WORD - 1] ^= 1;
+        let err = verify_decider_calldata::<RootCircuit<Fr, DEPTH>>(
+            &decider_params.vp,
+            &z_0,
+            &z_i,
+            &tampered,
+        )
+        .unwrap_err();
+        assert!(matches!(err, NovaError::DeciderVerificationError(_)));
+    }
+
+    #[test]
+    fn verify_rejects_garbage_vp_after_decoding() {
+        let state = vec![Fr::zero(); 3];
+        let calldata = vec![0u8; decider_calldata_len(3)];
+        let err = verify_decider_proof::<RootCircuit<Fr, 4>>(
+            &[],
+            Compress::No,
+            &state,
+            &state,
+            &calldata,
+        )
+        .unwrap_err();
+        assert!(matches!(err, NovaError::DeserializationError(_)));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod constants;
+pub mod decider_verifier;
 pub mod params;
 pub mod root_nova;
 pub mod withdraw_nova;
+pub mod withdraw_session;
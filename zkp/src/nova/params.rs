@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::Read;
 
 use ark_bn254::{Bn254, Fr, G1Projective as G1};
 use ark_groth16::Groth16;
@@ -37,6 +37,12 @@ pub enum NovaError {
 
     #[error("Decider Proof Error: {0}")]
     DeciderProofError(String),
+
+    #[error("Invalid Decider Calldata: {0}")]
+    InvalidDeciderCalldata(String),
+
+    #[error("Decider Verification Error: {0}")]
+    DeciderVerificationError(String),
 }
 
 pub type N<C> = Nova<G1, G2, C, KZG<'static, Bn254>, Pedersen<G2>, false>;
@@ -47,6 +53,7 @@ pub type NovaPP<C> = <N<C> as FoldingScheme<G1, G2, C>>::ProverParam;
 pub type NovaVP<C> = <N<C> as FoldingScheme<G1, G2, C>>::VerifierParam;
 pub type DeciderPP<C> = <D<C> as Decider<G1, G2, C, N<C>>>::ProverParam;
 pub type DeciderVP<C> = <D<C> as Decider<G1, G2, C, N<C>>>::VerifierParam;
+pub type DeciderProof<C> = <D<C> as Decider<G1, G2, C, N<C>>>::Proof;
 
 pub struct NovaParams<C: FCircuit<Fr>>
 where
@@ -85,23 +92,37 @@ where
         pp_bytes: Vec<u8>,
         vp_bytes: Vec<u8>,
     ) -> Result<Self, NovaError> {
-        let nova_pp = {
-            let mut cur = Cursor::new(&pp_bytes);
-            N::<C>::pp_deserialize_with_mode(
-                &mut cur,
-                Compress::No,
-                Validate::Yes,
-                f_params.clone(),
-            )
-            .map_err(|e| NovaError::DeserializationError(format!("Nova Prover Params: {}", e)))?
-        };
-        let nova_vp = {
-            let mut rd = Cursor::new(&vp_bytes);
-            N::<C>::vp_deserialize_with_mode(&mut rd, Compress::No, Validate::Yes, f_params.clone())
-                .map_err(|e| {
-                    NovaError::DeserializationError(format!("Nova Verifier Params: {}", e))
-                })?
-        };
+        Self::from_bytes_with_mode(f_params, pp_bytes, vp_bytes, Compress::No)
+    }
+
+    pub fn from_bytes_with_mode(
+        f_params: FParams<C>,
+        pp_bytes: Vec<u8>,
+        vp_bytes: Vec<u8>,
+        compress: Compress,
+    ) -> Result<Self, NovaError> {
+        Self::from_readers(
+            f_params,
+            pp_bytes.as_slice(),
+            vp_bytes.as_slice(),
+            compress,
+            Validate::Yes,
+        )
+    }
+
+    /// Deserializes the params straight from `pp`/`vp` (e.g. memory-mapped artifacts) without
+    /// buffering them. Only pass `Validate::No` for bytes whose hash matched a trusted manifest.
+    pub fn from_readers(
+        f_params: FParams<C>,
+        pp: impl Read,
+        vp: impl Read,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, NovaError> {
+        let nova_pp = N::<C>::pp_deserialize_with_mode(pp, compress, validate, f_params.clone())
+            .map_err(|e| NovaError::DeserializationError(format!("Nova Prover Params: {}", e)))?;
+        let nova_vp = N::<C>::vp_deserialize_with_mode(vp, compress, validate, f_params.clone())
+            .map_err(|e| NovaError::DeserializationError(format!("Nova Verifier Params: {}", e)))?;
         Ok(Self {
             f_params,
             pp: nova_pp,
@@ -110,13 +131,17 @@ where
     }
 
     pub fn to_bytes(&self) -> Result<(Vec<u8>, Vec<u8>), NovaError> {
+        self.to_bytes_with_mode(Compress::No)
+    }
+
+    pub fn to_bytes_with_mode(&self, compress: Compress) -> Result<(Vec<u8>, Vec<u8>), NovaError> {
         let mut pp_bytes = Vec::new();
         self.pp
-            .serialize_with_mode(&mut pp_bytes, Compress::No)
+            .serialize_with_mode(&mut pp_bytes, compress)
             .map_err(|e| NovaError::SerializationError(format!("Nova Prover Params: {}", e)))?;
         let mut vp_bytes = Vec::new();
         self.vp
-            .serialize_with_mode(&mut vp_bytes, Compress::No)
+            .serialize_with_mode(&mut vp_bytes, compress)
             .map_err(|e| NovaError::SerializationError(format!("Nova Verifier Params: {}", e)))?;
         Ok((pp_bytes, vp_bytes))
     }
@@ -186,18 +211,37 @@ where
     }
 
     pub fn from_bytes(pp_bytes: Vec<u8>, vp_bytes: Vec<u8>) -> Result<Self, NovaError> {
-        let decider_pp = {
-            let mut rd = Cursor::new(&pp_bytes);
-            DeciderPP::<C>::deserialize_uncompressed(&mut rd).map_err(|e| {
+        Self::from_bytes_with_mode(pp_bytes, vp_bytes, Compress::No)
+    }
+
+    pub fn from_bytes_with_mode(
+        pp_bytes: Vec<u8>,
+        vp_bytes: Vec<u8>,
+        compress: Compress,
+    ) -> Result<Self, NovaError> {
+        Self::from_readers(
+            pp_bytes.as_slice(),
+            vp_bytes.as_slice(),
+            compress,
+            Validate::Yes,
+        )
+    }
+
+    /// Streaming counterpart of [`Self::from_bytes_with_mode`]; see [`NovaParams::from_readers`].
+    pub fn from_readers(
+        pp: impl Read,
+        vp: impl Read,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, NovaError> {
+        let decider_pp =
+            DeciderPP::<C>::deserialize_with_mode(pp, compress, validate).map_err(|e| {
                 NovaError::DeserializationError(format!("Decider Prover Params: {}", e))
-            })?
-        };
-        let decider_vp = {
-            let mut rd = Cursor::new(&vp_bytes);
-            DeciderVP::<C>::deserialize_uncompressed(&mut rd).map_err(|e| {
+            })?;
+        let decider_vp =
+            DeciderVP::<C>::deserialize_with_mode(vp, compress, validate).map_err(|e| {
                 NovaError::DeserializationError(format!("Decider Verifier Params: {}", e))
-            })?
-        };
+            })?;
         Ok(Self {
             pp: decider_pp,
             vp: decider_vp,
@@ -205,13 +249,17 @@ where
     }
 
     pub fn to_bytes(&self) -> Result<(Vec<u8>, Vec<u8>), NovaError> {
+        self.to_bytes_with_mode(Compress::No)
+    }
+
+    pub fn to_bytes_with_mode(&self, compress: Compress) -> Result<(Vec<u8>, Vec<u8>), NovaError> {
         let mut pp_bytes = Vec::new();
         self.pp
-            .serialize_with_mode(&mut pp_bytes, Compress::No)
+            .serialize_with_mode(&mut pp_bytes, compress)
             .map_err(|e| NovaError::SerializationError(format!("Decider Prover Params: {}", e)))?;
         let mut vp_bytes = Vec::new();
         self.vp
-            .serialize_with_mode(&mut vp_bytes, Compress::No)
+            .serialize_with_mode(&mut vp_bytes, compress)
             .map_err(|e| {
                 NovaError::SerializationError(format!("Decider Verifier Params: {}", e))
             })?;
@@ -219,8 +267,7 @@ where
     }
 
     pub fn verifier_solidity_code(&self, state_len: usize) -> String {
-        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((self.vp.clone(), state_len));
-        get_decider_template_for_cyclefold_decider(nova_cyclefold_vk)
+        decider_verifier_solidity_code::<C>(&self.vp, state_len)
     }
 
     pub fn generate_decider_proof(&self, nova: N<C>) -> Result<Vec<u8>, NovaError> {
@@ -269,3 +316,14 @@ where
         Ok(proof)
     }
 }
+
+/// Renders the Solidity decider verifier from the verifier parameters alone, so verifiers can be
+/// regenerated from a `*_decider_vp.bin` without the (much larger) prover parameters.
+pub fn decider_verifier_solidity_code<C>(vp: &DeciderVP<C>, state_len: usize) -> String
+where
+    C: FCircuit<Fr>,
+    FParams<C>: Clone,
+{
+    let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((vp.clone(), state_len));
+    get_decider_template_for_cyclefold_decider(nova_cyclefold_vk)
+}
@@ -16,20 +16,46 @@ use folding_schemes::{Error, frontend::FCircuit};
 
 const ROOT_STATE_LEN: usize = 3;
 
+/// Artifact file prefix for the root circuit over a `height`-level transfer tree. The production
+/// height keeps the historical `root` prefix.
+pub fn root_artifact_prefix(height: usize) -> String {
+    if height == TRANSFER_TREE_HEIGHT {
+        "root".to_string()
+    } else {
+        format!("root_h{height}")
+    }
+}
+
+/// Folds `K` sequential transfer insertions into a `DEPTH`-level transfer tree per Nova step.
+/// `K = 1` keeps the original one-transfer-per-fold circuit; larger `K` amortize the folding
+/// overhead on busy tokens. Smaller `DEPTH` are meant for dev/staging deployments.
 #[derive(Clone, Debug)]
-pub struct RootCircuit<F: PrimeField + Absorb> {
+pub struct RootCircuit<
+    F: PrimeField + Absorb,
+    const DEPTH: usize = TRANSFER_TREE_HEIGHT,
+    const K: usize = 1,
+> {
     pub poseidon_params: PoseidonConfig<F>,
 }
 
 #[derive(Clone, Debug)]
-pub struct RootExternalInputs<F: PrimeField> {
+pub struct RootExternalInputs<F: PrimeField, const DEPTH: usize = TRANSFER_TREE_HEIGHT> {
     pub is_dummy: bool,
     pub address: F,
     pub value: F,
-    pub siblings: [F; TRANSFER_TREE_HEIGHT],
+    pub siblings: [F; DEPTH],
 }
 
-impl<F: PrimeField> Default for RootExternalInputs<F> {
+impl<F: PrimeField, const DEPTH: usize> RootExternalInputs<F, DEPTH> {
+    pub fn dummy() -> Self {
+        Self {
+            is_dummy: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl<F: PrimeField, const DEPTH: usize> Default for RootExternalInputs<F, DEPTH> {
     fn default() -> Self {
         Self {
             is_dummy: false,
@@ -41,15 +67,17 @@ impl<F: PrimeField> Default for RootExternalInputs<F> {
 }
 
 #[derive(Clone, Debug)]
-pub struct RootExternalInputsVar<F: PrimeField> {
+pub struct RootExternalInputsVar<F: PrimeField, const DEPTH: usize> {
     pub is_dummy: Boolean<F>,
     pub address: FpVar<F>,
     pub value: FpVar<F>,
-    pub siblings: [FpVar<F>; TRANSFER_TREE_HEIGHT],
+    pub siblings: [FpVar<F>; DEPTH],
 }
 
-impl<F: PrimeField> AllocVar<RootExternalInputs<F>, F> for RootExternalInputsVar<F> {
-    fn new_variable<T: Borrow<RootExternalInputs<F>>>(
+impl<F: PrimeField, const DEPTH: usize> AllocVar<RootExternalInputs<F, DEPTH>, F>
+    for RootExternalInputsVar<F, DEPTH>
+{
+    fn new_variable<T: Borrow<RootExternalInputs<F, DEPTH>>>(
         cs: impl Into<Namespace<F>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
@@ -61,11 +89,10 @@ impl<F: PrimeField> AllocVar<RootExternalInputs<F>, F> for RootExternalInputsVar
             let is_dummy = Boolean::new_variable(cs.clone(), || Ok(value.is_dummy), mode)?;
             let address = FpVar::<F>::new_variable(cs.clone(), || Ok(value.address), mode)?;
             let val = FpVar::<F>::new_variable(cs.clone(), || Ok(value.value), mode)?;
-            let siblings = <[FpVar<F>; TRANSFER_TREE_HEIGHT] as AllocVar<
-                [F; TRANSFER_TREE_HEIGHT],
-                F,
-            >>::new_variable(
-                cs.clone(), || Ok(value.siblings.clone()), mode
+            let siblings = <[FpVar<F>; DEPTH] as AllocVar<[F; DEPTH], F>>::new_variable(
+                cs.clone(),
+                || Ok(value.siblings.clone()),
+                mode,
             )?;
             Ok(Self {
                 is_dummy,
@@ -77,11 +104,86 @@ impl<F: PrimeField> AllocVar<RootExternalInputs<F>, F> for RootExternalInputsVar
     }
 }
 
-impl<F: PrimeField + Absorb> FCircuit<F> for RootCircuit<F> {
+/// External inputs for one `RootCircuit<F, DEPTH, K>` step. Partial batches pad the tail with
+/// [`RootExternalInputs::dummy`] entries, which leave the state untouched.
+#[derive(Clone, Debug)]
+pub struct RootStepInputs<F: PrimeField, const DEPTH: usize, const K: usize> {
+    pub transfers: [RootExternalInputs<F, DEPTH>; K],
+}
+
+impl<F: PrimeField, const DEPTH: usize, const K: usize> Default for RootStepInputs<F, DEPTH, K> {
+    fn default() -> Self {
+        Self {
+            transfers: core::array::from_fn(|_| RootExternalInputs::default()),
+        }
+    }
+}
+
+impl<F: PrimeField, const DEPTH: usize> From<RootExternalInputs<F, DEPTH>>
+    for RootStepInputs<F, DEPTH, 1>
+{
+    fn from(transfer: RootExternalInputs<F, DEPTH>) -> Self {
+        Self {
+            transfers: [transfer],
+        }
+    }
+}
+
+impl<F: PrimeField, const DEPTH: usize, const K: usize> RootStepInputs<F, DEPTH, K> {
+    /// Packs up to `K` transfers into a step, padding the remainder with dummies.
+    pub fn padded(transfers: Vec<RootExternalInputs<F, DEPTH>>) -> Option<Self> {
+        if transfers.len() > K {
+            return None;
+        }
+        let mut transfers = transfers.into_iter();
+        Some(Self {
+            transfers: core::array::from_fn(|_| {
+                transfers.next().unwrap_or_else(RootExternalInputs::dummy)
+            }),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RootStepInputsVar<F: PrimeField, const DEPTH: usize, const K: usize> {
+    pub transfers: [RootExternalInputsVar<F, DEPTH>; K],
+}
+
+impl<F: PrimeField, const DEPTH: usize, const K: usize> AllocVar<RootStepInputs<F, DEPTH, K>, F>
+    for RootStepInputsVar<F, DEPTH, K>
+{
+    fn new_variable<T: Borrow<RootStepInputs<F, DEPTH, K>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        f().and_then(|value| {
+            let value = value.borrow();
+            let transfers = value
+                .transfers
+                .iter()
+                .map(|transfer| {
+                    RootExternalInputsVar::new_variable(cs.clone(), || Ok(transfer), mode)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Self {
+                transfers: transfers
+                    .try_into()
+                    .map_err(|_| SynthesisError::Unsatisfiable)?,
+            })
+        })
+    }
+}
+
+impl<F: PrimeField + Absorb, const DEPTH: usize, const K: usize> FCircuit<F>
+    for RootCircuit<F, DEPTH, K>
+{
     type Params = PoseidonConfig<F>;
-    // External inputs layout: [address, value, sibling_0, ..., sibling_{DEPTH-1}]
-    type ExternalInputs = RootExternalInputs<F>;
-    type ExternalInputsVar = RootExternalInputsVar<F>;
+    // External inputs layout: K x [is_dummy, address, value, sibling_0, ..., sibling_{DEPTH-1}]
+    type ExternalInputs = RootStepInputs<F, DEPTH, K>;
+    type ExternalInputsVar = RootStepInputsVar<F, DEPTH, K>;
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         Ok(Self {
@@ -98,33 +200,35 @@ impl<F: PrimeField + Absorb> FCircuit<F> for RootCircuit<F> {
         _cs: ConstraintSystemRef<F>,
         _i: usize,
         z_i: Vec<FpVar<F>>, // [index, hash_chain, merkle_root]
-        external_inputs: Self::ExternalInputsVar, // [address, value, siblings..]
+        external_inputs: Self::ExternalInputsVar, // K x [is_dummy, address, value, siblings..]
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        let [index, prev_hash_chain, prev_root]: [FpVar<F>; ROOT_STATE_LEN] = z_i
+        let [mut index, mut hash_chain, mut root]: [FpVar<F>; ROOT_STATE_LEN] = z_i
             .try_into()
             .map_err(|_| SynthesisError::AssignmentMissing)?;
-        let RootExternalInputsVar {
-            is_dummy,
-            address,
-            value,
-            siblings,
-        } = external_inputs;
-        let siblings: Vec<FpVar<F>> = siblings.into_iter().collect();
 
         let poseidon_params = CircomCRHParametersVar {
             parameters: self.poseidon_params.clone(),
         };
-        let (new_index, new_hash_chain, new_root) = root_transition_step::<F, TRANSFER_TREE_HEIGHT>(
-            &poseidon_params,
-            &index,
-            &prev_hash_chain,
-            &prev_root,
-            &address,
-            &value,
-            siblings.as_slice(),
-            &is_dummy,
-        )?;
-        Ok(vec![new_index, new_hash_chain, new_root])
+        for transfer in external_inputs.transfers {
+            let RootExternalInputsVar {
+                is_dummy,
+                address,
+                value,
+                siblings,
+            } = transfer;
+            let siblings: Vec<FpVar<F>> = siblings.into_iter().collect();
+            (index, hash_chain, root) = root_transition_step::<F, DEPTH>(
+                &poseidon_params,
+                &index,
+                &hash_chain,
+                &root,
+                &address,
+                &value,
+                siblings.as_slice(),
+                &is_dummy,
+            )?;
+        }
+        Ok(vec![index, hash_chain, root])
     }
 }
 
@@ -146,19 +250,13 @@ mod tests {
     use folding_schemes::FoldingScheme;
     use rand::{RngCore, SeedableRng, rngs::StdRng};
 
-    #[test]
-    fn test_root_circuit() {
-        let mut rng = StdRng::seed_from_u64(42);
-
-        let mut tree = IncrementalMerkleTree::new(TRANSFER_TREE_HEIGHT);
-        let z_0 = vec![
-            Fr::from(tree.index),
-            u256_to_fr(tree.hash_chain),
-            tree.get_root(),
-        ];
-
+    fn build_transfers<const DEPTH: usize>(
+        rng: &mut StdRng,
+        tree: &mut IncrementalMerkleTree,
+        count: usize,
+    ) -> Vec<RootExternalInputs<Fr, DEPTH>> {
         let mut external_inputs = vec![];
-        for i in 0..4 {
+        for i in 0..count {
             let address = Address::left_padding_from(&[i as u8]);
             let value = U256::from(rng.next_u64());
 
@@ -172,18 +270,36 @@ mod tests {
             let calculated_root = proof.get_root(leaf_hash, index);
             assert_eq!(calculated_root, tree.get_root());
 
-            let siblings: [Fr; TRANSFER_TREE_HEIGHT] = proof
+            let siblings: [Fr; DEPTH] = proof
                 .siblings
                 .clone()
                 .try_into()
                 .expect("sibling path length");
-            external_inputs.push(RootExternalInputs::<Fr> {
+            external_inputs.push(RootExternalInputs::<Fr, DEPTH> {
                 is_dummy: false,
                 address: address_to_fr(address),
                 value: u256_to_fr(value),
                 siblings,
             });
         }
+        external_inputs
+    }
+
+    fn initial_state(tree: &IncrementalMerkleTree) -> Vec<Fr> {
+        vec![
+            Fr::from(tree.index),
+            u256_to_fr(tree.hash_chain),
+            tree.get_root(),
+        ]
+    }
+
+    #[test]
+    fn test_root_circuit() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut tree = IncrementalMerkleTree::new(TRANSFER_TREE_HEIGHT);
+        let z_0 = initial_state(&tree);
+        let external_inputs = build_transfers::<TRANSFER_TREE_HEIGHT>(&mut rng, &mut tree, 4);
 
         let expected_index = tree.index;
         let expected_hash_chain = tree.hash_chain;
@@ -195,7 +311,7 @@ mod tests {
         let mut nova = nova_params.initial_nova(z_0.clone()).unwrap();
 
         for external_input in external_inputs.iter() {
-            nova.prove_step(&mut rng, external_input.clone(), None)
+            nova.prove_step(&mut rng, external_input.clone().into(), None)
                 .unwrap();
         }
 
@@ -207,4 +323,62 @@ mod tests {
         let ivc_proof = nova.ivc_proof();
         nova_params.verify(ivc_proof).unwrap();
     }
+
+    #[test]
+    fn test_root_circuit_multiple_transfers_per_step() {
+        const K: usize = 3;
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let mut tree = IncrementalMerkleTree::new(TRANSFER_TREE_HEIGHT);
+        let z_0 = initial_state(&tree);
+        // 3 + 2 (padded with a dummy) transfers, so the last fold is a partial batch
+        let external_inputs = build_transfers::<TRANSFER_TREE_HEIGHT>(&mut rng, &mut tree, 5);
+
+        let f_params = circom_poseidon_config::<Fr>();
+        let nova_params =
+            NovaParams::<RootCircuit<Fr, TRANSFER_TREE_HEIGHT, K>>::rand(f_params, &mut rng)
+                .unwrap();
+
+        let mut nova = nova_params.initial_nova(z_0).unwrap();
+        for chunk in external_inputs.chunks(K) {
+            let step = RootStepInputs::<Fr, TRANSFER_TREE_HEIGHT, K>::padded(chunk.to_vec())
+                .expect("chunk fits");
+            nova.prove_step(&mut rng, step, None).unwrap();
+        }
+
+        let state = nova.state();
+        assert_eq!(state[0], Fr::from(tree.index));
+        assert_eq!(state[1], u256_to_fr(tree.hash_chain));
+        assert_eq!(state[2], tree.get_root());
+
+        let ivc_proof = nova.ivc_proof();
+        nova_params.verify(ivc_proof).unwrap();
+    }
+
+    #[test]
+    fn test_root_circuit_small_depth() {
+        const DEPTH: usize = 16;
+        let mut rng = StdRng::seed_from_u64(16);
+
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let z_0 = initial_state(&tree);
+        let external_inputs = build_transfers::<DEPTH>(&mut rng, &mut tree, 2);
+
+        let f_params = circom_poseidon_config::<Fr>();
+        let nova_params = NovaParams::<RootCircuit<Fr, DEPTH>>::rand(f_params, &mut rng).unwrap();
+
+        let mut nova = nova_params.initial_nova(z_0).unwrap();
+        for external_input in external_inputs {
+            nova.prove_step(&mut rng, external_input.into(), None)
+                .unwrap();
+        }
+
+        let state = nova.state();
+        assert_eq!(state[0], Fr::from(tree.index));
+        assert_eq!(state[1], u256_to_fr(tree.hash_chain));
+        assert_eq!(state[2], tree.get_root());
+
+        let ivc_proof = nova.ivc_proof();
+        nova_params.verify(ivc_proof).unwrap();
+    }
 }
@@ -17,20 +17,47 @@ use crate::{
     utils::{convertion::u256_to_fr, poseidon::gadgets::CircomCRHParametersVar},
 };
 
-pub const WITHDRAW_STATE_LEN: usize = 4;
+/// [merkle_root, recipient, prev_leaf_index_with_offset, prev_total_value, relayer, fee,
+/// association_root]
+///
+/// `relayer`, `fee` and `association_root` are carried through every step unchanged so that they
+/// are bound to the proof through `z_0`/`z_i`; `Verifier.teleport` pays `fee` out of the minted
+/// amount and only accepts published association roots. A zero `association_root` withdraws
+/// without an association set.
+pub const WITHDRAW_STATE_LEN: usize = 7;
 
 #[derive(Clone, Debug)]
 pub struct WithdrawCircuit<F: PrimeField + Absorb, const DEPTH: usize> {
     pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
 }
 
+/// `FCircuit::Params` of [`WithdrawCircuit`]. The PoW difficulty is part of the circuit, so it
+/// must match the one the Nova params were generated with (see the artifact manifest).
 #[derive(Clone, Debug)]
+pub struct WithdrawCircuitParams<F: PrimeField> {
+    pub poseidon_params: PoseidonConfig<F>,
+    pub pow_difficulty: usize,
+}
+
+impl<F: PrimeField> WithdrawCircuitParams<F> {
+    pub fn new(poseidon_params: PoseidonConfig<F>, pow_difficulty: usize) -> Self {
+        Self {
+            poseidon_params,
+            pow_difficulty,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WithdrawExternalInputs<F: PrimeField, const DEPTH: usize> {
     pub is_dummy: F,
     pub value: F,
     pub secret: F,
     pub leaf_index: F,
     pub siblings: [F; DEPTH],
+    /// Path of the leaf in the association tree; ignored while `association_root` is zero.
+    pub association_siblings: [F; DEPTH],
 }
 
 impl<F: PrimeField, const DEPTH: usize> Default for WithdrawExternalInputs<F, DEPTH> {
@@ -41,6 +68,7 @@ impl<F: PrimeField, const DEPTH: usize> Default for WithdrawExternalInputs<F, DE
             secret: F::zero(),
             leaf_index: F::zero(),
             siblings: core::array::from_fn(|_| F::zero()),
+            association_siblings: core::array::from_fn(|_| F::zero()),
         }
     }
 }
@@ -52,6 +80,7 @@ pub struct WithdrawExternalInputsVar<F: PrimeField, const DEPTH: usize> {
     pub secret: FpVar<F>,
     pub leaf_index: FpVar<F>,
     pub siblings: [FpVar<F>; DEPTH],
+    pub association_siblings: [FpVar<F>; DEPTH],
 }
 
 impl<F: PrimeField, const DEPTH: usize> AllocVar<WithdrawExternalInputs<F, DEPTH>, F>
@@ -83,29 +112,37 @@ impl<F: PrimeField, const DEPTH: usize> AllocVar<WithdrawExternalInputs<F, DEPTH
             let secret = FpVar::<F>::new_variable(cs.clone(), || Ok(value.secret), mode)?;
             let leaf_index = FpVar::<F>::new_variable(cs.clone(), || Ok(value.leaf_index), mode)?;
             let siblings = <[FpVar<F>; DEPTH] as AllocVar<[F; DEPTH], F>>::new_variable(
-                cs,
+                cs.clone(),
                 || Ok(value.siblings.clone()),
                 mode,
             )?;
+            let association_siblings =
+                <[FpVar<F>; DEPTH] as AllocVar<[F; DEPTH], F>>::new_variable(
+                    cs,
+                    || Ok(value.association_siblings.clone()),
+                    mode,
+                )?;
             Ok(Self {
                 is_dummy,
                 value: val,
                 secret,
                 leaf_index,
                 siblings,
+                association_siblings,
             })
         })
     }
 }
 
 impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit<F, DEPTH> {
-    type Params = PoseidonConfig<F>;
+    type Params = WithdrawCircuitParams<F>;
     type ExternalInputs = WithdrawExternalInputs<F, DEPTH>;
     type ExternalInputsVar = WithdrawExternalInputsVar<F, DEPTH>;
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         Ok(Self {
-            poseidon_params: params,
+            poseidon_params: params.poseidon_params,
+            pow_difficulty: params.pow_difficulty,
         })
     }
 
@@ -117,7 +154,7 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit
         &self,
         _cs: ConstraintSystemRef<F>,
         _i: usize,
-        z_i: Vec<FpVar<F>>, // [merkle_root, recipient, prev_leaf_index_with_offset, prev_total_value]
+        z_i: Vec<FpVar<F>>, // [merkle_root, recipient, prev_leaf_index_with_offset, prev_total_value, relayer, fee, association_root]
         external_inputs: Self::ExternalInputsVar,
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
         let [
@@ -125,6 +162,9 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit
             recipient,
             prev_leaf_index_with_offset,
             prev_total_value,
+            relayer,
+            fee,
+            association_root,
         ]: [FpVar<F>; WITHDRAW_STATE_LEN] = z_i
             .try_into()
             .map_err(|_| SynthesisError::AssignmentMissing)?;
@@ -135,8 +175,8 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit
             secret,
             leaf_index,
             siblings,
+            association_siblings,
         } = external_inputs;
-        let siblings: Vec<FpVar<F>> = siblings.into_iter().collect();
 
         let poseidon_params = CircomCRHParametersVar {
             parameters: self.poseidon_params.clone(),
@@ -145,15 +185,18 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit
         let (out_root, out_recipient, out_leaf_index_with_offset, out_total) =
             withdraw_step::<F, DEPTH>(
                 &poseidon_params,
+                self.pow_difficulty,
                 &merkle_root,
                 &recipient,
                 &prev_leaf_index_with_offset,
                 &prev_total_value,
+                &association_root,
                 &is_dummy,
                 &value,
                 &secret,
                 &leaf_index,
-                siblings.as_slice(),
+                &siblings,
+                &association_siblings,
             )?;
 
         Ok(vec![
@@ -161,6 +204,9 @@ impl<F: PrimeField + Absorb, const DEPTH: usize> FCircuit<F> for WithdrawCircuit
             out_recipient,
             out_leaf_index_with_offset,
             out_total,
+            relayer,
+            fee,
+            association_root,
         ])
     }
 }
@@ -175,6 +221,7 @@ pub fn dummy_withdraw_ext_input<const DEPTH: usize>(
         secret: Fr::ZERO,
         leaf_index: Fr::from(index),
         siblings: core::array::from_fn(|_| Fr::ZERO),
+        association_siblings: core::array::from_fn(|_| Fr::ZERO),
     }
 }
 
@@ -182,14 +229,19 @@ pub fn dummy_withdraw_ext_input<const DEPTH: usize>(
 mod tests {
     use super::*;
     use crate::{
-        circuits::burn_address::{
-            compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce,
+        circuits::{
+            burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+            constants::POW_DIFFICULTY,
         },
         nova::params::NovaParams,
         utils::{
-            convertion::fr_to_address, general_recipient::GeneralRecipient,
+            convertion::fr_to_address,
+            general_recipient::{GeneralRecipient, RecipientFamily},
             poseidon::utils::circom_poseidon_config,
-            tree::incremental_merkle_tree::IncrementalMerkleTree,
+            tree::{
+                gadgets::leaf_hash::compute_leaf_hash,
+                incremental_merkle_tree::IncrementalMerkleTree, merkle_tree::MerkleTree,
+            },
         },
     };
     use alloy::primitives::{Address, B256, U256};
@@ -207,15 +259,16 @@ mod tests {
             chain_id: 1,
             address: B256::left_padding_from(&[42]),
             tweak: B256::ZERO,
+            family: RecipientFamily::Evm,
         }
         .to_fr();
         let secret_seeds = [123u64, 456, 789, 101112].map(Fr::from);
         let secrets_and_addresses = secret_seeds
             .iter()
             .map(|seed| {
-                let nonce = find_pow_nonce(recipient, *seed);
+                let nonce = find_pow_nonce(recipient, *seed, POW_DIFFICULTY);
                 let secret = secret_from_nonce(*seed, nonce);
-                let address = compute_burn_address_from_secret(recipient, secret)
+                let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
                     .expect("nonce should satisfy PoW");
                 (secret, address)
             })
@@ -245,7 +298,15 @@ mod tests {
         }
         let root = tree.get_root();
 
-        let z_0 = vec![root, recipient, Fr::ZERO, Fr::ZERO];
+        let z_0 = vec![
+            root,
+            recipient,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+        ];
 
         let mut external_inputs = vec![];
         for (i, value) in values.iter().enumerate() {
@@ -257,6 +318,7 @@ mod tests {
                 secret: secrets[i],
                 leaf_index: Fr::from(leaf_index),
                 siblings: proof.siblings.try_into().unwrap(),
+                association_siblings: [Fr::ZERO; DEPTH],
             };
             external_inputs.push(ext_input);
         }
@@ -266,7 +328,7 @@ mod tests {
             external_inputs.push(ext_input);
         }
 
-        let f_params = circom_poseidon_config::<Fr>();
+        let f_params = WithdrawCircuitParams::new(circom_poseidon_config::<Fr>(), POW_DIFFICULTY);
         let nova_params =
             NovaParams::<WithdrawCircuit<Fr, DEPTH>>::rand(f_params, &mut rng).unwrap();
 
@@ -278,4 +340,76 @@ mod tests {
         let ivc_proof = nova.ivc_proof();
         nova_params.verify(ivc_proof).unwrap();
     }
+
+    #[test]
+    fn test_withdraw_circuit_partial_amount() {
+        const DEPTH: usize = 4;
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let recipient = GeneralRecipient {
+            chain_id: 1,
+            address: B256::left_padding_from(&[7]),
+            tweak: B256::ZERO,
+            family: RecipientFamily::Evm,
+        }
+        .to_fr();
+        let values = [U256::from(1000u64), U256::from(2000u64)];
+        let delta = U256::from(1200u64);
+
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        // both burns are approved; the unrelated leaf at index 1 is left out of the set
+        let mut association_tree = MerkleTree::new(DEPTH);
+        let mut external_inputs = vec![];
+        let mut leaves = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let seed = Fr::from(100 + i as u64);
+            let nonce = find_pow_nonce(recipient, seed, POW_DIFFICULTY);
+            let secret = secret_from_nonce(seed, nonce);
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
+            let leaf_index = tree.insert(fr_to_address(address), *value);
+            association_tree
+                .update_leaf(leaf_index, compute_leaf_hash(address, u256_to_fr(*value)));
+            leaves.push((leaf_index, secret, *value));
+            tree.insert(Address::repeat_byte(0xaa), U256::from(1u64));
+        }
+        for (leaf_index, secret, value) in leaves {
+            let proof = tree.prove(leaf_index);
+            let association_proof = association_tree.prove(leaf_index);
+            external_inputs.push(WithdrawExternalInputs::<Fr, DEPTH> {
+                is_dummy: Fr::ZERO,
+                value: u256_to_fr(value),
+                secret,
+                leaf_index: Fr::from(leaf_index),
+                siblings: proof.siblings.try_into().unwrap(),
+                association_siblings: association_proof.siblings.try_into().unwrap(),
+            });
+        }
+        external_inputs.push(dummy_withdraw_ext_input(5, delta));
+
+        let f_params = WithdrawCircuitParams::new(circom_poseidon_config::<Fr>(), POW_DIFFICULTY);
+        let nova_params =
+            NovaParams::<WithdrawCircuit<Fr, DEPTH>>::rand(f_params, &mut rng).unwrap();
+
+        let relayer = Fr::from(0xbeefu64);
+        let fee = Fr::from(30u64);
+        let association_root = association_tree.get_root();
+        let z_0 = vec![
+            tree.get_root(),
+            recipient,
+            Fr::ZERO,
+            Fr::ZERO,
+            relayer,
+            fee,
+            association_root,
+        ];
+        let mut nova = nova_params.initial_nova(z_0).unwrap();
+        for external_input in external_inputs {
+            nova.prove_step(&mut rng, external_input, None).unwrap();
+        }
+        let state = nova.state();
+        assert_eq!(state[3], u256_to_fr(U256::from(1800u64)));
+        assert_eq!(state[4..], [relayer, fee, association_root]);
+        nova_params.verify(nova.ivc_proof()).unwrap();
+    }
 }
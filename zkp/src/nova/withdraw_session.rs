@@ -0,0 +1,380 @@
+//! Checkpointable batch withdraw proving. A `WithdrawSession` owns the planned steps of a batch
+//! withdraw together with the in-progress Nova instance, and can be serialized after any step so
+//! that an interrupted prover resumes where it stopped instead of folding every step again.
+//!
+//! A checkpoint contains the burn secrets of every planned step in plain form; store it with the
+//! same care as the secrets themselves.
+
+use ark_bn254::{Fr, G1Projective as G1};
+use ark_ff::{One as _, Zero as _};
+use ark_grumpkin::Projective as G2;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use folding_schemes::{FoldingScheme, folding::nova::IVCProof};
+use rand::RngCore;
+use thiserror::Error;
+
+use crate::{
+    nova::{
+        params::{N, NovaError, NovaParams},
+        withdraw_nova::{WithdrawCircuit, WithdrawExternalInputs},
+    },
+    witness::{WitnessError, validate_withdraw_steps},
+};
+
+/// Bumped whenever the checkpoint layout changes; older checkpoints are rejected rather than
+/// misread.
+pub const CHECKPOINT_VERSION: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error(transparent)]
+    Nova(#[from] NovaError),
+
+    #[error("invalid withdraw witness: {0}")]
+    Witness(#[from] WitnessError),
+
+    #[error("unsupported checkpoint version {found} (expected {expected})")]
+    UnsupportedVersion { expected: u32, found: u32 },
+
+    #[error("checkpoint was taken for a depth {found} withdraw circuit, expected depth {expected}")]
+    DepthMismatch { expected: usize, found: usize },
+
+    #[error("checkpoint claims {done} proven steps out of {total} planned steps")]
+    InvalidProgress { done: u64, total: usize },
+
+    #[error("checkpoint state does not match its planned steps after {done} steps")]
+    StateMismatch { done: usize },
+
+    #[error("all {total} planned steps are already proven")]
+    Complete { total: usize },
+
+    #[error("failed to prove step {step}: {message}")]
+    ProveStep { step: usize, message: String },
+}
+
+pub struct WithdrawSession<const DEPTH: usize> {
+    nova: N<WithdrawCircuit<Fr, DEPTH>>,
+    z_0: Vec<Fr>,
+    steps: Vec<WithdrawExternalInputs<Fr, DEPTH>>,
+    done: usize,
+}
+
+impl<const DEPTH: usize> WithdrawSession<DEPTH> {
+    /// Starts a session that will fold `steps` from `z_0`. The whole plan is validated up front so
+    /// that a bad witness fails before any proving work is spent on it.
+    pub fn new(
+        params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+        z_0: Vec<Fr>,
+        steps: Vec<WithdrawExternalInputs<Fr, DEPTH>>,
+    ) -> Result<Self, SessionError> {
+        validate_withdraw_steps(&z_0, &steps, params.f_params.pow_difficulty)?;
+        let nova = params.initial_nova(z_0.clone())?;
+        Ok(Self {
+            nova,
+            z_0,
+            steps,
+            done: 0,
+        })
+    }
+
+    /// Restores a session from bytes produced by [`WithdrawSession::checkpoint`]. The embedded IVC
+    /// proof is verified and its state is checked against the native replay of the proven steps,
+    /// so a corrupted or tampered checkpoint is rejected instead of producing an invalid proof.
+    pub fn resume(
+        params: &NovaParams<WithdrawCircuit<Fr, DEPTH>>,
+        checkpoint: &[u8],
+    ) -> Result<Self, SessionError> {
+        let mut reader = checkpoint;
+        let found = read_header(&mut reader)?;
+        if found != DEPTH {
+            return Err(SessionError::DepthMismatch {
+                expected: DEPTH,
+                found,
+            });
+        }
+        let done = u64::deserialize_uncompressed(&mut reader).map_err(deserialization_error)?;
+        let z_0 =
+            Vec::<Fr>::deserialize_uncompressed(&mut reader).map_err(deserialization_error)?;
+        let steps = Vec::<Vec<Fr>>::deserialize_uncompressed(&mut reader)
+            .map_err(deserialization_error)?
+            .into_iter()
+            .map(step_from_fields)
+            .collect::<Result<Vec<_>, _>>()?;
+        let done = usize::try_from(done)
+            .ok()
+            .filter(|&done| done <= steps.len())
+            .ok_or(SessionError::InvalidProgress {
+                done,
+                total: steps.len(),
+            })?;
+
+        let pow_difficulty = params.f_params.pow_difficulty;
+        let expected_state = validate_withdraw_steps(&z_0, &steps[..done], pow_difficulty)?;
+        validate_withdraw_steps(&z_0, &steps, pow_difficulty)?;
+
+        let nova = if done == 0 {
+            params.initial_nova(z_0.clone())?
+        } else {
+            let ivc_proof = IVCProof::<G1, G2>::deserialize_uncompressed(&mut reader)
+                .map_err(deserialization_error)?;
+            if ivc_proof.i != Fr::from(done as u64)
+                || ivc_proof.z_0 != z_0
+                || ivc_proof.z_i != expected_state
+            {
+                return Err(SessionError::StateMismatch { done });
+            }
+            params.verify(ivc_proof.clone())?;
+            params.nova_from_ivc_proof(ivc_proof)?
+        };
+
+        Ok(Self {
+            nova,
+            z_0,
+            steps,
+            done,
+        })
+    }
+
+    /// Serializes the plan and the current IVC proof. Taking a checkpoint does not consume the
+    /// session, so callers can persist one after every step.
+    pub fn checkpoint(&self) -> Result<Vec<u8>, SessionError> {
+        let mut bytes = Vec::new();
+        CHECKPOINT_VERSION
+            .serialize_uncompressed(&mut bytes)
+            .and_then(|_| (DEPTH as u32).serialize_uncompressed(&mut bytes))
+            .and_then(|_| (self.done as u64).serialize_uncompressed(&mut bytes))
+            .and_then(|_| self.z_0.serialize_uncompressed(&mut bytes))
+            .and_then(|_| {
+                self.steps
+                    .iter()
+                    .map(step_to_fields)
+                    .collect::<Vec<_>>()
+                    .serialize_uncompressed(&mut bytes)
+            })
+            .map_err(|e| NovaError::SerializationError(format!("Withdraw Checkpoint: {}", e)))?;
+        if self.done > 0 {
+            self.nova
+                .ivc_proof()
+                .serialize_uncompressed(&mut bytes)
+                .map_err(|e| {
+                    NovaError::SerializationError(format!("Withdraw Checkpoint IVC Proof: {}", e))
+                })?;
+        }
+        Ok(bytes)
+    }
+
+    /// Folds the next planned step.
+    pub fn prove_next_step(&mut self, rng: impl RngCore) -> Result<(), SessionError> {
+        let step = self.done;
+        let external_inputs = self
+            .steps
+            .get(step)
+            .cloned()
+            .ok_or(SessionError::Complete {
+                total: self.steps.len(),
+            })?;
+        self.nova
+            .prove_step(rng, external_inputs, None)
+            .map_err(|e| SessionError::ProveStep {
+                step,
+                message: e.to_string(),
+            })?;
+        self.done += 1;
+        Ok(())
+    }
+
+    pub fn steps_done(&self) -> usize {
+        self.done
+    }
+
+    pub fn total_steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.done == self.steps.len()
+    }
+
+    pub fn z_0(&self) -> &[Fr] {
+        &self.z_0
+    }
+
+    pub fn steps(&self) -> &[WithdrawExternalInputs<Fr, DEPTH>] {
+        &self.steps
+    }
+
+    pub fn state(&self) -> Vec<Fr> {
+        self.nova.state()
+    }
+
+    pub fn ivc_proof(&self) -> IVCProof<G1, G2> {
+        self.nova.ivc_proof()
+    }
+}
+
+/// Returns the tree depth a checkpoint was taken for, so callers holding params for several
+/// depths can pick the matching one before calling [`WithdrawSession::resume`].
+pub fn checkpoint_depth(checkpoint: &[u8]) -> Result<usize, SessionError> {
+    read_header(&mut &checkpoint[..])
+}
+
+fn read_header(reader: &mut &[u8]) -> Result<usize, SessionError> {
+    let version = u32::deserialize_uncompressed(&mut *reader).map_err(deserialization_error)?;
+    if version != CHECKPOINT_VERSION {
+        return Err(SessionError::UnsupportedVersion {
+            expected: CHECKPOINT_VERSION,
+            found: version,
+        });
+    }
+    let depth = u32::deserialize_uncompressed(&mut *reader).map_err(deserialization_error)?;
+    Ok(depth as usize)
+}
+
+fn step_to_fields<const DEPTH: usize>(step: &WithdrawExternalInputs<Fr, DEPTH>) -> Vec<Fr> {
+    let mut fields = vec![step.is_dummy, step.value, step.secret, step.leaf_index];
+    fields.extend_from_slice(&step.siblings);
+    fields.extend_from_slice(&step.association_siblings);
+    fields
+}
+
+fn step_from_fields<const DEPTH: usize>(
+    fields: Vec<Fr>,
+) -> Result<WithdrawExternalInputs<Fr, DEPTH>, SessionError> {
+    let [is_dummy, value, secret, leaf_index] = fields
+        .get(..4)
+        .and_then(|head| <[Fr; 4]>::try_from(head).ok())
+        .ok_or_else(|| malformed_step(fields.len()))?;
+    if fields.len() != 4 + 2 * DEPTH {
+        return Err(malformed_step(fields.len()));
+    }
+    let siblings =
+        <[Fr; DEPTH]>::try_from(&fields[4..4 + DEPTH]).map_err(|_| malformed_step(fields.len()))?;
+    let association_siblings =
+        <[Fr; DEPTH]>::try_from(&fields[4 + DEPTH..]).map_err(|_| malformed_step(fields.len()))?;
+    if !is_dummy.is_zero() && !is_dummy.is_one() {
+        return Err(NovaError::DeserializationError(
+            "Withdraw Checkpoint: is_dummy must be 0 or 1".to_string(),
+        )
+        .into());
+    }
+    Ok(WithdrawExternalInputs {
+        is_dummy,
+        value,
+        secret,
+        leaf_index,
+        siblings,
+        association_siblings,
+    })
+}
+
+fn malformed_step(len: usize) -> SessionError {
+    NovaError::DeserializationError(format!(
+        "Withdraw Checkpoint: step has {} field elements",
+        len
+    ))
+    .into()
+}
+
+fn deserialization_error(e: ark_serialize::SerializationError) -> SessionError {
+    NovaError::DeserializationError(format!("Withdraw Checkpoint: {}", e)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuits::{
+            burn_address::{compute_burn_address_from_secret, find_pow_nonce, secret_from_nonce},
+            constants::POW_DIFFICULTY,
+        },
+        nova::withdraw_nova::{WithdrawCircuitParams, dummy_withdraw_ext_input},
+        utils::{
+            convertion::{fr_to_address, u256_to_fr},
+            general_recipient::{GeneralRecipient, RecipientFamily},
+            poseidon::utils::circom_poseidon_config,
+            tree::incremental_merkle_tree::IncrementalMerkleTree,
+        },
+    };
+    use alloy::primitives::{B256, U256};
+    use ark_ff::AdditiveGroup;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn session_resumes_from_checkpoint() {
+        const DEPTH: usize = 4;
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let recipient = GeneralRecipient {
+            chain_id: 1,
+            address: B256::left_padding_from(&[11]),
+            tweak: B256::ZERO,
+            family: RecipientFamily::Evm,
+        }
+        .to_fr();
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let mut leaves = vec![];
+        for (i, value) in [1000u64, 2000].into_iter().enumerate() {
+            let seed = Fr::from(200 + i as u64);
+            let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+            let address = compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY)
+                .expect("nonce should satisfy PoW");
+            let leaf_index = tree.insert(fr_to_address(address), U256::from(value));
+            leaves.push((leaf_index, secret, U256::from(value)));
+        }
+        let mut steps = leaves
+            .into_iter()
+            .map(
+                |(leaf_index, secret, value)| WithdrawExternalInputs::<Fr, DEPTH> {
+                    is_dummy: Fr::ZERO,
+                    value: u256_to_fr(value),
+                    secret,
+                    leaf_index: Fr::from(leaf_index),
+                    siblings: tree.prove(leaf_index).siblings.try_into().unwrap(),
+                    association_siblings: [Fr::ZERO; DEPTH],
+                },
+            )
+            .collect::<Vec<_>>();
+        steps.push(dummy_withdraw_ext_input(5, U256::from(500u64)));
+        let z_0 = vec![
+            tree.get_root(),
+            recipient,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+            Fr::ZERO,
+        ];
+
+        let params = NovaParams::<WithdrawCircuit<Fr, DEPTH>>::rand(
+            WithdrawCircuitParams::new(circom_poseidon_config::<Fr>(), POW_DIFFICULTY),
+            &mut rng,
+        )
+        .unwrap();
+
+        let mut session = WithdrawSession::new(&params, z_0.clone(), steps.clone()).unwrap();
+        let fresh = WithdrawSession::resume(&params, &session.checkpoint().unwrap()).unwrap();
+        assert_eq!(fresh.steps_done(), 0);
+        assert_eq!(fresh.steps(), steps.as_slice());
+
+        session.prove_next_step(&mut rng).unwrap();
+        session.prove_next_step(&mut rng).unwrap();
+        let checkpoint = session.checkpoint().unwrap();
+        assert_eq!(checkpoint_depth(&checkpoint).unwrap(), DEPTH);
+        let mut resumed = WithdrawSession::resume(&params, &checkpoint).unwrap();
+        assert_eq!(resumed.steps_done(), 2);
+        assert_eq!(resumed.z_0(), z_0.as_slice());
+        resumed.prove_next_step(&mut rng).unwrap();
+        assert!(resumed.is_complete());
+        assert!(matches!(
+            resumed.prove_next_step(&mut rng),
+            Err(SessionError::Complete { total: 3 })
+        ));
+        assert_eq!(resumed.state()[3], u256_to_fr(U256::from(2500u64)));
+        params.verify(resumed.ivc_proof()).unwrap();
+
+        let mut tampered = checkpoint.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(WithdrawSession::resume(&params, &tampered).is_err());
+    }
+}
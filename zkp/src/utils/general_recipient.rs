@@ -1,9 +1,204 @@
+use std::{fmt, str::FromStr};
+
 use alloy::primitives::{Address, B256, U256};
 use ark_bn254::Fr;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest as _, Sha256};
+use thiserror::Error;
 
 use crate::utils::convertion::u256_to_fr;
 
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GeneralRecipientError {
+    #[error("unknown recipient version {0}")]
+    UnknownVersion(u8),
+    #[error("unknown recipient namespace `{0}`")]
+    UnknownNamespace(String),
+    #[error("recipient account must look like `<namespace>:<chain id>:<address>`, got `{0}`")]
+    MalformedAccount(String),
+    #[error("invalid chain id `{0}`")]
+    InvalidChainId(String),
+    #[error("invalid {family} address `{address}`")]
+    InvalidAddress {
+        family: RecipientFamily,
+        address: String,
+    },
+}
+
+/// Address family of a recipient. Each family has its own version byte, so the same 32 address
+/// bytes on different families never hash to the same recipient, and its own namespace for the
+/// string form of an account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecipientFamily {
+    /// 20-byte EVM accounts, left-padded to 32 bytes. Formatted as checksummed hex.
+    #[default]
+    Evm,
+    /// 32-byte Solana public keys. Formatted as base58.
+    Solana,
+    /// 32-byte Move (Aptos/Sui) account addresses. Formatted as 0x-prefixed hex.
+    Move,
+}
+
+impl RecipientFamily {
+    pub const ALL: [Self; 3] = [Self::Evm, Self::Solana, Self::Move];
+
+    pub const fn version(self) -> u8 {
+        match self {
+            Self::Evm => 1,
+            Self::Solana => 2,
+            Self::Move => 3,
+        }
+    }
+
+    pub const fn namespace(self) -> &'static str {
+        match self {
+            Self::Evm => "eip155",
+            Self::Solana => "solana",
+            Self::Move => "move",
+        }
+    }
+
+    pub fn from_version(version: u8) -> Result<Self, GeneralRecipientError> {
+        Self::ALL
+            .into_iter()
+            .find(|family| family.version() == version)
+            .ok_or(GeneralRecipientError::UnknownVersion(version))
+    }
+
+    pub fn from_namespace(namespace: &str) -> Result<Self, GeneralRecipientError> {
+        Self::ALL
+            .into_iter()
+            .find(|family| family.namespace() == namespace)
+            .ok_or_else(|| GeneralRecipientError::UnknownNamespace(namespace.to_string()))
+    }
+
+    pub fn format_address(self, address: &B256) -> String {
+        match self {
+            Self::Evm => Address::from_word(*address).to_string(),
+            Self::Solana => bs58::encode(address).into_string(),
+            Self::Move => address.to_string(),
+        }
+    }
+
+    pub fn parse_address(self, address: &str) -> Result<B256, GeneralRecipientError> {
+        let invalid = || GeneralRecipientError::InvalidAddress {
+            family: self,
+            address: address.to_string(),
+        };
+        match self {
+            Self::Evm => Address::from_str(address)
+                .map(Address::into_word)
+                .map_err(|_| invalid()),
+            Self::Solana => {
+                let bytes = bs58::decode(address).into_vec().map_err(|_| invalid())?;
+                <[u8; 32]>::try_from(bytes)
+                    .map(B256::from)
+                    .map_err(|_| invalid())
+            }
+            Self::Move => {
+                // Move tooling prints short addresses such as `0x1`; pad them to 32 bytes.
+                let digits = address.strip_prefix("0x").ok_or_else(invalid)?;
+                if digits.is_empty() || digits.len() > 64 {
+                    return Err(invalid());
+                }
+                let bytes = hex::decode(format!("{digits:0>64}")).map_err(|_| invalid())?;
+                Ok(B256::from_slice(&bytes))
+            }
+        }
+    }
+}
+
+impl fmt::Display for RecipientFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.namespace())
+    }
+}
+
+/// The account part of a `GeneralRecipient`, without the tweak. Its string form is
+/// `<namespace>:<chain id>:<address>`, e.g. `eip155:10:0x…` or `solana:900:<base58>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RecipientAccount {
+    pub family: RecipientFamily,
+    pub chain_id: u64,
+    pub address: B256,
+}
+
+impl RecipientAccount {
+    pub fn new_evm(chain_id: u64, address: Address) -> Self {
+        Self {
+            family: RecipientFamily::Evm,
+            chain_id,
+            address: address.into_word(),
+        }
+    }
+
+    pub fn new_solana(chain_id: u64, pubkey: [u8; 32]) -> Self {
+        Self {
+            family: RecipientFamily::Solana,
+            chain_id,
+            address: B256::from(pubkey),
+        }
+    }
+
+    pub fn new_move(chain_id: u64, address: B256) -> Self {
+        Self {
+            family: RecipientFamily::Move,
+            chain_id,
+            address,
+        }
+    }
+
+    /// The EVM address of the account, if it is an EVM account.
+    pub fn evm_address(&self) -> Option<Address> {
+        (self.family == RecipientFamily::Evm && self.address[..12].iter().all(|&b| b == 0))
+            .then(|| Address::from_word(self.address))
+    }
+
+    pub fn with_tweak(self, tweak: B256) -> GeneralRecipient {
+        GeneralRecipient {
+            chain_id: self.chain_id,
+            address: self.address,
+            tweak,
+            family: self.family,
+        }
+    }
+}
+
+impl fmt::Display for RecipientAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.family.namespace(),
+            self.chain_id,
+            self.family.format_address(&self.address)
+        )
+    }
+}
+
+impl FromStr for RecipientAccount {
+    type Err = GeneralRecipientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, ':');
+        let (Some(namespace), Some(chain_id), Some(address)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(GeneralRecipientError::MalformedAccount(s.to_string()));
+        };
+        let family = RecipientFamily::from_namespace(namespace)?;
+        let chain_id = chain_id
+            .parse()
+            .map_err(|_| GeneralRecipientError::InvalidChainId(chain_id.to_string()))?;
+        Ok(Self {
+            family,
+            chain_id,
+            address: family.parse_address(address)?,
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct GeneralRecipient {
     // The chain ID of the recipient
@@ -15,21 +210,36 @@ pub struct GeneralRecipient {
 
     // A tweak to change the recipient and prevent generating long withdraw proofs.
     pub tweak: B256,
+
+    // How `address` is interpreted on the recipient chain.
+    pub family: RecipientFamily,
 }
 
 impl GeneralRecipient {
     pub fn new_evm(chain_id: u64, address: Address, tweak: B256) -> Self {
-        Self {
-            chain_id,
-            address: address.into_word(),
-            tweak,
+        RecipientAccount::new_evm(chain_id, address).with_tweak(tweak)
+    }
+
+    pub fn new_solana(chain_id: u64, pubkey: [u8; 32], tweak: B256) -> Self {
+        RecipientAccount::new_solana(chain_id, pubkey).with_tweak(tweak)
+    }
+
+    pub fn new_move(chain_id: u64, address: B256, tweak: B256) -> Self {
+        RecipientAccount::new_move(chain_id, address).with_tweak(tweak)
+    }
+
+    pub fn account(&self) -> RecipientAccount {
+        RecipientAccount {
+            family: self.family,
+            chain_id: self.chain_id,
+            address: self.address,
         }
     }
 
     // The version byte to use in the U256 representation.
-    // This can be used to distinguish different formats in the future.
-    pub fn version() -> u8 {
-        1
+    // It distinguishes the address families, see `RecipientFamily::version`.
+    pub fn version(&self) -> u8 {
+        self.family.version()
     }
 
     pub fn to_u256(&self) -> U256 {
@@ -44,7 +254,7 @@ impl GeneralRecipient {
         )
         .into();
         // replace the most significant byte with version
-        digest[0] = Self::version();
+        digest[0] = self.version();
         U256::from_be_slice(&digest)
     }
 
@@ -55,8 +265,8 @@ impl GeneralRecipient {
 
 #[cfg(test)]
 mod tests {
-    use super::GeneralRecipient;
-    use alloy::primitives::{B256, U256};
+    use super::{GeneralRecipient, GeneralRecipientError, RecipientAccount, RecipientFamily};
+    use alloy::primitives::{Address, B256, U256};
     use sha2::{Digest, Sha256};
 
     fn sample_recipient() -> GeneralRecipient {
@@ -67,6 +277,7 @@ mod tests {
             chain_id,
             address,
             tweak,
+            family: RecipientFamily::Evm,
         }
     }
 
@@ -82,9 +293,92 @@ mod tests {
             .concat(),
         )
         .into();
-        digest[0] = GeneralRecipient::version();
+        digest[0] = recipient.version();
         let expected = U256::from_be_slice(&digest);
 
+        assert_eq!(recipient.version(), 1);
         assert_eq!(recipient.to_u256(), expected);
     }
+
+    #[test]
+    fn families_hash_to_distinct_recipients() {
+        let recipient = sample_recipient();
+        let solana = GeneralRecipient {
+            family: RecipientFamily::Solana,
+            ..recipient
+        };
+        let moved = GeneralRecipient {
+            family: RecipientFamily::Move,
+            ..recipient
+        };
+
+        assert_eq!(solana.to_u256().byte(31), 2);
+        assert_eq!(moved.to_u256().byte(31), 3);
+        assert_eq!(
+            solana.to_u256() & (U256::MAX >> 8),
+            recipient.to_u256() & (U256::MAX >> 8)
+        );
+        assert_ne!(solana.to_fr(), recipient.to_fr());
+        assert_ne!(solana.to_fr(), moved.to_fr());
+        for family in RecipientFamily::ALL {
+            assert_eq!(RecipientFamily::from_version(family.version()), Ok(family));
+        }
+    }
+
+    #[test]
+    fn account_strings_round_trip() {
+        let evm = RecipientAccount::new_evm(10, Address::repeat_byte(0xab));
+        let solana = RecipientAccount::new_solana(900, [7u8; 32]);
+        let moved = RecipientAccount::new_move(1, B256::with_last_byte(1));
+
+        for account in [evm, solana, moved] {
+            assert_eq!(account.to_string().parse::<RecipientAccount>(), Ok(account));
+        }
+        assert!(evm.to_string().starts_with("eip155:10:0x"));
+        assert_eq!(evm.evm_address(), Some(Address::repeat_byte(0xab)));
+        assert_eq!(solana.evm_address(), None);
+        assert_eq!("move:1:0x1".parse::<RecipientAccount>(), Ok(moved));
+        assert_eq!(
+            format!("solana:900:{}", bs58::encode([7u8; 32]).into_string())
+                .parse::<RecipientAccount>(),
+            Ok(solana)
+        );
+    }
+
+    #[test]
+    fn malformed_account_strings_are_rejected() {
+        assert!(matches!(
+            "eip155:10".parse::<RecipientAccount>(),
+            Err(GeneralRecipientError::MalformedAccount(_))
+        ));
+        assert!(matches!(
+            "cosmos:1:abc".parse::<RecipientAccount>(),
+            Err(GeneralRecipientError::UnknownNamespace(_))
+        ));
+        assert!(matches!(
+            "eip155:x:0x0000000000000000000000000000000000000001".parse::<RecipientAccount>(),
+            Err(GeneralRecipientError::InvalidChainId(_))
+        ));
+        // a 32-byte key is not an EVM address, and an EVM address is not a Solana key
+        let pubkey = bs58::encode([7u8; 32]).into_string();
+        assert!(matches!(
+            format!("eip155:1:{}", B256::repeat_byte(1)).parse::<RecipientAccount>(),
+            Err(GeneralRecipientError::InvalidAddress { .. })
+        ));
+        assert!(
+            format!("solana:1:{}", bs58::encode([7u8; 20]).into_string())
+                .parse::<RecipientAccount>()
+                .is_err()
+        );
+        assert!(
+            format!("solana:1:{pubkey}0")
+                .parse::<RecipientAccount>()
+                .is_err()
+        );
+        assert!(
+            format!("move:1:{}", &pubkey[..8])
+                .parse::<RecipientAccount>()
+                .is_err()
+        );
+    }
 }
@@ -1,11 +1,16 @@
 use std::collections::HashMap;
+use std::ops::Range;
 
 use crate::utils::convertion::{address_to_fr, u256_to_fr};
 use crate::utils::tree::gadgets::hash_chain::hash_chain;
 use crate::utils::tree::gadgets::leaf_hash::compute_leaf_hash;
-use crate::utils::tree::merkle_tree::{MerkleProof, MerkleTree};
+use crate::utils::tree::merkle_tree::{
+    MerkleProof, MerkleTree, MerkleTreeError, check_leaf_index, decode_header, encode_header,
+    ensure_consumed,
+};
 use alloy::primitives::{Address, U256};
 use ark_bn254::Fr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 pub struct Leaf {
     pub address: Address,
@@ -61,6 +66,32 @@ impl IncrementalMerkleTree {
         index
     }
 
+    /// Appends `leaves` in order and returns the indices they were assigned. Produces the same
+    /// tree and hash chain as calling [`Self::insert`] for each leaf, but hashes the leaves and
+    /// their shared ancestors in one batch.
+    pub fn insert_batch(
+        &mut self,
+        leaves: impl IntoIterator<Item = (Address, U256)>,
+    ) -> Range<u64> {
+        let start = self.index;
+        let leaves: Vec<Leaf> = leaves
+            .into_iter()
+            .map(|(address, value)| Leaf { address, value })
+            .collect();
+        let leaf_hashes = hash_leaves(&leaves);
+        self.tree.update_leaves((start..).zip(leaf_hashes));
+        for leaf in leaves {
+            self.hash_chain = hash_chain(self.hash_chain, leaf.address, leaf.value);
+            self.address_to_indices
+                .entry(leaf.address)
+                .or_default()
+                .push(self.index);
+            self.leaves.insert(self.index, leaf);
+            self.index += 1;
+        }
+        start..self.index
+    }
+
     pub fn get_root(&self) -> Fr {
         self.tree.get_root()
     }
@@ -68,4 +99,116 @@ impl IncrementalMerkleTree {
     pub fn prove(&self, index: u64) -> MerkleProof {
         self.tree.prove(index)
     }
+
+    /// Compact encoding holding the height and the `(address, value)` of every inserted leaf.
+    /// Leaf hashes, internal nodes and the hash chain are recomputed by [`Self::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + self.index as usize * 52);
+        encode_header(&mut bytes, self.tree.height(), self.index);
+        for index in 0..self.index {
+            let leaf = &self.leaves[&index];
+            leaf.address
+                .0
+                .0
+                .serialize_compressed(&mut bytes)
+                .and_then(|_| {
+                    leaf.value
+                        .to_be_bytes::<32>()
+                        .serialize_compressed(&mut bytes)
+                })
+                .expect("writing to a Vec cannot fail");
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleTreeError> {
+        let mut reader = bytes;
+        let (height, count) = decode_header(&mut reader)?;
+        if count > 0 {
+            check_leaf_index(count - 1, height)?;
+        }
+        let mut leaves = Vec::new();
+        for _ in 0..count {
+            let address = <[u8; 20]>::deserialize_compressed(&mut reader)?;
+            let value = <[u8; 32]>::deserialize_compressed(&mut reader)?;
+            leaves.push((Address::from(address), U256::from_be_bytes(value)));
+        }
+        ensure_consumed(reader)?;
+        let mut tree = Self::new(height);
+        tree.insert_batch(leaves);
+        Ok(tree)
+    }
+}
+
+fn hash_leaves(leaves: &[Leaf]) -> Vec<Fr> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        leaves.par_iter().map(Leaf::hash).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        leaves.iter().map(Leaf::hash).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn random_leaves(n: usize) -> Vec<(Address, U256)> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| {
+                let address = Address::repeat_byte(rng.gen_range(0..4));
+                (address, U256::from(rng.gen_range(0..u64::MAX)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn insert_batch_matches_sequential_inserts() {
+        let leaves = random_leaves(100);
+        let mut sequential = IncrementalMerkleTree::new(16);
+        for (address, value) in &leaves {
+            sequential.insert(*address, *value);
+        }
+        let mut batched = IncrementalMerkleTree::new(16);
+        assert_eq!(batched.insert_batch(leaves[..30].to_vec()), 0..30);
+        assert_eq!(batched.insert_batch(leaves[30..].to_vec()), 30..100);
+
+        assert_eq!(batched.get_root(), sequential.get_root());
+        assert_eq!(batched.hash_chain, sequential.hash_chain);
+        assert_eq!(batched.address_to_indices, sequential.address_to_indices);
+        for index in [0, 29, 30, 99] {
+            assert_eq!(
+                batched.prove(index).siblings,
+                sequential.prove(index).siblings
+            );
+        }
+    }
+
+    #[test]
+    fn bytes_roundtrip_restores_tree_and_hash_chain() {
+        let mut tree = IncrementalMerkleTree::new(16);
+        tree.insert_batch(random_leaves(40));
+
+        let restored = IncrementalMerkleTree::from_bytes(&tree.to_bytes()).unwrap();
+        assert_eq!(restored.index, tree.index);
+        assert_eq!(restored.get_root(), tree.get_root());
+        assert_eq!(restored.hash_chain, tree.hash_chain);
+        assert_eq!(restored.address_to_indices, tree.address_to_indices);
+
+        // claim a height the 40 leaves do not fit in
+        let mut bytes = tree.to_bytes();
+        bytes[4..8].copy_from_slice(&5u32.to_le_bytes());
+        assert!(matches!(
+            IncrementalMerkleTree::from_bytes(&bytes),
+            Err(MerkleTreeError::IndexOutOfRange {
+                index: 39,
+                height: 5
+            })
+        ));
+    }
 }
@@ -1,10 +1,34 @@
-use crate::utils::poseidon::utils::poseidon2;
+use crate::utils::poseidon::utils::{circom_poseidon_config, circom_poseidon_hash, poseidon2};
 use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
 use ark_ff::AdditiveGroup;
-use std::collections::HashMap;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
 
 use super::bit_path::BitPath;
 
+/// Version of the compact tree encodings produced by [`MerkleTree::to_bytes`] and
+/// `IncrementalMerkleTree::to_bytes`.
+pub const TREE_ENCODING_VERSION: u32 = 1;
+
+/// Trees are addressed by `u64` leaf indices, so no encoding may claim a taller tree.
+pub const MAX_TREE_HEIGHT: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum MerkleTreeError {
+    #[error("unsupported tree encoding version {found}, expected {expected}")]
+    UnsupportedVersion { expected: u32, found: u32 },
+    #[error("tree height {height} exceeds the maximum of {max}")]
+    UnsupportedHeight { height: usize, max: usize },
+    #[error("leaf index {index} does not fit in a tree of height {height}")]
+    IndexOutOfRange { index: u64, height: usize },
+    #[error("{0} trailing bytes after the tree encoding")]
+    TrailingBytes(usize),
+    #[error("malformed tree encoding: {0}")]
+    Serialization(#[from] SerializationError),
+}
+
 /// A Merkle tree that only keeps non-zero nodes. It has zero_hashes that hold the hash of each
 /// level of empty leaves.
 #[derive(Clone, Debug)]
@@ -70,6 +94,144 @@ impl MerkleTree {
         }
         MerkleProof { siblings }
     }
+
+    /// Writes many leaves at once. Leaves are applied in order, so a later write to the same
+    /// index wins. Each touched ancestor is hashed once per batch rather than once per leaf, and
+    /// with the `parallel` feature every level is hashed on the rayon thread pool.
+    pub fn update_leaves(&mut self, leaves: impl IntoIterator<Item = (u64, Fr)>) {
+        let leaves: BTreeMap<u64, Fr> = leaves.into_iter().collect();
+        if leaves.is_empty() {
+            return;
+        }
+        let config = circom_poseidon_config();
+        let mut length = self.height as u32;
+        self.node_hashes.extend(
+            leaves
+                .iter()
+                .map(|(&index, &h)| (BitPath::new(length, index), h)),
+        );
+        // sorted child indices map to sorted parent indices, so dedup removes shared ancestors
+        let mut indices: Vec<u64> = leaves.into_keys().collect();
+        while length > 0 {
+            indices.iter_mut().for_each(|index| *index >>= 1);
+            indices.dedup();
+            let hashes = self.hash_parents(length, &indices, &config);
+            length -= 1;
+            self.node_hashes.extend(
+                indices
+                    .iter()
+                    .zip(hashes)
+                    .map(|(&index, h)| (BitPath::new(length, index), h)),
+            );
+        }
+    }
+
+    /// Hashes the parents at `indices` from their children at depth `child_length`.
+    fn hash_parents(
+        &self,
+        child_length: u32,
+        indices: &[u64],
+        config: &PoseidonConfig<Fr>,
+    ) -> Vec<Fr> {
+        let hash = |&index: &u64| {
+            let left = self.get_node_hash(BitPath::new(child_length, index << 1));
+            let right = self.get_node_hash(BitPath::new(child_length, (index << 1) | 1));
+            circom_poseidon_hash(config, &[left, right])
+        };
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            indices.par_iter().map(hash).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            indices.iter().map(hash).collect()
+        }
+    }
+
+    /// Written leaves in index order. Internal nodes are derived from these.
+    pub fn leaves(&self) -> Vec<(u64, Fr)> {
+        let mut leaves: Vec<(u64, Fr)> = self
+            .node_hashes
+            .iter()
+            .filter(|(path, _)| path.len() as usize == self.height)
+            .map(|(path, h)| (path.value(), *h))
+            .collect();
+        leaves.sort_unstable_by_key(|(index, _)| *index);
+        leaves
+    }
+
+    /// Compact encoding holding only the height and the written leaves. [`Self::from_bytes`]
+    /// recomputes the internal nodes in one batch.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let leaves = self.leaves();
+        let mut bytes = Vec::with_capacity(16 + leaves.len() * 40);
+        encode_header(&mut bytes, self.height, leaves.len() as u64);
+        for (index, h) in &leaves {
+            index
+                .serialize_compressed(&mut bytes)
+                .and_then(|_| h.serialize_compressed(&mut bytes))
+                .expect("writing to a Vec cannot fail");
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleTreeError> {
+        let mut reader = bytes;
+        let (height, count) = decode_header(&mut reader)?;
+        let mut leaves = Vec::new();
+        for _ in 0..count {
+            let index = u64::deserialize_compressed(&mut reader)?;
+            check_leaf_index(index, height)?;
+            leaves.push((index, Fr::deserialize_compressed(&mut reader)?));
+        }
+        ensure_consumed(reader)?;
+        let mut tree = Self::new(height);
+        tree.update_leaves(leaves);
+        Ok(tree)
+    }
+}
+
+pub(crate) fn encode_header(bytes: &mut Vec<u8>, height: usize, count: u64) {
+    TREE_ENCODING_VERSION
+        .serialize_compressed(&mut *bytes)
+        .and_then(|_| (height as u32).serialize_compressed(&mut *bytes))
+        .and_then(|_| count.serialize_compressed(&mut *bytes))
+        .expect("writing to a Vec cannot fail");
+}
+
+/// Reads the version, height and entry count shared by the tree encodings.
+pub(crate) fn decode_header(reader: &mut &[u8]) -> Result<(usize, u64), MerkleTreeError> {
+    let version = u32::deserialize_compressed(&mut *reader)?;
+    if version != TREE_ENCODING_VERSION {
+        return Err(MerkleTreeError::UnsupportedVersion {
+            expected: TREE_ENCODING_VERSION,
+            found: version,
+        });
+    }
+    let height = u32::deserialize_compressed(&mut *reader)? as usize;
+    if height > MAX_TREE_HEIGHT {
+        return Err(MerkleTreeError::UnsupportedHeight {
+            height,
+            max: MAX_TREE_HEIGHT,
+        });
+    }
+    let count = u64::deserialize_compressed(&mut *reader)?;
+    Ok((height, count))
+}
+
+pub(crate) fn check_leaf_index(index: u64, height: usize) -> Result<(), MerkleTreeError> {
+    if height < MAX_TREE_HEIGHT && index >> height != 0 {
+        return Err(MerkleTreeError::IndexOutOfRange { index, height });
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_consumed(reader: &[u8]) -> Result<(), MerkleTreeError> {
+    if !reader.is_empty() {
+        return Err(MerkleTreeError::TrailingBytes(reader.len()));
+    }
+    Ok(())
 }
 
 #[derive(Clone, Debug)]
@@ -185,4 +347,49 @@ mod tests {
             assert_eq!(calc_root, tree.get_root());
         }
     }
+
+    #[test]
+    fn test_merkle_tree_update_leaves_matches_sequential() {
+        let mut rng = rand::thread_rng();
+        let height = 12;
+        let mut leaves: Vec<(u64, Fr)> = (0..200)
+            .map(|_| (rng.gen_range(0..1 << height), Fr::rand(&mut rng)))
+            .collect();
+        // a repeated index keeps the last write
+        leaves.push((leaves[0].0, Fr::rand(&mut rng)));
+
+        let mut sequential = MerkleTree::new(height);
+        for (index, leaf_hash) in &leaves {
+            sequential.update_leaf(*index, *leaf_hash);
+        }
+        let mut batched = MerkleTree::new(height);
+        let (head, tail) = leaves.split_at(leaves.len() / 2);
+        batched.update_leaves(head.iter().copied());
+        batched.update_leaves(tail.iter().copied());
+
+        assert_eq!(batched.get_root(), sequential.get_root());
+        assert_eq!(batched.node_hashes, sequential.node_hashes);
+    }
+
+    #[test]
+    fn test_merkle_tree_bytes_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let height = 20;
+        let mut tree = MerkleTree::new(height);
+        tree.update_leaves((0..50).map(|_| (rng.gen_range(0..1 << height), Fr::rand(&mut rng))));
+
+        let bytes = tree.to_bytes();
+        let restored = MerkleTree::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.height(), height);
+        assert_eq!(restored.get_root(), tree.get_root());
+        assert_eq!(restored.node_hashes, tree.node_hashes);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            MerkleTree::from_bytes(&trailing),
+            Err(MerkleTreeError::TrailingBytes(1))
+        ));
+        assert!(MerkleTree::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
 }
@@ -0,0 +1,591 @@
+//! Native checks that mirror the withdraw and root circuits. Running them before proving turns
+//! an opaque `prove_step` failure or unsatisfied constraint system into an error naming the step
+//! and the rule it breaks.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use thiserror::Error;
+
+use crate::{
+    circuits::{
+        burn_address::compute_burn_address_from_secret,
+        constants::{ADDRESS_BIT_LENGTH, BYTES31_BIT_LENGTH},
+    },
+    groth16::withdraw::SingleWithdrawCircuit,
+    nova::{
+        root_nova::RootExternalInputs,
+        withdraw_nova::{WITHDRAW_STATE_LEN, WithdrawExternalInputs},
+    },
+    utils::tree::{gadgets::leaf_hash::compute_leaf_hash, merkle_tree::MerkleProof},
+};
+
+/// `step` is the position of the offending input in the slice being validated; single withdraw
+/// witnesses always report step 0.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WitnessError {
+    #[error("expected an initial state of {expected} elements, got {actual}")]
+    InvalidStateLength { expected: usize, actual: usize },
+
+    #[error("step {step}: {field} is not assigned")]
+    MissingAssignment { step: usize, field: &'static str },
+
+    #[error("step {step}: is_dummy must be 0 or 1")]
+    InvalidDummyFlag { step: usize },
+
+    #[error("step {step}: {field} does not fit in {bits} bits")]
+    OutOfRange {
+        step: usize,
+        field: &'static str,
+        bits: usize,
+    },
+
+    #[error(
+        "step {step}: leaf_index {leaf_index} must be greater than the previous leaf index {previous}"
+    )]
+    NonIncreasingLeafIndex {
+        step: usize,
+        leaf_index: u64,
+        previous: u64,
+    },
+
+    #[error(
+        "step {step}: burn address of (recipient, secret) does not satisfy the PoW difficulty of {difficulty} bits"
+    )]
+    PowUnsatisfied { step: usize, difficulty: usize },
+
+    #[error(
+        "step {step}: merkle path of leaf_index {leaf_index} does not reproduce the merkle root"
+    )]
+    MerkleRootMismatch { step: usize, leaf_index: u64 },
+
+    #[error(
+        "step {step}: association path of leaf_index {leaf_index} does not reproduce the association root"
+    )]
+    AssociationRootMismatch { step: usize, leaf_index: u64 },
+
+    #[error("step {step}: transfer tree slot {index} is not empty under the previous root")]
+    NonEmptyLeaf { step: usize, index: u64 },
+
+    #[error("step {step}: dummy value exceeds the running total")]
+    TotalUnderflow { step: usize },
+
+    #[error("step {step}: delta exceeds the withdrawn leaf value")]
+    DeltaExceedsValue { step: usize },
+
+    #[error("step {step}: withdraw_value does not equal value - delta")]
+    WithdrawValueMismatch { step: usize },
+
+    #[error("step {step}: fee exceeds withdraw_value")]
+    FeeExceedsWithdrawValue { step: usize },
+}
+
+/// Validates `steps` as consecutive `WithdrawCircuit` folds starting from `z0`
+/// (`[merkle_root, recipient, prev_leaf_index_with_offset, prev_total_value, relayer, fee,
+/// association_root]`) and returns the expected final state. `pow_difficulty` must be the one the
+/// circuit was built with.
+pub fn validate_withdraw_steps<const DEPTH: usize>(
+    z0: &[Fr],
+    steps: &[WithdrawExternalInputs<Fr, DEPTH>],
+    pow_difficulty: usize,
+) -> Result<Vec<Fr>, WitnessError> {
+    if z0.len() != WITHDRAW_STATE_LEN {
+        return Err(WitnessError::InvalidStateLength {
+            expected: WITHDRAW_STATE_LEN,
+            actual: z0.len(),
+        });
+    }
+    let (merkle_root, recipient) = (z0[0], z0[1]);
+    if !fits_bits(z0[2], DEPTH + 1) {
+        return Err(WitnessError::OutOfRange {
+            step: 0,
+            field: "prev_leaf_index_with_offset",
+            bits: DEPTH + 1,
+        });
+    }
+    let mut prev_leaf_index_with_offset = to_u64(z0[2]);
+    let mut total_value = z0[3];
+    let association_root = z0[6];
+
+    for (step, input) in steps.iter().enumerate() {
+        (prev_leaf_index_with_offset, total_value) = check_withdraw_step(
+            step,
+            merkle_root,
+            recipient,
+            association_root,
+            pow_difficulty,
+            prev_leaf_index_with_offset,
+            total_value,
+            input,
+        )?;
+    }
+
+    let mut state = z0.to_vec();
+    state[2] = Fr::from(prev_leaf_index_with_offset);
+    state[3] = total_value;
+    Ok(state)
+}
+
+/// Validates `transfers` as consecutive `root_transition_step`s starting from transfer `index`
+/// under `prev_root`, and returns the resulting `(index, root)`.
+pub fn validate_root_transfers<const DEPTH: usize>(
+    mut index: u64,
+    mut prev_root: Fr,
+    transfers: &[RootExternalInputs<Fr, DEPTH>],
+) -> Result<(u64, Fr), WitnessError> {
+    for (step, transfer) in transfers.iter().enumerate() {
+        if !fits_bits(transfer.address, ADDRESS_BIT_LENGTH) {
+            return Err(WitnessError::OutOfRange {
+                step,
+                field: "address",
+                bits: ADDRESS_BIT_LENGTH,
+            });
+        }
+        check_value_range(step, "value", transfer.value)?;
+        if DEPTH < 64 && index >> DEPTH != 0 {
+            return Err(WitnessError::OutOfRange {
+                step,
+                field: "index",
+                bits: DEPTH,
+            });
+        }
+        if transfer.is_dummy {
+            continue;
+        }
+
+        let proof = MerkleProof {
+            siblings: transfer.siblings.to_vec(),
+        };
+        if proof.get_root(Fr::zero(), index) != prev_root {
+            return Err(WitnessError::NonEmptyLeaf { step, index });
+        }
+        let leaf_hash = compute_leaf_hash(transfer.address, transfer.value);
+        prev_root = proof.get_root(leaf_hash, index);
+        index += 1;
+    }
+    Ok((index, prev_root))
+}
+
+/// Validates a fully assigned `SingleWithdrawCircuit` against its own public inputs.
+pub fn validate_single_withdraw<const DEPTH: usize>(
+    circuit: &SingleWithdrawCircuit<Fr, DEPTH>,
+) -> Result<(), WitnessError> {
+    let step = 0;
+    let merkle_root = assigned(circuit.merkle_root, "merkle_root")?;
+    let recipient = assigned(circuit.recipient, "recipient")?;
+    let withdraw_value = assigned(circuit.withdraw_value, "withdraw_value")?;
+    let relayer = assigned(circuit.relayer, "relayer")?;
+    let fee = assigned(circuit.fee, "fee")?;
+    let association_root = assigned(circuit.association_root, "association_root")?;
+    let value = assigned(circuit.value, "value")?;
+    let delta = assigned(circuit.delta, "delta")?;
+    let secret = assigned(circuit.secret, "secret")?;
+    let leaf_index = assigned(circuit.leaf_index, "leaf_index")?;
+    let siblings = circuit
+        .siblings
+        .iter()
+        .map(|sibling| assigned(*sibling, "siblings"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let association_siblings = circuit
+        .association_siblings
+        .iter()
+        .map(|sibling| assigned(*sibling, "association_siblings"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if DEPTH < 64 && leaf_index >> DEPTH != 0 {
+        return Err(WitnessError::OutOfRange {
+            step,
+            field: "leaf_index",
+            bits: DEPTH,
+        });
+    }
+    check_value_range(step, "value", value)?;
+    check_value_range(step, "delta", delta)?;
+    let leaf_hash = check_leaf_membership(
+        step,
+        merkle_root,
+        recipient,
+        secret,
+        value,
+        leaf_index,
+        siblings,
+        circuit.pow_difficulty,
+    )?;
+    check_association_membership(
+        step,
+        association_root,
+        leaf_hash,
+        leaf_index,
+        association_siblings,
+    )?;
+
+    if delta.into_bigint() > value.into_bigint() {
+        return Err(WitnessError::DeltaExceedsValue { step });
+    }
+    if value - delta != withdraw_value {
+        return Err(WitnessError::WithdrawValueMismatch { step });
+    }
+    check_relayer_fee(step, relayer, fee, withdraw_value)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_withdraw_step<const DEPTH: usize>(
+    step: usize,
+    merkle_root: Fr,
+    recipient: Fr,
+    association_root: Fr,
+    pow_difficulty: usize,
+    prev_leaf_index_with_offset: u64,
+    total_value: Fr,
+    input: &WithdrawExternalInputs<Fr, DEPTH>,
+) -> Result<(u64, Fr), WitnessError> {
+    let is_dummy = if input.is_dummy.is_zero() {
+        false
+    } else if input.is_dummy.is_one() {
+        true
+    } else {
+        return Err(WitnessError::InvalidDummyFlag { step });
+    };
+    if !fits_bits(input.leaf_index, DEPTH) {
+        return Err(WitnessError::OutOfRange {
+            step,
+            field: "leaf_index",
+            bits: DEPTH,
+        });
+    }
+    let leaf_index = to_u64(input.leaf_index);
+    let value = input.value;
+    check_value_range(step, "value", value)?;
+
+    let leaf_index_with_offset = leaf_index + 1;
+    if prev_leaf_index_with_offset >= leaf_index_with_offset {
+        return Err(WitnessError::NonIncreasingLeafIndex {
+            step,
+            leaf_index,
+            previous: prev_leaf_index_with_offset - 1,
+        });
+    }
+
+    let new_total_value = if is_dummy {
+        if value.into_bigint() > total_value.into_bigint() {
+            return Err(WitnessError::TotalUnderflow { step });
+        }
+        total_value - value
+    } else {
+        let leaf_hash = check_leaf_membership(
+            step,
+            merkle_root,
+            recipient,
+            input.secret,
+            value,
+            leaf_index,
+            input.siblings.to_vec(),
+            pow_difficulty,
+        )?;
+        check_association_membership(
+            step,
+            association_root,
+            leaf_hash,
+            leaf_index,
+            input.association_siblings.to_vec(),
+        )?;
+        total_value + value
+    };
+    check_value_range(step, "total_value", new_total_value)?;
+
+    Ok((leaf_index_with_offset, new_total_value))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_leaf_membership(
+    step: usize,
+    merkle_root: Fr,
+    recipient: Fr,
+    secret: Fr,
+    value: Fr,
+    leaf_index: u64,
+    siblings: Vec<Fr>,
+    pow_difficulty: usize,
+) -> Result<Fr, WitnessError> {
+    let address =
+        compute_burn_address_from_secret(recipient, secret, pow_difficulty).map_err(|_| {
+            WitnessError::PowUnsatisfied {
+                step,
+                difficulty: pow_difficulty,
+            }
+        })?;
+    let leaf_hash = compute_leaf_hash(address, value);
+    if (MerkleProof { siblings }).get_root(leaf_hash, leaf_index) != merkle_root {
+        return Err(WitnessError::MerkleRootMismatch { step, leaf_index });
+    }
+    Ok(leaf_hash)
+}
+
+/// A zero `association_root` opts out, mirroring `enforce_association_membership`.
+fn check_association_membership(
+    step: usize,
+    association_root: Fr,
+    leaf_hash: Fr,
+    leaf_index: u64,
+    siblings: Vec<Fr>,
+) -> Result<(), WitnessError> {
+    if association_root.is_zero() {
+        return Ok(());
+    }
+    if (MerkleProof { siblings }).get_root(leaf_hash, leaf_index) != association_root {
+        return Err(WitnessError::AssociationRootMismatch { step, leaf_index });
+    }
+    Ok(())
+}
+
+fn check_relayer_fee(
+    step: usize,
+    relayer: Fr,
+    fee: Fr,
+    withdraw_value: Fr,
+) -> Result<(), WitnessError> {
+    if !fits_bits(relayer, ADDRESS_BIT_LENGTH) {
+        return Err(WitnessError::OutOfRange {
+            step,
+            field: "relayer",
+            bits: ADDRESS_BIT_LENGTH,
+        });
+    }
+    check_value_range(step, "fee", fee)?;
+    if fee.into_bigint() > withdraw_value.into_bigint() {
+        return Err(WitnessError::FeeExceedsWithdrawValue { step });
+    }
+    Ok(())
+}
+
+fn check_value_range(step: usize, field: &'static str, value: Fr) -> Result<(), WitnessError> {
+    if !fits_bits(value, BYTES31_BIT_LENGTH) {
+        return Err(WitnessError::OutOfRange {
+            step,
+            field,
+            bits: BYTES31_BIT_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+fn assigned<T>(value: Option<T>, field: &'static str) -> Result<T, WitnessError> {
+    value.ok_or(WitnessError::MissingAssignment { step: 0, field })
+}
+
+fn fits_bits(value: Fr, bits: usize) -> bool {
+    value.into_bigint().num_bits() as usize <= bits
+}
+
+/// Callers must range-check `value` to at most 64 bits first.
+fn to_u64(value: Fr) -> u64 {
+    value.into_bigint().as_ref()[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::{
+        burn_address::{find_pow_nonce, secret_from_nonce},
+        constants::POW_DIFFICULTY,
+    };
+    use crate::nova::withdraw_nova::dummy_withdraw_ext_input;
+    use crate::utils::{
+        convertion::{address_to_fr, fr_to_address, u256_to_fr},
+        poseidon::utils::circom_poseidon_config,
+        tree::{incremental_merkle_tree::IncrementalMerkleTree, merkle_tree::MerkleTree},
+    };
+    use alloy::primitives::{Address, U256};
+
+    const DEPTH: usize = 4;
+
+    fn build_withdraw(values: &[u64]) -> (Vec<Fr>, Vec<WithdrawExternalInputs<Fr, DEPTH>>) {
+        let recipient = Fr::from(777u64);
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let mut leaves = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let seed = Fr::from(10 + i as u64);
+            let secret = secret_from_nonce(seed, find_pow_nonce(recipient, seed, POW_DIFFICULTY));
+            let address =
+                compute_burn_address_from_secret(recipient, secret, POW_DIFFICULTY).unwrap();
+            let index = tree.insert(fr_to_address(address), U256::from(*value));
+            leaves.push((index, secret, *value));
+        }
+        let steps = leaves
+            .into_iter()
+            .map(|(index, secret, value)| WithdrawExternalInputs {
+                is_dummy: Fr::zero(),
+                value: Fr::from(value),
+                secret,
+                leaf_index: Fr::from(index),
+                siblings: tree.prove(index).siblings.try_into().unwrap(),
+                association_siblings: [Fr::zero(); DEPTH],
+            })
+            .collect();
+        let z0 = vec![
+            tree.get_root(),
+            recipient,
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+        ];
+        (z0, steps)
+    }
+
+    #[test]
+    fn withdraw_steps_accept_valid_witness() {
+        let (z0, mut steps) = build_withdraw(&[100, 200]);
+        steps.push(dummy_withdraw_ext_input::<DEPTH>(14, U256::from(50u64)));
+        let state = validate_withdraw_steps(&z0, &steps, POW_DIFFICULTY).unwrap();
+        assert_eq!(state[2], Fr::from(15u64));
+        assert_eq!(state[3], Fr::from(250u64));
+    }
+
+    #[test]
+    fn withdraw_steps_report_offending_step() {
+        let (z0, steps) = build_withdraw(&[100, 200, 300]);
+
+        let mut repeated = steps.clone();
+        repeated[2] = repeated[1].clone();
+        assert_eq!(
+            validate_withdraw_steps(&z0, &repeated, POW_DIFFICULTY),
+            Err(WitnessError::NonIncreasingLeafIndex {
+                step: 2,
+                leaf_index: 1,
+                previous: 1,
+            })
+        );
+
+        let mut wrong_sibling = steps.clone();
+        wrong_sibling[1].siblings[0] += Fr::one();
+        assert_eq!(
+            validate_withdraw_steps(&z0, &wrong_sibling, POW_DIFFICULTY),
+            Err(WitnessError::MerkleRootMismatch {
+                step: 1,
+                leaf_index: 1,
+            })
+        );
+
+        let mut overdrawn = steps.clone();
+        overdrawn.push(dummy_withdraw_ext_input::<DEPTH>(15, U256::from(601u64)));
+        assert_eq!(
+            validate_withdraw_steps(&z0, &overdrawn, POW_DIFFICULTY),
+            Err(WitnessError::TotalUnderflow { step: 3 })
+        );
+
+        let mut no_pow = steps;
+        no_pow[0].secret += Fr::one();
+        let err = validate_withdraw_steps(&z0, &no_pow, POW_DIFFICULTY).unwrap_err();
+        assert!(matches!(
+            err,
+            WitnessError::PowUnsatisfied { step: 0, .. }
+                | WitnessError::MerkleRootMismatch { step: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn withdraw_steps_check_association_set() {
+        let (mut z0, mut steps) = build_withdraw(&[100, 200, 300]);
+        // the association set approves leaves 0 and 2 only
+        let mut association_tree = MerkleTree::new(DEPTH);
+        for index in [0, 2] {
+            let step = &steps[index];
+            let address =
+                compute_burn_address_from_secret(z0[1], step.secret, POW_DIFFICULTY).unwrap();
+            association_tree.update_leaf(index as u64, compute_leaf_hash(address, step.value));
+        }
+        z0[6] = association_tree.get_root();
+        for (index, step) in steps.iter_mut().enumerate() {
+            step.association_siblings = association_tree
+                .prove(index as u64)
+                .siblings
+                .try_into()
+                .unwrap();
+        }
+
+        let approved = [steps[0].clone(), steps[2].clone()];
+        assert!(validate_withdraw_steps(&z0, &approved, POW_DIFFICULTY).is_ok());
+
+        // the same approved steps against a root the association siblings do not lead to
+        let mut wrong_root_z0 = z0.clone();
+        wrong_root_z0[6] += Fr::one();
+        assert_eq!(
+            validate_withdraw_steps(&wrong_root_z0, &approved, POW_DIFFICULTY),
+            Err(WitnessError::AssociationRootMismatch {
+                step: 0,
+                leaf_index: 0,
+            })
+        );
+        assert_eq!(
+            validate_withdraw_steps(&z0, &steps, POW_DIFFICULTY),
+            Err(WitnessError::AssociationRootMismatch {
+                step: 1,
+                leaf_index: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn root_transfers_track_index_and_root() {
+        let mut tree = IncrementalMerkleTree::new(DEPTH);
+        let prev_root = tree.get_root();
+        let mut transfers = vec![];
+        for i in 0..3u64 {
+            let address = Address::repeat_byte(i as u8 + 1);
+            let value = U256::from(10 * (i + 1));
+            let siblings = tree.prove(i).siblings.try_into().unwrap();
+            tree.insert(address, value);
+            transfers.push(RootExternalInputs::<Fr, DEPTH> {
+                is_dummy: false,
+                address: address_to_fr(address),
+                value: u256_to_fr(value),
+                siblings,
+            });
+        }
+        transfers.push(RootExternalInputs::dummy());
+        assert_eq!(
+            validate_root_transfers(0, prev_root, &transfers),
+            Ok((3, tree.get_root()))
+        );
+
+        // replaying the first transfer on top of the updated tree hits an occupied slot
+        assert_eq!(
+            validate_root_transfers(0, tree.get_root(), &transfers[..1]),
+            Err(WitnessError::NonEmptyLeaf { step: 0, index: 0 })
+        );
+    }
+
+    #[test]
+    fn single_withdraw_checks_public_inputs() {
+        let (z0, steps) = build_withdraw(&[100]);
+        let mut circuit =
+            SingleWithdrawCircuit::<Fr, DEPTH>::new(circom_poseidon_config(), POW_DIFFICULTY);
+        circuit.merkle_root = Some(z0[0]);
+        circuit.recipient = Some(z0[1]);
+        circuit.withdraw_value = Some(Fr::from(75u64));
+        circuit.relayer = Some(Fr::from(0xbeefu64));
+        circuit.fee = Some(Fr::from(5u64));
+        circuit.association_root = Some(Fr::zero());
+        circuit.value = Some(steps[0].value);
+        circuit.delta = Some(Fr::from(25u64));
+        circuit.secret = Some(steps[0].secret);
+        circuit.leaf_index = Some(0);
+        circuit.siblings = steps[0].siblings.map(Some);
+        circuit.association_siblings = steps[0].association_siblings.map(Some);
+        assert_eq!(validate_single_withdraw(&circuit), Ok(()));
+
+        let mut mismatch = circuit.clone();
+        mismatch.withdraw_value = Some(Fr::from(80u64));
+        assert_eq!(
+            validate_single_withdraw(&mismatch),
+            Err(WitnessError::WithdrawValueMismatch { step: 0 })
+        );
+
+        circuit.fee = Some(Fr::from(76u64));
+        assert_eq!(
+            validate_single_withdraw(&circuit),
+            Err(WitnessError::FeeExceedsWithdrawValue { step: 0 })
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Randomized equivalence checks between the native helpers and their in-circuit counterparts.
+//! Each property synthesizes the gadget over witness variables, so a divergence shows up either as
+//! a different output or as an unsatisfied constraint system.
+
+use alloy::primitives::{Address, U256};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_relations::ns;
+use proptest::prelude::*;
+use zkp::{
+    circuits::{
+        burn_address::{burn_address_var, compute_burn_address_from_secret},
+        constants::{ADDRESS_BIT_LENGTH, BYTES31_BIT_LENGTH},
+    },
+    utils::{
+        convertion::{address_to_fr, fr_to_u256, u256_to_fr},
+        poseidon::{
+            gadgets::CircomCRHParametersVar,
+            utils::{circom_poseidon_config, poseidon2},
+        },
+        tree::gadgets::{
+            hash_chain::{hash_chain, hash_chain_var},
+            leaf_hash::{compute_leaf_hash, leaf_hash_var},
+            merkle::{
+                enforce_bit_length, enforce_strict_less_than, merkle_root_from_leaf,
+                to_bits_le_limited,
+            },
+        },
+    },
+};
+
+const DEPTH: usize = 8;
+
+fn max_bytes31() -> U256 {
+    (U256::from(1u8) << BYTES31_BIT_LENGTH) - U256::from(1u8)
+}
+
+fn address() -> impl Strategy<Value = Address> {
+    prop_oneof![
+        Just(Address::ZERO),
+        Just(Address::repeat_byte(0xff)),
+        any::<[u8; 20]>().prop_map(Address::from),
+    ]
+}
+
+/// Values that fit the 31-byte range shared by amounts and hash chains, biased toward the edges.
+fn bytes31() -> impl Strategy<Value = U256> {
+    prop_oneof![
+        Just(U256::ZERO),
+        Just(U256::from(1u8)),
+        Just(max_bytes31()),
+        any::<[u8; 31]>().prop_map(|bytes| U256::from_be_slice(&bytes)),
+    ]
+}
+
+fn field() -> impl Strategy<Value = Fr> {
+    prop_oneof![
+        Just(Fr::from(0u64)),
+        Just(-Fr::from(1u64)),
+        any::<[u8; 32]>().prop_map(|bytes| Fr::from_le_bytes_mod_order(&bytes)),
+    ]
+}
+
+fn witness(cs: &ConstraintSystemRef<Fr>, value: Fr) -> Result<FpVar<Fr>, SynthesisError> {
+    FpVar::new_witness(ns!(cs, "value"), || Ok(value))
+}
+
+fn poseidon_params(
+    cs: &ConstraintSystemRef<Fr>,
+) -> Result<CircomCRHParametersVar<Fr>, SynthesisError> {
+    CircomCRHParametersVar::new_constant(ns!(cs, "poseidon_params"), &circom_poseidon_config())
+}
+
+/// Native Merkle root over the same path encoding as `merkle_root_from_leaf`.
+fn native_merkle_root(index: u64, leaf: Fr, siblings: &[Fr]) -> Fr {
+    siblings
+        .iter()
+        .enumerate()
+        .fold(leaf, |current, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                poseidon2(current, *sibling)
+            } else {
+                poseidon2(*sibling, current)
+            }
+        })
+}
+
+fn bit_length_satisfied(value: Fr, num_bits: usize) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    enforce_bit_length(&witness(&cs, value)?, num_bits)?;
+    cs.is_satisfied()
+}
+
+fn strict_less_than_satisfied(
+    left: Fr,
+    right: Fr,
+    num_bits: usize,
+) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    enforce_strict_less_than(&witness(&cs, left)?, &witness(&cs, right)?, num_bits)?;
+    cs.is_satisfied()
+}
+
+proptest! {
+    // SHA-256 dominates the runtime of this block, so it runs fewer cases.
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn hash_chain_var_matches_native(prev in bytes31(), addr in address(), value in bytes31()) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let out = hash_chain_var(
+            &witness(&cs, u256_to_fr(prev))?,
+            &witness(&cs, address_to_fr(addr))?,
+            &witness(&cs, u256_to_fr(value))?,
+        )?;
+        prop_assert!(cs.is_satisfied()?);
+        prop_assert_eq!(fr_to_u256(out.value()?), hash_chain(prev, addr, value));
+    }
+
+    #[test]
+    fn hash_chain_var_rejects_values_above_31_bytes(
+        prev in bytes31(),
+        addr in address(),
+        value in bytes31(),
+    ) {
+        let overflow = value + (U256::from(1u8) << BYTES31_BIT_LENGTH);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        hash_chain_var(
+            &witness(&cs, u256_to_fr(prev))?,
+            &witness(&cs, address_to_fr(addr))?,
+            &witness(&cs, u256_to_fr(overflow))?,
+        )?;
+        prop_assert!(!cs.is_satisfied()?);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn leaf_hash_var_matches_native(addr in address(), amount in bytes31()) {
+        let (addr, amount) = (address_to_fr(addr), u256_to_fr(amount));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params = poseidon_params(&cs)?;
+        let out = leaf_hash_var(&params, &witness(&cs, addr)?, &witness(&cs, amount)?)?;
+        prop_assert!(cs.is_satisfied()?);
+        prop_assert_eq!(out.value()?, compute_leaf_hash(addr, amount));
+    }
+
+    /// Low difficulties make both PoW outcomes common, so the property sees accepted and rejected
+    /// secrets alike.
+    #[test]
+    fn burn_address_var_matches_native(
+        recipient in address(),
+        secret in field(),
+        pow_difficulty in 0usize..=3,
+    ) {
+        let recipient = address_to_fr(recipient);
+        let native = compute_burn_address_from_secret(recipient, secret, pow_difficulty);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params = poseidon_params(&cs)?;
+        let is_constrained = Boolean::new_witness(ns!(cs, "is_constrained"), || Ok(true))?;
+        let out = burn_address_var(
+            &params,
+            &witness(&cs, recipient)?,
+            &witness(&cs, secret)?,
+            &is_constrained,
+            pow_difficulty,
+        )?;
+        prop_assert_eq!(cs.is_satisfied()?, native.is_ok());
+
+        // the address is the low 160 bits of the hash whether or not PoW holds
+        let hash_bits = poseidon2(recipient, secret).into_bigint().to_bits_le();
+        let truncated = Fr::from_bigint(<Fr as PrimeField>::BigInt::from_bits_le(
+            &hash_bits[..ADDRESS_BIT_LENGTH],
+        ))
+        .unwrap();
+        prop_assert_eq!(out.value()?, truncated);
+        if let Ok(address) = native {
+            prop_assert_eq!(address, truncated);
+        }
+    }
+
+    #[test]
+    fn burn_address_var_skips_pow_for_dummy_steps(
+        recipient in address(),
+        secret in field(),
+        pow_difficulty in 0usize..=16,
+    ) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params = poseidon_params(&cs)?;
+        let is_constrained = Boolean::new_witness(ns!(cs, "is_constrained"), || Ok(false))?;
+        burn_address_var(
+            &params,
+            &witness(&cs, address_to_fr(recipient))?,
+            &witness(&cs, secret)?,
+            &is_constrained,
+            pow_difficulty,
+        )?;
+        prop_assert!(cs.is_satisfied()?);
+    }
+
+    #[test]
+    fn merkle_root_from_leaf_matches_native(
+        index in prop_oneof![Just(0u64), Just((1u64 << DEPTH) - 1), 0u64..(1u64 << DEPTH)],
+        leaf in field(),
+        siblings in prop::array::uniform8(field()),
+    ) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params = poseidon_params(&cs)?;
+        let index_bits = to_bits_le_limited(&witness(&cs, Fr::from(index))?, DEPTH)?;
+        let sibling_vars = siblings
+            .iter()
+            .map(|sibling| witness(&cs, *sibling))
+            .collect::<Result<Vec<_>, _>>()?;
+        let leaf_var = witness(&cs, leaf)?;
+        let root = merkle_root_from_leaf(&params, &leaf_var, &index_bits, &sibling_vars)?;
+        prop_assert!(cs.is_satisfied()?);
+        prop_assert_eq!(root.value()?, native_merkle_root(index, leaf, &siblings));
+    }
+
+    #[test]
+    fn enforce_bit_length_accepts_exactly_the_range(
+        value in any::<u128>(),
+        num_bits in 0usize..=128,
+    ) {
+        let fits = num_bits == 128 || value >> num_bits == 0;
+        prop_assert_eq!(bit_length_satisfied(Fr::from(value), num_bits)?, fits);
+
+        // both sides of the 2^num_bits boundary
+        let bound = Fr::from(2u64).pow([num_bits as u64]);
+        prop_assert!(bit_length_satisfied(bound - Fr::from(1u64), num_bits)?);
+        prop_assert!(!bit_length_satisfied(bound, num_bits)?);
+    }
+
+    #[test]
+    fn enforce_bit_length_rejects_negated_values(
+        value in 1u64..,
+        num_bits in 0usize..=BYTES31_BIT_LENGTH,
+    ) {
+        // field wrap-around must not sneak a huge element into a small range
+        prop_assert!(!bit_length_satisfied(-Fr::from(value), num_bits)?);
+    }
+
+    #[test]
+    fn enforce_strict_less_than_matches_native(
+        left in any::<u64>(),
+        right in any::<u64>(),
+        num_bits in 1usize..=64,
+    ) {
+        let mask = if num_bits == 64 { u64::MAX } else { (1u64 << num_bits) - 1 };
+        let (left, right) = (left & mask, right & mask);
+        prop_assert_eq!(
+            strict_less_than_satisfied(Fr::from(left), Fr::from(right), num_bits)?,
+            left < right
+        );
+        // neighbours and equal operands around `right`
+        prop_assert!(!strict_less_than_satisfied(Fr::from(right), Fr::from(right), num_bits)?);
+        if right > 0 {
+            let below = Fr::from(right - 1);
+            prop_assert!(strict_less_than_satisfied(below, Fr::from(right), num_bits)?);
+        }
+    }
+
+    #[test]
+    fn enforce_strict_less_than_rejects_out_of_range_operands(
+        left in any::<u64>(),
+        num_bits in 1usize..=64,
+    ) {
+        // an operand at 2^num_bits fails the range check even though `left < right` holds
+        let left = left >> (64 - num_bits);
+        let right = Fr::from(2u64).pow([num_bits as u64]);
+        prop_assert!(!strict_less_than_satisfied(Fr::from(left), right, num_bits)?);
+    }
+}